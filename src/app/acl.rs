@@ -0,0 +1,94 @@
+use ratatui::widgets::ListState;
+
+use super::confirm::{Gate, Guarded};
+use super::{App, Entry, Location};
+
+/// Canned ACLs S3 accepts on objects.
+pub const CANNED_ACLS: &[&str] = &[
+    "private",
+    "public-read",
+    "public-read-write",
+    "authenticated-read",
+    "bucket-owner-read",
+    "bucket-owner-full-control",
+];
+
+/// The canned ACL after `current`, cycling through the bucket default (`None`).
+pub(crate) fn next_acl(current: Option<&str>) -> Option<String> {
+    match current.and_then(|acl| CANNED_ACLS.iter().position(|a| *a == acl)) {
+        None => Some(CANNED_ACLS[0].to_string()),
+        Some(i) => CANNED_ACLS.get(i + 1).map(|a| a.to_string()),
+    }
+}
+
+/// Popup choosing the canned ACL to put on the selected object (`Shift+A`).
+pub struct AclPicker {
+    pub remote: String,
+    pub bucket: String,
+    pub key: String,
+    pub name: String,
+    pub state: ListState,
+}
+
+impl App {
+    pub fn open_acl_picker(&mut self) {
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            return;
+        };
+        let Some(Entry::Object(obj)) = self.browser_state.selected().and_then(|i| self.entries.get(i)) else {
+            return;
+        };
+        if obj.is_dir {
+            self.notify_warn("ACLs are set on objects, not prefixes");
+            return;
+        }
+        let (remote, bucket) = (remote.clone(), bucket.clone());
+        let (key, name) = (obj.key.clone(), obj.display_name.clone());
+        if self.gate(&[&remote], false) == Gate::Refused || self.refuse_locked(&remote, &bucket) {
+            return;
+        }
+        let mut state = ListState::default();
+        state.select(Some(0));
+        self.acl_picker = Some(AclPicker {
+            remote,
+            bucket,
+            key,
+            name,
+            state,
+        });
+    }
+
+    pub fn acl_move(&mut self, down: bool) {
+        let Some(picker) = &mut self.acl_picker else {
+            return;
+        };
+        let i = picker.state.selected().unwrap_or(0);
+        picker
+            .state
+            .select(Some(if down { (i + 1).min(CANNED_ACLS.len() - 1) } else { i.saturating_sub(1) }));
+    }
+
+    /// Put the highlighted ACL on the object, once a protected remote's name is typed.
+    pub async fn apply_acl(&mut self) {
+        let Some(picker) = self.acl_picker.take() else {
+            return;
+        };
+        let Some(acl) = picker.state.selected().and_then(|i| CANNED_ACLS.get(i)).copied() else {
+            return;
+        };
+        let gate = self.gate(&[&picker.remote], true);
+        let summary = format!("Set {} on {}", acl, picker.name);
+        self.run_gated(gate, summary, Guarded::Acl(picker, acl)).await;
+    }
+
+    pub(crate) async fn put_acl(&mut self, picker: AclPicker, acl: &str) {
+        let Some(client) = self.clients.get(&picker.remote).cloned() else {
+            self.notify_error("Not connected to remote");
+            return;
+        };
+        match client.put_object_acl(&picker.bucket, &picker.key, acl).await {
+            Ok(()) => self.notify(format!("Set {} on {}", acl, picker.name)),
+            Err(e) => self.notify_error(format!("Failed to set ACL: {}", e)),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use super::cli::CliTool;
+use super::links::KeyForm;
+use super::mode::PopupAction;
+use super::App;
+
+/// Everything the browser can be asked to do. The ui only decides which action a key
+/// means in the current [`Mode`](super::mode::Mode); what the action does lives here,
+/// away from the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Search,
+    SearchNext,
+    SearchPrev,
+    DownloadMode,
+    MoveUp,
+    MoveDown,
+    JumpTop,
+    JumpBottom,
+    Select,
+    Back,
+    GoBucketRoot,
+    GoBucketList,
+    Delete,
+    Refresh,
+    ReloadConfig,
+    BucketInput,
+    SwitchPane,
+    Preview,
+    Peek,
+    ToggleNaturalSort,
+    ToggleDirsFirst,
+    CycleSort,
+    ReverseSort,
+    Filter,
+    ToggleZoom,
+    ToggleHistory,
+    RepeatTransfer,
+    ToggleFavorite,
+    ToggleFavorites,
+    Slideshow,
+    Palette,
+    ToggleMark,
+    ExportMarks,
+    ImportMarks,
+    Inventory,
+    QuickViews,
+    ToggleTree,
+    CleanMarkers,
+    ToggleBucketLock,
+    Identity,
+    CycleProfile,
+    Jumps,
+    EntryMenu,
+    /// Show the selected object's metadata panel.
+    Properties,
+    SetAcl,
+    NewObject,
+    Duplicate,
+    LinkBundle,
+    Copy,
+    Move,
+    YankKey(KeyForm),
+    YankCommands(CliTool),
+    ToggleMetadata,
+    ToggleIndexPause,
+    StopIndexing,
+    /// Esc: dismiss toasts and clear whatever is shown for the selection.
+    Dismiss,
+    /// Help on the current mode's bindings.
+    Help,
+    /// The first key of a `g` / `y` sequence, or `None` to drop one.
+    Pending(Option<char>),
+    /// A user-defined action from the config, by index.
+    Custom(usize),
+    /// A shell in the local directory. It takes over the terminal, so the ui runs it.
+    Shell,
+    /// A key for whichever popup or sub-mode is open.
+    Popup(PopupAction),
+}
+
+impl App {
+    /// Apply one action to the browser state.
+    pub async fn dispatch(&mut self, action: Action) {
+        // Any key ends a half-typed sequence; `Pending` starts the next one
+        self.pending_key = None;
+        match action {
+            Action::Quit => self.request_quit(),
+            Action::Search => self.start_search(),
+            Action::SearchNext => self.search_next(true),
+            Action::SearchPrev => self.search_next(false),
+            Action::DownloadMode => self.start_download_mode(),
+            Action::MoveUp => self.move_up(),
+            Action::MoveDown => self.move_down(),
+            Action::JumpTop => self.jump_to_edge(false),
+            Action::JumpBottom => self.jump_to_edge(true),
+            Action::Select => {
+                if !self.tree_toggle().await {
+                    self.select().await;
+                }
+            }
+            Action::Back => {
+                if !self.tree_back() {
+                    self.go_back().await;
+                }
+            }
+            Action::GoBucketRoot => self.go_bucket_root().await,
+            Action::GoBucketList => self.go_bucket_list().await,
+            Action::Delete => self.request_delete().await,
+            Action::Refresh => self.refresh().await,
+            Action::ReloadConfig => self.reload_config(),
+            Action::BucketInput => self.start_bucket_input(),
+            Action::SwitchPane => self.switch_pane(),
+            Action::Preview => self.request_preview(),
+            Action::Peek => self.peek().await,
+            Action::ToggleNaturalSort => self.toggle_natural_sort(),
+            Action::ToggleDirsFirst => self.toggle_dirs_first(),
+            Action::CycleSort => self.cycle_sort(),
+            Action::ReverseSort => self.reverse_sort(),
+            Action::Filter => self.start_filter_input(),
+            Action::ToggleZoom => self.toggle_zoom(),
+            Action::ToggleHistory => self.toggle_history(),
+            Action::RepeatTransfer => self.repeat_last_transfer().await,
+            Action::ToggleFavorite => self.toggle_favorite(),
+            Action::ToggleFavorites => self.toggle_favorites(),
+            Action::Slideshow => self.start_slideshow(),
+            Action::Palette => self.open_palette(),
+            Action::ToggleMark => self.toggle_mark(),
+            Action::ExportMarks => self.export_marks(),
+            Action::ImportMarks => self.start_marks_input(),
+            Action::Inventory => self.start_inventory(),
+            Action::QuickViews => self.open_quick_views(),
+            Action::ToggleTree => self.toggle_tree(),
+            Action::CleanMarkers => self.request_marker_cleanup(),
+            Action::SetAcl => self.open_acl_picker(),
+            Action::NewObject => self.start_new_object(),
+            Action::Duplicate => self.start_duplicate(),
+            Action::ToggleBucketLock => self.toggle_bucket_lock(),
+            Action::Identity => self.toggle_identity(),
+            Action::CycleProfile => self.cycle_profile().await,
+            Action::Jumps => self.open_jumps(),
+            Action::EntryMenu => self.open_entry_menu(),
+            Action::Properties => {
+                self.show_metadata = true;
+                self.select().await;
+            }
+            Action::LinkBundle => self.start_link_bundle(),
+            Action::Copy => self.start_copy(false).await,
+            Action::Move => self.start_copy(true).await,
+            Action::YankKey(form) => self.yank_key(form),
+            Action::YankCommands(tool) => self.yank_commands(tool),
+            Action::ToggleMetadata => self.show_metadata = !self.show_metadata,
+            Action::ToggleIndexPause => self.toggle_index_pause(),
+            Action::StopIndexing => self.stop_indexing(),
+            Action::Dismiss => {
+                self.toasts.dismiss_all();
+                self.metadata = None;
+                self.download_progress = None;
+                self.preview.clear();
+                self.marked.clear();
+            }
+            Action::Help => self.open_help(),
+            Action::Pending(prefix) => self.pending_key = prefix,
+            Action::Custom(index) => self.run_action(index),
+            Action::Shell => {}
+            Action::Popup(action) => self.dispatch_popup(action).await,
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use super::tasks::TaskKind;
+use super::{App, Entry, Location};
+
+/// How long an action may run before it is killed.
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Finished action output, shown in a scrollable popup.
+pub struct ActionOutput {
+    pub title: String,
+    pub lines: Vec<String>,
+    pub scroll: usize,
+}
+
+/// Quote for a POSIX shell: wrap in single quotes, escaping embedded ones.
+pub(crate) fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Replace each `{name}` placeholder with its shell-quoted value in one pass, so
+/// values that contain braces are never expanded again. Unknown ones are left as is.
+fn substitute(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let value = tail.find('}').and_then(|end| {
+            let name = &tail[1..end];
+            vars.iter().find(|(n, _)| *n == name).map(|(_, v)| (end, *v))
+        });
+        match value {
+            Some((end, v)) => {
+                out.push_str(&shell_quote(v));
+                rest = &tail[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &tail[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+impl App {
+    /// The configured action bound to `key`, if any.
+    pub fn action_for_key(&self, key: char) -> Option<usize> {
+        self.settings.actions.iter().position(|a| a.key == key)
+    }
+
+    /// Run action `index` on the selected object (or the current prefix) in the background.
+    pub fn run_action(&mut self, index: usize) {
+        let Some(action) = self.settings.actions.get(index).cloned() else {
+            return;
+        };
+        if self.action_rx.is_some() {
+            self.notify_warn("An action is already running");
+            return;
+        }
+        let Location::ObjectList {
+            remote,
+            bucket,
+            prefix,
+        } = &self.location
+        else {
+            self.notify_warn("Navigate into a bucket first");
+            return;
+        };
+        let (key, name) = match self.browser_state.selected().and_then(|i| self.entries.get(i)) {
+            Some(Entry::Object(obj)) => (obj.key.clone(), obj.display_name.clone()),
+            _ => (prefix.clone(), String::new()),
+        };
+        let uri = format!("s3://{}/{}", bucket, key);
+        let local = self.local_path.display().to_string();
+        let command = substitute(
+            &action.cmd,
+            &[
+                ("remote", remote),
+                ("bucket", bucket),
+                ("key", &key),
+                ("name", &name),
+                ("prefix", prefix),
+                ("uri", &uri),
+                ("local", &local),
+            ],
+        );
+
+        let title = action.title().to_string();
+        let (tx, rx) = mpsc::channel(1);
+        self.action_rx = Some(rx.into());
+        self.notify(format!("Running {}...", title));
+        self.tasks.spawn(TaskKind::Action, title.clone(), async move {
+            let output = tokio::process::Command::new("sh")
+                .arg("-c")
+                .arg(&command)
+                .kill_on_drop(true)
+                .output();
+            let lines = match tokio::time::timeout(TIMEOUT, output).await {
+                Ok(Ok(out)) => {
+                    let mut lines: Vec<String> = String::from_utf8_lossy(&out.stdout)
+                        .lines()
+                        .chain(String::from_utf8_lossy(&out.stderr).lines())
+                        .map(str::to_string)
+                        .collect();
+                    if !out.status.success() {
+                        lines.push(format!("[{}]", out.status));
+                    }
+                    lines
+                }
+                Ok(Err(e)) => vec![format!("Failed to start: {}", e)],
+                Err(_) => vec![format!("Timed out after {}s", TIMEOUT.as_secs())],
+            };
+            let _ = tx
+                .send(ActionOutput {
+                    title,
+                    lines,
+                    scroll: 0,
+                })
+                .await;
+        });
+    }
+
+    /// Show a finished action's output. Call every tick.
+    pub fn drain_action(&mut self) {
+        let Some(rx) = &mut self.action_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(mut output) => {
+                if output.lines.is_empty() {
+                    output.lines.push("(no output)".to_string());
+                }
+                self.action_output = Some(output);
+            }
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => {}
+        }
+        self.action_rx = None;
+    }
+
+    pub fn scroll_action_output(&mut self, down: bool, lines: usize) {
+        if let Some(output) = &mut self.action_output {
+            output.scroll = if down {
+                (output.scroll + lines).min(output.lines.len().saturating_sub(1))
+            } else {
+                output.scroll.saturating_sub(lines)
+            };
+        }
+    }
+
+    /// Copy the whole output to the clipboard.
+    pub fn copy_action_output(&mut self) {
+        let Some(output) = &self.action_output else {
+            return;
+        };
+        match crate::clipboard::copy(&output.lines.join("\n")) {
+            Ok(()) => self.notify("Copied output"),
+            Err(e) => self.notify_error(format!("Clipboard failed: {}", e)),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+
+use crate::object_store::ObjectEntry;
+
+use super::indexing::IndexState;
+use super::listing::Freshness;
+use super::{App, Entry, Location};
+
+/// A change this app made to a bucket. `apply_change` brings every view of the
+/// bucket in line with it, so none keeps showing a deleted object or misses a new one.
+pub(crate) enum Change {
+    /// `key` is gone: one object, or everything under it when it ends in `/`.
+    /// `kept` are keys under it the endpoint refused to delete.
+    Deleted { key: String, kept: HashSet<String> },
+    /// Objects uploaded or copied in, with their sizes.
+    Written(Vec<(String, u64)>),
+    /// Directory markers deleted; their directories stay, held by other objects.
+    Markers(Vec<String>),
+}
+
+impl App {
+    /// Apply a change to the listing, the search results and index, marks,
+    /// metadata, the context and quick views and the HEAD cache.
+    pub(crate) fn apply_change(&mut self, remote: &str, bucket: &str, change: Change) {
+        let shows = |location: &Location| {
+            matches!(location, Location::ObjectList { remote: r, bucket: b, .. } if r == remote && b == bucket)
+        };
+        let here = shows(&self.location);
+        self.forget_prefetched(remote, bucket);
+        let indexed = self
+            .index_key
+            .as_ref()
+            .is_some_and(|(r, b)| r == remote && b == bucket);
+
+        match change {
+            Change::Deleted { key, kept } => {
+                self.head_cache.invalidate(remote, bucket, &key);
+                let gone = |k: &str| (k == key || (key.ends_with('/') && k.starts_with(&key))) && !kept.contains(k);
+                // A prefix that still holds refused keys stays
+                let removed = |k: &str| gone(k) && !kept.iter().any(|c| c.starts_with(k));
+
+                if indexed {
+                    self.search_pool.retain(|o| !gone(&o.key));
+                }
+                if here {
+                    self.entries.retain(|e| !removed(e.key()));
+                    self.marked.retain(|k| !removed(k));
+                    if self.metadata.as_ref().is_some_and(|m| removed(&m.key)) {
+                        self.metadata = None;
+                    }
+                    self.fix_selection();
+                }
+                if here && let Some(tree) = &mut self.tree {
+                    tree.forget(removed);
+                }
+                if self.saved_location.as_ref().is_some_and(shows) {
+                    self.saved_entries.retain(|e| !removed(e.key()));
+                }
+                if let Some(view) = &mut self.context_view
+                    && view.remote == remote
+                    && view.bucket == bucket
+                {
+                    view.entries.retain(|o| !removed(&o.key));
+                }
+                if let Some(view) = &mut self.quick_view
+                    && view.remote == remote
+                    && view.bucket == bucket
+                {
+                    view.entries.retain(|o| !gone(&o.key));
+                }
+            }
+            Change::Written(objects) => {
+                for (key, _) in &objects {
+                    self.head_cache.invalidate(remote, bucket, key);
+                }
+                if indexed {
+                    let written: HashSet<&str> = objects.iter().map(|(k, _)| k.as_str()).collect();
+                    self.search_pool.retain(|o| !written.contains(o.key.as_str()));
+                    let now = chrono::Utc::now();
+                    self.search_pool.extend(objects.iter().map(|(key, size)| ObjectEntry {
+                        key: key.clone(),
+                        display_name: key.clone(),
+                        size: *size as i64,
+                        last_modified: Some(now),
+                        etag: None,
+                        is_dir: false,
+                        storage_class: None,
+                    }));
+                }
+                // Listed again on the next tick, in case a new object or sub-prefix shows here
+                if let Location::ObjectList { prefix, .. } = &self.location
+                    && here
+                    && objects.iter().any(|(k, _)| k.starts_with(prefix.as_str()))
+                {
+                    self.listing_stale = true;
+                    // A single new object is what the user will want to look at next
+                    if let [(key, _)] = objects.as_slice() {
+                        self.focus_key = Some(key.clone());
+                    }
+                }
+            }
+            Change::Markers(keys) => {
+                for key in &keys {
+                    self.head_cache.invalidate(remote, bucket, key);
+                }
+                if indexed {
+                    let keys: HashSet<&str> = keys.iter().map(String::as_str).collect();
+                    self.search_pool.retain(|o| !keys.contains(o.key.as_str()));
+                }
+            }
+        }
+
+        if indexed && self.index_state == IndexState::Complete {
+            self.index_totals
+                .insert((remote.to_string(), bucket.to_string()), self.search_pool.len());
+        }
+    }
+
+    /// List the current prefix again after a change, keeping the cursor on the same
+    /// key. Waits while a search is open, since the listing is then put aside.
+    pub async fn reload_if_stale(&mut self) {
+        if !self.listing_stale || self.search_active {
+            return;
+        }
+        self.listing_stale = false;
+        let Location::ObjectList { remote, bucket, prefix } = self.location.clone() else {
+            return;
+        };
+        let Some(client) = self.clients.get(&remote).cloned() else {
+            return;
+        };
+        match client.list_objects_page(&bucket, &prefix).await {
+            Ok((objects, truncated)) => {
+                self.listing_freshness = if truncated { Freshness::Partial } else { Freshness::Live };
+                let selected = self.selected_key();
+                self.relist_tree().await;
+                let objects = self.arrange_listing(objects);
+                self.entries = self.tree_rows(objects);
+                let listed: HashSet<&str> = self.entries.iter().map(Entry::key).collect();
+                self.marked.retain(|k| listed.contains(k.as_str()));
+                let focus = self.focus_key.take();
+                if !focus.is_some_and(|key| self.focus_on(&key))
+                    && let Some(key) = selected
+                {
+                    self.focus_on(&key);
+                }
+                self.fix_selection();
+            }
+            Err(e) => self.notify_error(format!("Failed to list objects: {}", e)),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use crate::provider::Provider;
+
+use super::actions::shell_quote;
+use super::{App, Entry, Location};
+
+/// Command-line client to write the selection's commands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CliTool {
+    /// `mc cp alias/bucket/key`, using the same alias names as the mc config.
+    Mc,
+    /// `aws s3 cp s3://bucket/key`, with `--endpoint-url` for non-AWS remotes.
+    Aws,
+}
+
+/// Quote `s` for a shell only when it needs it, so commands stay readable.
+pub(crate) fn shell_arg(s: &str) -> String {
+    let plain = !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:@%+=,".contains(c));
+    if plain { s.to_string() } else { shell_quote(s) }
+}
+
+impl App {
+    /// Copy `mc cp` or `aws s3 cp` commands that download the marked objects (or
+    /// the selected object or directory) into the current directory.
+    pub fn yank_commands(&mut self, tool: CliTool) {
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            self.notify_warn("Navigate into a bucket first");
+            return;
+        };
+        let Some(config) = self.config.aliases.get(remote) else {
+            return;
+        };
+        if config.backend.as_deref().is_some_and(|b| b != "s3") {
+            self.notify_warn(format!("'{}' is not an S3 remote", remote));
+            return;
+        }
+        let selected = self.browser_state.selected().and_then(|i| self.entries.get(i));
+        let objects: Vec<(&str, &str, bool)> = self
+            .entries
+            .iter()
+            .filter_map(|e| match e {
+                Entry::Object(obj)
+                    if self.marked.contains(&obj.key)
+                        || self.marked.is_empty() && selected.is_some_and(|s| s.key() == obj.key) =>
+                {
+                    Some((obj.key.as_str(), obj.display_name.as_str(), obj.is_dir))
+                }
+                _ => None,
+            })
+            .collect();
+        if objects.is_empty() {
+            return;
+        }
+
+        let endpoint = match tool {
+            CliTool::Aws if Provider::resolve(config.provider.as_deref(), &config.url) != Provider::Aws => {
+                format!(" --endpoint-url {}", shell_arg(config.url.trim_end_matches('/')))
+            }
+            _ => String::new(),
+        };
+        let lines: Vec<String> = objects
+            .iter()
+            .map(|(key, name, is_dir)| {
+                let name = name.trim_end_matches('/');
+                match (tool, is_dir) {
+                    (CliTool::Mc, false) => format!("mc cp {} ./", shell_arg(&format!("{}/{}/{}", remote, bucket, key))),
+                    (CliTool::Mc, true) => format!(
+                        "mc cp --recursive {} {}",
+                        shell_arg(&format!("{}/{}/{}", remote, bucket, key)),
+                        shell_arg(&format!("./{}/", name))
+                    ),
+                    (CliTool::Aws, false) => {
+                        format!("aws s3 cp {} ./{}", shell_arg(&format!("s3://{}/{}", bucket, key)), endpoint)
+                    }
+                    (CliTool::Aws, true) => format!(
+                        "aws s3 cp --recursive {} {}{}",
+                        shell_arg(&format!("s3://{}/{}", bucket, key)),
+                        shell_arg(&format!("./{}/", name)),
+                        endpoint
+                    ),
+                }
+            })
+            .collect();
+
+        let count = lines.len();
+        match crate::clipboard::copy(&lines.join("\n")) {
+            Ok(()) => self.notify(format!(
+                "Copied {} {} command{}",
+                count,
+                if tool == CliTool::Mc { "mc" } else { "aws" },
+                if count == 1 { "" } else { "s" }
+            )),
+            Err(e) => self.notify_error(format!("Clipboard failed: {}", e)),
+        }
+    }
+}
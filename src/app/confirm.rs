@@ -0,0 +1,127 @@
+use crate::credentials::Protection;
+
+use super::acl::AclPicker;
+use super::history::CopySpec;
+use super::sync::SyncRun;
+use super::App;
+
+/// An operation that may ask before it runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Confirm {
+    /// Deleting one object of this many bytes.
+    DeleteFile(i64),
+    /// Deleting everything under a prefix; asking means reviewing the plan.
+    DeletePrefix,
+    /// Downloading a prefix; asking means reviewing the plan.
+    Download,
+    /// Quitting while transfers run.
+    Quit,
+}
+
+/// Whether a write may go ahead, from the protection of the remotes it touches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Gate {
+    /// A remote is read-only; the user has been told.
+    Refused,
+    /// Only once this protected remote's name is typed.
+    Typed(String),
+    Open,
+}
+
+/// A destructive operation held until the protected remote's name is typed.
+pub(crate) enum Guarded {
+    Copy(CopySpec),
+    Sync(SyncRun),
+    Acl(AclPicker, &'static str),
+}
+
+/// Popup asking for a protected remote's name before a copy, move, overwriting
+/// sync or ACL change runs on it.
+pub struct ProtectedConfirm {
+    pub remote: String,
+    /// What will run, e.g. `Move a.txt -> prod/bkt/a.txt`.
+    pub summary: String,
+    pub typed: String,
+    op: Guarded,
+}
+
+impl ProtectedConfirm {
+    pub fn typed_ok(&self) -> bool {
+        self.typed == self.remote
+    }
+}
+
+impl App {
+    /// The protection check every write goes through. Read-only remotes refuse all
+    /// writes; `confirm` remotes want their name typed before a `destructive` one,
+    /// i.e. anything that deletes, overwrites, moves away or changes permissions.
+    pub(crate) fn gate(&mut self, remotes: &[&str], destructive: bool) -> Gate {
+        let mut gate = Gate::Open;
+        for remote in remotes {
+            match self.remote_protection(remote) {
+                Protection::ReadOnly => {
+                    self.notify_error(format!("Remote '{}' is read-only", remote));
+                    return Gate::Refused;
+                }
+                Protection::Confirm if destructive && gate == Gate::Open => {
+                    gate = Gate::Typed(remote.to_string());
+                }
+                _ => {}
+            }
+        }
+        gate
+    }
+
+    /// Run `op` once `gate` allows it, asking for the remote's name first when it
+    /// is protected.
+    pub(crate) async fn run_gated(&mut self, gate: Gate, summary: String, op: Guarded) {
+        match gate {
+            Gate::Refused => {}
+            Gate::Typed(remote) => {
+                self.protected_confirm = Some(ProtectedConfirm {
+                    remote,
+                    summary,
+                    typed: String::new(),
+                    op,
+                });
+            }
+            Gate::Open => self.run_guarded(op).await,
+        }
+    }
+
+    /// Run the held operation if the remote's name was typed correctly.
+    pub async fn confirm_protected(&mut self) {
+        if !self.protected_confirm.as_ref().is_some_and(|c| c.typed_ok()) {
+            return;
+        }
+        if let Some(confirm) = self.protected_confirm.take() {
+            self.run_guarded(confirm.op).await;
+        }
+    }
+
+    async fn run_guarded(&mut self, op: Guarded) {
+        match op {
+            Guarded::Copy(spec) => self.run_copy(spec),
+            Guarded::Sync(run) => self.start_sync(run),
+            Guarded::Acl(picker, acl) => self.put_acl(picker, acl).await,
+        }
+    }
+
+    /// Whether `op` on `remote` should ask first. Every confirmation is decided
+    /// here: by the `confirm` settings, except that protected remotes always ask
+    /// before a delete.
+    pub(crate) fn should_confirm(&self, op: Confirm, remote: &str) -> bool {
+        let config = &self.settings.confirm;
+        match op {
+            Confirm::DeleteFile(_) | Confirm::DeletePrefix
+                if self.remote_protection(remote) == Protection::Confirm =>
+            {
+                true
+            }
+            Confirm::DeleteFile(size) => config.delete_file && size.max(0) as u64 >= config.delete_file_below,
+            Confirm::DeletePrefix => config.delete_prefix,
+            Confirm::Download => config.download,
+            Confirm::Quit => config.quit,
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use ratatui::widgets::ListState;
+
+use crate::object_store::ObjectEntry;
+
+use super::{parent_prefix, App, Entry};
+
+/// The parent prefix of a search result, opened over the results without leaving search.
+pub struct ContextView {
+    pub remote: String,
+    pub bucket: String,
+    pub prefix: String,
+    pub entries: Vec<ObjectEntry>,
+    pub state: ListState,
+}
+
+impl App {
+    /// Open the selected result's parent prefix with the cursor on the result.
+    pub async fn open_result_context(&mut self) {
+        let Some((remote, bucket)) = self.search_context.clone() else {
+            return;
+        };
+        if bucket.is_empty() {
+            return;
+        }
+        let Some(Entry::Object(obj)) = self.browser_state.selected().and_then(|i| self.entries.get(i))
+        else {
+            return;
+        };
+        let key = obj.key.clone();
+        self.load_context(remote, bucket, parent_prefix(&key), Some(&key)).await;
+    }
+
+    async fn load_context(&mut self, remote: String, bucket: String, prefix: String, select: Option<&str>) {
+        let Some(client) = self.clients.get(&remote) else {
+            return;
+        };
+        match client.list_objects(&bucket, &prefix).await {
+            Ok(entries) => {
+                let pos = select
+                    .and_then(|k| entries.iter().position(|e| e.key == k))
+                    .or((!entries.is_empty()).then_some(0));
+                let mut state = ListState::default();
+                state.select(pos);
+                self.context_view = Some(ContextView {
+                    remote,
+                    bucket,
+                    prefix,
+                    entries,
+                    state,
+                });
+            }
+            Err(e) => self.notify_error(format!("Failed to list objects: {}", e)),
+        }
+    }
+
+    pub fn close_context(&mut self) {
+        self.context_view = None;
+    }
+
+    pub fn context_move_up(&mut self) {
+        if let Some(view) = &mut self.context_view {
+            let i = view.state.selected().unwrap_or(0);
+            view.state.select(Some(i.saturating_sub(1)));
+        }
+    }
+
+    pub fn context_move_down(&mut self) {
+        if let Some(view) = &mut self.context_view {
+            let i = view.state.selected().unwrap_or(0);
+            if i + 1 < view.entries.len() {
+                view.state.select(Some(i + 1));
+            }
+        }
+    }
+
+    /// Descend into the highlighted directory.
+    pub async fn context_enter(&mut self) {
+        let Some(view) = &self.context_view else {
+            return;
+        };
+        let Some(dir) = view
+            .state
+            .selected()
+            .and_then(|i| view.entries.get(i))
+            .filter(|e| e.is_dir)
+        else {
+            return;
+        };
+        let (remote, bucket, prefix) = (view.remote.clone(), view.bucket.clone(), dir.key.clone());
+        self.load_context(remote, bucket, prefix, None).await;
+    }
+
+    /// Go up one level, keeping the cursor on the directory we came from.
+    pub async fn context_back(&mut self) {
+        let Some(view) = &self.context_view else {
+            return;
+        };
+        if view.prefix.is_empty() {
+            return;
+        }
+        let (remote, bucket, from) = (view.remote.clone(), view.bucket.clone(), view.prefix.clone());
+        self.load_context(remote, bucket, parent_prefix(&from), Some(&from)).await;
+    }
+}
@@ -0,0 +1,551 @@
+use std::collections::HashSet;
+use std::time::Instant;
+
+use ratatui::widgets::ListState;
+use tokio::sync::mpsc;
+
+use crate::hooks::Op;
+use crate::object_store::CopyOptions;
+
+use super::changes::Change;
+use super::confirm::{Gate, Guarded};
+use super::history::{unix_now, CopySpec, TransferRecord, TransferSpec};
+use super::preview_cache::preview_dir;
+use super::tasks::TaskKind;
+use super::{parent_prefix, App, Entry, Location};
+
+/// The object (or prefix) being copied or moved.
+#[derive(Clone)]
+pub struct CopySource {
+    pub remote: String,
+    pub bucket: String,
+    pub key: String,
+    pub name: String,
+    pub is_dir: bool,
+    /// Object size from the listing; unused for prefixes.
+    pub size: u64,
+}
+
+/// One line of the destination picker: a remote, bucket, prefix or object.
+pub struct PickerRow {
+    pub name: String,
+    /// Alias, bucket name or object key, depending on the level.
+    pub key: String,
+    pub is_dir: bool,
+}
+
+/// Mini-browser for choosing where to copy/move to, on any configured remote.
+pub struct CopyPicker {
+    pub moving: bool,
+    pub source: CopySource,
+    pub location: Location,
+    pub rows: Vec<PickerRow>,
+    pub state: ListState,
+    /// Storage class, encryption and metadata for the copies (`o`).
+    pub options: CopyOptions,
+    pub options_open: bool,
+    /// `key=value, ...` being typed for the metadata option.
+    pub metadata_input: Option<String>,
+}
+
+/// Storage classes `s` cycles through in the copy options; `None` is the bucket default.
+const STORAGE_CLASSES: &[Option<&str>] = &[
+    None,
+    Some("STANDARD"),
+    Some("STANDARD_IA"),
+    Some("ONEZONE_IA"),
+    Some("INTELLIGENT_TIERING"),
+    Some("GLACIER_IR"),
+    Some("GLACIER"),
+    Some("DEEP_ARCHIVE"),
+];
+
+const SSE: &[Option<&str>] = &[None, Some("AES256"), Some("aws:kms")];
+
+/// The value after `current` in `values`, wrapping around.
+fn cycle(values: &[Option<&str>], current: &Option<String>) -> Option<String> {
+    let i = values.iter().position(|v| *v == current.as_deref()).unwrap_or(0);
+    values[(i + 1) % values.len()].map(str::to_string)
+}
+
+/// `key=value` pairs separated by commas, as typed for the metadata option.
+fn parse_metadata(input: &str) -> Result<Vec<(String, String)>, String> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((k, v)) if !k.trim().is_empty() => Ok((k.trim().to_lowercase(), v.trim().to_string())),
+            _ => Err(format!("Expected key=value, got '{}'", pair)),
+        })
+        .collect()
+}
+
+/// Keys a finished copy wrote at the destination, with their sizes.
+pub type Written = Vec<(String, u64)>;
+
+impl CopyPicker {
+    /// Current destination as "remote/bucket/prefix", or `None` above bucket level.
+    pub fn target_display(&self) -> Option<String> {
+        match &self.location {
+            Location::ObjectList {
+                remote,
+                bucket,
+                prefix,
+            } => Some(format!("{}/{}/{}", remote, bucket, prefix)),
+            _ => None,
+        }
+    }
+}
+
+impl App {
+    /// Open the destination picker for the selected object, starting at the current location.
+    pub async fn start_copy(&mut self, moving: bool) {
+        if self.copy_rx.is_some() {
+            self.notify_warn("A copy is already running");
+            return;
+        }
+        // A protected remote asks for its name once the destination is picked
+        if moving
+            && let Location::ObjectList { remote, bucket, .. } = self.location.clone()
+            && (self.refuse_locked(&remote, &bucket) || self.gate(&[&remote], false) == Gate::Refused)
+        {
+            return;
+        }
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            self.notify_warn("Navigate into a bucket first");
+            return;
+        };
+        let Some(Entry::Object(obj)) = self.browser_state.selected().and_then(|i| self.entries.get(i))
+        else {
+            return;
+        };
+
+        let source = CopySource {
+            remote: remote.clone(),
+            bucket: bucket.clone(),
+            key: obj.key.clone(),
+            name: obj.display_name.clone(),
+            is_dir: obj.is_dir,
+            size: obj.size.max(0) as u64,
+        };
+        let location = self.location.clone();
+        self.copy_picker = Some(CopyPicker {
+            moving,
+            source,
+            location: location.clone(),
+            rows: Vec::new(),
+            state: ListState::default(),
+            options: CopyOptions::default(),
+            options_open: false,
+            metadata_input: None,
+        });
+        self.picker_list(location).await;
+    }
+
+    pub fn cancel_copy(&mut self) {
+        self.copy_picker = None;
+    }
+
+    pub fn toggle_copy_options(&mut self) {
+        if let Some(picker) = &mut self.copy_picker {
+            picker.options_open = !picker.options_open;
+        }
+    }
+
+    pub fn cycle_copy_storage_class(&mut self) {
+        if let Some(picker) = &mut self.copy_picker {
+            picker.options.storage_class = cycle(STORAGE_CLASSES, &picker.options.storage_class);
+        }
+    }
+
+    pub fn cycle_copy_sse(&mut self) {
+        if let Some(picker) = &mut self.copy_picker {
+            picker.options.sse = cycle(SSE, &picker.options.sse);
+        }
+    }
+
+    /// Start typing the metadata option, from what it is now.
+    pub fn start_copy_metadata_input(&mut self) {
+        if let Some(picker) = &mut self.copy_picker {
+            let current = picker.options.metadata.as_ref().map(|pairs| {
+                pairs.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join(", ")
+            });
+            picker.metadata_input = Some(current.unwrap_or_default());
+        }
+    }
+
+    /// Set the typed metadata; left empty, the copies keep the source's.
+    pub fn finish_copy_metadata_input(&mut self) {
+        let Some(picker) = &mut self.copy_picker else {
+            return;
+        };
+        let Some(input) = picker.metadata_input.take() else {
+            return;
+        };
+        if input.trim().is_empty() {
+            picker.options.metadata = None;
+            return;
+        }
+        match parse_metadata(&input) {
+            Ok(pairs) => picker.options.metadata = Some(pairs),
+            Err(e) => {
+                picker.metadata_input = Some(input);
+                self.notify_error(e);
+            }
+        }
+    }
+
+    /// List `location` into the picker, leaving the picker where it was on failure.
+    async fn picker_list(&mut self, location: Location) {
+        let rows: Vec<PickerRow> = match &location {
+            Location::RemoteList => self
+                .remotes
+                .iter()
+                .map(|r| PickerRow {
+                    name: r.clone(),
+                    key: r.clone(),
+                    is_dir: true,
+                })
+                .collect(),
+            Location::BucketList { remote } => {
+                let Some(buckets) = self.remote_buckets(remote).await else {
+                    return;
+                };
+                buckets
+                    .into_iter()
+                    .map(|b| PickerRow {
+                        key: b.name.clone(),
+                        name: b.name,
+                        is_dir: true,
+                    })
+                    .collect()
+            }
+            Location::ObjectList {
+                remote,
+                bucket,
+                prefix,
+            } => {
+                if let Err(e) = self.ensure_client(remote) {
+                    self.notify_error(format!("Connection failed: {}", e));
+                    return;
+                }
+                match self.clients[remote].list_objects(bucket, prefix).await {
+                    Ok(objects) => objects
+                        .into_iter()
+                        .map(|o| PickerRow {
+                            name: o.display_name,
+                            key: o.key,
+                            is_dir: o.is_dir,
+                        })
+                        .collect(),
+                    Err(e) => {
+                        self.notify_error(format!("Failed to list objects: {}", e));
+                        return;
+                    }
+                }
+            }
+        };
+
+        if let Some(picker) = &mut self.copy_picker {
+            picker.state.select((!rows.is_empty()).then_some(0));
+            picker.rows = rows;
+            picker.location = location;
+        }
+    }
+
+    pub fn picker_move_up(&mut self) {
+        if let Some(picker) = &mut self.copy_picker {
+            let i = picker.state.selected().unwrap_or(0);
+            picker.state.select(Some(i.saturating_sub(1)));
+        }
+    }
+
+    pub fn picker_move_down(&mut self) {
+        if let Some(picker) = &mut self.copy_picker {
+            let i = picker.state.selected().unwrap_or(0);
+            if i + 1 < picker.rows.len() {
+                picker.state.select(Some(i + 1));
+            }
+        }
+    }
+
+    /// Descend into the highlighted remote, bucket or prefix.
+    pub async fn picker_enter(&mut self) {
+        let Some(picker) = &self.copy_picker else {
+            return;
+        };
+        let Some(row) = picker.state.selected().and_then(|i| picker.rows.get(i)) else {
+            return;
+        };
+        if !row.is_dir {
+            return;
+        }
+        let next = match &picker.location {
+            Location::RemoteList => Location::BucketList {
+                remote: row.key.clone(),
+            },
+            Location::BucketList { remote } => Location::ObjectList {
+                remote: remote.clone(),
+                bucket: row.key.clone(),
+                prefix: String::new(),
+            },
+            Location::ObjectList { remote, bucket, .. } => Location::ObjectList {
+                remote: remote.clone(),
+                bucket: bucket.clone(),
+                prefix: row.key.clone(),
+            },
+        };
+        self.picker_list(next).await;
+    }
+
+    pub async fn picker_back(&mut self) {
+        let Some(picker) = &self.copy_picker else {
+            return;
+        };
+        let prev = match &picker.location {
+            Location::RemoteList => return,
+            Location::BucketList { .. } => Location::RemoteList,
+            Location::ObjectList {
+                remote,
+                bucket: _,
+                prefix,
+            } if prefix.is_empty() => Location::BucketList {
+                remote: remote.clone(),
+            },
+            Location::ObjectList {
+                remote,
+                bucket,
+                prefix,
+            } => Location::ObjectList {
+                remote: remote.clone(),
+                bucket: bucket.clone(),
+                prefix: parent_prefix(prefix),
+            },
+        };
+        self.picker_list(prev).await;
+    }
+
+    /// Copy (or move) the source into the picker's current prefix in the background.
+    pub async fn confirm_copy(&mut self) {
+        let Some(picker) = &self.copy_picker else {
+            return;
+        };
+        let Location::ObjectList {
+            remote: dst_remote,
+            bucket: dst_bucket,
+            prefix: dst_prefix,
+        } = picker.location.clone()
+        else {
+            self.notify_error("Pick a bucket or prefix to copy into");
+            return;
+        };
+        let source = picker.source.clone();
+        let moving = picker.moving;
+        let options = picker.options.clone();
+        if self.refuse_locked(&dst_remote, &dst_bucket) {
+            return;
+        }
+        let dst_key = if source.is_dir {
+            format!("{}{}/", dst_prefix, source.name)
+        } else {
+            format!("{}{}", dst_prefix, source.name)
+        };
+        // Copying onto itself only makes sense to change its options
+        let in_place = dst_remote == source.remote && dst_bucket == source.bucket && dst_key == source.key;
+        if in_place && (moving || options.is_empty()) {
+            self.notify_error("Source and destination are the same");
+            return;
+        }
+        if dst_remote != source.remote && !options.is_empty() {
+            self.notify_error("Copy options need the destination on the same remote");
+            return;
+        }
+        if source.is_dir
+            && !in_place
+            && dst_remote == source.remote
+            && dst_bucket == source.bucket
+            && dst_key.starts_with(&source.key)
+        {
+            self.notify_error("Cannot copy a directory into itself");
+            return;
+        }
+        let spec = CopySpec {
+            name: source.name,
+            moving,
+            remote: source.remote,
+            bucket: source.bucket,
+            key: source.key,
+            is_dir: source.is_dir,
+            size: source.size,
+            dst_remote,
+            dst_bucket,
+            dst_key,
+            options,
+        };
+        self.copy_picker = None;
+        self.run_gated_copy(spec).await;
+    }
+
+    /// Copy (or move) a recorded copy's source to the same destination again.
+    pub(crate) async fn repeat_copy(&mut self, spec: CopySpec) {
+        if self.copy_rx.is_some() {
+            self.notify_warn("A copy is already running");
+            return;
+        }
+        for (remote, bucket, writes) in [
+            (&spec.dst_remote, &spec.dst_bucket, true),
+            (&spec.remote, &spec.bucket, spec.moving),
+        ] {
+            if writes && self.refuse_locked(remote, bucket) {
+                return;
+            }
+        }
+        if let Err(e) = self.ensure_client(&spec.remote) {
+            self.notify_error(format!("Connection failed: {}", e));
+            return;
+        }
+        self.run_gated_copy(spec).await;
+    }
+
+    /// Run a copy past the protection of both ends: it writes the destination, and
+    /// a move deletes its source.
+    async fn run_gated_copy(&mut self, spec: CopySpec) {
+        let remotes: &[&str] = if spec.moving { &[&spec.dst_remote, &spec.remote] } else { &[&spec.dst_remote] };
+        let gate = self.gate(remotes, true);
+        let summary = format!(
+            "{} {} -> {}/{}/{}",
+            if spec.moving { "Move" } else { "Copy" },
+            spec.name,
+            spec.dst_remote,
+            spec.dst_bucket,
+            spec.dst_key
+        );
+        self.run_gated(gate, summary, Guarded::Copy(spec)).await;
+    }
+
+    /// Start the copy (or move) described by `spec` in the background.
+    pub(crate) fn run_copy(&mut self, spec: CopySpec) {
+        if let Err(e) = self.ensure_client(&spec.dst_remote) {
+            self.notify_error(format!("Connection failed: {}", e));
+            return;
+        }
+        let src_client = self.clients[&spec.remote].clone();
+        let dst_client = self.clients[&spec.dst_remote].clone();
+        let same_remote = spec.dst_remote == spec.remote;
+
+        let (tx, rx) = mpsc::channel(1);
+        self.copy_rx = Some(rx.into());
+        self.track_unfinished(&TransferSpec::Copy(spec.clone()));
+        self.active_copy = Some((spec.clone(), Instant::now()));
+        self.notify(format!(
+            "{} {} -> {}/{}/{}",
+            if spec.moving { "Moving" } else { "Copying" },
+            spec.name,
+            spec.dst_remote,
+            spec.dst_bucket,
+            spec.dst_key
+        ));
+
+        let hooks = self.settings.hooks.clone();
+        let hook_tx = self.hook_tx.clone();
+        let label = format!("{} {}", if spec.moving { "Move" } else { "Copy" }, spec.name);
+        self.tasks.spawn(TaskKind::Copy, label, async move {
+            let result = async {
+                let keys: Vec<(String, u64)> = if spec.is_dir {
+                    src_client
+                        .list_all_keys(&spec.bucket, &spec.key)
+                        .await?
+                        .into_iter()
+                        .map(|o| (o.key, o.size.max(0) as u64))
+                        .collect()
+                } else {
+                    vec![(spec.key.clone(), spec.size)]
+                };
+                // A move deletes its sources, so the delete hooks see them; the
+                // pre hook runs first so refusing it cancels the whole move
+                let sources: Vec<String> = keys.iter().map(|(key, _)| key.clone()).collect();
+                if spec.moving {
+                    hooks.run(true, Op::Delete, &spec.remote, &spec.bucket, &sources).await?;
+                }
+                let tmp = preview_dir().join("copy.part");
+                let mut written = Vec::with_capacity(keys.len());
+                for (key, size) in &keys {
+                    let rel = key.strip_prefix(&spec.key).unwrap_or(key);
+                    let to = format!("{}{}", spec.dst_key, rel);
+                    if same_remote {
+                        src_client.copy_object_with(&spec.bucket, key, &spec.dst_bucket, &to, &spec.options).await?;
+                    } else {
+                        src_client
+                            .copy_object_to(&spec.bucket, key, &*dst_client, &spec.dst_bucket, &to, &tmp)
+                            .await?;
+                    }
+                    written.push((to, *size));
+                }
+                // Only remove the source once every copy has succeeded
+                if spec.moving {
+                    for (key, _) in &keys {
+                        src_client.delete_object(&spec.bucket, key).await?;
+                    }
+                    if let Err(e) = hooks.run(false, Op::Delete, &spec.remote, &spec.bucket, &sources).await {
+                        let _ = hook_tx.send(e.to_string()).await;
+                    }
+                }
+                anyhow::Ok(written)
+            }
+            .await;
+            let _ = tx.send(result.map_err(|e| e.to_string())).await;
+        });
+    }
+
+    /// Report a finished copy/move and record it in the history. Call every tick.
+    pub fn drain_copy(&mut self) {
+        let Some(rx) = &mut self.copy_rx else {
+            return;
+        };
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => Err("copy task ended early".to_string()),
+        };
+        self.copy_rx = None;
+        let Some((spec, started)) = self.active_copy.take() else {
+            return;
+        };
+        let (bytes, files) = match &result {
+            Ok(written) => (written.iter().map(|(_, size)| size).sum(), written.len()),
+            Err(_) => (0, 0),
+        };
+        match &result {
+            Ok(written) => {
+                self.notify(format!(
+                    "{} {} {} to {}/{}/{}",
+                    if spec.moving { "Moved" } else { "Copied" },
+                    written.len(),
+                    if written.len() == 1 { "object" } else { "objects" },
+                    spec.dst_remote,
+                    spec.dst_bucket,
+                    spec.dst_key
+                ));
+                self.apply_change(&spec.dst_remote, &spec.dst_bucket, Change::Written(written.clone()));
+                self.finish_unfinished(&TransferSpec::Copy(spec.clone()));
+                if spec.moving {
+                    let change = Change::Deleted {
+                        key: spec.key.clone(),
+                        kept: HashSet::new(),
+                    };
+                    self.apply_change(&spec.remote, &spec.bucket, change);
+                }
+            }
+            Err(e) => self.notify_error(format!("Copy failed: {}", e)),
+        }
+        self.record_transfer(TransferRecord {
+            spec: TransferSpec::Copy(spec),
+            bytes,
+            files,
+            duration_secs: started.elapsed().as_secs_f64(),
+            finished_at: unix_now(),
+            error: result.err(),
+        });
+    }
+}
@@ -1,38 +1,127 @@
+use std::collections::HashSet;
+use std::time::Instant;
+
+use tokio::sync::mpsc;
+
+use crate::hooks::Op;
+use crate::object_store::{self, DeleteFailure, DeleteMsg, Store};
+
+use super::actions::ActionOutput;
+use super::changes::Change;
+use super::confirm::{Confirm, Gate};
+use super::hooks::affected_keys;
+use super::tasks::TaskKind;
 use super::{App, DeleteConfirm, Entry, Location};
 
 impl App {
-    pub fn request_delete(&mut self) {
+    pub async fn request_delete(&mut self) {
         if self.search_active {
             return;
         }
-        self.status_message = None;
-        if let Some(idx) = self.browser_state.selected() {
-            if idx >= self.entries.len() {
-                return;
-            }
-            match &self.entries[idx] {
-                Entry::Object(obj) => {
-                    self.confirm_delete = Some(DeleteConfirm {
-                        display_name: obj.display_name.clone(),
-                        key: obj.key.clone(),
-                        is_dir: obj.is_dir,
-                        selected_yes: false,
-                    });
-                }
-                Entry::Bucket(_) => {
-                    self.error = Some("Bucket deletion is not supported".to_string());
-                }
-            }
+        if let Location::ObjectList { remote, bucket, .. } = self.location.clone()
+            && self.refuse_locked(&remote, &bucket)
+        {
+            return;
+        }
+        let remote = self.current_remote().unwrap_or_default().to_string();
+        let typed = match self.gate(&[&remote], true) {
+            Gate::Refused => return,
+            Gate::Typed(_) => Some(String::new()),
+            Gate::Open => None,
+        };
+        let Some(entry) = self.browser_state.selected().and_then(|i| self.entries.get(i)) else {
+            return;
+        };
+        let Entry::Object(obj) = entry else {
+            self.notify_error("Bucket deletion is not supported");
+            return;
+        };
+        let op = if obj.is_dir { Confirm::DeletePrefix } else { Confirm::DeleteFile(obj.size) };
+        let mut confirm = DeleteConfirm {
+            display_name: obj.display_name.clone(),
+            key: obj.key.clone(),
+            is_dir: obj.is_dir,
+            selected_yes: false,
+            typed,
+            remote,
+            objects: None,
+            markers: None,
+        };
+        if !self.should_confirm(op, &confirm.remote) {
+            confirm.selected_yes = true;
+            self.confirm_delete = Some(confirm);
+            self.confirm_delete_yes().await;
+            return;
+        }
+        // Directories get a dry-run listing of everything that will go
+        if confirm.is_dir
+            && let Location::ObjectList { bucket, .. } = &self.location
+            && let Some(client) = self.clients.get(&confirm.remote)
+        {
+            let (client, bucket) = (client.clone(), bucket.clone());
+            self.plan_delete_prefix(client, bucket, confirm);
+        } else {
+            self.confirm_delete = Some(confirm);
         }
     }
 
+    /// Find the directory markers under the current prefix that other objects make
+    /// redundant, and show them for review before deleting them.
+    pub fn request_marker_cleanup(&mut self) {
+        if self.search_active {
+            return;
+        }
+        let Location::ObjectList { remote, bucket, prefix } = self.location.clone() else {
+            self.notify_warn("Navigate into a bucket first");
+            return;
+        };
+        if self.refuse_locked(&remote, &bucket) {
+            return;
+        }
+        let typed = match self.gate(&[&remote], true) {
+            Gate::Refused => return,
+            Gate::Typed(_) => Some(String::new()),
+            Gate::Open => None,
+        };
+        let Some(client) = self.clients.get(&remote).cloned() else {
+            return;
+        };
+        let display_name = if prefix.is_empty() { bucket.clone() } else { prefix.trim_end_matches('/').to_string() };
+        let confirm = DeleteConfirm {
+            display_name,
+            key: prefix,
+            is_dir: true,
+            selected_yes: false,
+            typed,
+            remote,
+            objects: None,
+            markers: Some(Vec::new()),
+        };
+        self.plan_delete_markers(client, bucket, confirm);
+    }
+
     pub fn toggle_delete_confirm(&mut self) {
         if let Some(ref mut confirm) = self.confirm_delete {
             confirm.selected_yes = !confirm.selected_yes;
         }
     }
 
+    pub fn delete_confirm_input(&mut self, c: char) {
+        if let Some(typed) = self.confirm_delete.as_mut().and_then(|c| c.typed.as_mut()) {
+            typed.push(c);
+        }
+    }
+
+    pub fn delete_confirm_backspace(&mut self) {
+        if let Some(typed) = self.confirm_delete.as_mut().and_then(|c| c.typed.as_mut()) {
+            typed.pop();
+        }
+    }
+
     pub async fn confirm_delete_yes(&mut self) {
+        if !self.confirm_delete.as_ref().is_some_and(|c| c.typed_ok()) {
+            return;
+        }
         let confirm = match self.confirm_delete.take() {
             Some(v) => v,
             None => return,
@@ -49,43 +138,208 @@ impl App {
             let client = match self.clients.get(&remote) {
                 Some(c) => c.clone(),
                 None => {
-                    self.error = Some("Not connected to remote".to_string());
+                    self.notify_error("Not connected to remote");
                     return;
                 }
             };
 
             if confirm.is_dir {
-                match client.delete_prefix(&bucket, &confirm.key).await {
-                    Ok(count) => {
-                        self.entries.retain(|e| e.key() != confirm.key);
-                        self.search_pool
-                            .retain(|o| !o.key.starts_with(&confirm.key));
-                        self.fix_selection();
-                        self.metadata = None;
-                        self.status_message = Some(format!(
-                            "Deleted {} objects from {}",
-                            count, confirm.display_name
-                        ));
+                self.start_delete_prefix(client, remote, bucket, confirm);
+                return;
+            }
+
+            let hooks = self.settings.hooks.clone();
+            let keys = affected_keys(&hooks, Op::Delete, &client, &bucket, &confirm.key, false).await;
+            if let Err(e) = hooks.run(true, Op::Delete, &remote, &bucket, &keys).await {
+                self.notify_error(format!("Delete cancelled: {}", e));
+                return;
+            }
+
+            let deleted = match client.delete_object(&bucket, &confirm.key).await {
+                Ok(()) => {
+                    let change = Change::Deleted {
+                        key: confirm.key.clone(),
+                        kept: HashSet::new(),
+                    };
+                    self.apply_change(&remote, &bucket, change);
+                    self.notify(format!("Deleted {}", confirm.display_name));
+                    true
+                }
+                Err(e) => {
+                    self.notify_error(format!("Delete failed: {}", e));
+                    false
+                }
+            };
+            if deleted && let Err(e) = hooks.run(false, Op::Delete, &remote, &bucket, &keys).await {
+                self.notify_warn(e.to_string());
+            }
+        }
+    }
+
+    /// Delete everything under a prefix in the background, reporting progress in
+    /// the status bar. The keys come from a complete index when there is one.
+    fn start_delete_prefix(&mut self, client: Store, remote: String, bucket: String, confirm: DeleteConfirm) {
+        if self.delete_rx.is_some() {
+            self.notify_warn("A delete is already running");
+            return;
+        }
+        // Marker cleanups delete exactly the planned keys and sweep nothing else
+        let indexed: Option<Vec<String>> = confirm.markers.clone().or_else(|| {
+            self.indexed_under(&remote, &bucket, &confirm.key)
+                .map(|objects| objects.into_iter().map(|o| o.key).collect())
+        });
+
+        self.delete_progress = Some(DeleteProgress {
+            name: confirm.display_name.clone(),
+            remote: remote.clone(),
+            bucket: bucket.clone(),
+            key: confirm.key.clone(),
+            deleted: 0,
+            failed: Vec::new(),
+            total: indexed.as_ref().map(Vec::len).or(confirm.objects),
+            markers: confirm.markers.clone(),
+            started: Instant::now(),
+        });
+        let (tx, rx) = mpsc::channel(16);
+        self.delete_rx = Some(rx.into());
+
+        let hooks = self.settings.hooks.clone();
+        let hook_tx = self.hook_tx.clone();
+        let label = format!("Delete {}", confirm.display_name);
+        self.tasks.spawn(TaskKind::Delete, label, async move {
+            let prefix = confirm.key;
+            let sweep = confirm.markers.is_none();
+            let keys = match &indexed {
+                Some(keys) if !keys.is_empty() => keys.clone(),
+                _ => affected_keys(&hooks, Op::Delete, &client, &bucket, &prefix, true).await,
+            };
+            if let Err(e) = hooks.run(true, Op::Delete, &remote, &bucket, &keys).await {
+                let _ = tx.send(DeleteMsg::Error(e.to_string())).await;
+                return;
+            }
+            let result = async {
+                let mut deleted = 0;
+                // With the whole bucket indexed the keys are already known; the
+                // listing then only sweeps up what the index missed (markers, newer uploads)
+                if let Some(keys) = &indexed {
+                    for chunk in keys.chunks(1000) {
+                        let failed = client.delete_keys(&bucket, chunk).await?;
+                        deleted += object_store::report_batch(&tx, chunk.len(), failed).await;
                     }
-                    Err(e) => {
-                        self.error = Some(format!("Delete failed: {}", e));
+                }
+                if sweep {
+                    deleted += client.delete_prefix(&bucket, &prefix, &tx).await?;
+                }
+                anyhow::Ok(deleted)
+            }
+            .await;
+            match result {
+                Ok(_) => {
+                    if let Err(e) = hooks.run(false, Op::Delete, &remote, &bucket, &keys).await {
+                        let _ = hook_tx.send(e.to_string()).await;
                     }
+                    let _ = tx.send(DeleteMsg::Done).await;
                 }
-            } else {
-                match client.delete_object(&bucket, &confirm.key).await {
-                    Ok(()) => {
-                        self.entries.retain(|e| e.key() != confirm.key);
-                        self.search_pool.retain(|o| o.key != confirm.key);
-                        self.fix_selection();
-                        self.metadata = None;
-                        self.status_message =
-                            Some(format!("Deleted {}", confirm.display_name));
+                Err(e) => {
+                    let _ = tx.send(DeleteMsg::Error(e.to_string())).await;
+                }
+            }
+        });
+    }
+
+    /// Apply progress of a running prefix delete, and its result. Call every tick.
+    pub fn drain_delete(&mut self) {
+        let Some(rx) = &mut self.delete_rx else {
+            return;
+        };
+        let result = loop {
+            match rx.try_recv() {
+                Ok(DeleteMsg::Deleted(count)) => {
+                    if let Some(progress) = &mut self.delete_progress {
+                        progress.deleted += count;
                     }
-                    Err(e) => {
-                        self.error = Some(format!("Delete failed: {}", e));
+                }
+                Ok(DeleteMsg::Failed(failed)) => {
+                    if let Some(progress) = &mut self.delete_progress {
+                        progress.failed.extend(failed);
                     }
                 }
+                Ok(DeleteMsg::Done) => break Ok(()),
+                Ok(DeleteMsg::Error(e)) => break Err(e),
+                Err(mpsc::error::TryRecvError::Empty) => return,
+                Err(mpsc::error::TryRecvError::Disconnected) => break Err("delete task ended early".to_string()),
             }
+        };
+        self.delete_rx = None;
+        let Some(mut progress) = self.delete_progress.take() else {
+            return;
+        };
+        if let Err(e) = result {
+            // Unknown how far it got; list again to show what's left
+            self.notify_error(format!("Delete failed: {}", e));
+            self.head_cache.invalidate(&progress.remote, &progress.bucket, &progress.key);
+            self.listing_stale = true;
+            return;
         }
+        // The sweep after an indexed pass lists the keys that pass refused and is refused again
+        let mut kept = HashSet::new();
+        progress.failed.retain(|f| kept.insert(f.key.clone()));
+        let refused = progress.failed.len();
+        let change = match &progress.markers {
+            Some(markers) => Change::Markers(markers.iter().filter(|k| !kept.contains(*k)).cloned().collect()),
+            None => Change::Deleted {
+                key: progress.key.clone(),
+                kept,
+            },
+        };
+        self.apply_change(&progress.remote, &progress.bucket, change);
+        if refused == 0 {
+            self.notify(format!("Deleted {} objects from {}", progress.deleted, progress.name));
+            return;
+        }
+        // The prefix stays listed, since it still holds the refused keys
+        self.notify_error(format!(
+            "Deleted {} objects from {}; {} could not be deleted",
+            progress.deleted, progress.name, refused
+        ));
+        self.action_output = Some(ActionOutput {
+            title: format!("Not deleted from {}/ ({})", progress.name, refused),
+            lines: progress
+                .failed
+                .iter()
+                .map(|f| format!("{}  {}", f.key, f.reason))
+                .collect(),
+            scroll: 0,
+        });
+    }
+}
+
+/// A recursive delete running in the background.
+pub struct DeleteProgress {
+    pub name: String,
+    remote: String,
+    bucket: String,
+    key: String,
+    pub deleted: usize,
+    /// Keys the endpoint refused to delete, reported once it's done.
+    failed: Vec<DeleteFailure>,
+    /// Objects under the prefix, known from the index or the plan's listing.
+    pub total: Option<usize>,
+    /// The directory markers being deleted, when it's a marker cleanup.
+    markers: Option<Vec<String>>,
+    started: Instant,
+}
+
+impl DeleteProgress {
+    /// Objects deleted per second so far.
+    pub fn rate(&self) -> f64 {
+        self.deleted as f64 / self.started.elapsed().as_secs_f64().max(0.01)
+    }
+
+    /// Seconds left at the current rate, when the total is known.
+    pub fn eta_secs(&self) -> Option<u64> {
+        let remaining = self.total?.checked_sub(self.deleted)?;
+        let rate = self.rate();
+        (rate > 0.0).then(|| (remaining as f64 / rate) as u64)
     }
 }
@@ -1,9 +1,72 @@
+use std::path::Path;
 use std::time::Instant;
 
 use tokio::sync::mpsc;
 
+use crate::hooks::Op;
+use crate::plugins::{Hook, PluginEvent};
+use crate::object_store::{self, Store};
+
+use super::confirm::Confirm;
+use super::history::{unix_now, DownloadSpec, TransferRecord, TransferSpec};
+use super::hooks::affected_keys;
+use super::notify::Severity;
+use super::tasks::TaskKind;
 use super::{App, DownloadProgress, Entry, Location, Pane};
 
+/// First "stem (n).ext" that doesn't exist in `dir`.
+fn unique_name(dir: &Path, name: &str) -> String {
+    let (stem, ext) = match name.rfind('.') {
+        Some(i) if i > 0 => name.split_at(i),
+        _ => (name, ""),
+    };
+    (1..)
+        .map(|n| format!("{} ({}){}", stem, n, ext))
+        .find(|candidate| !dir.join(candidate).exists())
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// "100MB", "4k", "512" -> bytes (binary units).
+fn parse_size(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let n: u64 = num.parse().ok()?;
+    let mult = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        _ => return None,
+    };
+    n.checked_mul(mult)
+}
+
+/// Parse "first N", "last N", "START-END" or "START-" against an object of
+/// `size` bytes into a half-open byte range.
+fn parse_range(spec: &str, size: u64) -> Result<(u64, u64), String> {
+    let spec = spec.trim().to_ascii_lowercase();
+    let (start, end) = if let Some(n) = spec.strip_prefix("first ") {
+        (0, parse_size(n).ok_or("bad size")?.min(size))
+    } else if let Some(n) = spec.strip_prefix("last ") {
+        (size.saturating_sub(parse_size(n).ok_or("bad size")?), size)
+    } else if let Some((a, b)) = spec.split_once('-') {
+        let start = parse_size(a).ok_or("bad start")?;
+        let end = if b.trim().is_empty() {
+            size
+        } else {
+            parse_size(b).ok_or("bad end")?.min(size)
+        };
+        (start, end)
+    } else {
+        return Err("use first N, last N or START-END (e.g. first 100MB)".to_string());
+    };
+    if start >= end {
+        return Err("empty range".to_string());
+    }
+    Ok((start, end))
+}
+
 impl App {
     /// Enter download mode: snapshot the selected S3 entry, open local FS pane.
     pub fn start_download_mode(&mut self) {
@@ -19,7 +82,7 @@ impl App {
 
         // Must be in an ObjectList (inside a bucket)
         if !matches!(self.location, Location::ObjectList { .. }) {
-            self.error = Some("Navigate into a bucket first".to_string());
+            self.notify_warn("Navigate into a bucket first");
             return;
         }
 
@@ -30,9 +93,10 @@ impl App {
                 self.download_source_is_dir = obj.is_dir;
             }
             Entry::Bucket(_) => {
-                self.error = Some("Cannot download a bucket".to_string());
+                self.notify_error("Cannot download a bucket");
                 return;
             }
+            Entry::Group(_) => return,
         }
 
         self.download_mode = true;
@@ -45,6 +109,11 @@ impl App {
     /// Cancel download mode and go back to normal 2-pane layout.
     pub fn cancel_download_mode(&mut self) {
         self.download_mode = false;
+        if self.local_search_active {
+            self.cancel_local_search();
+        }
+        self.download_conflict = None;
+        self.name_adjusted_from = None;
         self.download_source = None;
         self.rename_input = None;
         self.rename_active = false;
@@ -56,6 +125,7 @@ impl App {
 
     /// Start typing a custom filename.
     pub fn start_rename(&mut self) {
+        self.name_adjusted_from = None;
         if let Some((ref display_name, _)) = self.download_source {
             self.rename_active = true;
             self.rename_input = Some(display_name.clone());
@@ -86,18 +156,41 @@ impl App {
 
     /// The filename that will be used for downloading (custom or original).
     pub fn download_target_name(&self) -> Option<String> {
-        if let Some(ref custom) = self.rename_input {
-            if !custom.is_empty() {
-                return Some(custom.clone());
-            }
+        if let Some(ref custom) = self.rename_input
+            && !custom.is_empty()
+        {
+            return Some(custom.clone());
         }
         self.download_source
             .as_ref()
             .map(|(display, _)| display.clone())
     }
 
+    /// Take the suggested "name (1).ext" from the conflict prompt; the download
+    /// pane shows the adjusted name until it is confirmed with `c`.
+    pub fn accept_suffix_rename(&mut self) {
+        let Some(suggested) = self.download_conflict.take() else {
+            return;
+        };
+        self.name_adjusted_from = self.download_target_name();
+        self.rename_input = Some(suggested);
+    }
+
     /// Confirm download: start downloading to current local_path.
+    /// A file that already exists locally first opens the conflict prompt;
+    /// confirming again from the prompt overwrites it.
     pub async fn confirm_download(&mut self) {
+        if !self.download_source_is_dir
+            && self.download_conflict.is_none()
+            && let Some(target) = self.download_target_name()
+            && self.local_path.join(&target).exists()
+        {
+            self.download_conflict = Some(unique_name(&self.local_path, &target));
+            return;
+        }
+        self.download_conflict = None;
+        self.name_adjusted_from = None;
+
         let (display_name, key) = match self.download_source.take() {
             Some(v) => v,
             None => return,
@@ -116,13 +209,13 @@ impl App {
         let client = match self.clients.get(remote) {
             Some(c) => c.clone(),
             None => {
-                self.error = Some("Not connected to remote".to_string());
+                self.notify_error("Not connected to remote");
                 return;
             }
         };
 
+        let remote = remote.clone();
         let bucket = bucket.clone();
-        let dest_dir = self.local_path.clone();
 
         let target_name = self
             .rename_input
@@ -130,35 +223,161 @@ impl App {
             .filter(|s| !s.is_empty())
             .unwrap_or_else(|| display_name.clone());
 
+        let spec = DownloadSpec {
+            dest: self.local_path.join(&target_name),
+            name: target_name,
+            remote,
+            bucket,
+            key,
+            is_dir,
+            range: None,
+        };
+
         // Close the download mode pane
         self.download_mode = false;
         self.rename_active = false;
         self.local_entries.clear();
         self.pane = Pane::Browser;
 
-        // Set up progress tracking
+        if spec.is_dir && self.should_confirm(Confirm::Download, &spec.remote) {
+            self.plan_download(client, spec);
+        } else {
+            self.start_transfer(client, spec);
+        }
+    }
+
+    /// Start typing a byte range for the selected file.
+    pub fn start_range_input(&mut self) {
+        if self.download_source_is_dir {
+            self.notify_error("Byte ranges need a file");
+            return;
+        }
+        self.range_input = Some(String::new());
+    }
+
+    /// Save just the typed byte range of the source file into the current local directory.
+    pub async fn confirm_range_download(&mut self) {
+        let Some(range) = self.range_input.take() else {
+            return;
+        };
+        if self.download_rx.is_some() {
+            self.notify_warn("A transfer is already running");
+            return;
+        }
+        let Some((_, key)) = self.download_source.clone() else {
+            return;
+        };
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            return;
+        };
+        let Some(client) = self.clients.get(remote).cloned() else {
+            self.notify_error("Not connected to remote");
+            return;
+        };
+        let (remote, bucket) = (remote.clone(), bucket.clone());
+        let listed = self.entries.iter().find_map(|e| match e {
+            Entry::Object(obj) if obj.key == key => Some(obj.size.max(0) as u64),
+            _ => None,
+        });
+        let size = match listed {
+            Some(size) => size,
+            None => match client.head_object(&bucket, &key).await {
+                Ok(meta) => meta.size.max(0) as u64,
+                Err(e) => {
+                    self.notify_error(format!("Failed to read the object size: {}", e));
+                    return;
+                }
+            },
+        };
+        let (start, end) = match parse_range(&range, size) {
+            Ok(range) => range,
+            Err(e) => {
+                self.notify_error(format!("Invalid range: {}", e));
+                return;
+            }
+        };
+        let name = match self.rename_input.take().filter(|s| !s.is_empty()) {
+            Some(custom) => custom,
+            None => format!(
+                "{}.bytes-{}-{}",
+                self.download_target_name().unwrap_or_default(),
+                start,
+                end
+            ),
+        };
+        let spec = DownloadSpec {
+            dest: self.local_path.join(&name),
+            name,
+            remote,
+            bucket,
+            key,
+            is_dir: false,
+            range: Some((start, end)),
+        };
+        self.cancel_download_mode();
+        self.start_transfer(client, spec);
+    }
+
+    /// Spawn the background download described by `spec` and track its progress.
+    pub(crate) fn start_transfer(&mut self, client: Store, spec: DownloadSpec) {
         let (tx, rx) = mpsc::channel(64);
-        self.download_rx = Some(rx);
+        self.download_rx = Some(rx.into());
         self.download_started_at = Some(Instant::now());
         self.download_progress = Some(DownloadProgress {
-            filename: target_name.clone(),
+            filename: spec.name.clone(),
             bytes_downloaded: 0,
-            total_bytes: 0,
+            total_bytes: spec.range.map_or(0, |(start, end)| end - start),
             speed_bps: 0.0,
+            avg_speed_bps: 0.0,
+            throughput: Default::default(),
             files_done: 0,
-            files_total: if is_dir { 0 } else { 1 },
+            files_total: if spec.is_dir { 0 } else { 1 },
             complete: false,
             error: None,
         });
+        let transfer = TransferSpec::Download(spec.clone());
+        self.track_unfinished(&transfer);
+        self.active_transfer = Some(transfer);
+        let label = format!("Download {}", spec.name);
+
+        let DownloadSpec {
+            remote,
+            bucket,
+            key,
+            dest,
+            is_dir,
+            range,
+            ..
+        } = spec;
+        let hooks = self.settings.hooks.clone();
+        let hook_tx = self.hook_tx.clone();
 
         if is_dir {
-            let dest = dest_dir.join(&target_name);
-            let handle = tokio::spawn(async move {
-                let result = client
-                    .download_prefix(&bucket, &key, &dest, tx.clone(), 4)
-                    .await;
+            let task = self.tasks.spawn(TaskKind::Transfer, label, async move {
+                let keys = affected_keys(&hooks, Op::Download, &client, &bucket, &key, true).await;
+                let result = match hooks.run(true, Op::Download, &remote, &bucket, &keys).await {
+                    Ok(()) => object_store::download_prefix(&client, &bucket, &key, &dest, tx.clone(), 4).await,
+                    Err(e) => Err(e),
+                };
+                if let Ok(skipped) = &result
+                    && let Some(first) = skipped.first()
+                {
+                    let message = format!(
+                        "Skipped {} key{} that would be saved outside {}, such as {}",
+                        skipped.len(),
+                        if skipped.len() == 1 { "" } else { "s" },
+                        dest.display(),
+                        first
+                    );
+                    let _ = hook_tx.send(message).await;
+                }
+                if result.is_ok()
+                    && let Err(e) = hooks.run(false, Op::Download, &remote, &bucket, &keys).await
+                {
+                    let _ = hook_tx.send(e.to_string()).await;
+                }
                 let msg = match result {
-                    Ok(()) => crate::s3_client::DownloadMsg {
+                    Ok(_) => crate::object_store::DownloadMsg {
                         bytes_downloaded: 0,
                         total_bytes: 0,
                         files_done: 0,
@@ -166,7 +385,7 @@ impl App {
                         complete: true,
                         error: None,
                     },
-                    Err(e) => crate::s3_client::DownloadMsg {
+                    Err(e) => crate::object_store::DownloadMsg {
                         bytes_downloaded: 0,
                         total_bytes: 0,
                         files_done: 0,
@@ -177,13 +396,24 @@ impl App {
                 };
                 let _ = tx.send(msg).await;
             });
-            self.download_handle = Some(handle);
+            self.download_task = Some(task);
         } else {
-            let dest = dest_dir.join(&target_name);
-            let handle = tokio::spawn(async move {
-                let result = client.download_object(&bucket, &key, &dest, &tx).await;
+            let task = self.tasks.spawn(TaskKind::Transfer, label, async move {
+                let keys = [key.clone()];
+                let result = match hooks.run(true, Op::Download, &remote, &bucket, &keys).await {
+                    Ok(()) => match range {
+                        Some((start, end)) => client.download_range(&bucket, &key, start, end, &dest, &tx).await,
+                        None => client.download_object(&bucket, &key, &dest, &tx).await,
+                    },
+                    Err(e) => Err(e),
+                };
+                if result.is_ok()
+                    && let Err(e) = hooks.run(false, Op::Download, &remote, &bucket, &keys).await
+                {
+                    let _ = hook_tx.send(e.to_string()).await;
+                }
                 let msg = match result {
-                    Ok(()) => crate::s3_client::DownloadMsg {
+                    Ok(()) => crate::object_store::DownloadMsg {
                         bytes_downloaded: 0,
                         total_bytes: 0,
                         files_done: 1,
@@ -191,7 +421,7 @@ impl App {
                         complete: true,
                         error: None,
                     },
-                    Err(e) => crate::s3_client::DownloadMsg {
+                    Err(e) => crate::object_store::DownloadMsg {
                         bytes_downloaded: 0,
                         total_bytes: 0,
                         files_done: 0,
@@ -202,8 +432,41 @@ impl App {
                 };
                 let _ = tx.send(msg).await;
             });
-            self.download_handle = Some(handle);
+            self.download_task = Some(task);
+        }
+    }
+
+    /// Move the finished transfer into the history with its final statistics.
+    fn finish_transfer_record(&mut self, duration_secs: f64) {
+        let (Some(spec), Some(progress)) = (self.active_transfer.take(), &self.download_progress)
+        else {
+            return;
+        };
+        if progress.error.is_none() {
+            if let TransferSpec::Download(download) = &spec {
+                let event = PluginEvent {
+                    remote: download.remote.clone(),
+                    bucket: download.bucket.clone(),
+                    key: download.key.clone(),
+                    size: Some(progress.total_bytes as i64),
+                    local_path: Some(download.dest.display().to_string()),
+                };
+                self.fire_hook(Hook::DownloadComplete, Some(event));
+            }
+            self.finish_unfinished(&spec);
         }
+        let Some(progress) = &self.download_progress else {
+            return;
+        };
+        let record = TransferRecord {
+            spec,
+            bytes: progress.total_bytes.max(progress.bytes_downloaded),
+            files: progress.files_total.max(progress.files_done),
+            duration_secs,
+            finished_at: unix_now(),
+            error: progress.error.clone(),
+        };
+        self.record_transfer(record);
     }
 
     /// Non-blocking drain of download progress channel. Call every tick.
@@ -226,21 +489,27 @@ impl App {
                         if let Some(ref mut progress) = self.download_progress {
                             progress.complete = true;
                             progress.error = msg.error;
-                            if progress.error.is_none() {
-                                self.status_message = Some(format!(
-                                    "Downloaded {}",
-                                    progress.filename
-                                ));
-                            } else {
-                                self.error = Some(format!(
-                                    "Download failed: {}",
-                                    progress.error.as_deref().unwrap_or("unknown")
-                                ));
-                            }
+                            // `progress` borrows `self`, so push to the toasts field directly
+                            let (severity, message) = match (self.sync_summary.take(), &progress.error) {
+                                (Some(summary), None) => (Severity::Info, summary),
+                                (Some(_), Some(e)) => (Severity::Error, format!("Sync failed: {}", e)),
+                                (None, None) => (Severity::Info, format!("Downloaded {}", progress.filename)),
+                                (None, Some(e)) => (Severity::Error, format!("Download failed: {}", e)),
+                            };
+                            self.toasts.push(severity, message);
                         }
                         self.download_rx = None;
-                        self.download_handle = None;
+                        self.download_task = None;
                         self.download_started_at = None;
+                        if let Some((remote, bucket, change)) = self.sync_uploads.take() {
+                            if self.download_progress.as_ref().is_some_and(|p| p.error.is_none()) {
+                                self.apply_change(&remote, &bucket, change);
+                            } else {
+                                // Some may have made it; list again to show which
+                                self.listing_stale = true;
+                            }
+                        }
+                        self.finish_transfer_record(elapsed_secs);
                         // Keep progress briefly for display, clear on next action
                         return;
                     }
@@ -249,17 +518,28 @@ impl App {
                         progress.total_bytes = msg.total_bytes;
                         progress.files_done = msg.files_done;
                         progress.files_total = msg.files_total;
-                        progress.speed_bps = msg.bytes_downloaded as f64 / elapsed_secs;
+                        progress.throughput.record(elapsed_secs, msg.bytes_downloaded);
+                        progress.update_speed(elapsed_secs);
                     }
                 }
-                Err(mpsc::error::TryRecvError::Empty) => break,
-                Err(mpsc::error::TryRecvError::Disconnected) => {
+                Err(mpsc::error::TryRecvError::Empty) => {
+                    // Advance the sparkline through seconds with no progress
                     if let Some(ref mut progress) = self.download_progress {
-                        if !progress.complete {
-                            progress.complete = true;
-                        }
+                        progress.throughput.record(elapsed_secs, progress.bytes_downloaded);
+                        progress.update_speed(elapsed_secs);
+                    }
+                    break;
+                }
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    if let Some(ref mut progress) = self.download_progress
+                        && !progress.complete
+                    {
+                        progress.complete = true;
                     }
                     self.download_rx = None;
+                    if self.sync_uploads.take().is_some() {
+                        self.listing_stale = true;
+                    }
                     break;
                 }
             }
@@ -0,0 +1,90 @@
+use super::changes::Change;
+use super::confirm::Gate;
+use super::{App, Entry, Location};
+
+/// Name being typed for a server-side duplicate of the selected object (`Shift+Y`).
+pub struct Duplicate {
+    pub remote: String,
+    pub bucket: String,
+    pub prefix: String,
+    pub source_key: String,
+    pub name: String,
+}
+
+/// `config.yaml` -> `config-copy.yaml`; dotfiles and names without an extension
+/// get the suffix at the end.
+fn copy_name(name: &str) -> String {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{}-copy.{}", stem, ext),
+        _ => format!("{}-copy", name),
+    }
+}
+
+impl App {
+    pub fn start_duplicate(&mut self) {
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            return;
+        };
+        let Some(Entry::Object(obj)) = self.browser_state.selected().and_then(|i| self.entries.get(i)) else {
+            return;
+        };
+        if obj.is_dir {
+            self.notify_warn("Only objects can be duplicated; use yy to copy a directory");
+            return;
+        }
+        let duplicate = Duplicate {
+            remote: remote.clone(),
+            bucket: bucket.clone(),
+            prefix: super::parent_prefix(&obj.key),
+            source_key: obj.key.clone(),
+            name: copy_name(&obj.display_name),
+        };
+        if self.gate(&[&duplicate.remote], false) == Gate::Refused
+            || self.refuse_locked(&duplicate.remote, &duplicate.bucket)
+        {
+            return;
+        }
+        self.duplicate = Some(duplicate);
+    }
+
+    /// Copy the object to the typed name next to it, unless something is already there.
+    pub async fn finish_duplicate(&mut self) {
+        let Some(duplicate) = self.duplicate.take() else {
+            return;
+        };
+        let name = duplicate.name.trim();
+        if name.is_empty() {
+            return;
+        }
+        let key = format!("{}{}", duplicate.prefix, name);
+        if key == duplicate.source_key {
+            self.notify_error("Pick a different name for the copy");
+            self.duplicate = Some(duplicate);
+            return;
+        }
+        let Some(client) = self.clients.get(&duplicate.remote).cloned() else {
+            self.notify_error("Not connected to remote");
+            return;
+        };
+        if client.head_object(&duplicate.bucket, &key).await.is_ok() {
+            self.notify_error(format!("{} already exists", key));
+            self.duplicate = Some(duplicate);
+            return;
+        }
+        match client.copy_object(&duplicate.bucket, &duplicate.source_key, &duplicate.bucket, &key).await {
+            Ok(()) => {
+                let size = self
+                    .entries
+                    .iter()
+                    .find_map(|e| match e {
+                        Entry::Object(o) if o.key == duplicate.source_key => Some(o.size.max(0) as u64),
+                        _ => None,
+                    })
+                    .unwrap_or(0);
+                self.notify(format!("Duplicated as {}", name));
+                self.apply_change(&duplicate.remote, &duplicate.bucket, Change::Written(vec![(key, size)]));
+            }
+            Err(e) => self.notify_error(format!("Duplicate failed: {}", e)),
+        }
+    }
+}
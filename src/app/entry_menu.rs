@@ -0,0 +1,94 @@
+use ratatui::widgets::ListState;
+
+use super::action::Action;
+use super::cli::CliTool;
+use super::links::KeyForm;
+use super::{App, Entry, Pane};
+
+/// One operation offered for the selected entry, with the key that does it directly.
+pub struct MenuItem {
+    pub label: &'static str,
+    pub keys: &'static str,
+    pub action: Action,
+}
+
+const fn item(label: &'static str, keys: &'static str, action: Action) -> MenuItem {
+    MenuItem { label, keys, action }
+}
+
+const FILE: &[MenuItem] = &[
+    item("Download", "Shift+C", Action::DownloadMode),
+    item("Preview", "p", Action::Preview),
+    item("Properties", "Enter", Action::Properties),
+    item("Share link", "Shift+P", Action::LinkBundle),
+    item("Copy key", "yk", Action::YankKey(KeyForm::Key)),
+    item("Copy key for a shell", "ys", Action::YankKey(KeyForm::Shell)),
+    item("Copy s3:// URI", "yu", Action::YankKey(KeyForm::Uri)),
+    item("Copy URL", "yw", Action::YankKey(KeyForm::Url)),
+    item("Copy mc command", "yc", Action::YankCommands(CliTool::Mc)),
+    item("Duplicate", "Shift+Y", Action::Duplicate),
+    item("Copy to...", "yy", Action::Copy),
+    item("Move to...", "Shift+M", Action::Move),
+    item("Set ACL...", "Shift+A", Action::SetAcl),
+    item("Pin to favorites", "f", Action::ToggleFavorite),
+    item("Delete", "d", Action::Delete),
+];
+
+const DIR: &[MenuItem] = &[
+    item("Open", "Enter", Action::Select),
+    item("Download", "Shift+C", Action::DownloadMode),
+    item("Copy key", "yk", Action::YankKey(KeyForm::Key)),
+    item("Copy key for a shell", "ys", Action::YankKey(KeyForm::Shell)),
+    item("Copy s3:// URI", "yu", Action::YankKey(KeyForm::Uri)),
+    item("Copy mc command", "yc", Action::YankCommands(CliTool::Mc)),
+    item("Copy to...", "yy", Action::Copy),
+    item("Move to...", "Shift+M", Action::Move),
+    item("Delete", "d", Action::Delete),
+];
+
+/// Popup of what can be done with the selected entry (`a`), for when the key
+/// for it doesn't come to mind.
+pub struct EntryMenu {
+    pub title: String,
+    pub items: &'static [MenuItem],
+    pub state: ListState,
+}
+
+impl App {
+    pub fn open_entry_menu(&mut self) {
+        if self.pane != Pane::Browser {
+            return;
+        }
+        let Some(entry) = self.browser_state.selected().and_then(|i| self.entries.get(i)) else {
+            return;
+        };
+        let (title, items) = match entry {
+            Entry::Bucket(_) | Entry::Group(_) => return,
+            Entry::Object(obj) if obj.is_dir => (format!("{}/", obj.display_name), DIR),
+            Entry::Object(obj) => (obj.display_name.clone(), FILE),
+        };
+        let mut state = ListState::default();
+        state.select(Some(0));
+        self.entry_menu = Some(EntryMenu { title, items, state });
+    }
+
+    pub fn entry_menu_move(&mut self, down: bool) {
+        let Some(menu) = &mut self.entry_menu else {
+            return;
+        };
+        let i = menu.state.selected().unwrap_or(0);
+        menu.state
+            .select(Some(if down { (i + 1).min(menu.items.len() - 1) } else { i.saturating_sub(1) }));
+    }
+
+    /// Close the menu and do the highlighted operation, as its key would.
+    pub async fn run_entry_menu(&mut self) {
+        let Some(menu) = self.entry_menu.take() else {
+            return;
+        };
+        if let Some(item) = menu.state.selected().and_then(|i| menu.items.get(i)) {
+            // Boxed: this runs from inside `dispatch` itself
+            Box::pin(self.dispatch(item.action)).await;
+        }
+    }
+}
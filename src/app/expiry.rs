@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc;
+
+use crate::object_store::Timestamp;
+use crate::temp_credentials::RENEW_BEFORE;
+
+use super::inbox::Inbox;
+use super::tasks::TaskKind;
+use super::App;
+
+/// Warn this long before credentials that can't be renewed run out.
+const WARN_BEFORE: Duration = Duration::from_secs(10 * 60);
+/// Renewable credentials this close to expiring mean renewing keeps failing.
+const RENEWAL_FAILING: Duration = Duration::from_secs(2 * 60);
+/// Wait between attempts at renewing one remote's credentials.
+const RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Renewals of temporary credentials and the expiry warnings already shown.
+pub struct ExpiryWatch {
+    /// When each remote's last renewal started.
+    renewed: HashMap<String, Instant>,
+    /// The expiry each remote was last warned about, and whether it had passed.
+    warned: HashMap<String, (Timestamp, bool)>,
+    /// Renewals that failed: remote and why.
+    tx: mpsc::Sender<(String, String)>,
+    pub(crate) rx: Inbox<(String, String)>,
+}
+
+impl Default for ExpiryWatch {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::channel(4);
+        Self {
+            renewed: HashMap::new(),
+            warned: HashMap::new(),
+            tx,
+            rx: rx.into(),
+        }
+    }
+}
+
+/// Time from now until `expiry`; zero once it has passed.
+fn time_left(expiry: Timestamp) -> Duration {
+    (expiry - chrono::Utc::now()).to_std().unwrap_or_default()
+}
+
+impl App {
+    /// Time left on the credentials of `alias`, if they are temporary.
+    pub fn credentials_left(&self, alias: &str) -> Option<Duration> {
+        self.clients.get(alias)?.credentials_expiry().map(time_left)
+    }
+
+    /// Renew temporary credentials that are about to expire, and warn about ones
+    /// that can't be renewed. Call every tick.
+    pub(crate) fn watch_credentials(&mut self) {
+        while let Ok((alias, error)) = self.expiry_watch.rx.try_recv() {
+            self.notify_error(format!("Renewing credentials of {} failed: {}", alias, error));
+        }
+
+        let mut warnings = Vec::new();
+        for (alias, client) in &self.clients {
+            let Some(expiry) = client.credentials_expiry() else {
+                continue;
+            };
+            let left = time_left(expiry);
+            let renews = client.renews_credentials();
+            if renews
+                && left <= RENEW_BEFORE
+                && self.expiry_watch.renewed.get(alias).is_none_or(|t| t.elapsed() >= RETRY_AFTER)
+            {
+                self.expiry_watch.renewed.insert(alias.clone(), Instant::now());
+                let (alias, client, tx) = (alias.clone(), client.clone(), self.expiry_watch.tx.clone());
+                let label = format!("Renew credentials of {}", alias);
+                self.tasks.spawn(TaskKind::Credentials, label, async move {
+                    if let Err(e) = client.renew_credentials().await {
+                        let _ = tx.send((alias, e.to_string())).await;
+                    }
+                });
+            }
+            let warn_before = if renews { RENEWAL_FAILING } else { WARN_BEFORE };
+            let state = (expiry, left.is_zero());
+            if left <= warn_before && self.expiry_watch.warned.get(alias) != Some(&state) {
+                self.expiry_watch.warned.insert(alias.clone(), state);
+                warnings.push((alias.clone(), left));
+            }
+        }
+        for (alias, left) in warnings {
+            if left.is_zero() {
+                self.notify_error(format!(
+                    "Credentials of {} have expired; renew them in the config and press Shift+R",
+                    alias
+                ));
+            } else {
+                self.notify_warn(format!(
+                    "Credentials of {} expire in {} min",
+                    alias,
+                    left.as_secs().div_ceil(60)
+                ));
+            }
+        }
+    }
+
+    /// Time until the countdown of some remote's credentials next changes, which
+    /// is also when renewals and warnings come due.
+    pub(crate) fn credentials_due_in(&self) -> Option<Duration> {
+        self.clients
+            .values()
+            .filter_map(|c| c.credentials_expiry())
+            .map(time_left)
+            .filter(|left| !left.is_zero())
+            .map(|left| {
+                if left < RENEWAL_FAILING {
+                    Duration::from_secs(1)
+                } else {
+                    Duration::from_secs(left.as_secs() % 60 + 1)
+                }
+            })
+            .min()
+    }
+}
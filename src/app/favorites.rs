@@ -0,0 +1,274 @@
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+use super::history::DownloadSpec;
+use super::{App, Entry, Location};
+
+const FAVORITES_FILE: &str = "favorites.json";
+
+/// A pinned object or prefix, remembered per remote.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Favorite {
+    pub remote: String,
+    pub bucket: String,
+    /// Object key, or a prefix ending in `/`.
+    pub key: String,
+    /// A pinned prefix also listed in the Remotes pane as `alias:bucket/prefix`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub promoted: bool,
+}
+
+impl Favorite {
+    pub fn name(&self) -> &str {
+        let key = self.key.trim_end_matches('/');
+        key.rsplit('/').next().unwrap_or(key)
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.key.ends_with('/')
+    }
+
+    /// How a promoted prefix is listed among the remotes.
+    pub fn alias_label(&self) -> String {
+        format!("{}:{}/{}", self.remote, self.bucket, self.key)
+    }
+
+    fn same_place(&self, other: &Favorite) -> bool {
+        self.remote == other.remote && self.bucket == other.bucket && self.key == other.key
+    }
+}
+
+pub(crate) fn load_favorites() -> Vec<Favorite> {
+    storage::load_json(FAVORITES_FILE)
+}
+
+impl App {
+    /// Pin or unpin the selected file or directory (`f`).
+    pub fn toggle_favorite(&mut self) {
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            return;
+        };
+        let Some(Entry::Object(obj)) = self
+            .browser_state
+            .selected()
+            .and_then(|i| self.entries.get(i))
+        else {
+            return;
+        };
+
+        let fav = Favorite {
+            remote: remote.clone(),
+            bucket: bucket.clone(),
+            key: obj.key.clone(),
+            promoted: false,
+        };
+        if let Some(pos) = self.favorites.iter().position(|f| f.same_place(&fav)) {
+            self.favorites.remove(pos);
+            self.clamp_remote_selection();
+            self.notify(format!("Unpinned {}", fav.name()));
+        } else {
+            self.notify(format!("Pinned {}", fav.name()));
+            self.favorites.push(fav);
+        }
+        self.save_favorites();
+    }
+
+    pub fn is_favorite(&self, key: &str) -> bool {
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            return false;
+        };
+        self.favorites
+            .iter()
+            .any(|f| f.remote == *remote && f.bucket == *bucket && f.key == key)
+    }
+
+    fn save_favorites(&mut self) {
+        if let Err(e) = storage::save_json(FAVORITES_FILE, &self.favorites) {
+            self.notify_error(format!("Failed to save favorites: {}", e));
+        }
+    }
+
+    /// Favorites of the current remote, or all of them when no remote is open.
+    pub fn visible_favorites(&self) -> Vec<&Favorite> {
+        let remote = self.current_remote();
+        self.favorites
+            .iter()
+            .filter(|f| remote.is_none_or(|r| f.remote == r))
+            .collect()
+    }
+
+    fn selected_favorite(&self) -> Option<Favorite> {
+        let idx = self.favorites_state.selected()?;
+        self.visible_favorites().get(idx).map(|f| (*f).clone())
+    }
+
+    pub fn toggle_favorites(&mut self) {
+        self.show_favorites = !self.show_favorites;
+        let empty = self.visible_favorites().is_empty();
+        self.favorites_state.select(if empty { None } else { Some(0) });
+    }
+
+    pub fn favorites_move_up(&mut self) {
+        let i = self.favorites_state.selected().unwrap_or(0);
+        if i > 0 {
+            self.favorites_state.select(Some(i - 1));
+        }
+    }
+
+    pub fn favorites_move_down(&mut self) {
+        let i = self.favorites_state.selected().unwrap_or(0);
+        if i + 1 < self.visible_favorites().len() {
+            self.favorites_state.select(Some(i + 1));
+        }
+    }
+
+    /// Jump to the selected favorite in the browser; a pinned prefix is opened.
+    pub async fn open_favorite(&mut self) {
+        let Some(fav) = self.selected_favorite() else {
+            return;
+        };
+        self.show_favorites = false;
+        if fav.is_dir() {
+            self.open_prefix(&fav.remote, &fav.bucket, &fav.key).await;
+        } else {
+            self.reveal_object(&fav.remote, &fav.bucket, &fav.key).await;
+        }
+    }
+
+    /// Jump to the selected favorite and open its preview.
+    pub async fn preview_favorite(&mut self) {
+        let errors = self.toasts.error_count();
+        let is_dir = self.selected_favorite().is_some_and(|f| f.is_dir());
+        self.open_favorite().await;
+        if !is_dir && self.toasts.error_count() == errors {
+            self.request_preview();
+        }
+    }
+
+    /// Download the selected favorite straight into the current local directory.
+    pub fn download_favorite(&mut self) {
+        let Some(fav) = self.selected_favorite() else {
+            return;
+        };
+        self.show_favorites = false;
+        let name = fav.name().to_string();
+        self.rerun_download(DownloadSpec {
+            dest: self.local_path.join(&name),
+            name,
+            is_dir: fav.is_dir(),
+            remote: fav.remote,
+            bucket: fav.bucket,
+            key: fav.key,
+            range: None,
+        });
+    }
+
+    /// Copy a presigned URL for the selected favorite to the clipboard.
+    pub async fn share_favorite(&mut self) {
+        let Some(fav) = self.selected_favorite() else {
+            return;
+        };
+        if fav.is_dir() {
+            self.notify("Links can only be shared for objects");
+            return;
+        }
+        if let Err(e) = self.ensure_client(&fav.remote) {
+            self.notify_error(format!("Connection failed: {}", e));
+            return;
+        }
+        let client = self.clients[&fav.remote].clone();
+        match client.presign_get_object(&fav.bucket, &fav.key).await {
+            Ok(url) => {
+                let (url, failed) = super::links::share_url(self.settings.shortener.as_ref(), url).await;
+                let _ = crate::clipboard::copy(&url);
+                self.notify(format!("Copied link to {}", fav.name()));
+                if let Some(e) = failed {
+                    self.notify_error(format!("Shortener failed, copied full link: {}", e));
+                }
+            }
+            Err(e) => self.notify_error(format!("Presign failed: {}", e)),
+        }
+    }
+
+    /// Unpin the selected favorite from the panel.
+    pub fn remove_favorite(&mut self) {
+        let Some(fav) = self.selected_favorite() else {
+            return;
+        };
+        self.favorites.retain(|f| *f != fav);
+        self.save_favorites();
+        self.clamp_remote_selection();
+        let len = self.visible_favorites().len();
+        self.favorites_state.select(match self.favorites_state.selected() {
+            _ if len == 0 => None,
+            Some(i) => Some(i.min(len - 1)),
+            None => Some(0),
+        });
+    }
+
+    /// List or unlist the selected pinned prefix in the Remotes pane (`a`).
+    pub fn toggle_promoted(&mut self) {
+        let Some(fav) = self.selected_favorite() else {
+            return;
+        };
+        if !fav.is_dir() {
+            self.notify("Only pinned directories can be listed as remotes");
+            return;
+        }
+        let Some(pinned) = self.favorites.iter_mut().find(|f| f.same_place(&fav)) else {
+            return;
+        };
+        pinned.promoted = !pinned.promoted;
+        let promoted = pinned.promoted;
+        self.notify(if promoted {
+            format!("Listed {} under remotes", fav.alias_label())
+        } else {
+            format!("Removed {} from remotes", fav.alias_label())
+        });
+        self.save_favorites();
+        self.clamp_remote_selection();
+    }
+
+    /// Pinned prefixes listed in the Remotes pane, after the mc aliases.
+    pub fn promoted_prefixes(&self) -> Vec<&Favorite> {
+        self.favorites.iter().filter(|f| f.promoted).collect()
+    }
+
+    /// Rows in the Remotes pane: mc aliases, then promoted prefixes.
+    pub(crate) fn remote_rows(&self) -> usize {
+        self.remotes.len() + self.promoted_prefixes().len()
+    }
+
+    /// Alias behind a Remotes pane row, promoted prefixes included.
+    pub(crate) fn remote_row_alias(&self, row: usize) -> Option<&str> {
+        match self.remotes.get(row) {
+            Some(alias) => Some(alias),
+            None => self
+                .promoted_prefixes()
+                .get(row - self.remotes.len())
+                .map(|f| f.remote.as_str()),
+        }
+    }
+
+    /// Keep the Remotes pane cursor on a row after promoted prefixes go away.
+    fn clamp_remote_selection(&mut self) {
+        let rows = self.remote_rows();
+        self.remote_state.select(match self.remote_state.selected() {
+            _ if rows == 0 => None,
+            Some(i) => Some(i.min(rows - 1)),
+            None => Some(0),
+        });
+    }
+
+    /// Enter the promoted prefix at `row` of the Remotes pane.
+    pub(crate) async fn open_promoted(&mut self, row: usize) {
+        let Some(fav) = row
+            .checked_sub(self.remotes.len())
+            .and_then(|i| self.promoted_prefixes().get(i).map(|f| (*f).clone()))
+        else {
+            return;
+        };
+        self.open_prefix(&fav.remote, &fav.bucket, &fav.key).await;
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::VecDeque;
+
+use crate::object_store::ObjectMetadata;
+
+/// Objects whose metadata is kept before the least recently used are dropped.
+const MAX_ENTRIES: usize = 512;
+
+struct CacheEntry {
+    /// remote/bucket/key.
+    object: String,
+    meta: ObjectMetadata,
+}
+
+/// HEAD results (image info included) of recently selected objects, so selecting
+/// one again or deciding how to preview it needs no round trip. An entry is only
+/// served while its ETag matches the listing's.
+#[derive(Default)]
+pub struct HeadCache {
+    /// Least recently used first.
+    entries: VecDeque<CacheEntry>,
+}
+
+fn object(remote: &str, bucket: &str, key: &str) -> String {
+    format!("{}/{}/{}", remote, bucket, key)
+}
+
+impl HeadCache {
+    /// Metadata of `key`, unless the listing's `etag` says the object has changed.
+    /// A hit becomes the most recently used.
+    pub fn get(&mut self, remote: &str, bucket: &str, key: &str, etag: Option<&str>) -> Option<&ObjectMetadata> {
+        let object = object(remote, bucket, key);
+        let pos = self.entries.iter().position(|e| e.object == object)?;
+        if etag.is_some() && self.entries[pos].meta.etag.as_deref() != etag {
+            return None;
+        }
+        let entry = self.entries.remove(pos)?;
+        self.entries.push_back(entry);
+        self.entries.back().map(|e| &e.meta)
+    }
+
+    /// Store (or refresh) an object's metadata as the most recently used.
+    pub fn insert(&mut self, remote: &str, bucket: &str, meta: ObjectMetadata) {
+        let object = object(remote, bucket, &meta.key);
+        self.entries.retain(|e| e.object != object);
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(CacheEntry { object, meta });
+    }
+
+    /// Forget `key`, or every object under it when it ends in `/` (or is empty for
+    /// the whole bucket), e.g. on refresh or delete.
+    pub fn invalidate(&mut self, remote: &str, bucket: &str, key: &str) {
+        let target = object(remote, bucket, key);
+        if key.is_empty() || key.ends_with('/') {
+            self.entries.retain(|e| !e.object.starts_with(&target));
+        } else {
+            self.entries.retain(|e| e.object != target);
+        }
+    }
+}
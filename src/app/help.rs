@@ -0,0 +1,141 @@
+use super::mode::Mode;
+use super::App;
+
+/// Input context whose keybindings the help popup lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpMode {
+    Normal,
+    Download,
+    Search,
+    Context,
+    QuickView,
+    EntryMenu,
+    Preview,
+    Media,
+    Slideshow,
+    Favorites,
+    History,
+    Plan,
+    CopyPicker,
+    ActionOutput,
+}
+
+impl HelpMode {
+    /// Every mode, in the order `Tab` cycles through them.
+    pub const ALL: [HelpMode; 14] = [
+        HelpMode::Normal,
+        HelpMode::Download,
+        HelpMode::Search,
+        HelpMode::Context,
+        HelpMode::QuickView,
+        HelpMode::EntryMenu,
+        HelpMode::Preview,
+        HelpMode::Media,
+        HelpMode::Slideshow,
+        HelpMode::Favorites,
+        HelpMode::History,
+        HelpMode::Plan,
+        HelpMode::CopyPicker,
+        HelpMode::ActionOutput,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            HelpMode::Normal => "Browser",
+            HelpMode::Download => "Download mode",
+            HelpMode::Search => "Search",
+            HelpMode::Context => "Search result folder",
+            HelpMode::QuickView => "Quick views",
+            HelpMode::EntryMenu => "Entry actions",
+            HelpMode::Preview => "Text preview",
+            HelpMode::Media => "Video / audio preview",
+            HelpMode::Slideshow => "Slideshow",
+            HelpMode::Favorites => "Favorites",
+            HelpMode::History => "Transfer history",
+            HelpMode::Plan => "Plan review",
+            HelpMode::CopyPicker => "Copy / move destination",
+            HelpMode::ActionOutput => "Action output",
+        }
+    }
+
+    /// The bindings that apply to the mode the next key press is handled in.
+    pub fn current(app: &App) -> Self {
+        match app.mode() {
+            Mode::ActionOutput => HelpMode::ActionOutput,
+            Mode::Plan => HelpMode::Plan,
+            Mode::CopyMetadata | Mode::CopyOptions | Mode::CopyPicker => HelpMode::CopyPicker,
+            Mode::DownloadConflict
+            | Mode::RangeInput
+            | Mode::Rename
+            | Mode::SyncSetup
+            | Mode::LocalSearch
+            | Mode::Download => HelpMode::Download,
+            Mode::Slideshow => HelpMode::Slideshow,
+            Mode::Favorites => HelpMode::Favorites,
+            Mode::History => HelpMode::History,
+            Mode::EntryMenu => HelpMode::EntryMenu,
+            Mode::QuickView(_) => HelpMode::QuickView,
+            Mode::Context => HelpMode::Context,
+            Mode::Search => HelpMode::Search,
+            Mode::Media => HelpMode::Media,
+            Mode::TextPreview => HelpMode::Preview,
+            _ => HelpMode::Normal,
+        }
+    }
+}
+
+/// Open help popup: the mode shown and the search typed into it.
+pub struct HelpView {
+    pub mode: HelpMode,
+    /// Non-empty searches the bindings of every mode.
+    pub query: String,
+    pub scroll: usize,
+}
+
+impl App {
+    /// Open help on the bindings of the current mode.
+    pub fn open_help(&mut self) {
+        self.pending_key = None;
+        self.help = Some(HelpView {
+            mode: HelpMode::current(self),
+            query: String::new(),
+            scroll: 0,
+        });
+    }
+
+    /// Show the next (or previous) mode's bindings.
+    pub fn help_cycle_mode(&mut self, forward: bool) {
+        let Some(help) = &mut self.help else {
+            return;
+        };
+        let modes = HelpMode::ALL;
+        let i = modes.iter().position(|m| *m == help.mode).unwrap_or(0);
+        let next = if forward { i + 1 } else { i + modes.len() - 1 };
+        help.mode = modes[next % modes.len()];
+        help.scroll = 0;
+    }
+
+    pub fn help_scroll(&mut self, down: bool, lines: usize) {
+        if let Some(help) = &mut self.help {
+            help.scroll = if down {
+                help.scroll + lines
+            } else {
+                help.scroll.saturating_sub(lines)
+            };
+        }
+    }
+
+    pub fn help_input(&mut self, c: char) {
+        if let Some(help) = &mut self.help {
+            help.query.push(c);
+            help.scroll = 0;
+        }
+    }
+
+    pub fn help_backspace(&mut self) {
+        if let Some(help) = &mut self.help {
+            help.query.pop();
+            help.scroll = 0;
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::object_store::{CopyOptions, Timestamp};
+use crate::storage;
+
+use super::sync::SyncPolicy;
+use super::App;
+
+const HISTORY_FILE: &str = "history.json";
+const MAX_HISTORY: usize = 200;
+
+/// A download: enough to run the same transfer again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadSpec {
+    /// Local file or directory name the object was saved as.
+    pub name: String,
+    pub remote: String,
+    pub bucket: String,
+    pub key: String,
+    pub is_dir: bool,
+    /// Full local destination path.
+    pub dest: PathBuf,
+    /// Byte range `[start, end)` when only part of the object was saved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<(u64, u64)>,
+}
+
+/// A local directory synced up to a bucket prefix. Repeating it plans the sync again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadSpec {
+    /// Local directory name.
+    pub name: String,
+    pub remote: String,
+    pub bucket: String,
+    /// Bucket prefix, ending in `/`.
+    pub prefix: String,
+    pub local: PathBuf,
+    pub policy: SyncPolicy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acl: Option<String>,
+}
+
+/// An object or prefix copied (or moved) to another bucket or remote.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CopySpec {
+    pub name: String,
+    pub moving: bool,
+    pub remote: String,
+    pub bucket: String,
+    pub key: String,
+    pub is_dir: bool,
+    /// Object size from the listing; unused for prefixes.
+    pub size: u64,
+    pub dst_remote: String,
+    pub dst_bucket: String,
+    pub dst_key: String,
+    #[serde(default, skip_serializing_if = "CopyOptions::is_empty")]
+    pub options: CopyOptions,
+}
+
+/// What was transferred and where to. Stored without a tag and told apart by
+/// their fields, so records kept before uploads and copies were still load.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TransferSpec {
+    Download(DownloadSpec),
+    Upload(UploadSpec),
+    Copy(CopySpec),
+}
+
+impl TransferSpec {
+    pub fn name(&self) -> &str {
+        match self {
+            TransferSpec::Download(spec) => &spec.name,
+            TransferSpec::Upload(spec) => &spec.name,
+            TransferSpec::Copy(spec) => &spec.name,
+        }
+    }
+
+    /// Source and destination, as the history panel shows them.
+    pub fn route(&self) -> String {
+        match self {
+            TransferSpec::Download(spec) => {
+                format!("{}:{}/{} -> {}", spec.remote, spec.bucket, spec.key, spec.dest.display())
+            }
+            TransferSpec::Upload(spec) => {
+                format!("{} -> {}:{}/{}", spec.local.display(), spec.remote, spec.bucket, spec.prefix)
+            }
+            TransferSpec::Copy(spec) => format!(
+                "{} {}:{}/{} -> {}:{}/{}",
+                if spec.moving { "move" } else { "copy" },
+                spec.remote,
+                spec.bucket,
+                spec.key,
+                spec.dst_remote,
+                spec.dst_bucket,
+                spec.dst_key
+            ),
+        }
+    }
+}
+
+/// A finished (or failed) transfer with its statistics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferRecord {
+    #[serde(flatten)]
+    pub spec: TransferSpec,
+    pub bytes: u64,
+    pub files: usize,
+    pub duration_secs: f64,
+    /// Unix timestamp (seconds) when the transfer finished.
+    pub finished_at: i64,
+    pub error: Option<String>,
+}
+
+impl TransferRecord {
+    pub fn avg_speed_bps(&self) -> f64 {
+        if self.duration_secs > 0.0 {
+            self.bytes as f64 / self.duration_secs
+        } else {
+            0.0
+        }
+    }
+
+    pub fn finished(&self) -> Option<Timestamp> {
+        chrono::DateTime::from_timestamp(self.finished_at, 0)
+    }
+}
+
+/// Unix seconds now, for `finished_at`.
+pub(crate) fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+pub(crate) fn load_history() -> Vec<TransferRecord> {
+    storage::load_json(HISTORY_FILE)
+}
+
+impl App {
+    pub(crate) fn record_transfer(&mut self, record: TransferRecord) {
+        self.history.push(record);
+        if self.history.len() > MAX_HISTORY {
+            let excess = self.history.len() - MAX_HISTORY;
+            self.history.drain(..excess);
+        }
+        if self.settings.persist_history
+            && let Err(e) = storage::save_json(HISTORY_FILE, &self.history)
+        {
+            self.notify_error(format!("Failed to save history: {}", e));
+        }
+    }
+
+    /// Run a recorded transfer again with the same source and destination.
+    pub async fn rerun_transfer(&mut self, spec: TransferSpec) {
+        match spec {
+            TransferSpec::Download(spec) => self.rerun_download(spec),
+            TransferSpec::Upload(spec) => self.repeat_upload(spec),
+            TransferSpec::Copy(spec) => self.repeat_copy(spec).await,
+        }
+    }
+
+    pub(crate) fn rerun_download(&mut self, spec: DownloadSpec) {
+        if self.download_rx.is_some() {
+            self.notify_warn("A transfer is already running");
+            return;
+        }
+        if let Err(e) = self.ensure_client(&spec.remote) {
+            self.notify_error(format!("Connection failed: {}", e));
+            return;
+        }
+        let client = self.clients[&spec.remote].clone();
+        self.start_transfer(client, spec);
+    }
+
+    /// Repeat the most recent transfer (`.`).
+    pub async fn repeat_last_transfer(&mut self) {
+        match self.history.last() {
+            Some(record) => self.rerun_transfer(record.spec.clone()).await,
+            None => self.notify("No transfer to repeat"),
+        }
+    }
+
+    /// Re-run the transfer selected in the history panel and close it.
+    pub async fn rerun_selected_history(&mut self) {
+        if let Some(record) = self.selected_history() {
+            let spec = record.spec.clone();
+            self.show_history = false;
+            self.rerun_transfer(spec).await;
+        }
+    }
+
+    pub fn toggle_history(&mut self) {
+        self.show_history = !self.show_history;
+        self.history_state.select(if self.history.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+
+    /// The record under the cursor (the panel lists newest first).
+    pub fn selected_history(&self) -> Option<&TransferRecord> {
+        let idx = self.history_state.selected()?;
+        self.history.iter().rev().nth(idx)
+    }
+
+    pub fn history_move_up(&mut self) {
+        let i = self.history_state.selected().unwrap_or(0);
+        if i > 0 {
+            self.history_state.select(Some(i - 1));
+        }
+    }
+
+    pub fn history_move_down(&mut self) {
+        let i = self.history_state.selected().unwrap_or(0);
+        if i + 1 < self.history.len() {
+            self.history_state.select(Some(i + 1));
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use crate::hooks::{HooksConfig, Op};
+use crate::object_store::Store;
+
+use super::App;
+
+/// Keys a prefix operation touches, listed only when a hook for `op` will receive them.
+pub(crate) async fn affected_keys(
+    hooks: &HooksConfig,
+    op: Op,
+    client: &Store,
+    bucket: &str,
+    key: &str,
+    is_dir: bool,
+) -> Vec<String> {
+    if !is_dir || !(hooks.has(true, op) || hooks.has(false, op)) {
+        return vec![key.to_string()];
+    }
+    match client.list_all_keys(bucket, key).await {
+        Ok(objects) => objects.into_iter().map(|o| o.key).collect(),
+        // The hook still learns which prefix was touched
+        Err(_) => vec![key.to_string()],
+    }
+}
+
+impl App {
+    /// Report `post_*` hook failures from background transfers. Call every tick.
+    pub fn drain_hooks(&mut self) {
+        while let Ok(message) = self.hook_rx.try_recv() {
+            self.notify_warn(message);
+        }
+    }
+}
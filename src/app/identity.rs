@@ -0,0 +1,73 @@
+use crate::provider::Provider;
+
+use super::{App, Location};
+
+/// Who the app talks to as a remote: where its requests go and whose key signs
+/// them, so similar aliases can be told apart before acting on one.
+pub struct Identity {
+    pub alias: String,
+    pub endpoint: String,
+    /// Access key id, or where it comes from; `None` for anonymous and local remotes.
+    pub access_key: Option<String>,
+    /// Profile whose keys are in use, `None` for the alias's own.
+    pub profile: Option<String>,
+    /// Provider and whether the alias names it or it was guessed from the URL.
+    pub provider: &'static str,
+    pub provider_guessed: bool,
+    pub region: Option<String>,
+}
+
+impl Identity {
+    /// Endpoint host without scheme or path, short enough for the title bar.
+    pub fn host(&self) -> &str {
+        let rest = self.endpoint.split_once("://").map_or(self.endpoint.as_str(), |(_, r)| r);
+        rest.split('/').next().filter(|h| !h.is_empty()).unwrap_or(rest)
+    }
+}
+
+impl App {
+    /// The identity of `alias` as configured.
+    pub fn identity(&self, alias: &str) -> Option<Identity> {
+        let config = self.alias_config(alias)?;
+        let local = config.backend.as_deref() == Some("local");
+        let (provider, provider_guessed) = if local {
+            ("local directory", false)
+        } else {
+            match config.provider.as_deref().and_then(Provider::named) {
+                Some(provider) => (provider.label(), false),
+                None => (Provider::resolve(None, &config.url).label(), true),
+            }
+        };
+        Some(Identity {
+            alias: alias.to_string(),
+            endpoint: config.url.clone(),
+            access_key: (!local && !config.is_anonymous()).then(|| match &config.credential_process {
+                Some(command) => format!("from `{}`", command),
+                None => config.access_key.clone(),
+            }),
+            profile: self.active_profile(alias).map(str::to_string),
+            provider,
+            provider_guessed,
+            region: config.region.clone(),
+        })
+    }
+
+    /// The remote browsed now, or the one under the cursor in the remote list.
+    fn focused_remote(&self) -> Option<&str> {
+        match &self.location {
+            Location::RemoteList => self.remote_row_alias(self.remote_state.selected()?),
+            _ => self.current_remote(),
+        }
+    }
+
+    /// `w`: show which endpoint and key the focused remote uses.
+    pub fn toggle_identity(&mut self) {
+        if self.identity_popup.take().is_some() {
+            return;
+        }
+        match self.focused_remote().and_then(|r| self.identity(r)) {
+            Some(identity) => self.identity_popup = Some(identity),
+            None => self.notify("No remote selected"),
+        }
+    }
+}
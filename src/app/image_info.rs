@@ -0,0 +1,75 @@
+use std::io::Cursor;
+
+use exif::{In, Tag, Value};
+
+/// Bytes fetched from the start of an image; enough for the header and EXIF block.
+pub const HEADER_BYTES: u64 = 128 * 1024;
+
+/// Dimensions, format and the interesting EXIF fields from an image header,
+/// as (label, value) pairs for the metadata panel.
+pub fn describe(header: &[u8]) -> Vec<(String, String)> {
+    let mut info = Vec::new();
+
+    if let Ok(kind) = imagesize::image_type(header) {
+        info.push(("Format".to_string(), format!("{:?}", kind)));
+    }
+    if let Ok(size) = imagesize::blob_size(header) {
+        info.push(("Dimensions".to_string(), format!("{}x{}", size.width, size.height)));
+    }
+
+    let Ok(exif) = exif::Reader::new().read_from_container(&mut Cursor::new(header)) else {
+        return info;
+    };
+    let field = |tag| {
+        exif.get_field(tag, In::PRIMARY)
+            .map(|f| f.display_value().with_unit(&exif).to_string().trim_matches('"').to_string())
+    };
+
+    if let Some(date) = field(Tag::DateTimeOriginal).or_else(|| field(Tag::DateTime)) {
+        info.push(("Taken".to_string(), date));
+    }
+    let camera = [field(Tag::Make), field(Tag::Model)]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if !camera.is_empty() {
+        info.push(("Camera".to_string(), camera));
+    }
+    let exposure = [
+        field(Tag::FNumber),
+        field(Tag::ExposureTime),
+        field(Tag::PhotographicSensitivity).map(|iso| format!("ISO {}", iso)),
+        field(Tag::FocalLength),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join("  ");
+    if !exposure.is_empty() {
+        info.push(("Exposure".to_string(), exposure));
+    }
+    if let (Some(lat), Some(lon)) = (
+        gps_coord(&exif, Tag::GPSLatitude, Tag::GPSLatitudeRef),
+        gps_coord(&exif, Tag::GPSLongitude, Tag::GPSLongitudeRef),
+    ) {
+        info.push(("GPS".to_string(), format!("{:.6}, {:.6}", lat, lon)));
+    }
+    info
+}
+
+/// Degrees/minutes/seconds plus N/S/E/W reference as a signed decimal degree.
+fn gps_coord(exif: &exif::Exif, tag: Tag, ref_tag: Tag) -> Option<f64> {
+    let Value::Rational(dms) = &exif.get_field(tag, In::PRIMARY)?.value else {
+        return None;
+    };
+    let degrees = dms
+        .iter()
+        .zip([1.0, 60.0, 3600.0])
+        .map(|(r, div)| r.to_f64() / div)
+        .sum::<f64>();
+    let negative = exif
+        .get_field(ref_tag, In::PRIMARY)
+        .is_some_and(|f| matches!(f.display_value().to_string().as_str(), "S" | "W"));
+    Some(if negative { -degrees } else { degrees })
+}
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use chrono::Timelike;
+use tokio::sync::mpsc::{self, error::TryRecvError};
+
+use crate::settings::StatusSegment;
+
+use super::indexing::IndexState;
+use super::App;
+
+/// Receiving end of a background task's channel that the event loop can wait on.
+/// Waiting holds the message back for the next `try_recv`, so the drain functions see
+/// it exactly as they would have without the wait.
+pub struct Inbox<T> {
+    rx: mpsc::Receiver<T>,
+    peeked: Option<T>,
+    /// Set once the closed channel has woken the loop; later waits never finish.
+    closed: bool,
+}
+
+impl<T> From<mpsc::Receiver<T>> for Inbox<T> {
+    fn from(rx: mpsc::Receiver<T>) -> Self {
+        Self {
+            rx,
+            peeked: None,
+            closed: false,
+        }
+    }
+}
+
+impl<T> Inbox<T> {
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        match self.peeked.take() {
+            Some(msg) => Ok(msg),
+            None => self.rx.try_recv(),
+        }
+    }
+
+    /// Finish when a message is waiting, or once when the senders are gone.
+    pub async fn ready(&mut self) {
+        if self.peeked.is_some() {
+            return;
+        }
+        if self.closed {
+            return std::future::pending().await;
+        }
+        match self.rx.recv().await {
+            Some(msg) => self.peeked = Some(msg),
+            None => self.closed = true,
+        }
+    }
+}
+
+/// [`Inbox::ready`] for a channel that may not be open.
+pub async fn ready<T>(inbox: &mut Option<Inbox<T>>) {
+    match inbox {
+        Some(inbox) => inbox.ready().await,
+        None => std::future::pending().await,
+    }
+}
+
+impl App {
+    /// Wait until any background task has sent something (or finished).
+    pub async fn next_message(&mut self) {
+        let slideshow = async {
+            match &mut self.slideshow {
+                Some(show) => show.url_rx.ready().await,
+                None => std::future::pending().await,
+            }
+        };
+        // A paused index isn't read, so its messages mustn't wake the loop either
+        let mut paused_index = None;
+        let index_rx = if self.index_state == IndexState::Paused {
+            &mut paused_index
+        } else {
+            &mut self.index_rx
+        };
+        tokio::select! {
+            _ = ready(index_rx) => {}
+            _ = ready(&mut self.download_rx) => {}
+            _ = ready(&mut self.preview.rx) => {}
+            _ = ready(&mut self.plan_rx) => {}
+            _ = ready(&mut self.copy_rx) => {}
+            _ = ready(&mut self.delete_rx) => {}
+            _ = ready(&mut self.inventory_rx) => {}
+            _ = ready(&mut self.links_rx) => {}
+            _ = ready(&mut self.action_rx) => {}
+            _ = self.plugin_rx.ready() => {}
+            _ = self.hook_rx.ready() => {}
+            _ = self.prewarm_rx.ready() => {}
+            _ = self.prefetch.rx.ready() => {}
+            _ = self.expiry_watch.rx.ready() => {}
+            _ = slideshow => {}
+        }
+    }
+
+    /// How long the event loop may sleep with nothing arriving before the screen
+    /// changes on its own: a toast expiring, the clock turning over, a player or job exiting,
+    /// the directory under the cursor becoming due for prefetching, or a credentials
+    /// countdown ticking over.
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        let clock = self
+            .settings
+            .status_bar
+            .contains(&StatusSegment::Clock)
+            .then(|| Duration::from_secs(60 - u64::from(chrono::Local::now().second())));
+        let player = self.preview.player.as_ref().map(|_| Duration::from_secs(1));
+        // Keeps the index spinner turning between batches
+        let spinner = (self.index_key.is_some() && self.index_state == IndexState::Running)
+            .then(|| Duration::from_millis(250));
+        // Not every job reports when it ends, e.g. one waiting on a viewer window
+        let tasks = (self.settings.status_bar.contains(&StatusSegment::Tasks)
+            && self.tasks.running().next().is_some())
+        .then(|| Duration::from_secs(1));
+        // A stalled transfer sends nothing, but its speed and sparkline should fall
+        let transfer = self.download_rx.is_some().then(|| Duration::from_secs(1));
+        [
+            self.toasts.next_expiry(),
+            clock,
+            player,
+            tasks,
+            spinner,
+            transfer,
+            self.prefetch_due_in(),
+            self.credentials_due_in(),
+        ]
+            .into_iter()
+            .flatten()
+            .min()
+    }
+}
@@ -1,14 +1,110 @@
 use tokio::sync::mpsc;
 
-use crate::s3_client::IndexMsg;
+use crate::object_store::{IndexMsg, ObjectEntry};
 
+use super::tasks::TaskKind;
 use super::App;
 
+/// Spinner shown next to a running index, one frame per redraw tick.
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Where the background index of the open bucket stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexState {
+    Running,
+    /// Not draining the channel; the lister stops once it fills up.
+    Paused,
+    /// Stopped by the user or a listing error; search works on what was indexed so far.
+    Stopped,
+    /// Listed to the end; the index holds every object in the bucket.
+    Complete,
+}
+
 impl App {
     pub fn index_object_count(&self) -> usize {
         self.search_pool.len()
     }
 
+    /// "indexing 12000 (40%)" while the index isn't complete, with a spinner when
+    /// it's running. The percentage comes from an earlier full index of the bucket.
+    pub fn index_progress_label(&self) -> Option<String> {
+        let key = self.index_key.as_ref()?;
+        let count = self.index_object_count();
+        let pct = self
+            .index_totals
+            .get(key)
+            .filter(|total| **total > 0)
+            .map(|total| format!(" ({}%)", (count * 100 / total).min(99)))
+            .unwrap_or_default();
+        match self.index_state {
+            IndexState::Running => {
+                let frame = chrono::Local::now().timestamp_millis() / 250;
+                let spinner = SPINNER[frame as usize % SPINNER.len()];
+                Some(format!("{} indexing {}{}", spinner, count, pct))
+            }
+            IndexState::Paused => Some(format!("index paused at {}{}", count, pct)),
+            IndexState::Stopped => Some(format!("index stopped at {}{}", count, pct)),
+            IndexState::Complete => None,
+        }
+    }
+
+    /// Every indexed object under `prefix` of `bucket`, if the bucket's index is
+    /// complete; lets bulk operations skip listing the prefix again.
+    pub(crate) fn indexed_under(&self, remote: &str, bucket: &str, prefix: &str) -> Option<Vec<ObjectEntry>> {
+        let key = self.index_key.as_ref()?;
+        if self.index_state != IndexState::Complete || key.0 != remote || key.1 != bucket {
+            return None;
+        }
+        Some(
+            self.search_pool
+                .iter()
+                .filter(|o| o.key.starts_with(prefix))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Pause a running index, or resume a paused one.
+    pub fn toggle_index_pause(&mut self) {
+        if self.index_key.is_none() {
+            self.notify("Nothing is being indexed");
+            return;
+        }
+        self.index_state = match self.index_state {
+            IndexState::Running => IndexState::Paused,
+            IndexState::Paused => IndexState::Running,
+            other => other,
+        };
+    }
+
+    /// Answer to the threshold prompt: index the whole bucket after all.
+    pub fn continue_indexing(&mut self) {
+        self.confirm_index = false;
+        if let Some(key) = &self.index_key {
+            self.index_allowed.insert(key.clone());
+        }
+        self.index_state = IndexState::Running;
+    }
+
+    /// Answer to the threshold prompt: keep what's indexed and list no further.
+    pub fn decline_indexing(&mut self) {
+        self.confirm_index = false;
+        self.stop_indexing();
+    }
+
+    /// Stop indexing for good, keeping what was indexed; `r` starts over.
+    pub fn stop_indexing(&mut self) {
+        if !matches!(self.index_state, IndexState::Running | IndexState::Paused) || self.index_key.is_none() {
+            return;
+        }
+        if let Some(task) = self.index_task.take() {
+            self.tasks.cancel(task);
+        }
+        self.index_rx = None;
+        self.index_state = IndexState::Stopped;
+        self.notify(format!("Indexing stopped at {} objects", self.index_object_count()));
+    }
+
     pub(crate) fn start_indexing(&mut self, remote: &str, bucket: &str) {
         let new_key = (remote.to_string(), bucket.to_string());
         if self.index_key.as_ref() == Some(&new_key) {
@@ -17,32 +113,38 @@ impl App {
 
         self.cancel_indexing();
 
-        let (tx, rx) = mpsc::channel(64);
+        // Small, so a paused index stops listing soon after its reader does
+        let (tx, rx) = mpsc::channel(4);
         let client = self.clients[remote].clone();
         let bucket_owned = bucket.to_string();
 
-        let handle = tokio::spawn(async move {
+        let label = format!("Index {}", bucket);
+        let task = self.tasks.spawn(TaskKind::Index, label, async move {
             client.stream_all_objects(&bucket_owned, tx).await;
         });
 
-        self.index_rx = Some(rx);
-        self.index_handle = Some(handle);
+        self.index_rx = Some(rx.into());
+        self.index_task = Some(task);
         self.index_key = Some(new_key);
         self.search_pool.clear();
-        self.index_complete = false;
+        self.index_state = IndexState::Running;
     }
 
     pub(crate) fn cancel_indexing(&mut self) {
-        if let Some(handle) = self.index_handle.take() {
-            handle.abort();
+        if let Some(task) = self.index_task.take() {
+            self.tasks.cancel(task);
         }
         self.index_rx = None;
         self.index_key = None;
-        self.index_complete = false;
+        self.index_state = IndexState::Running;
+        self.confirm_index = false;
         self.search_pool.clear();
     }
 
     pub fn drain_index(&mut self) {
+        if self.index_state == IndexState::Paused {
+            return;
+        }
         let rx = match &mut self.index_rx {
             Some(rx) => rx,
             None => return,
@@ -55,21 +157,34 @@ impl App {
                 Ok(IndexMsg::Batch(batch)) => {
                     self.search_pool.extend(batch);
                     got_new = true;
+                    // Past the threshold for a bucket not yet allowed: ask before listing more
+                    let limit = self.settings.index_confirm_above;
+                    if limit > 0
+                        && self.search_pool.len() >= limit
+                        && self.index_key.as_ref().is_some_and(|key| !self.index_allowed.contains(key))
+                    {
+                        self.index_state = IndexState::Paused;
+                        self.confirm_index = true;
+                        break;
+                    }
                 }
                 Ok(IndexMsg::Done) => {
-                    self.index_complete = true;
+                    self.index_state = IndexState::Complete;
+                    if let Some(key) = &self.index_key {
+                        self.index_totals.insert(key.clone(), self.search_pool.len());
+                    }
                     break;
                 }
                 Ok(IndexMsg::Error(e)) => {
                     if self.search_active {
-                        self.error = Some(format!("Index error: {}", e));
+                        self.notify_error(format!("Index error: {}", e));
                     }
-                    self.index_complete = true;
+                    self.index_state = IndexState::Stopped;
                     break;
                 }
                 Err(mpsc::error::TryRecvError::Empty) => break,
                 Err(mpsc::error::TryRecvError::Disconnected) => {
-                    self.index_complete = true;
+                    self.index_state = IndexState::Stopped;
                     break;
                 }
             }
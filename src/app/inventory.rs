@@ -0,0 +1,280 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::Result;
+use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use tokio::sync::mpsc;
+
+use crate::object_store::{IndexMsg, ObjectEntry};
+use crate::settings::InventoryFormat;
+
+use super::links::csv_field;
+use super::tasks::TaskKind;
+use super::{App, Location};
+
+/// Objects per Parquet row group.
+const ROW_GROUP: usize = 100_000;
+
+const PARQUET_SCHEMA: &str = "message inventory {
+    REQUIRED BYTE_ARRAY key (UTF8);
+    REQUIRED INT64 size;
+    OPTIONAL BYTE_ARRAY etag (UTF8);
+    OPTIONAL BYTE_ARRAY storage_class (UTF8);
+    OPTIONAL INT64 last_modified (TIMESTAMP(MILLIS,true));
+}";
+
+/// Progress of a report being written, sent from the background task to the UI.
+pub enum InventoryMsg {
+    /// Another batch of this many objects is written.
+    Rows(usize),
+    Done,
+    Error(String),
+}
+
+/// A bucket inventory report being written in the background.
+pub struct InventoryProgress {
+    pub bucket: String,
+    pub path: PathBuf,
+    pub rows: usize,
+    /// Objects in the bucket, known from an earlier full index.
+    pub total: Option<usize>,
+    started: Instant,
+}
+
+impl InventoryProgress {
+    /// Objects written per second so far.
+    pub fn rate(&self) -> f64 {
+        self.rows as f64 / self.started.elapsed().as_secs_f64().max(0.01)
+    }
+}
+
+/// Writes report rows as they stream in, in either format.
+enum ReportWriter {
+    Csv(BufWriter<File>),
+    Parquet(Box<ParquetReport>),
+}
+
+/// Rows buffered until a row group is full.
+struct ParquetReport {
+    writer: SerializedFileWriter<BufWriter<File>>,
+    rows: Vec<ObjectEntry>,
+}
+
+/// Values of an optional column, and the definition levels that say which rows have one.
+fn optional<T: Clone>(values: impl Iterator<Item = Option<T>>) -> (Vec<T>, Vec<i16>) {
+    let mut present = Vec::new();
+    let mut levels = Vec::new();
+    for value in values {
+        levels.push(value.is_some() as i16);
+        present.extend(value);
+    }
+    (present, levels)
+}
+
+impl ParquetReport {
+    fn flush(&mut self) -> Result<()> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+        let rows = std::mem::take(&mut self.rows);
+        let mut group = self.writer.next_row_group()?;
+        let mut index = 0;
+        while let Some(mut column) = group.next_column()? {
+            match index {
+                0 => {
+                    let keys: Vec<ByteArray> = rows.iter().map(|o| ByteArray::from(o.key.as_str())).collect();
+                    column.typed::<ByteArrayType>().write_batch(&keys, None, None)?;
+                }
+                1 => {
+                    let sizes: Vec<i64> = rows.iter().map(|o| o.size).collect();
+                    column.typed::<Int64Type>().write_batch(&sizes, None, None)?;
+                }
+                2 | 3 => {
+                    let (values, levels) = optional(rows.iter().map(|o| {
+                        let value = if index == 2 { &o.etag } else { &o.storage_class };
+                        value.as_deref().map(ByteArray::from)
+                    }));
+                    column.typed::<ByteArrayType>().write_batch(&values, Some(&levels), None)?;
+                }
+                _ => {
+                    let (values, levels) = optional(rows.iter().map(|o| o.last_modified.map(|t| t.timestamp_millis())));
+                    column.typed::<Int64Type>().write_batch(&values, Some(&levels), None)?;
+                }
+            }
+            column.close()?;
+            index += 1;
+        }
+        group.close()?;
+        Ok(())
+    }
+}
+
+impl ReportWriter {
+    fn create(path: &Path, format: InventoryFormat) -> Result<Self> {
+        let file = BufWriter::new(File::create(path)?);
+        Ok(match format {
+            InventoryFormat::Csv => {
+                let mut file = file;
+                writeln!(file, "key,size,etag,storage_class,last_modified")?;
+                ReportWriter::Csv(file)
+            }
+            InventoryFormat::Parquet => {
+                let schema = Arc::new(parse_message_type(PARQUET_SCHEMA)?);
+                let properties = Arc::new(WriterProperties::builder().build());
+                ReportWriter::Parquet(Box::new(ParquetReport {
+                    writer: SerializedFileWriter::new(file, schema, properties)?,
+                    rows: Vec::new(),
+                }))
+            }
+        })
+    }
+
+    fn write(&mut self, batch: Vec<ObjectEntry>) -> Result<()> {
+        match self {
+            ReportWriter::Csv(file) => {
+                for o in batch {
+                    writeln!(
+                        file,
+                        "{},{},{},{},{}",
+                        csv_field(&o.key),
+                        o.size,
+                        csv_field(o.etag.as_deref().unwrap_or("")),
+                        o.storage_class.as_deref().unwrap_or(""),
+                        o.last_modified
+                            .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+                            .unwrap_or_default()
+                    )?;
+                }
+            }
+            ReportWriter::Parquet(report) => {
+                report.rows.extend(batch);
+                if report.rows.len() >= ROW_GROUP {
+                    report.flush()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            ReportWriter::Csv(mut file) => file.flush()?,
+            ReportWriter::Parquet(mut report) => {
+                report.flush()?;
+                report.writer.close()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl App {
+    /// Walk the open bucket with the streaming lister and write every object to
+    /// `s3-inventory-<bucket>-<time>.csv` (or `.parquet`) in the local directory.
+    pub fn start_inventory(&mut self) {
+        if self.inventory_rx.is_some() {
+            self.notify_warn("An inventory report is already being written");
+            return;
+        }
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            self.notify_warn("Navigate into a bucket first");
+            return;
+        };
+        let Some(client) = self.clients.get(remote).cloned() else {
+            return;
+        };
+        let format = self.settings.inventory_format;
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let ext = match format {
+            InventoryFormat::Csv => "csv",
+            InventoryFormat::Parquet => "parquet",
+        };
+        let path = self.local_path.join(format!("s3-inventory-{}-{}.{}", bucket, secs, ext));
+        self.inventory = Some(InventoryProgress {
+            bucket: bucket.clone(),
+            path: path.clone(),
+            rows: 0,
+            total: self.index_totals.get(&(remote.clone(), bucket.clone())).copied(),
+            started: Instant::now(),
+        });
+        let (tx, rx) = mpsc::channel(16);
+        self.inventory_rx = Some(rx.into());
+
+        let bucket = bucket.clone();
+        let label = format!("Inventory {}", bucket);
+        self.tasks.spawn(TaskKind::Inventory, label, async move {
+            // Written under a temporary name, so an aborted report never looks complete
+            let part = PathBuf::from(format!("{}.part", path.display()));
+            let (objects_tx, mut objects_rx) = mpsc::channel(4);
+            let list = client.stream_all_objects(&bucket, objects_tx);
+            let write = async {
+                let mut writer = ReportWriter::create(&part, format)?;
+                loop {
+                    match objects_rx.recv().await {
+                        Some(IndexMsg::Batch(batch)) => {
+                            let rows = batch.len();
+                            writer.write(batch)?;
+                            let _ = tx.send(InventoryMsg::Rows(rows)).await;
+                        }
+                        Some(IndexMsg::Done) => break,
+                        Some(IndexMsg::Error(e)) => anyhow::bail!(e),
+                        None => anyhow::bail!("listing ended early"),
+                    }
+                }
+                writer.finish()?;
+                std::fs::rename(&part, &path)?;
+                anyhow::Ok(())
+            };
+            let ((), result) = tokio::join!(list, write);
+            let msg = match result {
+                Ok(()) => InventoryMsg::Done,
+                Err(e) => {
+                    let _ = std::fs::remove_file(&part);
+                    InventoryMsg::Error(e.to_string())
+                }
+            };
+            let _ = tx.send(msg).await;
+        });
+    }
+
+    /// Apply progress of a report being written, and its result. Call every tick.
+    pub fn drain_inventory(&mut self) {
+        let Some(rx) = &mut self.inventory_rx else {
+            return;
+        };
+        let result = loop {
+            match rx.try_recv() {
+                Ok(InventoryMsg::Rows(rows)) => {
+                    if let Some(progress) = &mut self.inventory {
+                        progress.rows += rows;
+                    }
+                }
+                Ok(InventoryMsg::Done) => break Ok(()),
+                Ok(InventoryMsg::Error(e)) => break Err(e),
+                Err(mpsc::error::TryRecvError::Empty) => return,
+                Err(mpsc::error::TryRecvError::Disconnected) => break Err("inventory task ended early".to_string()),
+            }
+        };
+        self.inventory_rx = None;
+        let Some(progress) = self.inventory.take() else {
+            return;
+        };
+        match result {
+            Ok(()) => self.notify(format!(
+                "Inventory of {} ({} objects) written to {}",
+                progress.bucket,
+                progress.rows,
+                progress.path.display()
+            )),
+            Err(e) => self.notify_error(format!("Inventory of {} failed: {}", progress.bucket, e)),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use ratatui::widgets::ListState;
+
+use super::App;
+
+/// Prefixes the jump list remembers.
+const MAX_JUMPS: usize = 50;
+
+/// A prefix visited this session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Jump {
+    pub remote: String,
+    pub bucket: String,
+    pub prefix: String,
+}
+
+impl Jump {
+    pub fn label(&self) -> String {
+        format!("{}/{}/{}", self.remote, self.bucket, self.prefix)
+    }
+}
+
+/// Jump list popup (`J`), filtered by what is typed.
+pub struct JumpPicker {
+    pub query: String,
+    pub state: ListState,
+}
+
+/// Whether `query`'s characters appear in `text` in order, ignoring case.
+fn fuzzy_matches(text: &str, query: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    query.chars().flat_map(char::to_lowercase).all(|q| text.any(|c| c == q))
+}
+
+impl App {
+    /// Put a prefix just entered at the top of the jump list.
+    pub(crate) fn record_jump(&mut self, remote: &str, bucket: &str, prefix: &str) {
+        let jump = Jump {
+            remote: remote.to_string(),
+            bucket: bucket.to_string(),
+            prefix: prefix.to_string(),
+        };
+        self.jumps.retain(|j| *j != jump);
+        self.jumps.push_front(jump);
+        self.jumps.truncate(MAX_JUMPS);
+    }
+
+    /// `J`: open the jump list on the prefix visited before this one.
+    pub fn open_jumps(&mut self) {
+        if self.jumps.is_empty() {
+            self.notify("No prefixes visited yet");
+            return;
+        }
+        let mut state = ListState::default();
+        state.select(Some(usize::from(self.jumps.len() > 1)));
+        self.jump_picker = Some(JumpPicker {
+            query: String::new(),
+            state,
+        });
+    }
+
+    /// Visited prefixes matching the picker's query, most recent first.
+    pub fn jump_items(&self) -> Vec<&Jump> {
+        let query = self.jump_picker.as_ref().map(|p| p.query.as_str()).unwrap_or_default();
+        self.jumps.iter().filter(|j| fuzzy_matches(&j.label(), query)).collect()
+    }
+
+    pub fn jump_input(&mut self, c: char) {
+        if let Some(picker) = &mut self.jump_picker {
+            picker.query.push(c);
+            picker.state.select(Some(0));
+        }
+    }
+
+    pub fn jump_backspace(&mut self) {
+        if let Some(picker) = &mut self.jump_picker {
+            picker.query.pop();
+            picker.state.select(Some(0));
+        }
+    }
+
+    pub fn jump_move(&mut self, down: bool) {
+        let count = self.jump_items().len();
+        if let Some(picker) = &mut self.jump_picker {
+            let i = picker.state.selected().unwrap_or(0);
+            let next = if down { (i + 1).min(count.saturating_sub(1)) } else { i.saturating_sub(1) };
+            picker.state.select(Some(next));
+        }
+    }
+
+    /// Reopen the highlighted prefix.
+    pub async fn open_jump(&mut self) {
+        let selected = self.jump_picker.as_ref().and_then(|p| p.state.selected());
+        let Some(jump) = selected.and_then(|i| self.jump_items().get(i).map(|j| (*j).clone())) else {
+            return;
+        };
+        self.jump_picker = None;
+        self.open_prefix(&jump.remote, &jump.bucket, &jump.prefix).await;
+    }
+}
@@ -0,0 +1,250 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+use tokio::sync::mpsc;
+
+use crate::s3_client::encode_key;
+use crate::shortener::{self, ShortenerConfig};
+
+use super::cli::shell_arg;
+use super::tasks::TaskKind;
+use super::{App, Entry, Location};
+
+/// How the selected object's key is put on the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyForm {
+    /// The key as it is.
+    Key,
+    /// `s3://bucket/key`.
+    Uri,
+    /// The key quoted for a POSIX shell when it needs it.
+    Shell,
+    /// The object's path-style URL on the endpoint, the key percent-encoded.
+    Url,
+}
+
+/// Where a batch of presigned links goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkTarget {
+    /// One URL per line on the clipboard.
+    Clipboard,
+    /// `key,size,url` CSV in the local directory.
+    Csv,
+}
+
+/// Objects waiting for a destination choice before their links are generated.
+pub struct LinkBundle {
+    pub remote: String,
+    pub bucket: String,
+    /// (key, size)
+    pub objects: Vec<(String, i64)>,
+}
+
+/// Generated links, sent back from the background task.
+pub struct LinkDone {
+    pub target: LinkTarget,
+    /// (key, size, url)
+    pub links: Vec<(String, i64, String)>,
+    /// Links the shortener failed on; the full URL is used for those.
+    pub unshortened: usize,
+}
+
+/// Run `url` through the configured shortener, if any. Falls back to the full
+/// URL on failure, returning the error alongside it.
+pub(crate) async fn share_url(
+    config: Option<&ShortenerConfig>,
+    url: String,
+) -> (String, Option<String>) {
+    let Some(config) = config else {
+        return (url, None);
+    };
+    match shortener::shorten(config, &url).await {
+        Ok(short) => (short, None),
+        Err(e) => (url, Some(e.to_string())),
+    }
+}
+
+/// Quote a CSV field when it contains a separator, quote or newline.
+pub(crate) fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+impl App {
+    /// Mark or unmark the selected object for batch actions.
+    pub fn toggle_mark(&mut self) {
+        let Some(Entry::Object(obj)) = self.browser_state.selected().and_then(|i| self.entries.get(i))
+        else {
+            return;
+        };
+        if obj.is_dir {
+            return;
+        }
+        if !self.marked.remove(&obj.key) {
+            self.marked.insert(obj.key.clone());
+        }
+        self.move_down();
+    }
+
+    /// Copy the selected object's key (`yk`) or `s3://bucket/key` URI (`yu`) to the clipboard.
+    pub fn yank_key(&mut self, form: KeyForm) {
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            return;
+        };
+        let Some(Entry::Object(obj)) = self.browser_state.selected().and_then(|i| self.entries.get(i))
+        else {
+            return;
+        };
+        let text = match form {
+            KeyForm::Key => obj.key.clone(),
+            KeyForm::Uri => format!("s3://{}/{}", bucket, obj.key),
+            KeyForm::Shell => shell_arg(&obj.key),
+            KeyForm::Url => {
+                let url = self.clients.get(remote).and_then(|c| c.object_url(bucket, &obj.key));
+                // No web endpoint to point at, e.g. a local remote
+                url.unwrap_or_else(|| encode_key(&obj.key))
+            }
+        };
+        match crate::clipboard::copy(&text) {
+            Ok(()) => self.notify(format!("Copied {}", text)),
+            Err(e) => self.notify_error(format!("Clipboard failed: {}", e)),
+        }
+    }
+
+    pub fn is_marked(&self, key: &str) -> bool {
+        self.marked.contains(key)
+    }
+
+    /// Ask where to put presigned links for the marked objects (or the selected one).
+    pub fn start_link_bundle(&mut self) {
+        if self.links_rx.is_some() {
+            self.notify_warn("Links are already being generated");
+            return;
+        }
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            self.notify_warn("Navigate into a bucket first");
+            return;
+        };
+        let selected = self.browser_state.selected().and_then(|i| self.entries.get(i));
+        let objects: Vec<(String, i64)> = self
+            .entries
+            .iter()
+            .filter_map(|e| match e {
+                Entry::Object(obj)
+                    if !obj.is_dir
+                        && (self.marked.contains(&obj.key)
+                            || self.marked.is_empty()
+                                && selected.is_some_and(|s| s.key() == obj.key)) =>
+                {
+                    Some((obj.key.clone(), obj.size))
+                }
+                _ => None,
+            })
+            .collect();
+        if objects.is_empty() {
+            self.notify("Mark objects with Space first");
+            return;
+        }
+        self.link_bundle = Some(LinkBundle {
+            remote: remote.clone(),
+            bucket: bucket.clone(),
+            objects,
+        });
+    }
+
+    /// Presign every object of the bundle in the background.
+    pub fn generate_links(&mut self, target: LinkTarget) {
+        let Some(bundle) = self.link_bundle.take() else {
+            return;
+        };
+        let Some(client) = self.clients.get(&bundle.remote).cloned() else {
+            self.notify_error("Not connected to remote");
+            return;
+        };
+        let shortener = self.settings.shortener.clone();
+        let (tx, rx) = mpsc::channel(1);
+        self.links_rx = Some(rx.into());
+        self.notify(format!("Presigning {} objects...", bundle.objects.len()));
+        let label = format!("Presign {} links", bundle.objects.len());
+        self.tasks.spawn(TaskKind::Links, label, async move {
+            let mut links = Vec::with_capacity(bundle.objects.len());
+            let mut unshortened = 0;
+            let mut result = Ok(());
+            for (key, size) in bundle.objects {
+                match client.presign_get_object(&bundle.bucket, &key).await {
+                    Ok(url) => {
+                        let (url, failed) = share_url(shortener.as_ref(), url).await;
+                        unshortened += failed.is_some() as usize;
+                        links.push((key, size, url));
+                    }
+                    Err(e) => {
+                        result = Err(format!("{}: {}", key, e));
+                        break;
+                    }
+                }
+            }
+            let _ = tx
+                .send(result.map(|()| LinkDone {
+                    target,
+                    links,
+                    unshortened,
+                }))
+                .await;
+        });
+    }
+
+    /// Deliver generated links. Call every tick.
+    pub fn drain_links(&mut self) {
+        let Some(rx) = &mut self.links_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(done)) => {
+                let count = done.links.len();
+                match done.target {
+                    LinkTarget::Clipboard => {
+                        let text: Vec<&str> = done.links.iter().map(|(_, _, url)| url.as_str()).collect();
+                        match crate::clipboard::copy(&text.join("\n")) {
+                            Ok(()) => self.notify(format!("Copied {} links", count)),
+                            Err(e) => self.notify_error(format!("Clipboard failed: {}", e)),
+                        }
+                    }
+                    LinkTarget::Csv => match self.write_links_csv(&done.links) {
+                        Ok(path) => {
+                            self.notify(format!("Wrote {} links to {}", count, path.display()))
+                        }
+                        Err(e) => self.notify_error(format!("Export failed: {}", e)),
+                    },
+                }
+                if done.unshortened > 0 {
+                    self.notify_warn(format!(
+                        "Shortener failed for {} links; full URLs used",
+                        done.unshortened
+                    ));
+                }
+                self.marked.clear();
+            }
+            Ok(Err(e)) => self.notify_error(format!("Presign failed: {}", e)),
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => {}
+        }
+        self.links_rx = None;
+    }
+
+    fn write_links_csv(&self, links: &[(String, i64, String)]) -> std::io::Result<PathBuf> {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let path = self.local_path.join(format!("s3-links-{}.csv", secs));
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+        writeln!(file, "key,size,url")?;
+        for (key, size, url) in links {
+            writeln!(file, "{},{},{}", csv_field(key), size, csv_field(url))?;
+        }
+        file.flush()?;
+        Ok(path)
+    }
+}
@@ -0,0 +1,342 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::object_store::ObjectEntry;
+use crate::storage;
+
+use super::{App, Entry, Location};
+
+const LISTING_PREFS_FILE: &str = "listing_prefs.json";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    #[default]
+    Name,
+    Size,
+    Modified,
+}
+
+impl SortKey {
+    pub fn label(self) -> &'static str {
+        match self {
+            SortKey::Name => "name",
+            SortKey::Size => "size",
+            SortKey::Modified => "date",
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            SortKey::Name => SortKey::Size,
+            SortKey::Size => SortKey::Modified,
+            SortKey::Modified => SortKey::Name,
+        }
+    }
+}
+
+/// How complete and current the listing on screen is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    Live,
+    /// Only the first page: the prefix holds more than one request lists.
+    Partial,
+    /// Buckets from the cache or a prefix listed ahead, at this time.
+    Cached(Instant),
+}
+
+impl Freshness {
+    /// `Cached` unless `at` is just now.
+    pub(crate) fn since(at: Instant) -> Self {
+        if at.elapsed().as_secs() < 1 { Freshness::Live } else { Freshness::Cached(at) }
+    }
+
+    /// Title badge: "partial", "cached 2m ago", or nothing for a live listing.
+    pub fn badge(self) -> Option<String> {
+        match self {
+            Freshness::Live => None,
+            Freshness::Partial => Some("partial".to_string()),
+            Freshness::Cached(at) => {
+                let secs = at.elapsed().as_secs();
+                let age = match secs {
+                    0..60 => format!("{}s", secs),
+                    60..3600 => format!("{}m", secs / 60),
+                    _ => format!("{}h", secs / 3600),
+                };
+                Some(format!("cached {} ago", age))
+            }
+        }
+    }
+}
+
+/// Sort order and name filter for a listing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListingPrefs {
+    pub sort: SortKey,
+    #[serde(default)]
+    pub descending: bool,
+    /// Only entries whose name contains this (case-insensitive) are listed.
+    #[serde(default)]
+    pub filter: Option<String>,
+    /// Compare digit runs by value, so `file2` sorts before `file10`.
+    #[serde(default)]
+    pub natural: bool,
+    /// List directories before files regardless of the sort column.
+    #[serde(default = "default_true")]
+    pub dirs_first: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for ListingPrefs {
+    fn default() -> Self {
+        Self {
+            sort: SortKey::Name,
+            descending: false,
+            filter: None,
+            natural: false,
+            dirs_first: true,
+        }
+    }
+}
+
+/// Version-aware comparison: digit runs compare by numeric value, the rest case-insensitively.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let take_run = |it: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut run = String::new();
+                    while let Some(c) = it.next_if(char::is_ascii_digit) {
+                        run.push(c);
+                    }
+                    run
+                };
+                let (ra, rb) = (take_run(&mut a), take_run(&mut b));
+                let (ta, tb) = (ra.trim_start_matches('0'), rb.trim_start_matches('0'));
+                let order = ta.len().cmp(&tb.len()).then_with(|| ta.cmp(tb));
+                if order != Ordering::Equal {
+                    return order;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let order = x.to_lowercase().cmp(y.to_lowercase());
+                if order != Ordering::Equal {
+                    return order;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+impl ListingPrefs {
+    fn compare(&self, a: &ObjectEntry, b: &ObjectEntry) -> Ordering {
+        let by_name = |a: &ObjectEntry, b: &ObjectEntry| {
+            if self.natural {
+                natural_cmp(&a.display_name, &b.display_name)
+                    .then_with(|| a.display_name.cmp(&b.display_name))
+            } else {
+                a.display_name.cmp(&b.display_name)
+            }
+        };
+        let order = match self.sort {
+            SortKey::Name => by_name(a, b),
+            SortKey::Size => a.size.cmp(&b.size),
+            SortKey::Modified => a.last_modified.cmp(&b.last_modified),
+        }
+        .then_with(|| by_name(a, b));
+        if self.descending { order.reverse() } else { order }
+    }
+
+    fn keeps(&self, obj: &ObjectEntry) -> bool {
+        self.filter
+            .as_ref()
+            .is_none_or(|f| obj.display_name.to_lowercase().contains(&f.to_lowercase()))
+    }
+}
+
+/// Preferences keyed by "remote/bucket/prefix"; a prefix without its own entry
+/// inherits from the closest parent, up to the bucket root.
+pub type ListingPrefsMap = HashMap<String, ListingPrefs>;
+
+pub(crate) fn load_listing_prefs() -> ListingPrefsMap {
+    storage::load_json(LISTING_PREFS_FILE)
+}
+
+fn prefs_key(remote: &str, bucket: &str, prefix: &str) -> String {
+    format!("{}/{}/{}", remote, bucket, prefix)
+}
+
+impl App {
+    /// Preferences in effect for the current listing.
+    pub fn listing_prefs(&self) -> ListingPrefs {
+        let Location::ObjectList {
+            remote,
+            bucket,
+            prefix,
+        } = &self.location
+        else {
+            return ListingPrefs::default();
+        };
+        let mut prefix = prefix.as_str();
+        loop {
+            if let Some(prefs) = self.listing_prefs.get(&prefs_key(remote, bucket, prefix)) {
+                return prefs.clone();
+            }
+            if prefix.is_empty() {
+                return ListingPrefs::default();
+            }
+            let trimmed = prefix.trim_end_matches('/');
+            prefix = trimmed.rfind('/').map_or("", |i| &prefix[..=i]);
+        }
+    }
+
+    /// Filter and sort freshly listed objects.
+    pub(crate) fn arrange_listing(&self, objects: Vec<ObjectEntry>) -> Vec<ObjectEntry> {
+        let prefs = self.listing_prefs();
+        let mut objects: Vec<ObjectEntry> = objects
+            .into_iter()
+            .filter(|o| o.is_dir || prefs.keeps(o))
+            .collect();
+        objects.sort_by(|a, b| {
+            let group = if prefs.dirs_first { b.is_dir.cmp(&a.is_dir) } else { Ordering::Equal };
+            group.then_with(|| prefs.compare(a, b))
+        });
+        objects
+    }
+
+    /// Object count, total bytes and directory count of the visible listing.
+    pub fn listing_totals(&self) -> Option<(usize, u64, usize)> {
+        if !matches!(self.location, Location::ObjectList { .. }) {
+            return None;
+        }
+        let (mut objects, mut bytes, mut dirs) = (0, 0, 0);
+        for entry in &self.entries {
+            if let Entry::Object(obj) = entry {
+                if obj.is_dir {
+                    dirs += 1;
+                } else {
+                    objects += 1;
+                    bytes += obj.size.max(0) as u64;
+                }
+            }
+        }
+        Some((objects, bytes, dirs))
+    }
+
+    /// Store preferences for the current prefix and persist them.
+    fn set_listing_prefs(&mut self, prefs: ListingPrefs) {
+        let Location::ObjectList {
+            remote,
+            bucket,
+            prefix,
+        } = &self.location
+        else {
+            return;
+        };
+        self.listing_prefs.insert(prefs_key(remote, bucket, prefix), prefs);
+        if let Err(e) = storage::save_json(LISTING_PREFS_FILE, &self.listing_prefs) {
+            self.notify_error(format!("Failed to save listing preferences: {}", e));
+        }
+    }
+
+    /// Re-sort the visible entries without listing again, keeping the cursor on the same entry.
+    fn resort_entries(&mut self) {
+        let selected = self
+            .browser_state
+            .selected()
+            .and_then(|i| self.entries.get(i))
+            .map(|e| e.key().to_string());
+        let objects = std::mem::take(&mut self.entries)
+            .into_iter()
+            .filter_map(|e| match e {
+                // Rows expanded in the tree are sorted within their directory
+                Entry::Object(obj) if self.tree_depth(&obj.key).unwrap_or(0) == 0 => Some(obj),
+                _ => None,
+            })
+            .collect();
+        let objects = self.arrange_listing(objects);
+        self.resort_tree();
+        self.entries = self.tree_rows(objects);
+        if let Some(key) = selected
+            && let Some(pos) = self.entries.iter().position(|e| e.key() == key)
+        {
+            self.browser_state.select(Some(pos));
+        }
+    }
+
+    /// Cycle the sort column (`o`).
+    pub fn cycle_sort(&mut self) {
+        if !matches!(self.location, Location::ObjectList { .. }) || self.search_active {
+            return;
+        }
+        let mut prefs = self.listing_prefs();
+        prefs.sort = prefs.sort.next();
+        self.set_listing_prefs(prefs);
+        self.resort_entries();
+    }
+
+    /// Flip ascending/descending (`O`).
+    pub fn reverse_sort(&mut self) {
+        if !matches!(self.location, Location::ObjectList { .. }) || self.search_active {
+            return;
+        }
+        let mut prefs = self.listing_prefs();
+        prefs.descending = !prefs.descending;
+        self.set_listing_prefs(prefs);
+        self.resort_entries();
+    }
+
+    /// Toggle natural (version-aware) name ordering (`Ctrl+N`).
+    pub fn toggle_natural_sort(&mut self) {
+        if !matches!(self.location, Location::ObjectList { .. }) || self.search_active {
+            return;
+        }
+        let mut prefs = self.listing_prefs();
+        prefs.natural = !prefs.natural;
+        self.set_listing_prefs(prefs);
+        self.resort_entries();
+    }
+
+    /// Toggle grouping directories before files (`Ctrl+G`).
+    pub fn toggle_dirs_first(&mut self) {
+        if !matches!(self.location, Location::ObjectList { .. }) || self.search_active {
+            return;
+        }
+        let mut prefs = self.listing_prefs();
+        prefs.dirs_first = !prefs.dirs_first;
+        self.set_listing_prefs(prefs);
+        self.resort_entries();
+    }
+
+    /// Start typing a name filter for this prefix (`\`), prefilled with the current one.
+    pub fn start_filter_input(&mut self) {
+        if !matches!(self.location, Location::ObjectList { .. }) || self.search_active {
+            return;
+        }
+        self.filter_input = Some(self.listing_prefs().filter.unwrap_or_default());
+    }
+
+    /// Save the typed filter (empty clears it) and list the prefix again.
+    pub async fn finish_filter_input(&mut self) {
+        let Some(input) = self.filter_input.take() else {
+            return;
+        };
+        let mut prefs = self.listing_prefs();
+        prefs.filter = Some(input.trim().to_string()).filter(|f| !f.is_empty());
+        self.set_listing_prefs(prefs);
+        self.refresh().await;
+    }
+}
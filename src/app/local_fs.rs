@@ -7,7 +7,7 @@ impl App {
         if let Ok(read_dir) = std::fs::read_dir(&self.local_path) {
             for entry in read_dir.flatten() {
                 let metadata = entry.metadata();
-                let is_dir = metadata.as_ref().map_or(false, |m| m.is_dir());
+                let is_dir = metadata.as_ref().is_ok_and(|m| m.is_dir());
                 let size = metadata.as_ref().map_or(0, |m| m.len());
                 let name = entry.file_name().to_string_lossy().to_string();
 
@@ -50,12 +50,13 @@ impl App {
     }
 
     pub fn local_enter(&mut self) {
-        if let Some(idx) = self.local_state.selected() {
-            if idx < self.local_entries.len() && self.local_entries[idx].is_dir {
-                let name = self.local_entries[idx].name.clone();
-                self.local_path.push(&name);
-                self.list_local_dir();
-            }
+        if let Some(idx) = self.local_state.selected()
+            && idx < self.local_entries.len()
+            && self.local_entries[idx].is_dir
+        {
+            let name = self.local_entries[idx].name.clone();
+            self.local_path.push(&name);
+            self.list_local_dir();
         }
     }
 
@@ -69,11 +70,67 @@ impl App {
     pub fn local_path_display(&self) -> String {
         let path = self.local_path.display().to_string();
         // Abbreviate home directory
-        if let Some(home) = dirs::home_dir() {
-            if let Some(rest) = path.strip_prefix(&home.display().to_string()) {
-                return format!("~{}", rest);
-            }
+        if let Some(home) = dirs::home_dir()
+            && let Some(rest) = path.strip_prefix(&home.display().to_string())
+        {
+            return format!("~{}", rest);
         }
         path
     }
+
+    /// Filter the local listing by name, like browser search.
+    pub fn start_local_search(&mut self) {
+        self.local_search_active = true;
+        self.local_search_query.clear();
+        self.local_pre_search_selection = self.local_state.selected();
+        self.local_saved_entries = self.local_entries.clone();
+    }
+
+    pub fn local_search_input(&mut self, c: char) {
+        self.local_search_query.push(c);
+        self.update_local_filter();
+    }
+
+    pub fn local_search_backspace(&mut self) {
+        self.local_search_query.pop();
+        self.update_local_filter();
+    }
+
+    fn update_local_filter(&mut self) {
+        let query = self.local_search_query.to_lowercase();
+        self.local_entries = self
+            .local_saved_entries
+            .iter()
+            .filter(|e| e.name.to_lowercase().contains(&query))
+            .cloned()
+            .collect();
+        self.local_state.select(if self.local_entries.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+
+    /// Restore the full listing and the selection from before the search.
+    pub fn cancel_local_search(&mut self) {
+        self.local_search_active = false;
+        self.local_search_query.clear();
+        self.local_entries = std::mem::take(&mut self.local_saved_entries);
+        self.local_state.select(self.local_pre_search_selection.take());
+    }
+
+    /// Restore the full listing with the cursor on the chosen entry.
+    pub fn finish_local_search(&mut self) {
+        let chosen = self
+            .local_state
+            .selected()
+            .and_then(|i| self.local_entries.get(i))
+            .map(|e| e.name.clone());
+        self.cancel_local_search();
+        if let Some(name) = chosen
+            && let Some(pos) = self.local_entries.iter().position(|e| e.name == name)
+        {
+            self.local_state.select(Some(pos));
+        }
+    }
 }
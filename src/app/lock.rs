@@ -0,0 +1,38 @@
+use super::{App, Entry, Location};
+
+impl App {
+    /// `Shift+L`: lock the current bucket (or the selected one in the bucket list)
+    /// against deletes, moves and uploads until unlocked or the app exits.
+    pub fn toggle_bucket_lock(&mut self) {
+        let (remote, bucket) = match &self.location {
+            Location::ObjectList { remote, bucket, .. } => (remote.clone(), bucket.clone()),
+            Location::BucketList { remote } => {
+                let Some(Entry::Bucket(b)) = self.browser_state.selected().and_then(|i| self.entries.get(i)) else {
+                    return;
+                };
+                (remote.clone(), b.name.clone())
+            }
+            Location::RemoteList => return,
+        };
+        let key = (remote, bucket);
+        if self.locked_buckets.remove(&key) {
+            self.notify(format!("Unlocked {}", key.1));
+        } else {
+            self.notify(format!("Locked {} against writes for this session", key.1));
+            self.locked_buckets.insert(key);
+        }
+    }
+
+    pub fn is_bucket_locked(&self, remote: &str, bucket: &str) -> bool {
+        self.locked_buckets.iter().any(|(r, b)| r == remote && b == bucket)
+    }
+
+    /// Refuse a write to a locked bucket, saying why. True when it was refused.
+    pub(crate) fn refuse_locked(&mut self, remote: &str, bucket: &str) -> bool {
+        if !self.is_bucket_locked(remote, bucket) {
+            return false;
+        }
+        self.notify_error(format!("Bucket '{}' is locked; Shift+L unlocks it", bucket));
+        true
+    }
+}
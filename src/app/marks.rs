@@ -0,0 +1,175 @@
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::PathBuf;
+
+use super::indexing::IndexState;
+use super::{parent_prefix, App, Location};
+
+/// First line of an exported list; names the remote and bucket the keys belong to.
+const HEADER: &str = "# s3-like-yazi marks";
+
+/// Keys read back from an exported list.
+struct MarkList {
+    /// `(remote, bucket)` from the header, if it has one.
+    origin: Option<(String, String)>,
+    keys: Vec<String>,
+}
+
+fn parse_marks(text: &str) -> MarkList {
+    let mut origin = None;
+    let mut keys = Vec::new();
+    for line in text.lines() {
+        if let Some(rest) = line.strip_prefix(HEADER) {
+            origin = rest
+                .trim()
+                .split_once('/')
+                .map(|(remote, bucket)| (remote.to_string(), bucket.to_string()));
+        } else if !line.is_empty() && !line.starts_with('#') {
+            keys.push(line.to_string());
+        }
+    }
+    MarkList { origin, keys }
+}
+
+/// Deepest prefix all `keys` live under, cut back to a whole folder.
+fn common_parent(keys: &[String]) -> String {
+    let mut parents = keys.iter().map(|k| parent_prefix(k));
+    let Some(mut common) = parents.next() else {
+        return String::new();
+    };
+    for parent in parents {
+        let shared = common
+            .char_indices()
+            .zip(parent.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(common.len().min(parent.len()), |((i, _), _)| i);
+        common.truncate(shared);
+    }
+    match common.rfind('/') {
+        Some(pos) => common[..=pos].to_string(),
+        None => String::new(),
+    }
+}
+
+impl App {
+    /// Write the marked keys to `s3-marks-<time>.txt` in the local directory, to
+    /// review and mark again later with `Shift+I`.
+    pub fn export_marks(&mut self) {
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            return;
+        };
+        if self.marked.is_empty() {
+            self.notify("Mark objects with Space first");
+            return;
+        }
+        let (remote, bucket) = (remote.clone(), bucket.clone());
+        match self.write_marks(&remote, &bucket) {
+            Ok(path) => self.notify(format!("Exported {} marked keys to {}", self.marked.len(), path.display())),
+            Err(e) => self.notify_error(format!("Export failed: {}", e)),
+        }
+    }
+
+    fn write_marks(&self, remote: &str, bucket: &str) -> std::io::Result<PathBuf> {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let path = self.local_path.join(format!("s3-marks-{}.txt", secs));
+        let mut keys: Vec<&String> = self.marked.iter().collect();
+        keys.sort();
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+        writeln!(file, "{} {}/{}", HEADER, remote, bucket)?;
+        for key in keys {
+            writeln!(file, "{}", key)?;
+        }
+        file.flush()?;
+        Ok(path)
+    }
+
+    /// Ask for an exported list to import, starting from the local directory.
+    pub fn start_marks_input(&mut self) {
+        if self.search_active {
+            return;
+        }
+        self.marks_input = Some(format!("{}/", self.local_path.display()));
+    }
+
+    /// Read the typed list, open the prefix its keys live in and mark the ones
+    /// still there. Keys that are gone are reported, checked against the index
+    /// when the bucket is fully indexed.
+    pub async fn finish_marks_input(&mut self) {
+        let Some(input) = self.marks_input.take() else {
+            return;
+        };
+        let path = PathBuf::from(input.trim());
+        let list = match std::fs::read_to_string(&path) {
+            Ok(text) => parse_marks(&text),
+            Err(e) => {
+                self.notify_error(format!("Cannot read {}: {}", path.display(), e));
+                return;
+            }
+        };
+        if list.keys.is_empty() {
+            self.notify_warn(format!("No keys in {}", path.display()));
+            return;
+        }
+        let (remote, bucket) = match (list.origin, &self.location) {
+            (Some(origin), _) => origin,
+            (None, Location::ObjectList { remote, bucket, .. }) => (remote.clone(), bucket.clone()),
+            (None, _) => {
+                self.notify_warn("The list names no bucket; open one first");
+                return;
+            }
+        };
+        if !self.config.aliases.contains_key(&remote) {
+            self.notify_error(format!("No remote named '{}'", remote));
+            return;
+        }
+        if let Err(e) = self.ensure_client(&remote) {
+            self.notify_error(format!("Connection failed: {}", e));
+            return;
+        }
+
+        let prefix = common_parent(&list.keys);
+        let already_here = matches!(&self.location, Location::ObjectList { remote: r, bucket: b, prefix: p }
+            if *r == remote && *b == bucket && *p == prefix);
+        if !already_here {
+            self.metadata = None;
+            self.preview.clear();
+            self.enter_prefix(&remote, &bucket, &prefix).await;
+        }
+        if !matches!(&self.location, Location::ObjectList { prefix: p, .. } if *p == prefix) {
+            return;
+        }
+
+        // With the bucket fully indexed, keys outside this listing can be told apart too
+        let indexed: Option<HashSet<&str>> = (self.index_state == IndexState::Complete
+            && self.index_key.as_ref().is_some_and(|(r, b)| *r == remote && *b == bucket))
+        .then(|| self.search_pool.iter().map(|o| o.key.as_str()).collect());
+        let listed: HashSet<&str> = self.entries.iter().map(|e| e.key()).collect();
+        let (mut found, mut deeper, mut gone) = (Vec::new(), 0, 0);
+        for key in &list.keys {
+            if listed.contains(key.as_str()) {
+                found.push(key.clone());
+            } else if parent_prefix(key) != prefix && indexed.as_ref().is_none_or(|pool| pool.contains(key.as_str())) {
+                // Still there (or unknown), just not in this listing
+                deeper += 1;
+            } else {
+                gone += 1;
+            }
+        }
+        let mut message = format!("Marked {} keys", found.len());
+        self.marked.extend(found);
+        if deeper > 0 {
+            let folder = if prefix.is_empty() { "the bucket root" } else { prefix.as_str() };
+            message.push_str(&format!(", {} in sub-folders of {} left unmarked", deeper, folder));
+        }
+        if gone > 0 {
+            message.push_str(&format!(", {} no longer exist", gone));
+        }
+        if deeper > 0 || gone > 0 {
+            self.notify_warn(message);
+        } else {
+            self.notify(message);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::process::Command;
+
+/// Width in pixels of the generated thumbnail; height follows the aspect ratio.
+const THUMB_WIDTH: usize = 96;
+
+/// Give up on ffprobe/ffmpeg after this long (slow endpoints, huge headers).
+const TOOL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A decoded video frame as packed RGB24 pixels.
+#[derive(Clone)]
+pub struct Thumbnail {
+    pub width: usize,
+    pub height: usize,
+    pub rgb: Vec<u8>,
+}
+
+impl Thumbnail {
+    pub fn pixel(&self, x: usize, y: usize) -> (u8, u8, u8) {
+        let i = (y * self.width + x) * 3;
+        (self.rgb[i], self.rgb[i + 1], self.rgb[i + 2])
+    }
+}
+
+/// Inline preview of a video or audio file: a thumbnail (or cover art) plus
+/// stream info and tags, playable on demand.
+#[derive(Clone)]
+pub struct MediaPreview {
+    /// Presigned URL, reused when the user asks for full playback.
+    pub url: String,
+    /// Audio files play without a window and are stopped from the TUI.
+    pub audio_only: bool,
+    /// (label, value) pairs from ffprobe.
+    pub info: Vec<(String, String)>,
+    pub thumbnail: Option<Thumbnail>,
+}
+
+/// Probe `url` and grab a frame about a tenth of the way in (or the cover art for audio).
+pub async fn load(url: String, audio_only: bool) -> Result<MediaPreview, String> {
+    let (info, duration) = probe(&url).await.unwrap_or_default();
+    let at = match duration {
+        Some(d) if !audio_only => (d / 10.0).min(30.0),
+        _ => 0.0,
+    };
+    let thumbnail = thumbnail(&url, at).await;
+
+    if info.is_empty() && thumbnail.is_none() {
+        return Err("ffprobe/ffmpeg failed - install ffmpeg for media preview".into());
+    }
+    Ok(MediaPreview {
+        url,
+        audio_only,
+        info,
+        thumbnail,
+    })
+}
+
+async fn run(cmd: &mut Command) -> Option<Vec<u8>> {
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+    let output = tokio::time::timeout(TOOL_TIMEOUT, cmd.output()).await.ok()?.ok()?;
+    output.status.success().then_some(output.stdout)
+}
+
+/// Duration, codecs, resolution and title/artist/album tags via ffprobe.
+async fn probe(url: &str) -> Option<(Vec<(String, String)>, Option<f64>)> {
+    let out = run(Command::new("ffprobe").args([
+        "-v",
+        "error",
+        "-show_entries",
+        "format=duration,bit_rate:format_tags:stream=codec_type,codec_name,width,height,r_frame_rate,sample_rate,channels",
+        "-of",
+        "json",
+        url,
+    ]))
+    .await?;
+    let json: serde_json::Value = serde_json::from_slice(&out).ok()?;
+
+    let mut info = Vec::new();
+    let format = &json["format"];
+    if let Some(tags) = format["tags"].as_object() {
+        for label in ["Title", "Artist", "Album"] {
+            let value = tags
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(label))
+                .and_then(|(_, v)| v.as_str());
+            if let Some(value) = value {
+                info.push((label.to_string(), value.to_string()));
+            }
+        }
+    }
+
+    let duration = format["duration"].as_str().and_then(|d| d.parse::<f64>().ok());
+    if let Some(d) = duration {
+        info.push(("Duration".into(), format_duration(d)));
+    }
+
+    for stream in json["streams"].as_array().into_iter().flatten() {
+        let codec = stream["codec_name"].as_str().unwrap_or("?");
+        match stream["codec_type"].as_str() {
+            Some("video") => {
+                let mut value = codec.to_string();
+                if let (Some(w), Some(h)) = (stream["width"].as_u64(), stream["height"].as_u64()) {
+                    value.push_str(&format!(" {}x{}", w, h));
+                }
+                if let Some(fps) = stream["r_frame_rate"].as_str().and_then(parse_rate) {
+                    value.push_str(&format!(" @ {:.2} fps", fps));
+                }
+                info.push(("Video".into(), value));
+            }
+            Some("audio") => {
+                let mut value = codec.to_string();
+                if let Some(rate) = stream["sample_rate"].as_str() {
+                    value.push_str(&format!(" {} Hz", rate));
+                }
+                if let Some(ch) = stream["channels"].as_u64() {
+                    value.push_str(&format!(" {}ch", ch));
+                }
+                info.push(("Audio".into(), value));
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(rate) = format["bit_rate"].as_str().and_then(|b| b.parse::<u64>().ok()) {
+        info.push(("Bitrate".into(), format!("{} kb/s", rate / 1000)));
+    }
+    Some((info, duration))
+}
+
+/// Decode one frame at `at` seconds, scaled down to a raw RGB buffer.
+async fn thumbnail(url: &str, at: f64) -> Option<Thumbnail> {
+    let scale = format!("scale={}:-2", THUMB_WIDTH);
+    let seek = format!("{:.2}", at);
+    let rgb = run(Command::new("ffmpeg").args([
+        "-v", "error", "-ss", &seek, "-i", url, "-frames:v", "1", "-vf", &scale, "-f", "rawvideo",
+        "-pix_fmt", "rgb24", "-",
+    ]))
+    .await?;
+
+    let height = rgb.len() / (THUMB_WIDTH * 3);
+    (height > 0).then_some(Thumbnail {
+        width: THUMB_WIDTH,
+        height,
+        rgb,
+    })
+}
+
+/// "30000/1001" -> 29.97
+fn parse_rate(rate: &str) -> Option<f64> {
+    let (num, den) = rate.split_once('/')?;
+    let (num, den) = (num.parse::<f64>().ok()?, den.parse::<f64>().ok()?);
+    (den > 0.0 && num > 0.0).then(|| num / den)
+}
+
+fn format_duration(secs: f64) -> String {
+    let total = secs.round() as u64;
+    let (h, m, s) = (total / 3600, total / 60 % 60, total % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
@@ -1,21 +1,67 @@
-mod delete;
+pub mod acl;
+pub mod action;
+mod actions;
+mod changes;
+pub mod cli;
+pub mod confirm;
+pub mod context;
+pub mod copy;
+pub mod delete;
 mod download;
-mod indexing;
+mod expiry;
+pub mod duplicate;
+pub mod entry_menu;
+pub mod favorites;
+mod head_cache;
+pub mod help;
+pub mod history;
+pub mod identity;
+pub mod jumps;
+mod hooks;
+mod image_info;
+mod inbox;
+pub mod indexing;
+pub mod inventory;
+pub mod links;
+pub mod listing;
 mod local_fs;
+mod lock;
+mod marks;
+pub mod media;
+pub mod mode;
 mod navigation;
+pub mod new_object;
+pub mod notify;
+mod peek;
+pub mod plugins;
+pub mod plan;
+mod prewarm;
+mod profiles;
 pub mod preview;
+mod prefetch;
+mod preview_cache;
+pub mod quick_views;
+mod resume;
 mod search;
+mod shell;
+#[cfg(test)]
+mod tests;
+pub mod slideshow;
+pub mod sync;
+pub mod tasks;
+pub mod throughput;
+pub mod tree;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
 use std::time::Instant;
 
 use ratatui::widgets::{ListState, TableState};
 use tokio::sync::mpsc;
-use tokio::task::JoinHandle;
 
-use crate::credentials::McConfig;
-use crate::s3_client::{BucketInfo, DownloadMsg, IndexMsg, ObjectEntry, ObjectMetadata, S3Client};
+use crate::credentials::{AliasConfig, McConfig, Protection, RemoteTag};
+use crate::settings::Settings;
+use crate::object_store::{self, BucketInfo, DeleteMsg, DownloadMsg, IndexMsg, ObjectEntry, ObjectMetadata, Store};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Pane {
@@ -25,6 +71,7 @@ pub enum Pane {
 }
 
 #[derive(Debug, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum Location {
     RemoteList,
     BucketList {
@@ -41,6 +88,8 @@ pub enum Location {
 pub enum Entry {
     Bucket(BucketInfo),
     Object(ObjectEntry),
+    /// Heading of the search results under one prefix.
+    Group(search::SearchGroup),
 }
 
 impl Entry {
@@ -48,6 +97,7 @@ impl Entry {
         match self {
             Entry::Bucket(b) => &b.name,
             Entry::Object(o) => &o.display_name,
+            Entry::Group(g) => &g.prefix,
         }
     }
 
@@ -55,6 +105,7 @@ impl Entry {
         match self {
             Entry::Bucket(b) => &b.name,
             Entry::Object(o) => &o.key,
+            Entry::Group(g) => &g.prefix,
         }
     }
 }
@@ -64,8 +115,23 @@ pub struct DeleteConfirm {
     pub key: String,
     pub is_dir: bool,
     pub selected_yes: bool,
+    /// Typed confirmation for protected remotes; must equal `remote` before deleting.
+    pub typed: Option<String>,
+    pub remote: String,
+    /// Objects under the prefix, once a dry-run plan has listed them.
+    pub objects: Option<usize>,
+    /// Only these directory markers under the prefix, not everything under it.
+    pub markers: Option<Vec<String>>,
 }
 
+impl DeleteConfirm {
+    /// Whether the typed confirmation (if required) matches the remote name.
+    pub fn typed_ok(&self) -> bool {
+        self.typed.as_ref().is_none_or(|t| *t == self.remote)
+    }
+}
+
+#[derive(Clone)]
 pub struct LocalEntry {
     pub name: String,
     pub is_dir: bool,
@@ -77,26 +143,104 @@ pub struct DownloadProgress {
     pub filename: String,
     pub bytes_downloaded: u64,
     pub total_bytes: u64,
+    /// Speed over the last few seconds.
     pub speed_bps: f64,
+    /// Speed over the whole transfer so far.
+    pub avg_speed_bps: f64,
+    /// Per-second throughput behind the progress sparkline and `speed_bps`.
+    pub throughput: throughput::Throughput,
     pub files_done: usize,
     pub files_total: usize,
     pub complete: bool,
     pub error: Option<String>,
 }
 
+impl DownloadProgress {
+    fn update_speed(&mut self, elapsed_secs: f64) {
+        self.avg_speed_bps = self.bytes_downloaded as f64 / elapsed_secs;
+        self.speed_bps = self.throughput.speed().unwrap_or(self.avg_speed_bps);
+    }
+}
+
 pub struct App {
     pub pane: Pane,
     pub remotes: Vec<String>,
     pub remote_state: ratatui::widgets::ListState,
     pub entries: Vec<Entry>,
     pub browser_state: TableState,
+    /// Browser rows drawn last frame with a hash of what they show, reused until
+    /// the entries in view, their marks or the columns change.
+    pub(crate) browser_rows: Option<(u64, ratatui::widgets::Table<'static>)>,
+    /// Keys of objects marked with Space in the current listing.
+    pub marked: HashSet<String>,
     pub location: Location,
     pub metadata: Option<ObjectMetadata>,
-    pub error: Option<String>,
     pub should_quit: bool,
-    pub show_help: bool,
+    /// "Quit anyway?" prompt, open while a transfer is running.
+    pub confirm_quit: bool,
+    /// Every spawned background job.
+    pub tasks: tasks::TaskManager,
+    pub(crate) action_rx: Option<inbox::Inbox<actions::ActionOutput>>,
+    /// Output of the last configured action, shown until closed.
+    pub action_output: Option<actions::ActionOutput>,
+    /// Plugin command palette (`:`).
+    pub palette: Option<plugins::CommandPalette>,
+    pub(crate) plugin_tx: mpsc::Sender<plugins::PluginDone>,
+    pub(crate) plugin_rx: inbox::Inbox<plugins::PluginDone>,
+    /// Warnings from inside transfer tasks: failed `post_*` hooks, skipped keys.
+    pub(crate) hook_tx: mpsc::Sender<String>,
+    pub(crate) hook_rx: inbox::Inbox<String>,
+    /// Remotes connected ahead of use by `prewarm_remotes`.
+    pub(crate) prewarm_tx: mpsc::Sender<prewarm::Warmed>,
+    pub(crate) prewarm_rx: inbox::Inbox<prewarm::Warmed>,
+    /// Listings of directories the cursor rested on, ahead of entering them.
+    pub(crate) prefetch: prefetch::Prefetch,
+    pub(crate) expiry_watch: expiry::ExpiryWatch,
+    /// First key of a `g…` / `y…` sequence, waiting for the second.
+    pub pending_key: Option<char>,
+    /// Keybinding help popup (`?` / `F1`).
+    pub help: Option<help::HelpView>,
+    /// Focused pane expanded to the full content area (toggled with `z`).
+    pub zoomed: bool,
+    /// Metadata panel visibility (toggled with `m`).
+    pub show_metadata: bool,
     pub confirm_delete: Option<DeleteConfirm>,
-    pub status_message: Option<String>,
+    /// Copy, sync or ACL change waiting for a protected remote's name.
+    pub protected_confirm: Option<confirm::ProtectedConfirm>,
+    /// Dry-run of a bulk operation awaiting confirmation.
+    pub plan: Option<plan::Plan>,
+    pub(crate) plan_rx: Option<inbox::Inbox<Result<plan::Plan, String>>>,
+    /// Transient notifications, shown stacked above the status bar.
+    pub toasts: notify::Toasts,
+    /// Marked objects waiting for a choice of where their presigned links go.
+    pub link_bundle: Option<links::LinkBundle>,
+    pub(crate) links_rx: Option<inbox::Inbox<Result<links::LinkDone, String>>>,
+    /// Destination picker for copy/move.
+    pub copy_picker: Option<copy::CopyPicker>,
+    pub(crate) copy_rx: Option<inbox::Inbox<Result<copy::Written, String>>>,
+    /// The running copy and when it started, recorded in the history once done.
+    pub(crate) active_copy: Option<(history::CopySpec, Instant)>,
+    /// Recursive delete running in the background.
+    pub delete_progress: Option<delete::DeleteProgress>,
+    pub(crate) delete_rx: Option<inbox::Inbox<DeleteMsg>>,
+    /// Bucket inventory report being written in the background.
+    pub inventory: Option<inventory::InventoryProgress>,
+    pub(crate) inventory_rx: Option<inbox::Inbox<inventory::InventoryMsg>>,
+    /// Name filter being typed for the current prefix (`\`).
+    pub filter_input: Option<String>,
+    /// Path of an exported mark list being typed for import (`Shift+I`).
+    pub marks_input: Option<String>,
+    /// Sort order and filter per bucket prefix, persisted.
+    pub(crate) listing_prefs: listing::ListingPrefsMap,
+    /// Bucket name being typed for direct open (`b`).
+    pub bucket_input: Option<String>,
+    /// The listing on screen misses objects written since; see `reload_if_stale`.
+    pub(crate) listing_stale: bool,
+    /// Key the cursor goes back to when the listing is next loaded, e.g. the
+    /// object just written or the one selected before a refresh.
+    pub(crate) focus_key: Option<String>,
+    /// Whether the listing on screen is complete and current, for the title badge.
+    pub listing_freshness: listing::Freshness,
 
     // Search state
     pub search_active: bool,
@@ -105,12 +249,44 @@ pub struct App {
     pub(crate) saved_entries: Vec<Entry>,
     pub(crate) saved_location: Option<Location>,
     pub(crate) pre_search_selection: Option<usize>,
+    /// Browser scroll offset when the search started, restored on cancel.
+    pub(crate) pre_search_offset: usize,
     pub(crate) search_context: Option<(String, String)>,
+    /// Parent prefix of a search result, shown over the results.
+    pub context_view: Option<context::ContextView>,
+    /// Largest / recently-modified objects of a bucket, from its index.
+    pub quick_view: Option<quick_views::QuickView>,
+    /// Operations on the selected entry, picked from a list (`a`).
+    pub entry_menu: Option<entry_menu::EntryMenu>,
+    /// Canned ACL being chosen for the selected object (`Shift+A`).
+    pub acl_picker: Option<acl::AclPicker>,
+    /// Key being typed for a new object written from text (`Shift+U`).
+    pub new_object: Option<new_object::NewObject>,
+    /// Name being typed for a copy of the selected object next to it (`Shift+Y`).
+    pub duplicate: Option<duplicate::Duplicate>,
+    /// Listing shown as a tree with directories expanded in place.
+    pub tree: Option<tree::TreeView>,
+    pub(crate) search_history: search::SearchHistory,
+    /// Index into this bucket's search history while cycling with Up/Down.
+    pub(crate) search_recall: Option<usize>,
+    /// Whether search results are grouped by prefix: forced on or off with
+    /// Ctrl+G, or `None` to group once there are many.
+    pub(crate) search_grouping: Option<bool>,
+    /// Prefixes whose grouped search results are shown.
+    pub(crate) search_expanded: HashSet<String>,
+    /// Query of the last search, for `n`/`N` in the listing.
+    pub(crate) last_search: Option<String>,
 
     // Background indexing
-    pub(crate) index_rx: Option<mpsc::Receiver<IndexMsg>>,
-    pub(crate) index_handle: Option<JoinHandle<()>>,
-    pub index_complete: bool,
+    pub(crate) index_rx: Option<inbox::Inbox<IndexMsg>>,
+    pub(crate) index_task: Option<tasks::TaskId>,
+    pub index_state: indexing::IndexState,
+    /// Object counts of buckets indexed to the end, to show a percentage next time.
+    pub(crate) index_totals: HashMap<(String, String), usize>,
+    /// Indexing passed `index_confirm_above` and waits for a yes or no.
+    pub confirm_index: bool,
+    /// Buckets the user agreed to index past the threshold this session.
+    pub(crate) index_allowed: HashSet<(String, String)>,
     pub(crate) index_key: Option<(String, String)>,
 
     // Download / Local FS state
@@ -118,24 +294,82 @@ pub struct App {
     pub local_path: PathBuf,
     pub local_entries: Vec<LocalEntry>,
     pub local_state: ListState,
+    pub local_search_active: bool,
+    pub local_search_query: String,
+    pub(crate) local_saved_entries: Vec<LocalEntry>,
+    pub(crate) local_pre_search_selection: Option<usize>,
     pub rename_input: Option<String>,
     pub rename_active: bool,
     pub download_source: Option<(String, String)>, // (display_name, full_key)
     pub download_source_is_dir: bool,
+    /// Byte range being typed for a partial download (`r` in download mode).
+    pub range_input: Option<String>,
+    /// Suggested free name while the "file exists" prompt is open.
+    pub download_conflict: Option<String>,
+    /// Original target name when the conflict prompt picked a suffixed one.
+    pub name_adjusted_from: Option<String>,
     pub download_progress: Option<DownloadProgress>,
-    pub(crate) download_rx: Option<mpsc::Receiver<DownloadMsg>>,
-    pub(crate) download_handle: Option<JoinHandle<()>>,
+    pub(crate) download_rx: Option<inbox::Inbox<DownloadMsg>>,
+    pub(crate) download_task: Option<tasks::TaskId>,
     pub(crate) download_started_at: Option<Instant>,
+    pub(crate) active_transfer: Option<history::TransferSpec>,
+    /// Sync options popup, opened from download mode.
+    pub sync_setup: Option<sync::SyncSetup>,
+    /// Summary of the running sync, reported when it finishes.
+    pub(crate) sync_summary: Option<String>,
+    /// Remote, bucket and objects the running sync uploads, applied once it succeeds.
+    pub(crate) sync_uploads: Option<(String, String, changes::Change)>,
+
+    // Transfer history panel
+    pub history: Vec<history::TransferRecord>,
+    pub show_history: bool,
+    pub history_state: ListState,
+    /// Transfers started in this or an earlier session that haven't succeeded.
+    pub unfinished: Vec<history::TransferSpec>,
+    /// "Resume unfinished transfers?" popup, shown at launch when there are some.
+    pub resume_prompt: bool,
+    /// Endpoint and key of a remote, shown by `w`.
+    pub identity_popup: Option<identity::Identity>,
+    /// Transfers waiting for the running one to finish.
+    pub(crate) transfer_queue: VecDeque<history::TransferSpec>,
+
+    // Pinned objects panel
+    pub favorites: Vec<favorites::Favorite>,
+    pub show_favorites: bool,
+    pub favorites_state: ListState,
+
+    // Previews of recently viewed objects, keyed by ETag
+    pub preview_cache: preview_cache::PreviewCache,
+    // HEAD results of recently selected objects, checked against the listing's ETag
+    pub(crate) head_cache: head_cache::HeadCache,
+
+    // Image slideshow over the current listing
+    pub slideshow: Option<slideshow::Slideshow>,
 
     // Preview state (triggered explicitly with 'p')
     pub preview: preview::PreviewState,
 
     pub(crate) config: McConfig,
-    pub(crate) clients: HashMap<String, S3Client>,
+    pub(crate) settings: Settings,
+    pub(crate) clients: HashMap<String, Store>,
+    /// ListBuckets results by alias and when they were listed; dropped on refresh
+    /// and when the alias changes.
+    pub(crate) bucket_cache: HashMap<String, (Instant, Vec<BucketInfo>)>,
+    /// Remote and bucket pairs locked against writes for this session (`Shift+L`).
+    pub(crate) locked_buckets: HashSet<(String, String)>,
+    /// Profile each remote signs with for this session (`K`), by alias.
+    pub(crate) active_profiles: HashMap<String, String>,
+    /// Prefixes visited this session, most recent first.
+    pub(crate) jumps: VecDeque<jumps::Jump>,
+    /// Jump list popup (`J`).
+    pub jump_picker: Option<jumps::JumpPicker>,
+    /// Remotes given on the command line rather than in the mc config; kept across reloads.
+    cli_remotes: HashMap<String, AliasConfig>,
 }
 
 impl App {
-    pub fn new(config: McConfig) -> Self {
+    pub fn new(mut config: McConfig, settings: Settings) -> Self {
+        config.apply_remote_settings(&settings.remotes);
         let mut remotes: Vec<String> = config.aliases.keys().cloned().collect();
         remotes.sort();
 
@@ -143,6 +377,14 @@ impl App {
         if !remotes.is_empty() {
             remote_state.select(Some(0));
         }
+        let (plugin_tx, plugin_rx) = mpsc::channel(16);
+        let (hook_tx, hook_rx) = mpsc::channel(16);
+        let (prewarm_tx, prewarm_rx) = mpsc::channel(16);
+        let unfinished = if settings.persist_history {
+            resume::load_unfinished()
+        } else {
+            Vec::new()
+        };
 
         Self {
             pane: Pane::Remotes,
@@ -150,60 +392,278 @@ impl App {
             remote_state,
             entries: Vec::new(),
             browser_state: TableState::default(),
+            browser_rows: None,
+            marked: HashSet::new(),
             location: Location::RemoteList,
             metadata: None,
-            error: None,
             should_quit: false,
-            show_help: false,
+            confirm_quit: false,
+            tasks: tasks::TaskManager::default(),
+            help: None,
+            action_rx: None,
+            action_output: None,
+            palette: None,
+            plugin_tx,
+            plugin_rx: plugin_rx.into(),
+            hook_tx,
+            hook_rx: hook_rx.into(),
+            prewarm_tx,
+            prewarm_rx: prewarm_rx.into(),
+            prefetch: prefetch::Prefetch::default(),
+            expiry_watch: expiry::ExpiryWatch::default(),
+            pending_key: None,
+            zoomed: false,
+            show_metadata: true,
             confirm_delete: None,
-            status_message: None,
+            protected_confirm: None,
+            plan: None,
+            plan_rx: None,
+            toasts: notify::Toasts::default(),
+            link_bundle: None,
+            links_rx: None,
+            copy_picker: None,
+            copy_rx: None,
+            active_copy: None,
+            listing_stale: false,
+            focus_key: None,
+            listing_freshness: listing::Freshness::Live,
+            delete_progress: None,
+            delete_rx: None,
+            inventory: None,
+            inventory_rx: None,
+            filter_input: None,
+            marks_input: None,
+            listing_prefs: listing::load_listing_prefs(),
+            bucket_input: None,
             search_active: false,
             search_query: String::new(),
             search_pool: Vec::new(),
             saved_entries: Vec::new(),
             saved_location: None,
             pre_search_selection: None,
+            pre_search_offset: 0,
             search_context: None,
+            context_view: None,
+            quick_view: None,
+            entry_menu: None,
+            acl_picker: None,
+            new_object: None,
+            duplicate: None,
+            tree: None,
+            search_history: if settings.persist_history {
+                search::load_search_history()
+            } else {
+                Default::default()
+            },
+            search_recall: None,
+            search_grouping: None,
+            search_expanded: HashSet::new(),
+            last_search: None,
             index_rx: None,
-            index_handle: None,
-            index_complete: false,
+            index_task: None,
+            index_state: indexing::IndexState::Running,
+            index_totals: HashMap::new(),
+            confirm_index: false,
+            index_allowed: HashSet::new(),
             index_key: None,
             download_mode: false,
             local_path: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
             local_entries: Vec::new(),
             local_state: ListState::default(),
+            local_search_active: false,
+            local_search_query: String::new(),
+            local_saved_entries: Vec::new(),
+            local_pre_search_selection: None,
             rename_input: None,
             rename_active: false,
             download_source: None,
             download_source_is_dir: false,
+            range_input: None,
+            download_conflict: None,
+            name_adjusted_from: None,
             download_progress: None,
             download_rx: None,
-            download_handle: None,
+            download_task: None,
             download_started_at: None,
+            active_transfer: None,
+            sync_setup: None,
+            sync_summary: None,
+            sync_uploads: None,
+            history: if settings.persist_history {
+                history::load_history()
+            } else {
+                Vec::new()
+            },
+            show_history: false,
+            history_state: ListState::default(),
+            resume_prompt: !unfinished.is_empty(),
+            identity_popup: None,
+            unfinished,
+            transfer_queue: VecDeque::new(),
+            favorites: favorites::load_favorites(),
+            show_favorites: false,
+            favorites_state: ListState::default(),
+            preview_cache: preview_cache::PreviewCache::new(),
+            head_cache: head_cache::HeadCache::default(),
+            slideshow: None,
             preview: preview::PreviewState::new(),
             config,
+            settings,
             clients: HashMap::new(),
+            bucket_cache: HashMap::new(),
+            locked_buckets: HashSet::new(),
+            active_profiles: HashMap::new(),
+            jumps: VecDeque::new(),
+            jump_picker: None,
+            cli_remotes: HashMap::new(),
         }
     }
 
     pub(crate) fn ensure_client(&mut self, alias: &str) -> anyhow::Result<()> {
         if !self.clients.contains_key(alias) {
             let alias_config = self
-                .config
-                .aliases
-                .get(alias)
+                .alias_config(alias)
                 .ok_or_else(|| anyhow::anyhow!("Unknown alias: {}", alias))?;
-            let client = S3Client::new(
-                alias,
-                &alias_config.url,
-                &alias_config.access_key,
-                &alias_config.secret_key,
-            )?;
+            let client = object_store::connect(alias, &alias_config)?;
             self.clients.insert(alias.to_string(), client);
         }
         Ok(())
     }
 
+    /// Add a remote that is not in the mc config, e.g. `--local DIR`.
+    pub fn add_cli_remote(&mut self, alias: &str, alias_config: AliasConfig) {
+        self.config.aliases.insert(alias.to_string(), alias_config.clone());
+        self.cli_remotes.insert(alias.to_string(), alias_config);
+        if !self.remotes.iter().any(|r| r == alias) {
+            self.remotes.push(alias.to_string());
+            self.remotes.sort();
+        }
+        if self.remote_state.selected().is_none() {
+            self.remote_state.select(Some(0));
+        }
+    }
+
+    /// Re-read the mc config and the aliases' options in settings.json, and
+    /// diff-update the remotes list and client cache. Clients are dropped only for
+    /// aliases that were removed or changed.
+    pub fn reload_config(&mut self) {
+        // A command-line remote may be all there is, with no config file behind it
+        let loaded = if self.cli_remotes.is_empty() {
+            McConfig::load()
+        } else {
+            McConfig::load_if_exists().map(|c| c.unwrap_or_else(McConfig::empty))
+        };
+        let mut config = match loaded {
+            Ok(c) => c,
+            Err(e) => {
+                self.notify_error(format!("Reload failed: {}", e));
+                return;
+            }
+        };
+        if let Ok(settings) = Settings::load() {
+            self.settings.remotes = settings.remotes;
+        }
+        config.apply_remote_settings(&self.settings.remotes);
+        for (alias, alias_config) in &self.cli_remotes {
+            config.aliases.insert(alias.clone(), alias_config.clone());
+        }
+
+        let added = config
+            .aliases
+            .keys()
+            .filter(|k| !self.config.aliases.contains_key(*k))
+            .count();
+        let removed = self
+            .config
+            .aliases
+            .keys()
+            .filter(|k| !config.aliases.contains_key(*k))
+            .count();
+        let changed = config
+            .aliases
+            .iter()
+            .filter(|(k, v)| self.config.aliases.get(*k).is_some_and(|old| old != *v))
+            .count();
+
+        self.clients
+            .retain(|alias, _| config.aliases.get(alias) == self.config.aliases.get(alias));
+        self.bucket_cache.retain(|alias, _| self.clients.contains_key(alias));
+
+        let selected = self.remote_state.selected();
+        let mut remotes: Vec<String> = config.aliases.keys().cloned().collect();
+        remotes.sort();
+        let sel = match selected {
+            // A promoted prefix keeps its place after the aliases
+            Some(i) if i >= self.remotes.len() => Some(remotes.len() + i - self.remotes.len()),
+            _ => selected
+                .and_then(|i| remotes.iter().position(|r| *r == self.remotes[i]))
+                .or(if remotes.is_empty() && self.promoted_prefixes().is_empty() { None } else { Some(0) }),
+        };
+        self.remote_state.select(sel);
+        self.remotes = remotes;
+        self.config = config;
+
+        // The remote being browsed may be gone or point somewhere else now
+        if let Some(remote) = self.current_remote().map(str::to_string)
+            && !self.clients.contains_key(&remote)
+        {
+            if self.search_active {
+                self.cancel_search();
+            }
+            self.cancel_indexing();
+            self.location = Location::RemoteList;
+            self.entries.clear();
+            self.marked.clear();
+            self.browser_state.select(None);
+            self.metadata = None;
+            self.preview.clear();
+            self.pane = Pane::Remotes;
+        }
+
+        self.notify(format!(
+            "Config reloaded: {} added, {} removed, {} changed",
+            added, removed, changed
+        ));
+        self.prewarm_remotes();
+    }
+
+    /// Alias of the remote currently being browsed, if any.
+    pub fn current_remote(&self) -> Option<&str> {
+        match &self.location {
+            Location::RemoteList => None,
+            Location::BucketList { remote } | Location::ObjectList { remote, .. } => Some(remote),
+        }
+    }
+
+    pub fn remote_tag(&self, alias: &str) -> Option<RemoteTag> {
+        self.config.aliases.get(alias).and_then(|a| a.tag())
+    }
+
+    pub fn remote_is_anonymous(&self, alias: &str) -> bool {
+        self.alias_config(alias).is_some_and(|a| a.is_anonymous())
+    }
+
+    pub(crate) fn remote_protection(&self, alias: &str) -> Protection {
+        self.config
+            .aliases
+            .get(alias)
+            .map_or(Protection::None, |a| a.protection())
+    }
+
+    /// Put the cursor on `key`, or on the directory holding it when it lies
+    /// deeper. Returns whether the listing shows either.
+    pub(crate) fn focus_on(&mut self, key: &str) -> bool {
+        let pos = self.entries.iter().position(|e| e.key() == key).or_else(|| {
+            self.entries
+                .iter()
+                .position(|e| matches!(e, Entry::Object(o) if o.is_dir && key.starts_with(o.key.as_str())))
+        });
+        if let Some(pos) = pos {
+            self.browser_state.select(Some(pos));
+        }
+        pos.is_some()
+    }
+
     pub(crate) fn fix_selection(&mut self) {
         if self.entries.is_empty() {
             self.browser_state.select(None);
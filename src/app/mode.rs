@@ -0,0 +1,756 @@
+//! Which popup or sub-mode has the keyboard, and what each of them can be asked to
+//! do. The ui maps a key to one of these actions for the current [`Mode`]; applying
+//! it lives here, next to the state it changes.
+
+use super::links::LinkTarget;
+use super::notify::Severity;
+use super::quick_views::QuickKind;
+use super::{App, Pane};
+
+/// The input mode the next key press is handled in. Listed topmost first: when
+/// several popups are open, the earlier one gets the key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Help,
+    ActionOutput,
+    Palette,
+    Jumps,
+    ConfirmDelete,
+    ConfirmProtected,
+    Plan,
+    LinkBundle,
+    CopyMetadata,
+    CopyOptions,
+    CopyPicker,
+    Filter,
+    MarksImport,
+    BucketInput,
+    Duplicate,
+    NewObject,
+    Identity,
+    Resume,
+    ConfirmQuit,
+    ConfirmIndex,
+    DownloadConflict,
+    RangeInput,
+    Rename,
+    SyncSetup,
+    LocalSearch,
+    Download,
+    Slideshow,
+    Favorites,
+    History,
+    AclPicker,
+    EntryMenu,
+    /// The quick view menu (`None`) or one of its lists.
+    QuickView(Option<QuickKind>),
+    Context,
+    Search,
+    Media,
+    TextPreview,
+    /// Waiting for the second key of a `g` / `y` sequence.
+    Sequence(char),
+    Normal,
+}
+
+impl Mode {
+    /// Whether keys go into a text field, where `?` is just a character.
+    pub fn takes_text(self) -> bool {
+        matches!(
+            self,
+            Mode::Palette
+                | Mode::Jumps
+                | Mode::ConfirmDelete
+                | Mode::ConfirmProtected
+                | Mode::CopyMetadata
+                | Mode::Filter
+                | Mode::MarksImport
+                | Mode::BucketInput
+                | Mode::Duplicate
+                | Mode::NewObject
+                | Mode::RangeInput
+                | Mode::Rename
+                | Mode::LocalSearch
+                | Mode::Search
+        )
+    }
+}
+
+/// A one-line text prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptAction {
+    Input(char),
+    Backspace,
+    Submit,
+    Cancel,
+    /// Fill the prompt's target from the clipboard (new objects only).
+    Paste,
+}
+
+/// A list narrowed by typing, moved through with the arrows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickerAction {
+    Input(char),
+    Backspace,
+    /// `true` moves down.
+    Move(bool),
+    Submit,
+    Cancel,
+}
+
+/// A plain list popup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListAction {
+    /// `true` moves down.
+    Move(bool),
+    Submit,
+    Cancel,
+}
+
+/// A yes / no question.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Yes,
+    No,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpAction {
+    Input(char),
+    Backspace,
+    /// Down (`true`) or up by this many lines.
+    Scroll(bool, usize),
+    /// Next (`true`) or previous mode's bindings.
+    CycleMode(bool),
+    Close,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputAction {
+    /// Down (`true`) or up by this many lines.
+    Scroll(bool, usize),
+    Copy,
+    Close,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeleteConfirmAction {
+    /// Type into the confirmation phrase, when one is asked for.
+    Input(char),
+    Backspace,
+    /// Switch between the yes and no buttons.
+    Toggle,
+    Submit,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanAction {
+    /// `true` moves down.
+    Move(bool),
+    Confirm,
+    Export,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkBundleAction {
+    Generate(LinkTarget),
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyOptionsAction {
+    CycleStorageClass,
+    CycleSse,
+    EditMetadata,
+    Close,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyPickerAction {
+    /// `true` moves down.
+    Move(bool),
+    Enter,
+    Back,
+    Options,
+    Confirm,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResumeAction {
+    Resume,
+    Discard,
+    /// Close the prompt and keep the unfinished transfers for next time.
+    Later,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictAction {
+    Overwrite,
+    /// Download under a free name instead.
+    Rename,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncSetupAction {
+    ToggleDirection,
+    CyclePolicy,
+    CycleAcl,
+    Plan,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadAction {
+    /// `true` moves down, in whichever pane has focus.
+    Move(bool),
+    Enter,
+    Back,
+    Download,
+    /// Rename the download target (local pane only).
+    Rename,
+    /// Filter the local listing (local pane only).
+    Search,
+    Sync,
+    Range,
+    SwitchPane,
+    Zoom,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlideshowAction {
+    /// Next (`true`) or previous image.
+    Step(bool),
+    Stop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FavoritesAction {
+    /// `true` moves down.
+    Move(bool),
+    Open,
+    Preview,
+    Download,
+    Share,
+    TogglePromoted,
+    Remove,
+    Close,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickViewAction {
+    /// `true` moves down.
+    Move(bool),
+    Show(QuickKind),
+    /// Open the view highlighted in the menu.
+    MenuSelect,
+    /// Go to the highlighted object in its listing.
+    Reveal,
+    Back,
+    Close,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextAction {
+    /// `true` moves down.
+    Move(bool),
+    Enter,
+    Back,
+    Close,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchAction {
+    Input(char),
+    Backspace,
+    /// `true` moves down; recalls an earlier search while the query is untouched.
+    Move(bool),
+    Select,
+    OpenContext,
+    ToggleGrouping,
+    ToggleGroup,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaAction {
+    Play,
+    Stop,
+    Zoom,
+    Close,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextPreviewAction {
+    /// Down (`true`) or up by this many lines.
+    Scroll(bool, usize),
+    /// Right (`true`) or left.
+    ScrollSideways(bool),
+    ResetSideways,
+    Top,
+    Bottom,
+    LoadMore,
+    ToggleWrap,
+    Copy,
+    Zoom,
+    Close,
+}
+
+/// A key meant for the open popup or sub-mode, one variant per [`Mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopupAction {
+    Help(HelpAction),
+    ActionOutput(OutputAction),
+    Palette(PickerAction),
+    Jumps(PickerAction),
+    ConfirmDelete(DeleteConfirmAction),
+    ConfirmProtected(PromptAction),
+    Plan(PlanAction),
+    LinkBundle(LinkBundleAction),
+    CopyMetadata(PromptAction),
+    CopyOptions(CopyOptionsAction),
+    CopyPicker(CopyPickerAction),
+    Filter(PromptAction),
+    MarksImport(PromptAction),
+    BucketInput(PromptAction),
+    Duplicate(PromptAction),
+    /// Submitting opens an editor on the terminal, so the ui handles that one.
+    NewObject(PromptAction),
+    CloseIdentity,
+    Resume(ResumeAction),
+    ConfirmQuit(Answer),
+    ConfirmIndex(Answer),
+    DownloadConflict(ConflictAction),
+    RangeInput(PromptAction),
+    Rename(PromptAction),
+    SyncSetup(SyncSetupAction),
+    LocalSearch(PickerAction),
+    Download(DownloadAction),
+    Slideshow(SlideshowAction),
+    Favorites(FavoritesAction),
+    History(ListAction),
+    AclPicker(ListAction),
+    EntryMenu(ListAction),
+    QuickView(QuickViewAction),
+    Context(ContextAction),
+    Search(SearchAction),
+    Media(MediaAction),
+    TextPreview(TextPreviewAction),
+}
+
+/// Type into or erase from a prompt's text; other prompt keys are the caller's.
+fn edit_text(text: &mut String, action: PromptAction) {
+    match action {
+        PromptAction::Input(c) => text.push(c),
+        PromptAction::Backspace => {
+            text.pop();
+        }
+        _ => {}
+    }
+}
+
+impl App {
+    /// The mode the next key press is handled in.
+    pub fn mode(&self) -> Mode {
+        let copy_picker = self.copy_picker.as_ref();
+        if self.help.is_some() {
+            Mode::Help
+        } else if self.action_output.is_some() {
+            Mode::ActionOutput
+        } else if self.palette.is_some() {
+            Mode::Palette
+        } else if self.jump_picker.is_some() {
+            Mode::Jumps
+        } else if self.confirm_delete.is_some() {
+            Mode::ConfirmDelete
+        } else if self.protected_confirm.is_some() {
+            Mode::ConfirmProtected
+        } else if self.plan.is_some() {
+            Mode::Plan
+        } else if self.link_bundle.is_some() {
+            Mode::LinkBundle
+        } else if copy_picker.is_some_and(|p| p.metadata_input.is_some()) {
+            Mode::CopyMetadata
+        } else if copy_picker.is_some_and(|p| p.options_open) {
+            Mode::CopyOptions
+        } else if copy_picker.is_some() {
+            Mode::CopyPicker
+        } else if self.filter_input.is_some() {
+            Mode::Filter
+        } else if self.marks_input.is_some() {
+            Mode::MarksImport
+        } else if self.bucket_input.is_some() {
+            Mode::BucketInput
+        } else if self.duplicate.is_some() {
+            Mode::Duplicate
+        } else if self.new_object.is_some() {
+            Mode::NewObject
+        } else if self.identity_popup.is_some() {
+            Mode::Identity
+        } else if self.resume_prompt {
+            Mode::Resume
+        } else if self.confirm_quit {
+            Mode::ConfirmQuit
+        } else if self.confirm_index {
+            Mode::ConfirmIndex
+        } else if self.download_conflict.is_some() {
+            Mode::DownloadConflict
+        } else if self.range_input.is_some() {
+            Mode::RangeInput
+        } else if self.rename_active {
+            Mode::Rename
+        } else if self.sync_setup.is_some() {
+            Mode::SyncSetup
+        } else if self.local_search_active {
+            Mode::LocalSearch
+        } else if self.download_mode {
+            Mode::Download
+        } else if self.slideshow.is_some() {
+            Mode::Slideshow
+        } else if self.show_favorites {
+            Mode::Favorites
+        } else if self.show_history {
+            Mode::History
+        } else if self.acl_picker.is_some() {
+            Mode::AclPicker
+        } else if self.entry_menu.is_some() {
+            Mode::EntryMenu
+        } else if let Some(view) = &self.quick_view {
+            Mode::QuickView(view.kind)
+        } else if self.context_view.is_some() {
+            Mode::Context
+        } else if self.search_active {
+            Mode::Search
+        } else if self.preview.media.is_some() {
+            Mode::Media
+        } else if self.preview.text_content.is_some() {
+            Mode::TextPreview
+        } else if let Some(prefix) = self.pending_key {
+            Mode::Sequence(prefix)
+        } else {
+            Mode::Normal
+        }
+    }
+
+    /// Apply one key meant for a popup or sub-mode.
+    pub(crate) async fn dispatch_popup(&mut self, action: PopupAction) {
+        match action {
+            PopupAction::Help(action) => match action {
+                HelpAction::Input(c) => self.help_input(c),
+                HelpAction::Backspace => self.help_backspace(),
+                HelpAction::Scroll(down, lines) => self.help_scroll(down, lines),
+                HelpAction::CycleMode(forward) => self.help_cycle_mode(forward),
+                HelpAction::Close => self.help = None,
+            },
+            PopupAction::ActionOutput(action) => match action {
+                OutputAction::Scroll(down, lines) => self.scroll_action_output(down, lines),
+                OutputAction::Copy => self.copy_action_output(),
+                OutputAction::Close => self.action_output = None,
+            },
+            PopupAction::Palette(action) => match action {
+                PickerAction::Input(c) => self.palette_input(c),
+                PickerAction::Backspace => self.palette_backspace(),
+                PickerAction::Move(down) => self.palette_move(down),
+                PickerAction::Submit => self.run_palette_command(),
+                PickerAction::Cancel => self.palette = None,
+            },
+            PopupAction::Jumps(action) => match action {
+                PickerAction::Input(c) => self.jump_input(c),
+                PickerAction::Backspace => self.jump_backspace(),
+                PickerAction::Move(down) => self.jump_move(down),
+                PickerAction::Submit => self.open_jump().await,
+                PickerAction::Cancel => self.jump_picker = None,
+            },
+            PopupAction::ConfirmDelete(action) => match action {
+                DeleteConfirmAction::Input(c) => self.delete_confirm_input(c),
+                DeleteConfirmAction::Backspace => self.delete_confirm_backspace(),
+                DeleteConfirmAction::Toggle => self.toggle_delete_confirm(),
+                DeleteConfirmAction::Submit => {
+                    if self.confirm_delete.as_ref().is_some_and(|c| c.selected_yes) {
+                        self.confirm_delete_yes().await;
+                    } else {
+                        self.confirm_delete = None;
+                    }
+                }
+                DeleteConfirmAction::Cancel => self.confirm_delete = None,
+            },
+            PopupAction::ConfirmProtected(action) => match action {
+                PromptAction::Submit => self.confirm_protected().await,
+                PromptAction::Cancel => self.protected_confirm = None,
+                other => {
+                    if let Some(confirm) = &mut self.protected_confirm {
+                        edit_text(&mut confirm.typed, other);
+                    }
+                }
+            },
+            PopupAction::Plan(action) => match action {
+                PlanAction::Move(true) => self.plan_move_down(),
+                PlanAction::Move(false) => self.plan_move_up(),
+                PlanAction::Confirm => self.confirm_plan().await,
+                PlanAction::Export => self.export_plan(),
+                PlanAction::Cancel => self.cancel_plan(),
+            },
+            PopupAction::LinkBundle(action) => match action {
+                LinkBundleAction::Generate(target) => self.generate_links(target),
+                LinkBundleAction::Cancel => self.link_bundle = None,
+            },
+            PopupAction::CopyMetadata(action) => match action {
+                PromptAction::Submit => self.finish_copy_metadata_input(),
+                PromptAction::Cancel => {
+                    if let Some(picker) = &mut self.copy_picker {
+                        picker.metadata_input = None;
+                    }
+                }
+                other => {
+                    if let Some(input) = self.copy_picker.as_mut().and_then(|p| p.metadata_input.as_mut()) {
+                        edit_text(input, other);
+                    }
+                }
+            },
+            PopupAction::CopyOptions(action) => match action {
+                CopyOptionsAction::CycleStorageClass => self.cycle_copy_storage_class(),
+                CopyOptionsAction::CycleSse => self.cycle_copy_sse(),
+                CopyOptionsAction::EditMetadata => self.start_copy_metadata_input(),
+                CopyOptionsAction::Close => self.toggle_copy_options(),
+            },
+            PopupAction::CopyPicker(action) => match action {
+                CopyPickerAction::Move(true) => self.picker_move_down(),
+                CopyPickerAction::Move(false) => self.picker_move_up(),
+                CopyPickerAction::Enter => self.picker_enter().await,
+                CopyPickerAction::Back => self.picker_back().await,
+                CopyPickerAction::Options => self.toggle_copy_options(),
+                CopyPickerAction::Confirm => self.confirm_copy().await,
+                CopyPickerAction::Cancel => self.cancel_copy(),
+            },
+            PopupAction::Filter(action) => match action {
+                PromptAction::Submit => self.finish_filter_input().await,
+                PromptAction::Cancel => self.filter_input = None,
+                other => {
+                    if let Some(input) = &mut self.filter_input {
+                        edit_text(input, other);
+                    }
+                }
+            },
+            PopupAction::MarksImport(action) => match action {
+                PromptAction::Submit => self.finish_marks_input().await,
+                PromptAction::Cancel => self.marks_input = None,
+                other => {
+                    if let Some(input) = &mut self.marks_input {
+                        edit_text(input, other);
+                    }
+                }
+            },
+            PopupAction::BucketInput(action) => match action {
+                PromptAction::Submit => self.finish_bucket_input().await,
+                PromptAction::Cancel => self.cancel_bucket_input(),
+                other => {
+                    if let Some(input) = &mut self.bucket_input {
+                        edit_text(input, other);
+                    }
+                }
+            },
+            PopupAction::Duplicate(action) => match action {
+                PromptAction::Submit => self.finish_duplicate().await,
+                PromptAction::Cancel => self.duplicate = None,
+                other => {
+                    if let Some(duplicate) = &mut self.duplicate {
+                        edit_text(&mut duplicate.name, other);
+                    }
+                }
+            },
+            PopupAction::NewObject(action) => match action {
+                // The ui leaves the terminal to the editor for this one
+                PromptAction::Submit => {}
+                PromptAction::Paste => self.paste_new_object().await,
+                PromptAction::Cancel => self.new_object = None,
+                other => {
+                    if let Some(new) = &mut self.new_object {
+                        edit_text(&mut new.name, other);
+                    }
+                }
+            },
+            PopupAction::CloseIdentity => self.identity_popup = None,
+            PopupAction::Resume(action) => match action {
+                ResumeAction::Resume => self.resume_unfinished().await,
+                ResumeAction::Discard => self.discard_unfinished(),
+                ResumeAction::Later => self.resume_prompt = false,
+            },
+            PopupAction::ConfirmQuit(Answer::Yes) => self.should_quit = true,
+            PopupAction::ConfirmQuit(Answer::No) => self.confirm_quit = false,
+            PopupAction::ConfirmIndex(Answer::Yes) => self.continue_indexing(),
+            PopupAction::ConfirmIndex(Answer::No) => self.decline_indexing(),
+            PopupAction::DownloadConflict(action) => match action {
+                ConflictAction::Overwrite => self.confirm_download().await,
+                ConflictAction::Rename => self.accept_suffix_rename(),
+                ConflictAction::Cancel => self.download_conflict = None,
+            },
+            PopupAction::RangeInput(action) => match action {
+                PromptAction::Submit => self.confirm_range_download().await,
+                PromptAction::Cancel => self.range_input = None,
+                other => {
+                    if let Some(input) = &mut self.range_input {
+                        edit_text(input, other);
+                    }
+                }
+            },
+            PopupAction::Rename(action) => match action {
+                PromptAction::Input(c) => self.rename_char(c),
+                PromptAction::Backspace => self.rename_backspace(),
+                PromptAction::Submit => self.finish_rename(),
+                PromptAction::Cancel => self.cancel_rename(),
+                PromptAction::Paste => {}
+            },
+            PopupAction::SyncSetup(action) => match action {
+                SyncSetupAction::ToggleDirection => self.toggle_sync_direction(),
+                SyncSetupAction::CyclePolicy => self.cycle_sync_policy(),
+                SyncSetupAction::CycleAcl => self.cycle_sync_acl(),
+                SyncSetupAction::Plan => self.plan_sync(),
+                SyncSetupAction::Cancel => self.sync_setup = None,
+            },
+            PopupAction::LocalSearch(action) => match action {
+                PickerAction::Input(c) => self.local_search_input(c),
+                PickerAction::Backspace => self.local_search_backspace(),
+                PickerAction::Move(true) => self.local_move_down(),
+                PickerAction::Move(false) => self.local_move_up(),
+                PickerAction::Submit => self.finish_local_search(),
+                PickerAction::Cancel => self.cancel_local_search(),
+            },
+            PopupAction::Download(action) => self.download_mode_action(action).await,
+            PopupAction::Slideshow(action) => match action {
+                SlideshowAction::Step(true) => self.slideshow_next(),
+                SlideshowAction::Step(false) => self.slideshow_prev(),
+                SlideshowAction::Stop => self.stop_slideshow(),
+            },
+            PopupAction::Favorites(action) => match action {
+                FavoritesAction::Move(true) => self.favorites_move_down(),
+                FavoritesAction::Move(false) => self.favorites_move_up(),
+                FavoritesAction::Open => self.open_favorite().await,
+                FavoritesAction::Preview => self.preview_favorite().await,
+                FavoritesAction::Download => self.download_favorite(),
+                FavoritesAction::Share => self.share_favorite().await,
+                FavoritesAction::TogglePromoted => self.toggle_promoted(),
+                FavoritesAction::Remove => self.remove_favorite(),
+                FavoritesAction::Close => self.toggle_favorites(),
+            },
+            PopupAction::History(action) => match action {
+                ListAction::Move(true) => self.history_move_down(),
+                ListAction::Move(false) => self.history_move_up(),
+                ListAction::Submit => self.rerun_selected_history().await,
+                ListAction::Cancel => self.toggle_history(),
+            },
+            PopupAction::AclPicker(action) => match action {
+                ListAction::Move(down) => self.acl_move(down),
+                ListAction::Submit => self.apply_acl().await,
+                ListAction::Cancel => self.acl_picker = None,
+            },
+            PopupAction::EntryMenu(action) => match action {
+                ListAction::Move(down) => self.entry_menu_move(down),
+                ListAction::Submit => self.run_entry_menu().await,
+                ListAction::Cancel => self.entry_menu = None,
+            },
+            PopupAction::QuickView(action) => match action {
+                QuickViewAction::Move(down) => self.quick_move(down),
+                QuickViewAction::Show(kind) => self.show_quick_view(kind),
+                QuickViewAction::MenuSelect => self.quick_menu_select(),
+                QuickViewAction::Reveal => self.quick_reveal().await,
+                QuickViewAction::Back => self.quick_back(),
+                QuickViewAction::Close => self.quick_view = None,
+            },
+            PopupAction::Context(action) => match action {
+                ContextAction::Move(true) => self.context_move_down(),
+                ContextAction::Move(false) => self.context_move_up(),
+                ContextAction::Enter => self.context_enter().await,
+                ContextAction::Back => self.context_back().await,
+                ContextAction::Close => self.close_context(),
+            },
+            PopupAction::Search(action) => match action {
+                SearchAction::Input(c) => self.search_input(c),
+                SearchAction::Backspace => self.search_backspace(),
+                SearchAction::Move(down) if self.search_recalling() => self.recall_search(!down),
+                SearchAction::Move(true) => self.move_down(),
+                SearchAction::Move(false) => self.move_up(),
+                SearchAction::Select => self.select().await,
+                SearchAction::OpenContext => self.open_result_context().await,
+                SearchAction::ToggleGrouping => self.toggle_search_grouping(),
+                SearchAction::ToggleGroup => self.toggle_search_group(),
+                SearchAction::Cancel => self.cancel_search(),
+            },
+            PopupAction::Media(action) => match action {
+                MediaAction::Play => self.play_media(),
+                MediaAction::Stop => {
+                    self.preview.stop_playback();
+                    self.toasts.clear(Severity::Info);
+                }
+                MediaAction::Zoom => self.toggle_zoom(),
+                MediaAction::Close => {
+                    self.preview.clear();
+                    self.toasts.clear(Severity::Info);
+                }
+            },
+            PopupAction::TextPreview(action) => match action {
+                TextPreviewAction::Scroll(true, lines) => self.preview.scroll_down(lines),
+                TextPreviewAction::Scroll(false, lines) => self.preview.scroll_up(lines),
+                TextPreviewAction::ScrollSideways(true) => self.preview.scroll_right(8),
+                TextPreviewAction::ScrollSideways(false) => self.preview.scroll_left(8),
+                TextPreviewAction::ResetSideways => self.preview.hscroll = 0,
+                TextPreviewAction::Top => self.preview.scroll_offset = 0,
+                TextPreviewAction::Bottom => {
+                    self.preview.scroll_offset = self.preview.line_count.saturating_sub(1);
+                }
+                TextPreviewAction::LoadMore => self.load_more_preview(),
+                TextPreviewAction::ToggleWrap => self.preview.toggle_wrap(),
+                TextPreviewAction::Copy => self.copy_preview(),
+                TextPreviewAction::Zoom => self.toggle_zoom(),
+                TextPreviewAction::Close => {
+                    self.preview.clear();
+                    self.toasts.clear(Severity::Info);
+                }
+            },
+        }
+    }
+
+    /// Download mode moves through whichever pane has focus. In the local pane the
+    /// row above the first entry stands for the parent directory.
+    async fn download_mode_action(&mut self, action: DownloadAction) {
+        let local = self.pane == Pane::LocalFs;
+        match action {
+            DownloadAction::Move(down) if local => match (down, self.local_state.selected()) {
+                (false, None) => {}
+                (false, Some(0)) => self.local_state.select(None),
+                (false, Some(_)) => self.local_move_up(),
+                (true, None) => {
+                    if !self.local_entries.is_empty() {
+                        self.local_state.select(Some(0));
+                    }
+                }
+                (true, Some(_)) => self.local_move_down(),
+            },
+            DownloadAction::Move(true) => self.move_down(),
+            DownloadAction::Move(false) => self.move_up(),
+            DownloadAction::Enter if local => match self.local_state.selected() {
+                None => self.local_go_back(),
+                Some(i) => {
+                    if self.local_entries.get(i).is_some_and(|e| e.is_dir) {
+                        self.local_enter();
+                    }
+                }
+            },
+            DownloadAction::Enter => self.select().await,
+            DownloadAction::Back if local => self.local_go_back(),
+            DownloadAction::Back => self.go_back().await,
+            DownloadAction::Download => self.confirm_download().await,
+            DownloadAction::Rename if local => self.start_rename(),
+            DownloadAction::Search if local => self.start_local_search(),
+            DownloadAction::Rename | DownloadAction::Search => {}
+            DownloadAction::Sync => self.start_sync_setup(),
+            DownloadAction::Range => self.start_range_input(),
+            DownloadAction::SwitchPane => self.switch_pane(),
+            DownloadAction::Zoom => self.toggle_zoom(),
+            DownloadAction::Cancel => self.cancel_download_mode(),
+        }
+    }
+}
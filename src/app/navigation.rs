@@ -1,3 +1,12 @@
+use std::time::Instant;
+
+use crate::plugins::Hook;
+use crate::object_store::BucketInfo;
+use crate::s3_error::{self, S3ErrorKind};
+
+use super::image_info;
+use super::listing::Freshness;
+use super::preview::{extension_to_kind, PreviewKind};
 use super::{parent_prefix, App, Entry, Location, Pane};
 
 impl App {
@@ -25,7 +34,7 @@ impl App {
         match self.pane {
             Pane::Remotes => {
                 let i = self.remote_state.selected().unwrap_or(0);
-                if i + 1 < self.remotes.len() {
+                if i + 1 < self.remote_rows() {
                     self.remote_state.select(Some(i + 1));
                 }
             }
@@ -41,6 +50,43 @@ impl App {
         }
     }
 
+    /// Jump to the first (`gg`) or last (`ge`) entry of the focused list.
+    pub fn jump_to_edge(&mut self, last: bool) {
+        match self.pane {
+            Pane::Remotes if self.remote_rows() > 0 => {
+                self.remote_state
+                    .select(Some(if last { self.remote_rows() - 1 } else { 0 }));
+            }
+            Pane::Browser if !self.entries.is_empty() => {
+                self.browser_state
+                    .select(Some(if last { self.entries.len() - 1 } else { 0 }));
+                self.metadata = None;
+                self.preview.clear();
+            }
+            Pane::LocalFs if !self.local_entries.is_empty() => {
+                self.local_state
+                    .select(Some(if last { self.local_entries.len() - 1 } else { 0 }));
+            }
+            _ => {}
+        }
+    }
+
+    /// Go to the root of the current bucket (`gr`).
+    pub async fn go_bucket_root(&mut self) {
+        if let Location::ObjectList { remote, bucket, .. } = self.location.clone() {
+            self.enter_prefix(&remote, &bucket, "").await;
+        }
+    }
+
+    /// Go to the bucket list of the current remote (`gb`).
+    pub async fn go_bucket_list(&mut self) {
+        if let Some(remote) = self.current_remote().map(str::to_string) {
+            self.metadata = None;
+            self.preview.clear();
+            self.enter_remote(&remote).await;
+        }
+    }
+
     pub fn switch_pane(&mut self) {
         if self.search_active {
             return;
@@ -58,14 +104,21 @@ impl App {
         };
     }
 
+    /// Expand the focused pane (or the text preview) to fill the content area.
+    pub fn toggle_zoom(&mut self) {
+        self.zoomed = !self.zoomed;
+    }
+
     pub async fn select(&mut self) {
-        self.error = None;
-        self.status_message = None;
         match self.pane {
             Pane::Remotes => {
-                if let Some(i) = self.remote_state.selected() {
-                    let alias = self.remotes[i].clone();
-                    self.enter_remote(&alias).await;
+                match self.remote_state.selected() {
+                    Some(i) if i < self.remotes.len() => {
+                        let alias = self.remotes[i].clone();
+                        self.enter_remote(&alias).await;
+                    }
+                    Some(i) => self.open_promoted(i).await,
+                    None => {}
                 }
             }
             Pane::Browser => {
@@ -87,6 +140,7 @@ impl App {
                                 self.enter_bucket(&remote, &b.name).await;
                             }
                         }
+                        Entry::Group(_) => {}
                         Entry::Object(obj) => {
                             if obj.is_dir {
                                 if let Location::ObjectList {
@@ -108,7 +162,9 @@ impl App {
                                 {
                                     let remote = remote.clone();
                                     let bucket = bucket.clone();
-                                    self.fetch_metadata(&remote, &bucket, &obj.key).await;
+                                    let (key, etag) = (obj.key.clone(), obj.etag.clone());
+                                    self.fetch_metadata(&remote, &bucket, &key, etag.as_deref()).await;
+                                    self.fire_hook(Hook::Select, None);
                                 }
                             }
                         }
@@ -124,8 +180,6 @@ impl App {
             self.cancel_search();
             return;
         }
-        self.error = None;
-        self.status_message = None;
         self.metadata = None;
         self.preview.clear();
         match self.location.clone() {
@@ -134,6 +188,7 @@ impl App {
                 self.cancel_indexing();
                 self.location = Location::RemoteList;
                 self.entries.clear();
+                self.marked.clear();
                 self.browser_state.select(None);
                 self.pane = Pane::Remotes;
             }
@@ -153,13 +208,13 @@ impl App {
     }
 
     pub async fn refresh(&mut self) {
-        self.error = None;
         if self.search_active {
             self.cancel_search();
         }
         match self.location.clone() {
             Location::RemoteList => {}
             Location::BucketList { remote } => {
+                self.bucket_cache.remove(&remote);
                 self.enter_remote(&remote).await;
             }
             Location::ObjectList {
@@ -168,6 +223,9 @@ impl App {
                 prefix,
             } => {
                 self.cancel_indexing();
+                self.head_cache.invalidate(&remote, &bucket, &prefix);
+                self.forget_prefetched(&remote, &bucket);
+                self.focus_key = self.selected_key();
                 self.enter_prefix(&remote, &bucket, &prefix).await;
             }
         }
@@ -176,29 +234,120 @@ impl App {
     // ── S3 operations ───────────────────────────────────────────
 
     pub(crate) async fn enter_remote(&mut self, alias: &str) {
-        if let Err(e) = self.ensure_client(alias) {
-            self.error = Some(format!("Connection failed: {}", e));
+        let Some(buckets) = self.remote_buckets(alias).await else {
             return;
+        };
+
+        self.entries = buckets.into_iter().map(Entry::Bucket).collect();
+        self.marked.clear();
+        self.location = Location::BucketList {
+            remote: alias.to_string(),
+        };
+        self.browser_state.select(if self.entries.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+        self.pane = Pane::Browser;
+    }
+
+    /// Buckets of a remote: its listing plus any configured for the alias. Reports
+    /// an error and returns `None` when the remote can't be listed.
+    pub(crate) async fn remote_buckets(&mut self, alias: &str) -> Option<Vec<BucketInfo>> {
+        if let Err(e) = self.ensure_client(alias) {
+            self.notify_error(format!("Connection failed: {}", e));
+            return None;
         }
 
         let client = self.clients[alias].clone();
-        match client.list_buckets().await {
-            Ok(buckets) => {
-                self.entries = buckets.into_iter().map(Entry::Bucket).collect();
-                self.location = Location::BucketList {
-                    remote: alias.to_string(),
-                };
-                self.browser_state.select(if self.entries.is_empty() {
-                    None
+        let declared = self
+            .config
+            .aliases
+            .get(alias)
+            .map(|a| a.buckets.clone())
+            .unwrap_or_default();
+
+        let listed = match self.bucket_cache.get(alias) {
+            Some((at, buckets)) => Ok((*at, buckets.clone())),
+            None => client.list_buckets().await.map(|buckets| (Instant::now(), buckets)),
+        };
+        let mut buckets = match listed {
+            Ok((at, buckets)) => {
+                self.listing_freshness = Freshness::since(at);
+                self.bucket_cache.insert(alias.to_string(), (at, buckets.clone()));
+                buckets
+            }
+            Err(e) if s3_error::kind_of(&e) == Some(S3ErrorKind::AccessDenied) => {
+                self.listing_freshness = Freshness::Live;
+                // Scoped keys often can't enumerate buckets but can use known ones
+                self.notify(if declared.is_empty() {
+                    "ListBuckets denied - press b to open a bucket by name".to_string()
                 } else {
-                    Some(0)
+                    "ListBuckets denied - showing configured buckets".to_string()
                 });
-                self.pane = Pane::Browser;
+                Vec::new()
             }
             Err(e) => {
-                self.error = Some(format!("Failed to list buckets: {}", e));
+                self.notify_error(format!("Failed to list buckets: {}", e));
+                return None;
+            }
+        };
+
+        for name in declared {
+            if !buckets.iter().any(|b| b.name == name) {
+                buckets.push(BucketInfo {
+                    name,
+                    creation_date: None,
+                });
             }
         }
+        Some(buckets)
+    }
+
+    /// Start typing a bucket name to open directly (for keys without ListBuckets).
+    pub fn start_bucket_input(&mut self) {
+        if self.search_active {
+            return;
+        }
+        if self.bucket_input_remote().is_none() {
+            self.notify_error("Select a remote first");
+            return;
+        }
+        self.bucket_input = Some(String::new());
+    }
+
+    pub fn cancel_bucket_input(&mut self) {
+        self.bucket_input = None;
+    }
+
+    /// The remote being browsed, or the one highlighted in the remotes pane.
+    fn bucket_input_remote(&self) -> Option<String> {
+        match self.current_remote() {
+            Some(r) => Some(r.to_string()),
+            None => self
+                .remote_state
+                .selected()
+                .and_then(|i| self.remote_row_alias(i).map(str::to_string)),
+        }
+    }
+
+    /// Open the typed bucket on the current (or selected) remote.
+    pub async fn finish_bucket_input(&mut self) {
+        let name = match self.bucket_input.take() {
+            Some(n) if !n.trim().is_empty() => n.trim().to_string(),
+            _ => return,
+        };
+        let Some(remote) = self.bucket_input_remote() else {
+            return;
+        };
+        if let Err(e) = self.ensure_client(&remote) {
+            self.notify_error(format!("Connection failed: {}", e));
+            return;
+        }
+        self.metadata = None;
+        self.preview.clear();
+        self.pane = Pane::Browser;
+        self.enter_bucket(&remote, &name).await;
     }
 
     pub(crate) async fn enter_bucket(&mut self, remote: &str, bucket: &str) {
@@ -206,51 +355,124 @@ impl App {
     }
 
     pub(crate) async fn enter_prefix(&mut self, remote: &str, bucket: &str, prefix: &str) {
+        let focus = self.focus_key.take();
         let client = match self.clients.get(remote) {
             Some(c) => c.clone(),
             None => {
-                self.error = Some("Not connected to remote".to_string());
+                self.notify_error("Not connected to remote");
                 return;
             }
         };
 
-        match client.list_objects(bucket, prefix).await {
-            Ok(objects) => {
-                self.entries = objects.into_iter().map(Entry::Object).collect();
+        let listing = match self.take_prefetched(remote, bucket, prefix) {
+            Some(page) => Ok(page),
+            None => client
+                .list_objects_page(bucket, prefix)
+                .await
+                .map(|(objects, truncated)| (objects, truncated, Instant::now())),
+        };
+        match listing {
+            Ok((objects, truncated, listed_at)) => {
+                self.listing_freshness = if truncated { Freshness::Partial } else { Freshness::since(listed_at) };
                 self.location = Location::ObjectList {
                     remote: remote.to_string(),
                     bucket: bucket.to_string(),
                     prefix: prefix.to_string(),
                 };
+                self.record_jump(remote, bucket, prefix);
+                if let Some(tree) = &mut self.tree {
+                    tree.reset();
+                }
+                self.entries = self
+                    .arrange_listing(objects)
+                    .into_iter()
+                    .map(Entry::Object)
+                    .collect();
+                self.marked.clear();
                 self.browser_state.select(if self.entries.is_empty() {
                     None
                 } else {
                     Some(0)
                 });
+                if let Some(key) = focus
+                    && key.starts_with(prefix)
+                {
+                    self.focus_on(&key);
+                }
 
-                self.start_indexing(remote, bucket);
+                if self.settings.auto_index {
+                    self.start_indexing(remote, bucket);
+                } else if self
+                    .index_key
+                    .as_ref()
+                    .is_some_and(|(r, b)| r != remote || b != bucket)
+                {
+                    // Left for the first search here; the old pool belongs to another bucket
+                    self.cancel_indexing();
+                }
             }
             Err(e) => {
-                self.error = Some(format!("Failed to list objects: {}", e));
+                self.notify_error(format!("Failed to list objects: {}", e));
             }
         }
     }
 
-    async fn fetch_metadata(&mut self, remote: &str, bucket: &str, key: &str) {
+    /// Connect to `remote` if needed and list `prefix` in the browser.
+    pub(crate) async fn open_prefix(&mut self, remote: &str, bucket: &str, prefix: &str) {
+        if let Err(e) = self.ensure_client(remote) {
+            self.notify_error(format!("Connection failed: {}", e));
+            return;
+        }
+        self.metadata = None;
+        self.preview.clear();
+        self.enter_prefix(remote, bucket, prefix).await;
+        self.pane = Pane::Browser;
+    }
+
+    /// Navigate to `key`'s parent prefix and put the cursor on it.
+    pub(crate) async fn reveal_object(&mut self, remote: &str, bucket: &str, key: &str) {
+        if let Err(e) = self.ensure_client(remote) {
+            self.notify_error(format!("Connection failed: {}", e));
+            return;
+        }
+        self.metadata = None;
+        self.preview.clear();
+        self.focus_key = Some(key.to_string());
+        self.enter_prefix(remote, bucket, &parent_prefix(key)).await;
+        self.pane = Pane::Browser;
+    }
+
+    /// Show `key`'s metadata, from the HEAD cache while `etag` (the listing's) still matches.
+    async fn fetch_metadata(&mut self, remote: &str, bucket: &str, key: &str, etag: Option<&str>) {
+        if let Some(meta) = self.head_cache.get(remote, bucket, key, etag) {
+            let meta = meta.clone();
+            self.head_cache.insert(remote, bucket, meta.clone());
+            self.metadata = Some(meta);
+            return;
+        }
         let client = match self.clients.get(remote) {
             Some(c) => c.clone(),
             None => {
-                self.error = Some("Not connected to remote".to_string());
+                self.notify_error("Not connected to remote");
                 return;
             }
         };
 
         match client.head_object(bucket, key).await {
-            Ok(meta) => {
+            Ok(mut meta) => {
+                let is_image = meta.content_type.as_deref().is_some_and(|ct| ct.starts_with("image/"))
+                    || extension_to_kind(key) == Some(PreviewKind::Image);
+                if is_image && meta.size > 0 {
+                    let len = (meta.size as u64).min(image_info::HEADER_BYTES);
+                    if let Ok(header) = client.get_object_range(bucket, key, 0, len).await {
+                        meta.image_info = image_info::describe(&header);
+                    }
+                }
+                self.head_cache.insert(remote, bucket, meta.clone());
                 self.metadata = Some(meta);
             }
             Err(e) => {
-                self.error = Some(format!("Failed to get metadata: {}", e));
+                self.notify_error(format!("Failed to get metadata: {}", e));
             }
         }
     }
@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use crate::hooks::Op;
+use crate::object_store;
+
+use super::changes::Change;
+use super::confirm::Gate;
+use super::preview_cache::preview_dir;
+use super::{App, Location};
+
+/// A new object being written from text (`Shift+U`): its name is typed first,
+/// then the text comes from `$EDITOR` or the clipboard.
+pub struct NewObject {
+    pub remote: String,
+    pub bucket: String,
+    pub prefix: String,
+    pub name: String,
+}
+
+impl NewObject {
+    pub fn key(&self) -> String {
+        format!("{}{}", self.prefix, self.name.trim())
+    }
+}
+
+impl App {
+    pub fn start_new_object(&mut self) {
+        let Location::ObjectList { remote, bucket, prefix } = self.location.clone() else {
+            self.notify_warn("Navigate into a bucket first");
+            return;
+        };
+        if self.gate(&[&remote], false) == Gate::Refused || self.refuse_locked(&remote, &bucket) {
+            return;
+        }
+        self.new_object = Some(NewObject {
+            remote,
+            bucket,
+            prefix,
+            name: String::new(),
+        });
+    }
+
+    /// File the editor opens for the new object's text, named like the object so
+    /// the editor picks the right syntax. `None` until a name is typed.
+    pub fn new_object_draft(&self) -> Option<PathBuf> {
+        let name = self.new_object.as_ref()?.name.trim();
+        let file = name.rsplit('/').next().filter(|f| !f.is_empty())?;
+        Some(preview_dir().join("new-object").join(file))
+    }
+
+    /// Upload the text read back from the editor's draft file.
+    pub async fn finish_new_object_draft(&mut self) {
+        let Some(draft) = self.new_object_draft() else {
+            return;
+        };
+        let text = tokio::fs::read(&draft).await.unwrap_or_default();
+        let _ = tokio::fs::remove_file(&draft).await;
+        self.put_new_object(text).await;
+    }
+
+    /// Upload the clipboard's text as the new object.
+    pub async fn paste_new_object(&mut self) {
+        if self.new_object_draft().is_none() {
+            self.notify_warn("Type a name first");
+            return;
+        }
+        match crate::clipboard::paste() {
+            Ok(text) => self.put_new_object(text.into_bytes()).await,
+            Err(e) => self.notify_error(format!("Failed to read the clipboard: {}", e)),
+        }
+    }
+
+    async fn put_new_object(&mut self, text: Vec<u8>) {
+        let Some(new) = self.new_object.take() else {
+            return;
+        };
+        if text.is_empty() {
+            self.notify_warn("Nothing written; no object created");
+            return;
+        }
+        let Some(client) = self.clients.get(&new.remote).cloned() else {
+            self.notify_error("Not connected to remote");
+            return;
+        };
+        let key = new.key();
+        let keys = vec![key.clone()];
+        let hooks = self.settings.hooks.clone();
+        if let Err(e) = hooks.run(true, Op::Upload, &new.remote, &new.bucket, &keys).await {
+            self.notify_error(format!("Upload cancelled: {}", e));
+            return;
+        }
+        let tmp = preview_dir().join("new-object.part");
+        match object_store::put_bytes(&*client, &new.bucket, &key, &text, &tmp).await {
+            Ok(()) => {
+                self.notify(format!("Wrote {} ({} bytes)", key, text.len()));
+                self.apply_change(&new.remote, &new.bucket, Change::Written(vec![(key, text.len() as u64)]));
+                if let Err(e) = hooks.run(false, Op::Upload, &new.remote, &new.bucket, &keys).await {
+                    self.notify_warn(e.to_string());
+                }
+            }
+            Err(e) => self.notify_error(format!("Upload failed: {}", e)),
+        }
+    }
+}
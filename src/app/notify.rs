@@ -0,0 +1,107 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::App;
+
+/// Most toasts shown at once; older ones are dropped first.
+const MAX_TOASTS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warn,
+    Error,
+}
+
+impl Severity {
+    /// How long a toast of this severity stays up.
+    fn lifetime(self) -> Duration {
+        match self {
+            Severity::Info => Duration::from_secs(4),
+            Severity::Warn => Duration::from_secs(7),
+            Severity::Error => Duration::from_secs(12),
+        }
+    }
+}
+
+pub struct Toast {
+    pub message: String,
+    pub severity: Severity,
+    shown_at: Instant,
+}
+
+/// Stack of transient notifications, oldest first.
+#[derive(Default)]
+pub struct Toasts {
+    items: VecDeque<Toast>,
+}
+
+impl Toasts {
+    /// Add a toast. Repeating the newest message restarts its timer instead of stacking it.
+    pub fn push(&mut self, severity: Severity, message: String) {
+        if let Some(last) = self.items.back_mut()
+            && last.severity == severity
+            && last.message == message
+        {
+            last.shown_at = Instant::now();
+            return;
+        }
+        self.items.push_back(Toast {
+            message,
+            severity,
+            shown_at: Instant::now(),
+        });
+        while self.items.len() > MAX_TOASTS {
+            self.items.pop_front();
+        }
+    }
+
+    /// Drop expired toasts. Call every tick.
+    pub fn prune(&mut self) {
+        self.items.retain(|t| t.shown_at.elapsed() < t.severity.lifetime());
+    }
+
+    /// Time until the next toast expires.
+    pub fn next_expiry(&self) -> Option<Duration> {
+        self.items
+            .iter()
+            .map(|t| t.severity.lifetime().saturating_sub(t.shown_at.elapsed()))
+            .min()
+    }
+
+    /// Drop toasts of one severity, e.g. a "Loading..." note once loading is done.
+    pub fn clear(&mut self, severity: Severity) {
+        self.items.retain(|t| t.severity != severity);
+    }
+
+    pub fn dismiss_all(&mut self) {
+        self.items.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Toast> {
+        self.items.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Number of error toasts currently up.
+    pub fn error_count(&self) -> usize {
+        self.items.iter().filter(|t| t.severity == Severity::Error).count()
+    }
+}
+
+impl App {
+    pub fn notify(&mut self, message: impl Into<String>) {
+        self.toasts.push(Severity::Info, message.into());
+    }
+
+    pub fn notify_warn(&mut self, message: impl Into<String>) {
+        self.toasts.push(Severity::Warn, message.into());
+    }
+
+    pub fn notify_error(&mut self, message: impl Into<String>) {
+        self.toasts.push(Severity::Error, message.into());
+    }
+}
@@ -0,0 +1,76 @@
+use super::preview::looks_like_text;
+use super::{App, Entry, Location};
+
+/// How much of an object a peek reads.
+const PEEK_BYTES: u64 = 1024;
+
+/// Longest first line shown in the status bar.
+const MAX_LINE_CHARS: usize = 80;
+
+/// Best guess at the text encoding of the first bytes of an object.
+fn detect_encoding(bytes: &[u8]) -> &'static str {
+    if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        "UTF-8 (BOM)"
+    } else if bytes.starts_with(&[0xFF, 0xFE]) {
+        "UTF-16LE"
+    } else if bytes.starts_with(&[0xFE, 0xFF]) {
+        "UTF-16BE"
+    } else if bytes.is_ascii() && !bytes.contains(&0) {
+        "ASCII"
+    } else if looks_like_text(bytes) {
+        "UTF-8"
+    } else if !bytes.iter().any(|&b| b < 0x20 && !b"\t\n\r\x0c".contains(&b)) {
+        "Latin-1?"
+    } else {
+        "binary"
+    }
+}
+
+/// One-line summary of an object's head: magic type, encoding and first line.
+fn describe(bytes: &[u8]) -> String {
+    let magic = infer::get(bytes).map(|t| t.mime_type());
+    let encoding = detect_encoding(bytes);
+    let mut parts = vec![magic.unwrap_or(if encoding == "binary" { "unknown" } else { "text" }).to_string()];
+    parts.push(encoding.to_string());
+
+    if magic.is_none() && encoding != "binary" {
+        let text = String::from_utf8_lossy(bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes));
+        let line = text.lines().next().unwrap_or("").trim_end();
+        let mut first: String = line.chars().take(MAX_LINE_CHARS).collect();
+        if line.chars().count() > MAX_LINE_CHARS {
+            first.push('…');
+        }
+        parts.push(format!("\"{}\"", first));
+    }
+    parts.join(" | ")
+}
+
+impl App {
+    /// Read the first KB of the selected object and summarize it in the status bar.
+    pub async fn peek(&mut self) {
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            return;
+        };
+        let Some(Entry::Object(obj)) = self.browser_state.selected().and_then(|i| self.entries.get(i))
+        else {
+            return;
+        };
+        if obj.is_dir {
+            return;
+        }
+        if obj.size == 0 {
+            self.notify(format!("{}: empty object", obj.display_name));
+            return;
+        }
+        let Some(client) = self.clients.get(remote) else {
+            return;
+        };
+
+        let name = obj.display_name.clone();
+        let end = PEEK_BYTES.min(obj.size as u64);
+        match client.get_object_range(bucket, &obj.key, 0, end).await {
+            Ok(bytes) => self.notify(format!("{}: {}", name, describe(&bytes))),
+            Err(e) => self.notify_error(format!("Peek failed: {}", e)),
+        }
+    }
+}
@@ -0,0 +1,354 @@
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use ratatui::widgets::ListState;
+use tokio::sync::mpsc;
+
+use crate::object_store::{ObjectEntry, Store};
+
+use super::confirm::Guarded;
+use super::history::DownloadSpec;
+use super::notify::Severity;
+use super::sync::SyncRun;
+use super::tasks::TaskKind;
+use super::{parent_prefix, App, DeleteConfirm};
+
+/// What a bulk operation will do to one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanAction {
+    Download,
+    Upload,
+    Overwrite,
+    Delete,
+    Skip,
+}
+
+impl PlanAction {
+    pub const ALL: [PlanAction; 5] = [
+        PlanAction::Download,
+        PlanAction::Upload,
+        PlanAction::Overwrite,
+        PlanAction::Delete,
+        PlanAction::Skip,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PlanAction::Download => "DOWNLOAD",
+            PlanAction::Upload => "UPLOAD",
+            PlanAction::Overwrite => "OVERWRITE",
+            PlanAction::Delete => "DELETE",
+            PlanAction::Skip => "SKIP",
+        }
+    }
+
+    fn verb(self) -> &'static str {
+        match self {
+            PlanAction::Download => "download",
+            PlanAction::Upload => "upload",
+            PlanAction::Overwrite => "overwrite",
+            PlanAction::Delete => "delete",
+            PlanAction::Skip => "skip",
+        }
+    }
+}
+
+pub struct PlanItem {
+    pub action: PlanAction,
+    /// Object key.
+    pub path: String,
+    pub size: u64,
+}
+
+/// The operation that runs once the plan is confirmed.
+pub enum PlanOp {
+    DeletePrefix(DeleteConfirm),
+    Download(DownloadSpec),
+    Sync(SyncRun),
+}
+
+/// Dry-run of a bulk operation, shown for review before anything is changed.
+pub struct Plan {
+    pub title: String,
+    pub op: PlanOp,
+    pub items: Vec<PlanItem>,
+    pub state: ListState,
+}
+
+impl Plan {
+    pub(super) fn new(title: String, op: PlanOp, items: Vec<PlanItem>) -> Self {
+        let mut state = ListState::default();
+        state.select((!items.is_empty()).then_some(0));
+        Self {
+            title,
+            op,
+            items,
+            state,
+        }
+    }
+
+    /// e.g. "will download 120 files (3.4 GiB), overwrite 7 (12 MiB), skip 2,410"
+    pub fn summary(&self) -> String {
+        let parts: Vec<String> = PlanAction::ALL
+            .iter()
+            .filter_map(|&action| {
+                let (count, bytes) = self
+                    .items
+                    .iter()
+                    .filter(|i| i.action == action)
+                    .fold((0usize, 0u64), |(n, b), i| (n + 1, b + i.size));
+                if count == 0 {
+                    return None;
+                }
+                let files = if count == 1 { "file" } else { "files" };
+                Some(if action == PlanAction::Skip || bytes == 0 {
+                    format!("{} {} {}", action.verb(), group_digits(count), files)
+                } else {
+                    format!(
+                        "{} {} {} ({})",
+                        action.verb(),
+                        group_digits(count),
+                        files,
+                        humansize::format_size(bytes, humansize::BINARY)
+                    )
+                })
+            })
+            .collect();
+        if parts.is_empty() {
+            "nothing to do".to_string()
+        } else {
+            format!("will {}", parts.join(", "))
+        }
+    }
+
+    /// Write the plan as tab-separated `action size path` lines into `dir`.
+    pub fn export(&self, dir: &Path) -> std::io::Result<PathBuf> {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let path = dir.join(format!("s3-plan-{}.tsv", secs));
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+        writeln!(file, "# {}: {}", self.title, self.summary())?;
+        for item in &self.items {
+            writeln!(file, "{}\t{}\t{}", item.action.label(), item.size, item.path)?;
+        }
+        file.flush()?;
+        Ok(path)
+    }
+}
+
+/// Zero-byte `dir/` keys with other objects under them. A marker of a directory
+/// holding nothing else is kept, since the directory would disappear with it.
+fn redundant_markers(objects: Vec<ObjectEntry>) -> Vec<ObjectEntry> {
+    let mut occupied = HashSet::new();
+    for o in &objects {
+        let mut parent = parent_prefix(&o.key);
+        while !parent.is_empty() && occupied.insert(parent.clone()) {
+            parent = parent_prefix(&parent);
+        }
+    }
+    objects
+        .into_iter()
+        .filter(|o| o.key.ends_with('/') && o.size == 0 && occupied.contains(&o.key))
+        .collect()
+}
+
+/// 2410 -> "2,410"
+fn group_digits(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+impl App {
+    /// List everything under a prefix that is about to be deleted and show the plan.
+    /// A complete index of the bucket stands in for the listing.
+    pub(crate) fn plan_delete_prefix(&mut self, client: Store, bucket: String, confirm: DeleteConfirm) {
+        let title = format!("Delete {}/", confirm.display_name);
+        let indexed = self.indexed_under(&confirm.remote, &bucket, &confirm.key);
+        self.spawn_plan(async move {
+            let objects = match indexed {
+                Some(objects) => objects,
+                None => client
+                    .list_all_keys(&bucket, &confirm.key)
+                    .await
+                    .map_err(|e| e.to_string())?,
+            };
+            let items = objects
+                .into_iter()
+                .map(|o| PlanItem {
+                    action: PlanAction::Delete,
+                    path: o.key,
+                    size: o.size.max(0) as u64,
+                })
+                .collect();
+            Ok(Plan::new(title, PlanOp::DeletePrefix(confirm), items))
+        });
+    }
+
+    /// List a prefix and show the directory markers under it that are redundant:
+    /// zero-byte `dir/` keys whose directory other objects keep in place anyway.
+    pub(crate) fn plan_delete_markers(&mut self, client: Store, bucket: String, mut confirm: DeleteConfirm) {
+        let title = format!("Delete redundant directory markers under {}/", confirm.display_name);
+        let indexed = self.indexed_under(&confirm.remote, &bucket, &confirm.key);
+        self.spawn_plan(async move {
+            let objects = match indexed {
+                Some(objects) => objects,
+                None => client
+                    .list_all_keys(&bucket, &confirm.key)
+                    .await
+                    .map_err(|e| e.to_string())?,
+            };
+            let markers = redundant_markers(objects);
+            confirm.markers = Some(markers.iter().map(|o| o.key.clone()).collect());
+            let items = markers
+                .into_iter()
+                .map(|o| PlanItem {
+                    action: PlanAction::Delete,
+                    path: o.key,
+                    size: 0,
+                })
+                .collect();
+            Ok(Plan::new(title, PlanOp::DeletePrefix(confirm), items))
+        });
+    }
+
+    /// Compare a prefix with the local destination and show what a download would do.
+    pub(crate) fn plan_download(&mut self, client: Store, spec: DownloadSpec) {
+        let title = format!("Download {} -> {}", spec.name, spec.dest.display());
+        self.spawn_plan(async move {
+            let objects = client
+                .list_all_keys(&spec.bucket, &spec.key)
+                .await
+                .map_err(|e| e.to_string())?;
+            let items = objects
+                .into_iter()
+                .map(|o| {
+                    let rel = o.key.strip_prefix(&spec.key).unwrap_or(&o.key);
+                    let action = if o.key.ends_with('/') {
+                        PlanAction::Skip
+                    } else if spec.dest.join(rel).exists() {
+                        PlanAction::Overwrite
+                    } else {
+                        PlanAction::Download
+                    };
+                    PlanItem {
+                        action,
+                        size: o.size.max(0) as u64,
+                        path: o.key,
+                    }
+                })
+                .collect();
+            Ok(Plan::new(title, PlanOp::Download(spec), items))
+        });
+    }
+
+    pub(super) fn spawn_plan<F>(&mut self, build: F)
+    where
+        F: std::future::Future<Output = Result<Plan, String>> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel(1);
+        self.plan_rx = Some(rx.into());
+        self.notify("Planning...");
+        self.tasks.spawn(TaskKind::Plan, "Plan", async move {
+            let _ = tx.send(build.await).await;
+        });
+    }
+
+    /// Pick up a finished plan. Call every tick.
+    pub fn drain_plan(&mut self) {
+        let Some(rx) = &mut self.plan_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(plan)) => {
+                self.plan = Some(plan);
+                self.toasts.clear(Severity::Info);
+            }
+            Ok(Err(e)) => {
+                self.notify_error(format!("Planning failed: {}", e));
+                self.toasts.clear(Severity::Info);
+            }
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => {}
+        }
+        self.plan_rx = None;
+    }
+
+    pub fn cancel_plan(&mut self) {
+        self.plan = None;
+        self.notify("Cancelled");
+    }
+
+    /// Run the planned operation.
+    pub async fn confirm_plan(&mut self) {
+        let Some(plan) = self.plan.take() else {
+            return;
+        };
+        let planned = plan.items.len();
+        match plan.op {
+            PlanOp::DeletePrefix(mut confirm) => {
+                if confirm.markers.as_ref().is_some_and(Vec::is_empty) {
+                    self.notify("No redundant directory markers");
+                    return;
+                }
+                // Protected remotes still ask for the typed remote name
+                confirm.selected_yes = true;
+                confirm.objects = Some(planned);
+                let typed = confirm.typed.is_some();
+                self.confirm_delete = Some(confirm);
+                if !typed {
+                    self.confirm_delete_yes().await;
+                }
+            }
+            PlanOp::Download(spec) => {
+                if let Err(e) = self.ensure_client(&spec.remote) {
+                    self.notify_error(format!("Connection failed: {}", e));
+                    return;
+                }
+                let client = self.clients[&spec.remote].clone();
+                self.start_transfer(client, spec);
+            }
+            // Uploads that only add objects need no typed confirmation
+            PlanOp::Sync(run) if run.overwrites_objects() => {
+                let gate = self.gate(&[&run.remote], true);
+                let summary = run.summary().to_string();
+                self.run_gated(gate, summary, Guarded::Sync(run)).await;
+            }
+            PlanOp::Sync(run) => self.start_sync(run),
+        }
+    }
+
+    pub fn export_plan(&mut self) {
+        let Some(plan) = &self.plan else {
+            return;
+        };
+        match plan.export(&self.local_path) {
+            Ok(path) => self.notify(format!("Plan written to {}", path.display())),
+            Err(e) => self.notify_error(format!("Export failed: {}", e)),
+        }
+    }
+
+    pub fn plan_move_up(&mut self) {
+        if let Some(plan) = &mut self.plan {
+            let i = plan.state.selected().unwrap_or(0);
+            plan.state.select(Some(i.saturating_sub(1)));
+        }
+    }
+
+    pub fn plan_move_down(&mut self) {
+        if let Some(plan) = &mut self.plan {
+            let i = plan.state.selected().unwrap_or(0);
+            if i + 1 < plan.items.len() {
+                plan.state.select(Some(i + 1));
+            }
+        }
+    }
+}
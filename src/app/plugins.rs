@@ -0,0 +1,214 @@
+use ratatui::widgets::ListState;
+
+use crate::plugins::{self, Hook, Level, PluginEvent, PluginMessage};
+
+use super::notify::Severity;
+use super::tasks::TaskKind;
+use super::{App, Entry, Location};
+
+/// Output of a finished plugin run, sent back from the background task.
+pub struct PluginDone {
+    pub plugin: String,
+    pub result: Result<Vec<PluginMessage>, String>,
+}
+
+/// What a palette row runs.
+pub enum PaletteTarget {
+    Plugin { plugin: String, command: String },
+    /// Index into the configured actions.
+    Action(usize),
+}
+
+/// One palette row: a plugin command or a configured action.
+pub struct PaletteItem {
+    pub title: String,
+    /// Plugin name, or the action's key.
+    pub source: String,
+    pub target: PaletteTarget,
+}
+
+/// Command palette (`:`), filtered by what is typed.
+pub struct CommandPalette {
+    pub query: String,
+    pub state: ListState,
+}
+
+impl App {
+    /// Event describing the selected object, or the current prefix when none is selected.
+    fn plugin_event(&self) -> Option<PluginEvent> {
+        let Location::ObjectList {
+            remote,
+            bucket,
+            prefix,
+        } = &self.location
+        else {
+            return None;
+        };
+        let selected = self.browser_state.selected().and_then(|i| self.entries.get(i));
+        let (key, size) = match selected {
+            Some(Entry::Object(obj)) => (obj.key.clone(), (!obj.is_dir).then_some(obj.size)),
+            _ => (prefix.clone(), None),
+        };
+        Some(PluginEvent {
+            remote: remote.clone(),
+            bucket: bucket.clone(),
+            key,
+            size,
+            local_path: None,
+        })
+    }
+
+    /// Run every plugin subscribed to `hook` in the background.
+    pub(crate) fn fire_hook(&mut self, hook: Hook, event: Option<PluginEvent>) {
+        let Some(event) = event.or_else(|| self.plugin_event()) else {
+            return;
+        };
+        let subscribed: Vec<_> = self
+            .settings
+            .plugins
+            .iter()
+            .filter(|p| p.hooks.contains(&hook))
+            .cloned()
+            .collect();
+        for plugin in subscribed {
+            self.spawn_plugin(plugin, vec![hook.name().to_string()], event.clone());
+        }
+    }
+
+    fn spawn_plugin(&mut self, plugin: plugins::PluginConfig, args: Vec<String>, event: PluginEvent) {
+        let tx = self.plugin_tx.clone();
+        let label = format!("Plugin {} {}", plugin.name, args.join(" "));
+        self.tasks.spawn(TaskKind::Plugin, label, async move {
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            let result = plugins::run(&plugin, &args, &event)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx
+                .send(PluginDone {
+                    plugin: plugin.name,
+                    result,
+                })
+                .await;
+        });
+    }
+
+    /// Show what finished plugins printed. Call every tick.
+    pub fn drain_plugins(&mut self) {
+        let mut done = Vec::new();
+        while let Ok(msg) = self.plugin_rx.try_recv() {
+            done.push(msg);
+        }
+        for msg in done {
+            match msg.result {
+                Ok(messages) => {
+                    for m in messages {
+                        let severity = match m.level {
+                            Level::Info => Severity::Info,
+                            Level::Warn => Severity::Warn,
+                            Level::Error => Severity::Error,
+                        };
+                        self.toasts.push(severity, format!("{}: {}", msg.plugin, m.message));
+                    }
+                }
+                Err(e) => self.notify_error(format!("Plugin {} failed: {}", msg.plugin, e)),
+            }
+        }
+    }
+
+    /// Open the command palette.
+    pub fn open_palette(&mut self) {
+        if self.settings.plugins.iter().all(|p| p.commands.is_empty()) && self.settings.actions.is_empty() {
+            self.notify("No plugin commands or actions configured");
+            return;
+        }
+        let mut state = ListState::default();
+        state.select(Some(0));
+        self.palette = Some(CommandPalette {
+            query: String::new(),
+            state,
+        });
+    }
+
+    /// Plugin commands and actions matching the palette query.
+    pub fn palette_items(&self) -> Vec<PaletteItem> {
+        let query = self
+            .palette
+            .as_ref()
+            .map(|p| p.query.to_lowercase())
+            .unwrap_or_default();
+        let commands = self.settings.plugins.iter().flat_map(|p| {
+            p.commands.iter().map(|c| PaletteItem {
+                title: c.title.clone().unwrap_or_else(|| c.name.clone()),
+                source: p.name.clone(),
+                target: PaletteTarget::Plugin {
+                    plugin: p.name.clone(),
+                    command: c.name.clone(),
+                },
+            })
+        });
+        let actions = self.settings.actions.iter().enumerate().map(|(i, a)| PaletteItem {
+            title: a.title().to_string(),
+            source: format!("key {}", a.key),
+            target: PaletteTarget::Action(i),
+        });
+        commands
+            .chain(actions)
+            .filter(|item| {
+                item.title.to_lowercase().contains(&query)
+                    || item.source.to_lowercase().contains(&query)
+            })
+            .collect()
+    }
+
+    pub fn palette_input(&mut self, c: char) {
+        if let Some(palette) = &mut self.palette {
+            palette.query.push(c);
+            palette.state.select(Some(0));
+        }
+    }
+
+    pub fn palette_backspace(&mut self) {
+        if let Some(palette) = &mut self.palette {
+            palette.query.pop();
+            palette.state.select(Some(0));
+        }
+    }
+
+    pub fn palette_move(&mut self, down: bool) {
+        let count = self.palette_items().len();
+        if let Some(palette) = &mut self.palette {
+            let i = palette.state.selected().unwrap_or(0);
+            let next = if down { (i + 1).min(count.saturating_sub(1)) } else { i.saturating_sub(1) };
+            palette.state.select(Some(next));
+        }
+    }
+
+    /// Run the highlighted entry on the selected object (or the current prefix).
+    pub fn run_palette_command(&mut self) {
+        let mut items = self.palette_items();
+        let Some(palette) = self.palette.take() else {
+            return;
+        };
+        let Some(i) = palette.state.selected().filter(|i| *i < items.len()) else {
+            return;
+        };
+        let item = items.swap_remove(i);
+        let (plugin_name, command) = match item.target {
+            PaletteTarget::Action(index) => {
+                self.run_action(index);
+                return;
+            }
+            PaletteTarget::Plugin { plugin, command } => (plugin, command),
+        };
+        let Some(event) = self.plugin_event() else {
+            self.notify_warn("Navigate into a bucket first");
+            return;
+        };
+        let Some(plugin) = self.settings.plugins.iter().find(|p| p.name == plugin_name).cloned()
+        else {
+            return;
+        };
+        self.notify(format!("Running {}...", item.title));
+        self.spawn_plugin(plugin, vec!["command".to_string(), command], event);
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc;
+
+use crate::object_store::ObjectEntry;
+
+use super::inbox::Inbox;
+use super::tasks::TaskKind;
+use super::{App, Entry, Location, Pane};
+
+/// How long the cursor rests on a directory before its listing is fetched ahead.
+const HOVER_DELAY: Duration = Duration::from_millis(300);
+/// Prefetched listings older than this are listed again on entering.
+const MAX_AGE: Duration = Duration::from_secs(30);
+/// Prefetched listings kept at once; the oldest goes first.
+const KEEP: usize = 8;
+
+/// Remote, bucket and prefix of a directory.
+type Dir = (String, String, String);
+
+/// First listing page of a directory, fetched before it was entered.
+pub struct Page {
+    dir: Dir,
+    listed_at: Instant,
+    /// Objects and whether there are more; `None` if listing failed, which is
+    /// left for entering the directory to report.
+    listing: Option<(Vec<ObjectEntry>, bool)>,
+}
+
+/// Listings of directories the cursor rested on, so entering them needs no
+/// round trip.
+pub struct Prefetch {
+    /// Directory under the cursor, and since when.
+    hover: Option<(Dir, Instant)>,
+    /// Directory being listed now.
+    pending: Option<Dir>,
+    pages: VecDeque<Page>,
+    tx: mpsc::Sender<Page>,
+    pub(crate) rx: Inbox<Page>,
+}
+
+impl Default for Prefetch {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::channel(4);
+        Self {
+            hover: None,
+            pending: None,
+            pages: VecDeque::new(),
+            tx,
+            rx: rx.into(),
+        }
+    }
+}
+
+impl Prefetch {
+    fn fresh(&self, dir: &Dir) -> bool {
+        self.pages.iter().any(|p| p.dir == *dir && p.listed_at.elapsed() < MAX_AGE)
+    }
+}
+
+impl App {
+    /// The directory under the browser cursor, if it could be prefetched.
+    fn hovered_dir(&self) -> Option<Dir> {
+        if !self.settings.prefetch_dirs {
+            return None;
+        }
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            return None;
+        };
+        if self.pane != Pane::Browser || self.search_active {
+            return None;
+        }
+        match self.browser_state.selected().and_then(|i| self.entries.get(i))? {
+            Entry::Object(obj) if obj.is_dir => Some((remote.clone(), bucket.clone(), obj.key.clone())),
+            _ => None,
+        }
+    }
+
+    /// List the directory under the cursor once it has rested there for
+    /// `HOVER_DELAY`. Call every tick.
+    pub(crate) fn prefetch_hovered(&mut self) {
+        let Some(dir) = self.hovered_dir() else {
+            self.prefetch.hover = None;
+            return;
+        };
+        let since = match &self.prefetch.hover {
+            Some((hovered, since)) if *hovered == dir => *since,
+            _ => {
+                self.prefetch.hover = Some((dir, Instant::now()));
+                return;
+            }
+        };
+        if since.elapsed() < HOVER_DELAY
+            || self.prefetch.pending.as_ref() == Some(&dir)
+            || self.prefetch.fresh(&dir)
+        {
+            return;
+        }
+        let Some(client) = self.clients.get(&dir.0).cloned() else {
+            return;
+        };
+        // Only the latest hover is worth finishing
+        self.tasks.cancel_kind(TaskKind::Prefetch);
+        self.prefetch.pending = Some(dir.clone());
+        let tx = self.prefetch.tx.clone();
+        let label = format!("Prefetch {}", dir.2);
+        self.tasks.spawn(TaskKind::Prefetch, label, async move {
+            let listing = client.list_objects_page(&dir.1, &dir.2).await.ok();
+            let page = Page {
+                dir,
+                listed_at: Instant::now(),
+                listing,
+            };
+            let _ = tx.send(page).await;
+        });
+    }
+
+    /// Time until the directory under the cursor is due for prefetching.
+    pub(crate) fn prefetch_due_in(&self) -> Option<Duration> {
+        let (dir, since) = self.prefetch.hover.as_ref()?;
+        if self.prefetch.pending.as_ref() == Some(dir) || self.prefetch.fresh(dir) {
+            return None;
+        }
+        Some(HOVER_DELAY.saturating_sub(since.elapsed()))
+    }
+
+    /// Keep listings that finished prefetching. Call every tick.
+    pub fn drain_prefetch(&mut self) {
+        while let Ok(page) = self.prefetch.rx.try_recv() {
+            if self.prefetch.pending.as_ref() == Some(&page.dir) {
+                self.prefetch.pending = None;
+            }
+            if page.listing.is_none() {
+                continue;
+            }
+            self.prefetch.pages.retain(|p| p.dir != page.dir);
+            self.prefetch.pages.push_back(page);
+            if self.prefetch.pages.len() > KEEP {
+                self.prefetch.pages.pop_front();
+            }
+        }
+    }
+
+    /// The prefetched first page of `prefix`, whether there is more and when it
+    /// was listed, if that was recent enough to show.
+    pub(crate) fn take_prefetched(&mut self, remote: &str, bucket: &str, prefix: &str) -> Option<(Vec<ObjectEntry>, bool, Instant)> {
+        let i = self
+            .prefetch
+            .pages
+            .iter()
+            .position(|p| p.dir.0 == remote && p.dir.1 == bucket && p.dir.2 == prefix)?;
+        let page = self.prefetch.pages.remove(i)?;
+        let (objects, truncated) = page.listing?;
+        (page.listed_at.elapsed() < MAX_AGE).then_some((objects, truncated, page.listed_at))
+    }
+
+    /// Drop prefetched listings of every bucket of a remote, e.g. after its keys changed.
+    pub(crate) fn forget_remote_prefetched(&mut self, remote: &str) {
+        self.prefetch.pages.retain(|p| p.dir.0 != remote);
+    }
+
+    /// Drop prefetched listings of a bucket that just changed.
+    pub(crate) fn forget_prefetched(&mut self, remote: &str, bucket: &str) {
+        self.prefetch.pages.retain(|p| p.dir.0 != remote || p.dir.1 != bucket);
+    }
+}
@@ -1,12 +1,33 @@
 use tokio::sync::mpsc;
-use tokio::task::JoinHandle;
 
+use crate::plugins::Hook;
+use crate::object_store::{ObjectMetadata, Store};
+
+use super::media::{self, MediaPreview};
+use super::notify::Severity;
+use super::preview_cache::{self, CachedPreview, PreviewCache};
+use super::tasks::TaskKind;
 use super::{App, Entry, Location};
 
 /// Messages sent from background preview task to the UI.
 pub enum PreviewMsg {
-    /// Text content ready to display inline.
-    TextReady(String),
+    /// Text content ready to display inline, or a further chunk to append.
+    TextReady {
+        text: String,
+        next_offset: u64,
+        truncated: bool,
+        append: bool,
+    },
+    /// Video/audio thumbnail and stream info ready to display inline.
+    MediaReady(MediaPreview),
+    /// File type detected from the first bytes of an object without a known extension.
+    Sniffed {
+        key: String,
+        kind: Option<PreviewKind>,
+        gzip: bool,
+    },
+    /// An image was downloaded into the preview cache.
+    ImageCached { object: String, id: String, bytes: u64 },
     /// Error during preview.
     Error(String),
 }
@@ -15,6 +36,7 @@ pub enum PreviewMsg {
 pub enum PreviewKind {
     Image,
     Video,
+    Audio,
     Text,
 }
 
@@ -32,17 +54,49 @@ pub struct PreviewState {
     pub scroll_offset: usize,
     /// Total line count of text_content (cached).
     pub line_count: usize,
+    /// Soft-wrap long lines instead of cutting them off; kept across previews.
+    pub wrap: bool,
+    /// Horizontal scroll (in characters) when not wrapping.
+    pub hscroll: usize,
+    /// Number of records when the text was rendered as NDJSON.
+    pub records: Option<usize>,
+    /// Fetched text before pretty-printing; chunks loaded later are appended here.
+    pub raw_text: String,
+    /// Where the text came from and how far it has been read.
+    pub text_source: Option<TextSource>,
+    /// Inline video/audio preview; full playback starts on a second `p`.
+    pub media: Option<MediaPreview>,
+    /// Cache identity (object, id) to store the result under once it arrives.
+    pub cache_key: Option<(String, String)>,
+    /// Windowless audio player, stopped with `s` or when the preview closes.
+    pub player: Option<std::process::Child>,
     /// Background task channel.
-    pub rx: Option<mpsc::Receiver<PreviewMsg>>,
-    /// Background task handle.
-    pub handle: Option<JoinHandle<()>>,
+    pub rx: Option<super::inbox::Inbox<PreviewMsg>>,
+}
+
+/// A text preview's object and read position, for loading more on demand.
+pub struct TextSource {
+    pub remote: String,
+    pub bucket: String,
+    pub key: String,
+    pub size: u64,
+    pub gzip: bool,
+    /// Next byte to fetch (compressed offset for gzip).
+    pub next_offset: u64,
+    /// Chunks loaded so far; a gzip preview re-inflates this many chunks' worth.
+    pub loads: u64,
+    /// More content is available than is shown.
+    pub truncated: bool,
 }
 
-/// Max bytes to download for text preview (512 KB).
-const MAX_TEXT_BYTES: i64 = 512 * 1024;
+/// Compressed bytes fetched per decompressed chunk of a gzip preview.
+const GZIP_RATIO: u64 = 4;
+
+/// Bytes fetched to sniff the type of an object without a known extension.
+const SNIFF_BYTES: i64 = 8 * 1024;
 
 impl PreviewState {
-    pub fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             current_key: None,
             text_content: None,
@@ -50,8 +104,15 @@ impl PreviewState {
             error: None,
             scroll_offset: 0,
             line_count: 0,
+            wrap: false,
+            hscroll: 0,
+            records: None,
+            raw_text: String::new(),
+            text_source: None,
+            media: None,
+            cache_key: None,
+            player: None,
             rx: None,
-            handle: None,
         }
     }
 
@@ -62,9 +123,45 @@ impl PreviewState {
         self.error = None;
         self.scroll_offset = 0;
         self.line_count = 0;
+        self.hscroll = 0;
+        self.records = None;
+        self.raw_text.clear();
+        self.text_source = None;
+        self.media = None;
+        self.cache_key = None;
+        self.stop_playback();
         self.rx = None;
-        if let Some(h) = self.handle.take() {
-            h.abort();
+    }
+
+    /// Stop the audio player, if one is running.
+    pub fn stop_playback(&mut self) {
+        if let Some(mut child) = self.player.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.player.is_some()
+    }
+
+    /// The preview ends before the object does.
+    pub fn is_truncated(&self) -> bool {
+        self.text_source.as_ref().is_some_and(|s| s.truncated)
+    }
+
+    pub fn toggle_wrap(&mut self) {
+        self.wrap = !self.wrap;
+        self.hscroll = 0;
+    }
+
+    pub fn scroll_left(&mut self, cols: usize) {
+        self.hscroll = self.hscroll.saturating_sub(cols);
+    }
+
+    pub fn scroll_right(&mut self, cols: usize) {
+        if !self.wrap {
+            self.hscroll += cols;
         }
     }
 
@@ -87,14 +184,90 @@ fn try_pretty_json(text: &str) -> String {
     }
 }
 
+/// Pretty-print newline-delimited JSON one record at a time, with a separator
+/// before each. Returns `None` unless every complete line is JSON (the last line
+/// may be cut off by the preview size limit and is kept as-is).
+fn try_pretty_ndjson(text: &str) -> Option<(String, usize)> {
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    let mut out = String::new();
+    let mut records = 0;
+    for (i, line) in lines.iter().enumerate() {
+        match serde_json::from_str::<serde_json::Value>(line) {
+            Ok(val) => {
+                records += 1;
+                out.push_str(&format!("── record {} ──\n", records));
+                out.push_str(&serde_json::to_string_pretty(&val).ok()?);
+                out.push('\n');
+            }
+            Err(_) if i + 1 == lines.len() && records > 0 => {
+                out.push_str("── truncated ──\n");
+                out.push_str(line);
+                out.push('\n');
+            }
+            Err(_) => return None,
+        }
+    }
+    (records > 0).then_some((out, records))
+}
+
+/// The key without a trailing `.gz`, and whether it had one.
+fn strip_gz(key: &str) -> (&str, bool) {
+    match key.len().checked_sub(3) {
+        Some(i) if key.is_char_boundary(i) && key[i..].eq_ignore_ascii_case(".gz") => (&key[..i], true),
+        _ => (key, false),
+    }
+}
+
+/// Inflate as much of a (possibly truncated) gzip stream as is available,
+/// up to `limit` output bytes.
+fn gunzip_prefix(data: &[u8], limit: usize) -> Vec<u8> {
+    use std::io::Read;
+
+    let mut decoder = flate2::read::MultiGzDecoder::new(data);
+    let mut out = Vec::new();
+    let mut buf = [0u8; 16 * 1024];
+    while out.len() < limit {
+        match decoder.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => out.extend_from_slice(&buf[..n]),
+        }
+    }
+    out.truncate(limit);
+    out
+}
+
+/// Pick a preview kind from magic bytes, falling back to "is it UTF-8 text".
+/// Returns the kind and whether the content is gzip-compressed.
+fn sniff_kind(head: &[u8]) -> Option<(PreviewKind, bool)> {
+    match infer::get(head) {
+        Some(t) if t.mime_type() == "application/gzip" => {
+            let inner = gunzip_prefix(head, SNIFF_BYTES as usize);
+            looks_like_text(&inner).then_some((PreviewKind::Text, true))
+        }
+        Some(t) => content_type_to_kind(t.mime_type()).map(|k| (k, false)),
+        None => looks_like_text(head).then_some((PreviewKind::Text, false)),
+    }
+}
+
+/// Valid UTF-8 without NUL bytes; a multi-byte char cut off at the end is fine.
+pub(super) fn looks_like_text(bytes: &[u8]) -> bool {
+    !bytes.is_empty()
+        && !bytes.contains(&0)
+        && std::str::from_utf8(bytes).map_or_else(|e| e.error_len().is_none(), |_| true)
+}
+
 fn content_type_to_kind(content_type: &str) -> Option<PreviewKind> {
     let ct = content_type.to_lowercase();
     if ct.starts_with("image/") {
         Some(PreviewKind::Image)
     } else if ct.starts_with("video/") {
         Some(PreviewKind::Video)
+    } else if ct.starts_with("audio/") {
+        Some(PreviewKind::Audio)
     } else if ct.starts_with("text/")
         || ct == "application/json"
+        || ct == "application/x-ndjson"
+        || ct == "application/jsonl"
         || ct == "application/xml"
         || ct == "application/javascript"
         || ct == "application/x-yaml"
@@ -107,7 +280,7 @@ fn content_type_to_kind(content_type: &str) -> Option<PreviewKind> {
     }
 }
 
-fn extension_to_kind(key: &str) -> Option<PreviewKind> {
+pub(super) fn extension_to_kind(key: &str) -> Option<PreviewKind> {
     let ext = key.rsplit('.').next()?.to_lowercase();
     match ext.as_str() {
         "jpg" | "jpeg" | "png" | "gif" | "webp" | "bmp" | "ico" | "tiff" | "tif" | "svg" => {
@@ -116,7 +289,9 @@ fn extension_to_kind(key: &str) -> Option<PreviewKind> {
         "mp4" | "mkv" | "avi" | "mov" | "webm" | "flv" | "wmv" | "m4v" | "3gp" => {
             Some(PreviewKind::Video)
         }
-        "txt" | "md" | "markdown" | "json" | "yaml" | "yml" | "toml" | "xml" | "csv"
+        "mp3" | "wav" | "flac" | "ogg" | "oga" | "opus" | "m4a" | "aac" | "wma" | "aif"
+        | "aiff" => Some(PreviewKind::Audio),
+        "txt" | "md" | "markdown" | "json" | "jsonl" | "ndjson" | "yaml" | "yml" | "toml" | "xml" | "csv"
         | "tsv" | "log" | "ini" | "cfg" | "conf" | "env" | "sh" | "bash" | "zsh"
         | "fish" | "py" | "rs" | "go" | "js" | "ts" | "jsx" | "tsx" | "html" | "htm"
         | "css" | "scss" | "less" | "sql" | "rb" | "lua" | "c" | "cpp" | "h" | "hpp"
@@ -132,27 +307,99 @@ fn extension_to_kind(key: &str) -> Option<PreviewKind> {
 impl App {
     /// Drain preview messages from background task.
     pub fn drain_preview(&mut self) {
-        let is_json = self.preview.current_key.as_deref()
+        let ext = self.preview.current_key.as_deref()
+            .map(|k| strip_gz(k).0)
             .and_then(|k| k.rsplit('.').next())
-            .map(|ext| ext.eq_ignore_ascii_case("json"))
-            .unwrap_or(false);
+            .map(|ext| ext.to_lowercase())
+            .unwrap_or_default();
+        let is_json = ext == "json";
+        let is_ndjson = ext == "jsonl" || ext == "ndjson";
+
+        // Forget the audio player once the track ends on its own
+        if let Some(child) = &mut self.preview.player
+            && !matches!(child.try_wait(), Ok(None))
+        {
+            self.preview.player = None;
+        }
 
         let Some(rx) = &mut self.preview.rx else {
             return;
         };
 
+        let mut sniffed = None;
         while let Ok(msg) = rx.try_recv() {
             match msg {
-                PreviewMsg::TextReady(text) => {
+                PreviewMsg::TextReady {
+                    text,
+                    next_offset,
+                    truncated,
+                    append,
+                } => {
                     self.preview.loading = false;
-                    let text = if is_json {
-                        try_pretty_json(&text)
+                    if let Some(source) = &mut self.preview.text_source {
+                        source.next_offset = next_offset;
+                        source.truncated = truncated;
+                    }
+                    if append {
+                        self.preview.raw_text.push_str(&text);
                     } else {
-                        text
+                        self.preview.raw_text = text;
+                    }
+                    let text = self.preview.raw_text.clone();
+                    // A .json file that isn't one document is often NDJSON in disguise
+                    let as_ndjson = if is_ndjson
+                        || (is_json && serde_json::from_str::<serde_json::Value>(&text).is_err())
+                    {
+                        try_pretty_ndjson(&text)
+                    } else {
+                        None
+                    };
+                    let text = match as_ndjson {
+                        Some((pretty, records)) => {
+                            self.preview.records = Some(records);
+                            pretty
+                        }
+                        None if is_json => try_pretty_json(&text),
+                        None => text,
                     };
                     self.preview.line_count = text.lines().count();
-                    self.preview.scroll_offset = 0;
+                    if !append {
+                        self.preview.scroll_offset = 0;
+                    }
                     self.preview.text_content = Some(text);
+                    self.toasts.clear(Severity::Info);
+
+                    if let Some((object, id)) = self.preview.cache_key.take()
+                        && let Some(source) = &self.preview.text_source
+                    {
+                        let path = self.preview_cache.path(&id, "txt");
+                        let written = std::fs::create_dir_all(preview_cache::preview_dir())
+                            .and_then(|()| std::fs::write(&path, &self.preview.raw_text));
+                        if written.is_ok() {
+                            let data = CachedPreview::Text {
+                                next_offset: source.next_offset,
+                                truncated: source.truncated,
+                            };
+                            self.preview_cache.insert(object, id, self.preview.raw_text.len() as u64, data);
+                        }
+                    }
+                }
+                PreviewMsg::MediaReady(media) => {
+                    self.preview.loading = false;
+                    if let Some((object, id)) = self.preview.cache_key.take() {
+                        let bytes = media.thumbnail.as_ref().map_or(0, |t| t.rgb.len() as u64);
+                        let data = CachedPreview::Media {
+                            preview: media.clone(),
+                            at: std::time::Instant::now(),
+                        };
+                        self.preview_cache.insert(object, id, bytes, data);
+                    }
+                    self.preview.media = Some(media);
+                    self.toasts.clear(Severity::Info);
+                }
+                PreviewMsg::Sniffed { key, kind, gzip } => sniffed = Some((key, kind, gzip)),
+                PreviewMsg::ImageCached { object, id, bytes } => {
+                    self.preview_cache.insert(object, id, bytes, CachedPreview::Image);
                 }
                 PreviewMsg::Error(e) => {
                     self.preview.loading = false;
@@ -160,35 +407,92 @@ impl App {
                 }
             }
         }
+
+        // Only start the preview if the cursor is still on the sniffed object
+        if let Some((key, kind, gzip)) = sniffed
+            && self.selected_file_info().is_some_and(|info| info.2 == key)
+        {
+            match kind {
+                Some(kind) => self.start_preview(kind, gzip),
+                None => self.notify("Unsupported file type for preview"),
+            }
+        }
     }
 
     /// Request preview for the currently selected entry.
     /// Triggered explicitly by the user pressing 'p'.
     pub fn request_preview(&mut self) {
-        let (remote, bucket, key, content_type, size) = match self.selected_file_info() {
-            Some(info) => info,
+        let (key, content_type, size) = match self.selected_file_info() {
+            Some((_, _, key, ct, size)) => (key, ct, size),
             None => {
-                self.status_message = Some("No file selected for preview".into());
+                self.notify("No file selected for preview");
                 return;
             }
         };
 
+        self.fire_hook(Hook::Preview, None);
+
+        // `foo.log.gz` or Content-Encoding: gzip is previewed as its decompressed content
+        let (inner_key, gz_ext) = strip_gz(&key);
+        let gzip = gz_ext
+            || self.selected_head().is_some_and(|m| {
+                m.content_encoding.as_deref().is_some_and(|e| e.eq_ignore_ascii_case("gzip"))
+            });
+
         // Determine preview kind from content_type (metadata) or extension
         let kind = content_type
             .as_deref()
             .and_then(content_type_to_kind)
-            .or_else(|| extension_to_kind(&key));
-
-        let kind = match kind {
-            Some(k) => k,
-            None => {
-                self.status_message = Some("Unsupported file type for preview".into());
-                return;
+            .or_else(|| extension_to_kind(inner_key));
+        match kind {
+            Some(k) if gzip && k != PreviewKind::Text => {
+                self.notify("Only gzip-compressed text can be previewed");
             }
+            Some(k) => self.start_preview(k, gzip),
+            None if size == 0 => self.notify("Empty file"),
+            // Nothing to go on (e.g. `data`, `backup`): look at the first bytes
+            None => self.sniff_preview(),
+        }
+    }
+
+    /// Fetch the head of the selected object and detect its type from magic bytes.
+    fn sniff_preview(&mut self) {
+        let Some((remote, bucket, key, _, size)) = self.selected_file_info() else {
+            return;
+        };
+        let Some(client) = self.clients.get(&remote).cloned() else {
+            return;
+        };
+
+        self.preview.clear();
+        self.tasks.cancel_kind(TaskKind::Preview);
+        self.notify("Detecting file type...");
+        let (tx, rx) = mpsc::channel(4);
+        self.preview.rx = Some(rx.into());
+
+        let len = size.min(SNIFF_BYTES) as u64;
+        let label = format!("Sniff {}", key);
+        self.tasks.spawn(TaskKind::Preview, label, async move {
+            let msg = match client.get_object_range(&bucket, &key, 0, len).await {
+                Ok(head) => {
+                    let (kind, gzip) = sniff_kind(&head).map_or((None, false), |(k, gz)| (Some(k), gz));
+                    PreviewMsg::Sniffed { key, kind, gzip }
+                }
+                Err(e) => PreviewMsg::Error(e.to_string()),
+            };
+            let _ = tx.send(msg).await;
+        });
+    }
+
+    /// Start previewing the selected entry as `kind`.
+    fn start_preview(&mut self, kind: PreviewKind, gzip: bool) {
+        let Some((remote, bucket, key, _, size)) = self.selected_file_info() else {
+            return;
         };
 
         // Cancel previous
         self.preview.clear();
+        self.tasks.cancel_kind(TaskKind::Preview);
         self.preview.current_key = Some(key.clone());
 
         let client = match self.clients.get(&remote) {
@@ -197,82 +501,120 @@ impl App {
         };
 
         let (tx, rx) = mpsc::channel(4);
-        self.preview.rx = Some(rx);
+        self.preview.rx = Some(rx.into());
+
+        // Previews are cached per object version; a new ETag misses the cache
+        let etag = self
+            .browser_state
+            .selected()
+            .and_then(|i| self.entries.get(i))
+            .and_then(|e| match e {
+                Entry::Object(obj) => obj.etag.clone(),
+                Entry::Bucket(_) | Entry::Group(_) => None,
+            });
+        let cache_key = PreviewCache::key(&remote, &bucket, &key, etag.as_deref());
+        let cached = cache_key.as_ref().and_then(|(_, id)| self.preview_cache.get(id));
 
         let bucket = bucket.clone();
         let key_clone = key.clone();
+        let label = format!("Preview {}", key);
 
         match kind {
             PreviewKind::Text => {
+                let mut source = TextSource {
+                    remote,
+                    bucket,
+                    key: key_clone,
+                    size: size.max(0) as u64,
+                    gzip,
+                    next_offset: 0,
+                    loads: 0,
+                    truncated: false,
+                };
+                if let Some(&CachedPreview::Text { next_offset, truncated }) = cached
+                    && let Some((_, id)) = &cache_key
+                    && let Ok(text) = std::fs::read_to_string(self.preview_cache.path(id, "txt"))
+                {
+                    source.loads = 1;
+                    self.preview.text_source = Some(source);
+                    let _ = tx.try_send(PreviewMsg::TextReady {
+                        text,
+                        next_offset,
+                        truncated,
+                        append: false,
+                    });
+                    return;
+                }
+
+                self.preview.text_source = Some(source);
+                self.preview.cache_key = cache_key;
+                self.notify("Loading text preview...");
+                self.fetch_text_chunk(client, tx);
+            }
+            PreviewKind::Video | PreviewKind::Audio => {
+                if let Some(CachedPreview::Media { preview, .. }) = cached {
+                    self.preview.media = Some(preview.clone());
+                    return;
+                }
+                self.preview.cache_key = cache_key;
+
+                let audio_only = kind == PreviewKind::Audio;
                 self.preview.loading = true;
-                self.status_message = Some("Loading text preview...".into());
+                self.notify("Reading media info...");
+
+                self.tasks.spawn(TaskKind::Preview, label, async move {
+                    let msg = match client.presign_get_object(&bucket, &key_clone).await {
+                        Ok(url) => match media::load(url, audio_only).await {
+                            Ok(m) => PreviewMsg::MediaReady(m),
+                            Err(e) => PreviewMsg::Error(e),
+                        },
+                        Err(e) => PreviewMsg::Error(format!("Presign failed: {}", e)),
+                    };
+                    let _ = tx.send(msg).await;
+                });
+            }
+            PreviewKind::Image if matches!(cached, Some(CachedPreview::Image)) => {
+                let Some((_, id)) = &cache_key else {
+                    return;
+                };
+                let path = self.preview_cache.path(id, "img").to_string_lossy().to_string();
+                self.notify("Opening cached image in ffplay...");
+                self.tasks.spawn(TaskKind::Preview, label, async move {
+                    launch_ffplay(&key_clone, &["-loop", "0"], &path, tx).await;
+                });
+            }
+            PreviewKind::Image if cache_key.is_some() && size <= preview_cache::MAX_CACHED_IMAGE => {
+                let Some((object, id)) = cache_key else {
+                    return;
+                };
+                let path = self.preview_cache.path(&id, "img");
+                self.notify("Opening image in ffplay...");
 
-                let fetch_size = size.min(MAX_TEXT_BYTES) as u64;
-                tokio::spawn(async move {
-                    match client
-                        .get_object_range(&bucket, &key_clone, 0, fetch_size)
-                        .await
-                    {
-                        Ok(bytes) => {
-                            let text = String::from_utf8_lossy(&bytes).to_string();
-                            let _ = tx.send(PreviewMsg::TextReady(text)).await;
-                        }
+                self.tasks.spawn(TaskKind::Preview, label, async move {
+                    let fetched = client.get_object_range(&bucket, &key_clone, 0, size.max(0) as u64).await;
+                    let bytes = match fetched {
+                        Ok(b) => b,
                         Err(e) => {
                             let _ = tx.send(PreviewMsg::Error(e.to_string())).await;
+                            return;
                         }
+                    };
+                    let _ = tokio::fs::create_dir_all(preview_cache::preview_dir()).await;
+                    if let Err(e) = tokio::fs::write(&path, &bytes).await {
+                        let _ = tx.send(PreviewMsg::Error(format!("Cache write failed: {}", e))).await;
+                        return;
                     }
+                    let bytes = bytes.len() as u64;
+                    let _ = tx.send(PreviewMsg::ImageCached { object, id, bytes }).await;
+                    launch_ffplay(&key_clone, &["-loop", "0"], &path.to_string_lossy(), tx).await;
                 });
             }
-            PreviewKind::Image | PreviewKind::Video => {
-                let label = match kind {
-                    PreviewKind::Image => "image",
-                    PreviewKind::Video => "video",
-                    _ => unreachable!(),
-                };
-                self.status_message = Some(format!("Opening {} in ffplay...", label));
+            PreviewKind::Image => {
+                self.notify("Opening image in ffplay...");
 
-                let extra_args: Vec<String> = match kind {
-                    PreviewKind::Image => vec!["-loop".into(), "0".into()],
-                    PreviewKind::Video => vec!["-showmode".into(), "video".into()],
-                    _ => unreachable!(),
-                };
-
-                tokio::spawn(async move {
+                self.tasks.spawn(TaskKind::Preview, label, async move {
                     match client.presign_get_object(&bucket, &key_clone).await {
-                        Ok(url) => {
-                            let mut args = vec![
-                                "-v".to_string(), "warning".to_string(),
-                                "-autoexit".to_string(),
-                                "-alwaysontop".to_string(),
-                                "-window_title".to_string(), key_clone.clone(),
-                            ];
-                            args.extend(extra_args);
-                            args.push(url);
-
-                            let result = std::process::Command::new("ffplay")
-                                .args(&args)
-                                .stdin(std::process::Stdio::null())
-                                .stdout(std::process::Stdio::null())
-                                .stderr(std::process::Stdio::null())
-                                .spawn();
-
-                            match result {
-                                Ok(child) => {
-                                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-                                    focus_window().await;
-                                    let _ = tokio::task::spawn_blocking(move || {
-                                        child.wait_with_output()
-                                    }).await;
-                                }
-                                Err(_) => {
-                                    let _ = tx
-                                        .send(PreviewMsg::Error(
-                                            "ffplay not found - install ffmpeg for preview".into(),
-                                        ))
-                                        .await;
-                                }
-                            }
-                        }
+                        Ok(url) => launch_ffplay(&key_clone, &["-loop", "0"], &url, tx).await,
                         Err(e) => {
                             let _ = tx
                                 .send(PreviewMsg::Error(format!("Presign failed: {}", e)))
@@ -284,8 +626,128 @@ impl App {
         }
     }
 
+    /// Copy the previewed text (as shown, after pretty-printing) to the clipboard.
+    pub fn copy_preview(&mut self) {
+        let Some(text) = &self.preview.text_content else {
+            return;
+        };
+        self.notify(match crate::clipboard::copy(text) {
+            Ok(()) => format!(
+                "Copied {} of preview text",
+                humansize::format_size(text.len() as u64, humansize::BINARY)
+            ),
+            Err(e) => format!("Copy failed: {}", e),
+        });
+    }
+
+    /// Fetch the next chunk of a truncated text preview (`L`).
+    pub fn load_more_preview(&mut self) {
+        if self.preview.loading || !self.preview.is_truncated() {
+            return;
+        }
+        let Some(client) = self
+            .preview
+            .text_source
+            .as_ref()
+            .and_then(|s| self.clients.get(&s.remote))
+            .cloned()
+        else {
+            return;
+        };
+        let (tx, rx) = mpsc::channel(4);
+        self.preview.rx = Some(rx.into());
+        self.notify("Loading more...");
+        self.fetch_text_chunk(client, tx);
+    }
+
+    /// Read one more chunk (of `preview_max_kb`) of the text preview in the background.
+    /// Plain text is appended at a line boundary; gzip is re-inflated from the start
+    /// with a larger budget since a deflate stream can't be resumed mid-way.
+    fn fetch_text_chunk(&mut self, client: Store, tx: mpsc::Sender<PreviewMsg>) {
+        let chunk = self.settings.preview_max_kb.max(1) * 1024;
+        let Some(source) = &mut self.preview.text_source else {
+            return;
+        };
+        source.loads += 1;
+        self.preview.loading = true;
+
+        let (bucket, key, size, gzip) = (source.bucket.clone(), source.key.clone(), source.size, source.gzip);
+        let append = !gzip && source.loads > 1;
+        let (start, end) = if gzip {
+            (0, size.min(chunk * GZIP_RATIO * source.loads))
+        } else {
+            (source.next_offset, size.min(source.next_offset + chunk))
+        };
+        let out_limit = (chunk * source.loads) as usize;
+
+        let label = format!("Preview {}", key);
+        self.tasks.spawn(TaskKind::Preview, label, async move {
+            let bytes = match client.get_object_range(&bucket, &key, start, end).await {
+                Ok(b) => b,
+                Err(e) => {
+                    let _ = tx.send(PreviewMsg::Error(e.to_string())).await;
+                    return;
+                }
+            };
+
+            let (text, next_offset, truncated) = if gzip {
+                let out = gunzip_prefix(&bytes, out_limit);
+                let truncated = end < size || out.len() >= out_limit;
+                (String::from_utf8_lossy(&out).to_string(), end, truncated)
+            } else {
+                // Stop at the last full line so chunks never split a line or a UTF-8 char
+                let keep = match bytes.iter().rposition(|&b| b == b'\n') {
+                    Some(pos) if end < size => pos + 1,
+                    _ => bytes.len(),
+                };
+                let next = start + keep as u64;
+                (String::from_utf8_lossy(&bytes[..keep]).to_string(), next, next < size)
+            };
+            let _ = tx
+                .send(PreviewMsg::TextReady {
+                    text,
+                    next_offset,
+                    truncated,
+                    append,
+                })
+                .await;
+        });
+    }
+
+    /// Start full playback of the media shown in the inline preview.
+    /// Audio plays without a window and keeps running until stopped.
+    pub fn play_media(&mut self) {
+        let (Some(media), Some(key)) = (&self.preview.media, &self.preview.current_key) else {
+            return;
+        };
+        let (url, key) = (media.url.clone(), key.clone());
+
+        if media.audio_only {
+            self.preview.stop_playback();
+            match ffplay_command(&key, &["-nodisp"], &url).spawn() {
+                Ok(child) => {
+                    self.preview.player = Some(child);
+                    self.notify("Playing - press s to stop");
+                }
+                Err(_) => {
+                    self.preview.error = Some("ffplay not found - install ffmpeg for preview".into());
+                }
+            }
+            return;
+        }
+
+        self.notify("Opening video in ffplay...");
+
+        let (tx, rx) = mpsc::channel(4);
+        self.preview.rx = Some(rx.into());
+        let label = format!("Play {}", key);
+        self.tasks.spawn(TaskKind::Preview, label, async move {
+            launch_ffplay(&key, &["-showmode", "video"], &url, tx).await;
+        });
+    }
+
     /// Extract info about the currently selected file for preview.
-    fn selected_file_info(&self) -> Option<(String, String, String, Option<String>, i64)> {
+    fn selected_file_info(&mut self) -> Option<(String, String, String, Option<String>, i64)> {
         let idx = self.browser_state.selected()?;
         let entry = self.entries.get(idx)?;
 
@@ -298,8 +760,8 @@ impl App {
                 } = self.location
                 {
                     let ct = self
-                        .metadata
-                        .as_ref()
+                        .head_cache
+                        .get(remote, bucket, &obj.key, obj.etag.as_deref())
                         .and_then(|m| m.content_type.clone());
                     Some((
                         remote.clone(),
@@ -316,10 +778,51 @@ impl App {
         }
     }
 
+    /// Cached HEAD of the selected object, known from an earlier selection, so
+    /// deciding how to preview it needs no round trip.
+    fn selected_head(&mut self) -> Option<&ObjectMetadata> {
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            return None;
+        };
+        match self.entries.get(self.browser_state.selected()?)? {
+            Entry::Object(obj) => self.head_cache.get(remote, bucket, &obj.key, obj.etag.as_deref()),
+            Entry::Bucket(_) | Entry::Group(_) => None,
+        }
+    }
+
     /// Clean up temp files on exit.
     pub fn cleanup_preview(&self) {
-        let temp_dir = std::env::temp_dir().join("s3-like-yazi-preview");
-        let _ = std::fs::remove_dir_all(temp_dir);
+        let _ = std::fs::remove_dir_all(preview_cache::preview_dir());
+    }
+}
+
+/// Build an ffplay invocation for `url` with its window titled after the key.
+pub(super) fn ffplay_command(title: &str, extra_args: &[&str], url: &str) -> std::process::Command {
+    let mut cmd = std::process::Command::new("ffplay");
+    cmd.args(["-v", "warning", "-autoexit", "-alwaysontop", "-window_title", title])
+        .args(extra_args)
+        .arg(url)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    cmd
+}
+
+/// Run ffplay on `url`, focus its window and wait for it to exit.
+async fn launch_ffplay(title: &str, extra_args: &[&str], url: &str, tx: mpsc::Sender<PreviewMsg>) {
+    match ffplay_command(title, extra_args, url).spawn() {
+        Ok(child) => {
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            focus_window().await;
+            let _ = tokio::task::spawn_blocking(move || child.wait_with_output()).await;
+        }
+        Err(_) => {
+            let _ = tx
+                .send(PreviewMsg::Error(
+                    "ffplay not found - install ffmpeg for preview".into(),
+                ))
+                .await;
+        }
     }
 }
 
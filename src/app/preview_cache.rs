@@ -0,0 +1,122 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use super::media::MediaPreview;
+
+/// Total size of cached previews before the least recently used are evicted.
+const MAX_CACHE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Images up to this size are downloaded into the cache instead of streamed.
+pub const MAX_CACHED_IMAGE: i64 = 32 * 1024 * 1024;
+
+/// Media previews hold a presigned URL for playback, so they go stale before it expires.
+const MEDIA_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// Temp directory for preview files; removed on exit.
+pub fn preview_dir() -> PathBuf {
+    std::env::temp_dir().join("s3-like-yazi-preview")
+}
+
+pub enum CachedPreview {
+    /// First text chunk, stored as `<id>.txt`.
+    Text { next_offset: u64, truncated: bool },
+    /// The whole image, stored as `<id>.img`.
+    Image,
+    /// Thumbnail and stream info of a video or audio file, kept in memory.
+    Media { preview: MediaPreview, at: Instant },
+}
+
+struct CacheEntry {
+    /// remote/bucket/key, to drop entries for older versions of the object.
+    object: String,
+    id: String,
+    bytes: u64,
+    data: CachedPreview,
+}
+
+/// Bounded LRU of previews keyed by object and ETag, so re-previewing an unchanged
+/// object skips the download and a changed ETag never serves stale content.
+pub struct PreviewCache {
+    dir: PathBuf,
+    used: u64,
+    /// Least recently used first.
+    entries: VecDeque<CacheEntry>,
+}
+
+impl PreviewCache {
+    pub fn new() -> Self {
+        Self {
+            dir: preview_dir(),
+            used: 0,
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Cache identity of an object version. Objects without an ETag aren't cached.
+    pub fn key(remote: &str, bucket: &str, key: &str, etag: Option<&str>) -> Option<(String, String)> {
+        let object = format!("{}/{}/{}", remote, bucket, key);
+        let mut hasher = DefaultHasher::new();
+        (&object, etag?).hash(&mut hasher);
+        Some((object, format!("{:016x}", hasher.finish())))
+    }
+
+    pub fn path(&self, id: &str, ext: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", id, ext))
+    }
+
+    /// Look up an entry and mark it as most recently used.
+    pub fn get(&mut self, id: &str) -> Option<&CachedPreview> {
+        let pos = self.entries.iter().position(|e| e.id == id)?;
+        let entry = self.entries.remove(pos)?;
+        if let CachedPreview::Media { at, .. } = &entry.data
+            && at.elapsed() > MEDIA_TTL
+        {
+            self.used -= entry.bytes;
+            return None;
+        }
+        self.entries.push_back(entry);
+        self.entries.back().map(|e| &e.data)
+    }
+
+    /// Register a preview (its file, if any, already written), replacing other
+    /// versions of the same object and evicting old entries over the size budget.
+    pub fn insert(&mut self, object: String, id: String, bytes: u64, data: CachedPreview) {
+        let stale: Vec<usize> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.object == object)
+            .map(|(i, _)| i)
+            .collect();
+        for i in stale.into_iter().rev() {
+            self.remove(i);
+        }
+
+        self.used += bytes;
+        self.entries.push_back(CacheEntry {
+            object,
+            id,
+            bytes,
+            data,
+        });
+        while self.used > MAX_CACHE_BYTES && self.entries.len() > 1 {
+            self.remove(0);
+        }
+    }
+
+    fn remove(&mut self, index: usize) {
+        let Some(entry) = self.entries.remove(index) else {
+            return;
+        };
+        self.used -= entry.bytes;
+        let ext = match entry.data {
+            CachedPreview::Text { .. } => "txt",
+            CachedPreview::Image => "img",
+            CachedPreview::Media { .. } => return,
+        };
+        let _ = std::fs::remove_file(self.path(&entry.id, ext));
+    }
+}
@@ -0,0 +1,69 @@
+use std::time::Instant;
+
+use crate::credentials::AliasConfig;
+use crate::object_store::{self, BucketInfo, Store};
+
+use super::tasks::TaskKind;
+use super::App;
+
+/// A remote connected in the background, sent back to the UI.
+pub struct Warmed {
+    alias: String,
+    /// The alias config the client was built from; stale if the config was reloaded
+    /// or the profile switched since.
+    config: AliasConfig,
+    client: Store,
+    /// `None` when ListBuckets failed; entering the remote reports why.
+    buckets: Option<Vec<BucketInfo>>,
+}
+
+impl App {
+    /// Connect every remote without a client yet and list its buckets, all in
+    /// parallel, so entering a remote later is instant.
+    pub fn prewarm_remotes(&mut self) {
+        if !self.settings.prewarm_remotes {
+            return;
+        }
+        for alias in self.config.aliases.keys() {
+            if self.clients.contains_key(alias) {
+                continue;
+            }
+            let Some(config) = self.alias_config(alias) else {
+                continue;
+            };
+            let config = config.into_owned();
+            let alias = alias.clone();
+            let tx = self.prewarm_tx.clone();
+            let label = format!("Connect {}", alias);
+            self.tasks.spawn(TaskKind::Connect, label, async move {
+                let Ok(client) = object_store::connect(&alias, &config) else {
+                    return;
+                };
+                let buckets = client.list_buckets().await.ok();
+                let _ = tx
+                    .send(Warmed {
+                        alias,
+                        config,
+                        client,
+                        buckets,
+                    })
+                    .await;
+            });
+        }
+    }
+
+    /// Keep the clients and bucket listings that finished warming. Call every tick.
+    pub fn drain_prewarm(&mut self) {
+        while let Ok(warmed) = self.prewarm_rx.try_recv() {
+            if self.alias_config(&warmed.alias).as_deref() != Some(&warmed.config)
+                || self.clients.contains_key(&warmed.alias)
+            {
+                continue;
+            }
+            if let Some(buckets) = warmed.buckets {
+                self.bucket_cache.insert(warmed.alias.clone(), (Instant::now(), buckets));
+            }
+            self.clients.insert(warmed.alias, warmed.client);
+        }
+    }
+}
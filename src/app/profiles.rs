@@ -0,0 +1,68 @@
+use std::borrow::Cow;
+
+use crate::credentials::AliasConfig;
+
+use super::{App, Location};
+
+impl App {
+    /// `alias` as requests are signed for it now: with the keys of its active
+    /// profile, if one was switched to.
+    pub(crate) fn alias_config(&self, alias: &str) -> Option<Cow<'_, AliasConfig>> {
+        let config = self.config.aliases.get(alias)?;
+        match self.active_profiles.get(alias).and_then(|name| config.with_profile(name)) {
+            Some(profiled) => Some(Cow::Owned(profiled)),
+            None => Some(Cow::Borrowed(config)),
+        }
+    }
+
+    /// The profile `alias` signs with, `None` for its own keys.
+    pub fn active_profile(&self, alias: &str) -> Option<&str> {
+        let name = self.active_profiles.get(alias)?;
+        let config = self.config.aliases.get(alias)?;
+        config.profiles.contains_key(name).then_some(name.as_str())
+    }
+
+    /// `K`: switch the current remote to its next profile (after the last one,
+    /// back to its own keys), then list the view again with the new permissions.
+    /// Running transfers finish with the keys they started with.
+    pub async fn cycle_profile(&mut self) {
+        let Some(remote) = self.current_remote().map(str::to_string) else {
+            return;
+        };
+        let Some(config) = self.config.aliases.get(&remote) else {
+            return;
+        };
+        if config.profiles.is_empty() {
+            self.notify(format!("{} has no other profiles configured", remote));
+            return;
+        }
+        let next = match self.active_profile(&remote) {
+            None => config.profiles.keys().next().cloned(),
+            Some(current) => config
+                .profiles
+                .range::<str, _>((std::ops::Bound::Excluded(current), std::ops::Bound::Unbounded))
+                .next()
+                .map(|(name, _)| name.clone()),
+        };
+        match &next {
+            Some(name) => self.active_profiles.insert(remote.clone(), name.clone()),
+            None => self.active_profiles.remove(&remote),
+        };
+
+        self.clients.remove(&remote);
+        self.bucket_cache.remove(&remote);
+        self.forget_remote_prefetched(&remote);
+        if let Err(e) = self.ensure_client(&remote) {
+            self.notify_error(format!("Connection failed: {}", e));
+            return;
+        }
+        self.notify(format!(
+            "{} now signs as {}",
+            remote,
+            next.as_deref().unwrap_or("its own keys")
+        ));
+        if !matches!(self.location, Location::RemoteList) {
+            self.refresh().await;
+        }
+    }
+}
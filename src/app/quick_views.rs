@@ -0,0 +1,152 @@
+use ratatui::widgets::ListState;
+
+use crate::object_store::ObjectEntry;
+
+use super::indexing::IndexState;
+use super::{App, Location};
+
+/// A built-in question answered from the bucket index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickKind {
+    Largest,
+    ModifiedDay,
+    ModifiedWeek,
+}
+
+impl QuickKind {
+    pub const ALL: [QuickKind; 3] = [QuickKind::Largest, QuickKind::ModifiedDay, QuickKind::ModifiedWeek];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            QuickKind::Largest => "Largest objects",
+            QuickKind::ModifiedDay => "Modified in the last 24 hours",
+            QuickKind::ModifiedWeek => "Modified in the last 7 days",
+        }
+    }
+
+    /// Oldest modification time the view keeps, for the recently-modified ones.
+    fn since(self, now: i64) -> Option<i64> {
+        match self {
+            QuickKind::Largest => None,
+            QuickKind::ModifiedDay => Some(now - 24 * 3600),
+            QuickKind::ModifiedWeek => Some(now - 7 * 24 * 3600),
+        }
+    }
+}
+
+/// The quick views menu (`Shift+V`) of a bucket, and the view picked from it.
+pub struct QuickView {
+    pub remote: String,
+    pub bucket: String,
+    /// `None` while the menu is showing.
+    pub kind: Option<QuickKind>,
+    pub menu: ListState,
+    pub entries: Vec<ObjectEntry>,
+    pub state: ListState,
+    /// Objects the view was picked from, and whether that's the whole bucket.
+    pub indexed: usize,
+    pub complete: bool,
+}
+
+impl App {
+    /// Open the quick views menu for the current bucket, starting its index if
+    /// nothing has indexed it yet.
+    pub fn open_quick_views(&mut self) {
+        let Location::ObjectList { remote, bucket, .. } = self.location.clone() else {
+            self.notify_warn("Navigate into a bucket first");
+            return;
+        };
+        self.start_indexing(&remote, &bucket);
+        let mut menu = ListState::default();
+        menu.select(Some(0));
+        self.quick_view = Some(QuickView {
+            remote,
+            bucket,
+            kind: None,
+            menu,
+            entries: Vec::new(),
+            state: ListState::default(),
+            indexed: 0,
+            complete: false,
+        });
+    }
+
+    pub fn quick_move(&mut self, down: bool) {
+        let Some(view) = &mut self.quick_view else {
+            return;
+        };
+        let (state, len) = match view.kind {
+            None => (&mut view.menu, QuickKind::ALL.len()),
+            Some(_) => (&mut view.state, view.entries.len()),
+        };
+        if len == 0 {
+            return;
+        }
+        let i = state.selected().unwrap_or(0);
+        state.select(Some(if down { (i + 1).min(len - 1) } else { i.saturating_sub(1) }));
+    }
+
+    /// Build the view picked in the menu from what's indexed right now: the
+    /// `quick_view_limit` largest objects, or the most recently modified ones.
+    pub fn show_quick_view(&mut self, kind: QuickKind) {
+        let limit = self.settings.quick_view_limit.max(1);
+        let Some(view) = &mut self.quick_view else {
+            return;
+        };
+        let indexed = self
+            .index_key
+            .as_ref()
+            .is_some_and(|(r, b)| *r == view.remote && *b == view.bucket);
+        let pool: &[ObjectEntry] = if indexed { &self.search_pool } else { &[] };
+
+        let since = kind.since(chrono::Utc::now().timestamp());
+        let mut entries: Vec<ObjectEntry> = pool
+            .iter()
+            .filter(|o| since.is_none_or(|since| o.last_modified.is_some_and(|m| m.timestamp() >= since)))
+            .cloned()
+            .collect();
+        match kind {
+            QuickKind::Largest => entries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.key.cmp(&b.key))),
+            _ => entries.sort_by(|a, b| b.last_modified.cmp(&a.last_modified).then_with(|| a.key.cmp(&b.key))),
+        }
+        entries.truncate(limit);
+
+        view.kind = Some(kind);
+        view.indexed = pool.len();
+        view.complete = indexed && self.index_state == IndexState::Complete;
+        view.state.select((!entries.is_empty()).then_some(0));
+        view.entries = entries;
+    }
+
+    /// Pick the highlighted view in the menu.
+    pub fn quick_menu_select(&mut self) {
+        let Some(i) = self.quick_view.as_ref().and_then(|v| v.menu.selected()) else {
+            return;
+        };
+        self.show_quick_view(QuickKind::ALL[i]);
+    }
+
+    /// From a view back to the menu; from the menu, close it.
+    pub fn quick_back(&mut self) {
+        match &mut self.quick_view {
+            Some(view) if view.kind.is_some() => {
+                view.kind = None;
+                view.entries.clear();
+            }
+            _ => self.quick_view = None,
+        }
+    }
+
+    /// Close the view and open the highlighted object's folder with the cursor on it.
+    pub async fn quick_reveal(&mut self) {
+        let Some(view) = &self.quick_view else {
+            return;
+        };
+        let Some(obj) = view.state.selected().and_then(|i| view.entries.get(i)) else {
+            return;
+        };
+        let (remote, bucket, key) = (view.remote.clone(), view.bucket.clone(), obj.key.clone());
+        self.quick_view = None;
+        self.reveal_object(&remote, &bucket, &key).await;
+    }
+}
@@ -0,0 +1,97 @@
+use crate::storage;
+
+use super::App;
+use super::history::TransferSpec;
+
+const UNFINISHED_FILE: &str = "unfinished.json";
+
+/// Transfers started but not finished, whether they failed or the app exited
+/// while they ran; offered for resuming at the next launch. Files written before
+/// uploads and copies were kept hold downloads only, which still load.
+pub(crate) fn load_unfinished() -> Vec<TransferSpec> {
+    storage::load_json(UNFINISHED_FILE)
+}
+
+/// Whether two specs move the same thing to the same place, so a retry replaces
+/// the earlier entry instead of adding another.
+fn same_transfer(a: &TransferSpec, b: &TransferSpec) -> bool {
+    match (a, b) {
+        (TransferSpec::Download(a), TransferSpec::Download(b)) => {
+            a.remote == b.remote && a.bucket == b.bucket && a.key == b.key && a.dest == b.dest
+        }
+        (TransferSpec::Upload(a), TransferSpec::Upload(b)) => {
+            a.remote == b.remote && a.bucket == b.bucket && a.prefix == b.prefix && a.local == b.local
+        }
+        (TransferSpec::Copy(a), TransferSpec::Copy(b)) => {
+            a.remote == b.remote
+                && a.bucket == b.bucket
+                && a.key == b.key
+                && a.dst_remote == b.dst_remote
+                && a.dst_bucket == b.dst_bucket
+                && a.dst_key == b.dst_key
+        }
+        _ => false,
+    }
+}
+
+impl App {
+    /// Remember a transfer as unfinished until it succeeds.
+    pub(crate) fn track_unfinished(&mut self, spec: &TransferSpec) {
+        if !self.unfinished.iter().any(|s| same_transfer(s, spec)) {
+            self.unfinished.push(spec.clone());
+            self.save_unfinished();
+        }
+    }
+
+    pub(crate) fn finish_unfinished(&mut self, spec: &TransferSpec) {
+        let before = self.unfinished.len();
+        self.unfinished.retain(|s| !same_transfer(s, spec));
+        if self.unfinished.len() != before {
+            self.save_unfinished();
+        }
+    }
+
+    fn save_unfinished(&mut self) {
+        if self.settings.persist_history
+            && let Err(e) = storage::save_json(UNFINISHED_FILE, &self.unfinished)
+        {
+            self.notify_error(format!("Failed to save unfinished transfers: {}", e));
+        }
+    }
+
+    /// Queue every unfinished transfer to run again, one after another.
+    pub async fn resume_unfinished(&mut self) {
+        self.resume_prompt = false;
+        self.transfer_queue.extend(self.unfinished.iter().cloned());
+        self.start_next_queued().await;
+    }
+
+    /// Forget the unfinished transfers instead of resuming them.
+    pub fn discard_unfinished(&mut self) {
+        self.resume_prompt = false;
+        self.unfinished.clear();
+        self.save_unfinished();
+    }
+
+    /// Whether a queued transfer has to wait: another one runs, or one is being
+    /// planned or confirmed.
+    fn transfer_pending(&self) -> bool {
+        self.download_rx.is_some()
+            || self.copy_rx.is_some()
+            || self.plan_rx.is_some()
+            || self.plan.is_some()
+            || self.protected_confirm.is_some()
+    }
+
+    /// Start the next queued transfer once nothing else is in flight; called from
+    /// the event loop. Uploads are planned again and copies pass the protection
+    /// check like a repeat from the history. Transfers that can't start are
+    /// skipped and stay unfinished.
+    pub async fn start_next_queued(&mut self) {
+        while !self.transfer_pending()
+            && let Some(spec) = self.transfer_queue.pop_front()
+        {
+            self.rerun_transfer(spec).await;
+        }
+    }
+}
@@ -1,28 +1,82 @@
-use super::{parent_prefix, App, Entry, Location, Pane};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::object_store::ObjectEntry;
+use crate::storage;
+
+use super::{App, Entry, Location, Pane, parent_prefix};
+
+const SEARCH_HISTORY_FILE: &str = "search_history.json";
+const MAX_QUERIES: usize = 20;
+/// Indexed search results are grouped by prefix once there are more than this.
+const GROUP_ABOVE: usize = 200;
+
+/// Search results under one prefix, collapsed to a heading with their count
+/// and size until expanded.
+#[derive(Debug, Clone)]
+pub struct SearchGroup {
+    pub prefix: String,
+    pub hits: usize,
+    pub bytes: u64,
+    pub expanded: bool,
+}
+
+/// `hits` as prefix headings in order, each followed by its hits when expanded;
+/// those are named relative to the prefix.
+fn grouped(hits: Vec<&ObjectEntry>, expanded: &HashSet<String>) -> Vec<Entry> {
+    let mut groups: BTreeMap<String, Vec<&ObjectEntry>> = BTreeMap::new();
+    for hit in hits {
+        groups.entry(parent_prefix(&hit.key)).or_default().push(hit);
+    }
+    let mut rows = Vec::new();
+    for (prefix, hits) in groups {
+        let open = expanded.contains(&prefix);
+        rows.push(Entry::Group(SearchGroup {
+            prefix: prefix.clone(),
+            hits: hits.len(),
+            bytes: hits.iter().map(|h| h.size.max(0) as u64).sum(),
+            expanded: open,
+        }));
+        if open {
+            rows.extend(hits.into_iter().map(|hit| {
+                Entry::Object(ObjectEntry {
+                    display_name: format!("  {}", &hit.key[prefix.len()..]),
+                    ..hit.clone()
+                })
+            }));
+        }
+    }
+    rows
+}
+
+/// Recent queries, newest first, keyed by "remote/bucket".
+pub type SearchHistory = HashMap<String, Vec<String>>;
+
+pub(crate) fn load_search_history() -> SearchHistory {
+    storage::load_json(SEARCH_HISTORY_FILE)
+}
 
 impl App {
     pub fn start_search(&mut self) {
         self.search_active = true;
         self.search_query.clear();
+        self.search_recall = None;
+        self.search_grouping = None;
+        self.search_expanded.clear();
         self.pre_search_selection = self.browser_state.selected();
+        self.pre_search_offset = self.browser_state.offset();
         self.saved_entries = self.entries.clone();
         self.saved_location = Some(self.location.clone());
         self.pane = Pane::Browser;
 
+        if let Location::ObjectList { remote, bucket, .. } = self.location.clone() {
+            // Without auto_index, the first search in a bucket starts its index
+            self.start_indexing(&remote, &bucket);
+        }
         match &self.location {
             Location::ObjectList { remote, bucket, .. } => {
                 self.search_context = Some((remote.clone(), bucket.clone()));
-                self.entries = self
-                    .search_pool
-                    .iter()
-                    .cloned()
-                    .map(Entry::Object)
-                    .collect();
-                self.browser_state.select(if self.entries.is_empty() {
-                    None
-                } else {
-                    Some(0)
-                });
+                self.browser_state.select(None);
+                self.update_search_filter();
             }
             Location::BucketList { remote } => {
                 self.search_context = Some((remote.clone(), String::new()));
@@ -34,23 +88,29 @@ impl App {
     }
 
     pub fn cancel_search(&mut self) {
+        self.record_search();
+        self.context_view = None;
         self.search_active = false;
         self.search_query.clear();
         self.entries = std::mem::take(&mut self.saved_entries);
         if let Some(loc) = self.saved_location.take() {
             self.location = loc;
         }
+        // Put the table back exactly as it was: same row, same scroll position
         self.browser_state
             .select(self.pre_search_selection.take());
+        *self.browser_state.offset_mut() = std::mem::take(&mut self.pre_search_offset);
         self.search_context = None;
     }
 
     pub fn search_input(&mut self, c: char) {
+        self.search_recall = None;
         self.search_query.push(c);
         self.update_search_filter();
     }
 
     pub fn search_backspace(&mut self) {
+        self.search_recall = None;
         self.search_query.pop();
         self.update_search_filter();
     }
@@ -58,24 +118,19 @@ impl App {
     pub(crate) fn update_search_filter(&mut self) {
         let query = self.search_query.to_lowercase();
         let prev_sel = self.browser_state.selected();
+        let prev_key = self.selected_key();
 
         if self.index_key.is_some() {
-            if query.is_empty() {
-                self.entries = self
-                    .search_pool
-                    .iter()
-                    .cloned()
-                    .map(Entry::Object)
-                    .collect();
+            let hits: Vec<&ObjectEntry> = self
+                .search_pool
+                .iter()
+                .filter(|obj| query.is_empty() || obj.key.to_lowercase().contains(&query))
+                .collect();
+            self.entries = if self.search_grouping.unwrap_or(hits.len() > GROUP_ABOVE) {
+                grouped(hits, &self.search_expanded)
             } else {
-                self.entries = self
-                    .search_pool
-                    .iter()
-                    .filter(|obj| obj.key.to_lowercase().contains(&query))
-                    .cloned()
-                    .map(Entry::Object)
-                    .collect();
-            }
+                hits.into_iter().cloned().map(Entry::Object).collect()
+            };
         } else {
             if query.is_empty() {
                 self.entries = self.saved_entries.clone();
@@ -91,13 +146,54 @@ impl App {
 
         if self.entries.is_empty() {
             self.browser_state.select(None);
+        } else if let Some(pos) = prev_key.and_then(|key| self.entries.iter().position(|e| e.key() == key)) {
+            // Index batches arriving mid-search mustn't move the cursor off its row
+            self.browser_state.select(Some(pos));
         } else {
             let sel = prev_sel.unwrap_or(0).min(self.entries.len() - 1);
             self.browser_state.select(Some(sel));
         }
     }
 
+    /// Tab, or Enter on a heading: expand or collapse the search results group
+    /// under the cursor, leaving the cursor on its heading.
+    pub fn toggle_search_group(&mut self) {
+        let prefix = match self.browser_state.selected().and_then(|i| self.entries.get(i)) {
+            Some(Entry::Group(g)) => g.prefix.clone(),
+            Some(Entry::Object(obj)) if self.entries.iter().any(|e| matches!(e, Entry::Group(_))) => {
+                parent_prefix(&obj.key)
+            }
+            _ => return,
+        };
+        if !self.search_expanded.remove(&prefix) {
+            self.search_expanded.insert(prefix.clone());
+        }
+        self.update_search_filter();
+        if let Some(pos) = self
+            .entries
+            .iter()
+            .position(|e| matches!(e, Entry::Group(g) if g.prefix == prefix))
+        {
+            self.browser_state.select(Some(pos));
+        }
+    }
+
+    /// Ctrl+G: switch indexed search results between grouped by prefix and a flat list.
+    pub fn toggle_search_grouping(&mut self) {
+        if self.index_key.is_none() {
+            return;
+        }
+        let grouped = self.entries.iter().any(|e| matches!(e, Entry::Group(_)));
+        self.search_grouping = Some(!grouped);
+        self.update_search_filter();
+    }
+
     pub(crate) async fn finish_search_select(&mut self, entry: Entry) {
+        if let Entry::Group(_) = entry {
+            self.toggle_search_group();
+            return;
+        }
+        self.record_search();
         self.search_active = false;
         self.search_query.clear();
 
@@ -105,12 +201,7 @@ impl App {
             let target_key = entry.key().to_string();
 
             if let Some((remote, bucket)) = self.search_context.take() {
-                let parent = parent_prefix(&target_key);
-                self.enter_prefix(&remote, &bucket, &parent).await;
-
-                if let Some(pos) = self.entries.iter().position(|e| e.key() == target_key) {
-                    self.browser_state.select(Some(pos));
-                }
+                self.reveal_object(&remote, &bucket, &target_key).await;
             }
             self.saved_entries.clear();
             self.saved_location = None;
@@ -126,4 +217,84 @@ impl App {
             self.search_context = None;
         }
     }
+
+    fn search_history_key(&self) -> Option<String> {
+        self.search_context
+            .as_ref()
+            .map(|(remote, bucket)| format!("{}/{}", remote, bucket))
+    }
+
+    /// Remember the current query for this bucket and for `n`/`N`.
+    fn record_search(&mut self) {
+        let query = self.search_query.trim().to_string();
+        if query.is_empty() {
+            return;
+        }
+        self.last_search = Some(query.clone());
+        let Some(key) = self.search_history_key() else {
+            return;
+        };
+        let queries = self.search_history.entry(key).or_default();
+        queries.retain(|q| *q != query);
+        queries.insert(0, query);
+        queries.truncate(MAX_QUERIES);
+        if self.settings.persist_history
+            && let Err(e) = storage::save_json(SEARCH_HISTORY_FILE, &self.search_history)
+        {
+            self.notify_error(format!("Failed to save search history: {}", e));
+        }
+    }
+
+    /// Whether Up/Down should cycle past queries instead of moving through results.
+    pub fn search_recalling(&self) -> bool {
+        self.search_query.is_empty() || self.search_recall.is_some()
+    }
+
+    /// Step to an older (`older == true`) or newer query of this bucket.
+    pub fn recall_search(&mut self, older: bool) {
+        let Some(queries) = self.search_history_key().and_then(|k| self.search_history.get(&k)) else {
+            return;
+        };
+        let next = match (self.search_recall, older) {
+            (None, true) if !queries.is_empty() => Some(0),
+            (None, _) => return,
+            (Some(i), true) => Some((i + 1).min(queries.len() - 1)),
+            (Some(0), false) => None,
+            (Some(i), false) => Some(i - 1),
+        };
+        self.search_query = next.map(|i| queries[i].clone()).unwrap_or_default();
+        self.search_recall = next;
+        self.update_search_filter();
+    }
+
+    /// Jump to the next (or previous) entry of the current listing matching the last query.
+    pub fn search_next(&mut self, forward: bool) {
+        let Some(query) = self.last_search.as_ref().map(|q| q.to_lowercase()) else {
+            self.notify("No previous search");
+            return;
+        };
+        let len = self.entries.len();
+        if len == 0 {
+            return;
+        }
+        let current = self.browser_state.selected().unwrap_or(0);
+        let found = (1..=len)
+            .map(|step| {
+                if forward {
+                    (current + step) % len
+                } else {
+                    (current + len - step % len) % len
+                }
+            })
+            .find(|&i| self.entries[i].name().to_lowercase().contains(&query));
+        match found {
+            Some(i) => {
+                self.browser_state.select(Some(i));
+                self.metadata = None;
+                self.preview.clear();
+                self.pane = Pane::Browser;
+            }
+            None => self.notify(format!("No match for \"{}\" here", query)),
+        }
+    }
 }
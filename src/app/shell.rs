@@ -0,0 +1,81 @@
+use crate::object_store::SigningKeys;
+
+use super::{App, Entry, Location};
+
+impl App {
+    /// Environment for a subshell: where the browser is, and the alias's endpoint
+    /// and credentials. Empty values are left out rather than exported as blanks.
+    pub async fn shell_env(&mut self) -> Vec<(&'static str, String)> {
+        let mut env = Vec::new();
+        let remote = match &self.location {
+            Location::RemoteList => None,
+            Location::BucketList { remote } => Some(remote.clone()),
+            Location::ObjectList {
+                remote,
+                bucket,
+                prefix,
+            } => {
+                env.push(("S3Y_BUCKET", bucket.clone()));
+                env.push(("S3Y_PREFIX", prefix.clone()));
+                if let Some(Entry::Object(obj)) =
+                    self.browser_state.selected().and_then(|i| self.entries.get(i))
+                {
+                    env.push(("S3Y_KEY", obj.key.clone()));
+                }
+                Some(remote.clone())
+            }
+        };
+        if let Some(remote) = remote {
+            env.push(("S3Y_REMOTE", remote.clone()));
+            env.extend(self.aws_env(&remote).await);
+        }
+        env.retain(|(_, value)| !value.is_empty());
+        env
+    }
+
+    /// `AWS_*` variables for an S3 remote. A `credential_process` alias exports the
+    /// keys its client signs with now, renewed first if they are about to expire.
+    async fn aws_env(&mut self, remote: &str) -> Vec<(&'static str, String)> {
+        let Some(alias) = self.alias_config(remote).map(|a| a.into_owned()) else {
+            return Vec::new();
+        };
+        if alias.backend.as_deref().is_some_and(|b| b != "s3") {
+            return Vec::new();
+        }
+        let mut env = vec![("AWS_ENDPOINT_URL", alias.url.clone())];
+        if let Some(region) = &alias.region {
+            env.push(("AWS_REGION", region.clone()));
+        }
+        if alias.is_anonymous() {
+            return env;
+        }
+        let keys = if alias.credential_process.is_some() {
+            match self.current_keys(remote).await {
+                Ok(keys) => keys,
+                Err(e) => {
+                    self.notify_warn(format!("No credentials exported to the shell: {}", e));
+                    None
+                }
+            }
+        } else {
+            Some(SigningKeys {
+                access_key: alias.access_key,
+                secret_key: alias.secret_key,
+                session_token: alias.session_token,
+            })
+        };
+        if let Some(keys) = keys {
+            env.push(("AWS_ACCESS_KEY_ID", keys.access_key));
+            env.push(("AWS_SECRET_ACCESS_KEY", keys.secret_key));
+            if let Some(token) = keys.session_token {
+                env.push(("AWS_SESSION_TOKEN", token));
+            }
+        }
+        env
+    }
+
+    async fn current_keys(&mut self, remote: &str) -> anyhow::Result<Option<SigningKeys>> {
+        self.ensure_client(remote)?;
+        self.clients[remote].clone().current_keys().await
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::{HashMap, HashSet};
+use std::process::Child;
+
+use tokio::sync::mpsc;
+
+use super::inbox::Inbox;
+use super::tasks::TaskKind;
+use super::preview::{extension_to_kind, ffplay_command, PreviewKind};
+use super::{App, Entry, Location};
+
+/// How many upcoming slides get a presigned URL prepared in the background.
+const PREFETCH_AHEAD: usize = 3;
+
+/// Stepping through the images of the current prefix in a single ffplay window.
+pub struct Slideshow {
+    pub remote: String,
+    pub bucket: String,
+    pub keys: Vec<String>,
+    pub index: usize,
+    /// Presigned URLs by key, prepared ahead of the cursor.
+    urls: HashMap<String, String>,
+    pending: HashSet<String>,
+    url_tx: mpsc::Sender<(String, Result<String, String>)>,
+    pub(crate) url_rx: Inbox<(String, Result<String, String>)>,
+    /// The one viewer window; replaced on every step.
+    viewer: Option<Child>,
+    /// The current slide is waiting for its URL before it can be shown.
+    waiting: bool,
+}
+
+impl Slideshow {
+    pub fn current_key(&self) -> &str {
+        &self.keys[self.index]
+    }
+
+    fn close_viewer(&mut self) {
+        if let Some(mut child) = self.viewer.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl App {
+    /// Start a slideshow over the images in the current listing, beginning at the selection.
+    pub fn start_slideshow(&mut self) {
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            self.notify("Navigate into a bucket first");
+            return;
+        };
+
+        let keys: Vec<String> = self
+            .entries
+            .iter()
+            .filter_map(|e| match e {
+                Entry::Object(obj)
+                    if !obj.is_dir && extension_to_kind(&obj.key) == Some(PreviewKind::Image) =>
+                {
+                    Some(obj.key.clone())
+                }
+                _ => None,
+            })
+            .collect();
+        if keys.is_empty() {
+            self.notify("No images in this listing");
+            return;
+        }
+
+        let selected_key = self
+            .browser_state
+            .selected()
+            .and_then(|i| self.entries.get(i))
+            .map(|e| e.key().to_string());
+        let index = selected_key
+            .and_then(|k| keys.iter().position(|key| *key == k))
+            .unwrap_or(0);
+
+        let (url_tx, url_rx) = mpsc::channel(16);
+        self.slideshow = Some(Slideshow {
+            remote: remote.clone(),
+            bucket: bucket.clone(),
+            keys,
+            index,
+            urls: HashMap::new(),
+            pending: HashSet::new(),
+            url_tx,
+            url_rx: url_rx.into(),
+            viewer: None,
+            waiting: false,
+        });
+        self.show_slide();
+    }
+
+    pub fn stop_slideshow(&mut self) {
+        if let Some(mut show) = self.slideshow.take() {
+            show.close_viewer();
+        }
+        self.tasks.cancel_kind(TaskKind::Slideshow);
+    }
+
+    pub fn slideshow_next(&mut self) {
+        if let Some(show) = &mut self.slideshow {
+            show.index = (show.index + 1) % show.keys.len();
+        }
+        self.show_slide();
+    }
+
+    pub fn slideshow_prev(&mut self) {
+        if let Some(show) = &mut self.slideshow {
+            show.index = (show.index + show.keys.len() - 1) % show.keys.len();
+        }
+        self.show_slide();
+    }
+
+    /// Move the browser cursor to the current slide, then display it (or wait for its URL).
+    fn show_slide(&mut self) {
+        self.prefetch_slides();
+        let Some(show) = &mut self.slideshow else {
+            return;
+        };
+
+        let key = show.current_key().to_string();
+        if let Some(pos) = self.entries.iter().position(|e| e.key() == key) {
+            self.browser_state.select(Some(pos));
+        }
+
+        let Some(url) = show.urls.get(&key).cloned() else {
+            show.waiting = true;
+            return;
+        };
+        show.waiting = false;
+        show.close_viewer();
+        match ffplay_command(&key, &["-loop", "0"], &url).spawn() {
+            Ok(child) => show.viewer = Some(child),
+            Err(_) => {
+                self.notify_error("ffplay not found - install ffmpeg for preview");
+                self.stop_slideshow();
+            }
+        }
+    }
+
+    /// Presign the current slide and the next few in the background.
+    fn prefetch_slides(&mut self) {
+        let Some(show) = &mut self.slideshow else {
+            return;
+        };
+        let Some(client) = self.clients.get(&show.remote) else {
+            return;
+        };
+
+        let len = show.keys.len();
+        for offset in 0..=PREFETCH_AHEAD.min(len - 1) {
+            let key = &show.keys[(show.index + offset) % len];
+            if show.urls.contains_key(key) || !show.pending.insert(key.clone()) {
+                continue;
+            }
+            let client = client.clone();
+            let bucket = show.bucket.clone();
+            let key = key.clone();
+            let tx = show.url_tx.clone();
+            let label = format!("Presign slide {}", key);
+            self.tasks.spawn(TaskKind::Slideshow, label, async move {
+                let url = client
+                    .presign_get_object(&bucket, &key)
+                    .await
+                    .map_err(|e| e.to_string());
+                let _ = tx.send((key, url)).await;
+            });
+        }
+    }
+
+    /// Collect prepared URLs and show the current slide once its URL arrives. Call every tick.
+    pub fn drain_slideshow(&mut self) {
+        let Some(show) = &mut self.slideshow else {
+            return;
+        };
+
+        let mut failed = None;
+        while let Ok((key, result)) = show.url_rx.try_recv() {
+            show.pending.remove(&key);
+            match result {
+                Ok(url) => {
+                    show.urls.insert(key, url);
+                }
+                Err(e) => failed = Some(e),
+            }
+        }
+
+        if let Some(e) = failed {
+            self.notify_error(format!("Presign failed: {}", e));
+            self.stop_slideshow();
+        } else if show.waiting && show.urls.contains_key(show.current_key()) {
+            self.show_slide();
+        }
+    }
+}
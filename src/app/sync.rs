@@ -0,0 +1,486 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::hooks::Op;
+use crate::object_store::{self, DownloadMsg, TransferJob, UploadOptions};
+use crate::upload_manifest::{FileStamp, UploadManifest};
+
+use super::acl::next_acl;
+use super::changes::Change;
+use super::confirm::Gate;
+use super::history::{TransferSpec, UploadSpec};
+use super::plan::{Plan, PlanAction, PlanItem, PlanOp};
+use super::tasks::TaskKind;
+use super::{App, DownloadProgress, Location, Pane};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncDirection {
+    /// Bucket prefix -> local directory.
+    Download,
+    /// Local directory -> bucket prefix.
+    Upload,
+}
+
+impl SyncDirection {
+    pub fn label(self) -> &'static str {
+        match self {
+            SyncDirection::Download => "bucket -> local",
+            SyncDirection::Upload => "local -> bucket",
+        }
+    }
+}
+
+/// What to do with a file that exists on both sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncPolicy {
+    /// Copy when the source is newer; compares sizes when a time is unknown.
+    NewerWins,
+    SizeDiffers,
+    AlwaysOverwrite,
+    NeverOverwrite,
+}
+
+impl SyncPolicy {
+    pub fn label(self) -> &'static str {
+        match self {
+            SyncPolicy::NewerWins => "newer-wins",
+            SyncPolicy::SizeDiffers => "size-differs",
+            SyncPolicy::AlwaysOverwrite => "always-overwrite",
+            SyncPolicy::NeverOverwrite => "never-overwrite",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            SyncPolicy::NewerWins => SyncPolicy::SizeDiffers,
+            SyncPolicy::SizeDiffers => SyncPolicy::AlwaysOverwrite,
+            SyncPolicy::AlwaysOverwrite => SyncPolicy::NeverOverwrite,
+            SyncPolicy::NeverOverwrite => SyncPolicy::NewerWins,
+        }
+    }
+
+    /// Whether `src` replaces an existing `dst`. The same rule applies in both directions.
+    fn overwrites(self, src: &FileStat, dst: &FileStat) -> bool {
+        match self {
+            SyncPolicy::NewerWins => match (src.modified, dst.modified) {
+                (Some(s), Some(d)) => s > d,
+                _ => src.size != dst.size,
+            },
+            SyncPolicy::SizeDiffers => src.size != dst.size,
+            SyncPolicy::AlwaysOverwrite => true,
+            SyncPolicy::NeverOverwrite => false,
+        }
+    }
+}
+
+/// Sync options being chosen before planning.
+pub struct SyncSetup {
+    pub remote: String,
+    pub bucket: String,
+    /// Bucket prefix, ending in `/`.
+    pub prefix: String,
+    pub local: PathBuf,
+    pub direction: SyncDirection,
+    pub policy: SyncPolicy,
+    /// Canned ACL for uploaded objects; `None` leaves it to the bucket.
+    pub acl: Option<String>,
+}
+
+/// A planned sync, ready to run.
+pub struct SyncRun {
+    pub remote: String,
+    pub bucket: String,
+    pub name: String,
+    jobs: Vec<TransferJob>,
+    /// Reported once the run finishes.
+    summary: String,
+    /// Files on the destination side it replaces.
+    overwrites: usize,
+    /// Resume manifest and ACL of an upload.
+    uploads: UploadOptions,
+    /// How to repeat it from the history, for uploads.
+    upload: Option<UploadSpec>,
+}
+
+impl SyncRun {
+    /// Whether running it replaces objects in the bucket.
+    pub(crate) fn overwrites_objects(&self) -> bool {
+        self.upload.is_some() && self.overwrites > 0
+    }
+
+    pub(crate) fn summary(&self) -> &str {
+        &self.summary
+    }
+}
+
+#[derive(Clone, Copy)]
+struct FileStat {
+    size: u64,
+    /// Unix seconds.
+    modified: Option<i64>,
+}
+
+/// Every file under `root`, keyed by its `/`-separated relative path.
+fn walk_local(root: &Path) -> HashMap<String, FileStat> {
+    let mut files = HashMap::new();
+    let mut dirs = vec![(root.to_path_buf(), String::new())];
+    while let Some((dir, rel)) = dirs.pop() {
+        let Ok(read) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in read.flatten() {
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            let name = entry.file_name().to_string_lossy().to_string();
+            let path = format!("{}{}", rel, name);
+            if meta.is_dir() {
+                dirs.push((entry.path(), format!("{}/", path)));
+            } else {
+                let modified = meta
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs() as i64);
+                files.insert(
+                    path,
+                    FileStat {
+                        size: meta.len(),
+                        modified,
+                    },
+                );
+            }
+        }
+    }
+    files
+}
+
+impl App {
+    /// Open sync options for the selected S3 directory and the current local directory.
+    pub fn start_sync_setup(&mut self) {
+        let Some((name, key)) = self.download_source.clone() else {
+            return;
+        };
+        if !self.download_source_is_dir {
+            self.notify_error("Sync works on directories");
+            return;
+        }
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            return;
+        };
+        let name = self
+            .rename_input
+            .clone()
+            .filter(|s| !s.is_empty())
+            .unwrap_or(name);
+        self.sync_setup = Some(SyncSetup {
+            remote: remote.clone(),
+            bucket: bucket.clone(),
+            prefix: key,
+            local: self.local_path.join(name),
+            direction: SyncDirection::Download,
+            policy: SyncPolicy::NewerWins,
+            acl: None,
+        });
+    }
+
+    pub fn toggle_sync_direction(&mut self) {
+        if let Some(setup) = &mut self.sync_setup {
+            setup.direction = match setup.direction {
+                SyncDirection::Download => SyncDirection::Upload,
+                SyncDirection::Upload => SyncDirection::Download,
+            };
+        }
+    }
+
+    pub fn cycle_sync_policy(&mut self) {
+        if let Some(setup) = &mut self.sync_setup {
+            setup.policy = setup.policy.next();
+        }
+    }
+
+    /// Next canned ACL for uploads, after the bucket default.
+    pub fn cycle_sync_acl(&mut self) {
+        if let Some(setup) = &mut self.sync_setup {
+            setup.acl = next_acl(setup.acl.as_deref());
+        }
+    }
+
+    /// Compare both sides under the chosen policy and show the plan.
+    pub fn plan_sync(&mut self) {
+        let Some(setup) = self.sync_setup.take() else {
+            return;
+        };
+        // Overwrites on a protected remote ask for its name once the plan is confirmed
+        if setup.direction == SyncDirection::Upload && self.gate(&[&setup.remote], false) == Gate::Refused {
+            return;
+        }
+        if setup.direction == SyncDirection::Upload && self.refuse_locked(&setup.remote, &setup.bucket) {
+            return;
+        }
+        let Some(client) = self.clients.get(&setup.remote).cloned() else {
+            self.notify_error("Not connected to remote");
+            return;
+        };
+
+        self.download_mode = false;
+        self.download_source = None;
+        self.rename_input = None;
+        self.local_entries.clear();
+        self.pane = Pane::Browser;
+
+        let name = setup
+            .local
+            .file_name()
+            .map_or_else(|| setup.prefix.clone(), |n| n.to_string_lossy().to_string());
+        let title = match setup.direction {
+            SyncDirection::Download => format!(
+                "Sync {} -> {} ({})",
+                setup.prefix,
+                setup.local.display(),
+                setup.policy.label()
+            ),
+            SyncDirection::Upload => format!(
+                "Sync {} -> {} ({})",
+                setup.local.display(),
+                setup.prefix,
+                setup.policy.label()
+            ),
+        };
+
+        self.spawn_plan(async move {
+            let mut remote_files: HashMap<String, FileStat> = HashMap::new();
+            // Keys whose path would leave the local directory; listed as skipped
+            let mut outside = Vec::new();
+            for o in client
+                .list_all_keys(&setup.bucket, &setup.prefix)
+                .await
+                .map_err(|e| e.to_string())?
+            {
+                if o.key.ends_with('/') {
+                    continue;
+                }
+                let rel = o.key.strip_prefix(&setup.prefix).unwrap_or(&o.key).to_string();
+                let stat = FileStat {
+                    size: o.size.max(0) as u64,
+                    modified: o.last_modified.map(|t| t.timestamp()),
+                };
+                if object_store::stays_inside(&rel) {
+                    remote_files.insert(rel, stat);
+                } else {
+                    outside.push(PlanItem {
+                        action: PlanAction::Skip,
+                        path: o.key,
+                        size: stat.size,
+                    });
+                }
+            }
+            let local = setup.local.clone();
+            let local_files = tokio::task::spawn_blocking(move || walk_local(&local))
+                .await
+                .map_err(|e| e.to_string())?;
+            let manifest = (setup.direction == SyncDirection::Upload)
+                .then(|| Arc::new(UploadManifest::open(&setup.remote, &setup.bucket, &setup.prefix, &setup.local)));
+
+            let (sources, dests, copy) = match setup.direction {
+                SyncDirection::Download => (&remote_files, &local_files, PlanAction::Download),
+                SyncDirection::Upload => (&local_files, &remote_files, PlanAction::Upload),
+            };
+            let mut rels: Vec<&String> = sources.keys().collect();
+            rels.sort();
+
+            let mut items = Vec::new();
+            let mut jobs = Vec::new();
+            let mut resuming = 0;
+            for rel in rels {
+                let src = &sources[rel];
+                let key = format!("{}{}", setup.prefix, rel);
+                let stamp = FileStamp {
+                    size: src.size,
+                    modified: src.modified,
+                };
+                let action = match dests.get(rel) {
+                    None => copy,
+                    // Uploaded by an earlier run of this sync that didn't finish
+                    Some(_) if manifest.as_ref().is_some_and(|m| m.is_done(&key, stamp)) => PlanAction::Skip,
+                    Some(dst) if setup.policy.overwrites(src, dst) => PlanAction::Overwrite,
+                    Some(_) => PlanAction::Skip,
+                };
+                if action != PlanAction::Skip {
+                    if manifest.as_ref().and_then(|m| m.pending(&key)).is_some_and(|p| p.stamp == stamp) {
+                        resuming += 1;
+                    }
+                    jobs.push(TransferJob {
+                        key: key.clone(),
+                        local: setup.local.join(rel),
+                        size: src.size,
+                        upload: setup.direction == SyncDirection::Upload,
+                    });
+                }
+                items.push(PlanItem {
+                    action,
+                    path: key,
+                    size: src.size,
+                });
+            }
+
+            let count = |action| items.iter().filter(|i| i.action == action).count();
+            let mut summary = format!(
+                "Sync ({}): {} {}, {} overwritten, {} skipped",
+                setup.policy.label(),
+                count(copy),
+                if copy == PlanAction::Upload { "uploaded" } else { "downloaded" },
+                count(PlanAction::Overwrite),
+                count(PlanAction::Skip)
+            );
+            if resuming > 0 {
+                summary.push_str(&format!(", {} resumed from the last run", resuming));
+            }
+            if !outside.is_empty() {
+                summary.push_str(&format!(
+                    ", {} with a path outside {} left alone",
+                    outside.len(),
+                    setup.local.display()
+                ));
+            }
+            let upload = (setup.direction == SyncDirection::Upload).then(|| UploadSpec {
+                name: name.clone(),
+                remote: setup.remote.clone(),
+                bucket: setup.bucket.clone(),
+                prefix: setup.prefix.clone(),
+                local: setup.local.clone(),
+                policy: setup.policy,
+                acl: setup.acl.clone(),
+            });
+            let run = SyncRun {
+                remote: setup.remote,
+                bucket: setup.bucket,
+                name,
+                jobs,
+                summary,
+                overwrites: count(PlanAction::Overwrite),
+                upload,
+                uploads: UploadOptions {
+                    manifest,
+                    acl: setup.acl,
+                },
+            };
+            items.extend(outside);
+            Ok(Plan::new(title, PlanOp::Sync(run), items))
+        });
+    }
+
+    /// Plan a recorded upload again; it runs once the plan is confirmed.
+    pub(crate) fn repeat_upload(&mut self, spec: UploadSpec) {
+        if self.download_rx.is_some() {
+            self.notify_warn("A transfer is already running");
+            return;
+        }
+        if let Err(e) = self.ensure_client(&spec.remote) {
+            self.notify_error(format!("Connection failed: {}", e));
+            return;
+        }
+        self.sync_setup = Some(SyncSetup {
+            remote: spec.remote,
+            bucket: spec.bucket,
+            prefix: spec.prefix,
+            local: spec.local,
+            direction: SyncDirection::Upload,
+            policy: spec.policy,
+            acl: spec.acl,
+        });
+        self.plan_sync();
+    }
+
+    /// Run a confirmed sync, reporting progress like a directory download.
+    pub(crate) fn start_sync(&mut self, run: SyncRun) {
+        if let Err(e) = self.ensure_client(&run.remote) {
+            self.notify_error(format!("Connection failed: {}", e));
+            return;
+        }
+        let client = self.clients[&run.remote].clone();
+        let upload = run.upload.map(TransferSpec::Upload);
+        if run.jobs.is_empty() {
+            if let Some(manifest) = &run.uploads.manifest {
+                manifest.remove();
+            }
+            if let Some(upload) = &upload {
+                self.finish_unfinished(upload);
+            }
+            self.notify(run.summary);
+            return;
+        }
+        if let Some(upload) = &upload {
+            self.track_unfinished(upload);
+        }
+        let (tx, rx) = mpsc::channel(64);
+        self.download_rx = Some(rx.into());
+        self.download_started_at = Some(Instant::now());
+        let label = format!("Sync {}", run.name);
+        self.download_progress = Some(DownloadProgress {
+            filename: run.name,
+            bytes_downloaded: 0,
+            total_bytes: run.jobs.iter().map(|j| j.size).sum(),
+            speed_bps: 0.0,
+            avg_speed_bps: 0.0,
+            throughput: Default::default(),
+            files_done: 0,
+            files_total: run.jobs.len(),
+            complete: false,
+            error: None,
+        });
+        self.sync_summary = Some(run.summary);
+        self.active_transfer = upload;
+        let uploads = run.jobs.iter().filter(|j| j.upload).map(|j| (j.key.clone(), j.size)).collect();
+        self.sync_uploads = Some((run.remote.clone(), run.bucket.clone(), Change::Written(uploads)));
+
+        let hooks = self.settings.hooks.clone();
+        let hook_tx = self.hook_tx.clone();
+        let task = self.tasks.spawn(TaskKind::Transfer, label, async move {
+            let (uploads, downloads): (Vec<_>, Vec<_>) = run.jobs.iter().partition(|j| j.upload);
+            let batches = [
+                (Op::Upload, uploads.into_iter().map(|j| j.key.clone()).collect::<Vec<_>>()),
+                (Op::Download, downloads.into_iter().map(|j| j.key.clone()).collect()),
+            ];
+            let mut result = Ok(());
+            for (op, keys) in batches.iter().filter(|(_, keys)| !keys.is_empty()) {
+                if let Err(e) = hooks.run(true, *op, &run.remote, &run.bucket, keys).await {
+                    result = Err(e);
+                    break;
+                }
+            }
+            if result.is_ok() {
+                result = object_store::transfer_files(&client, &run.bucket, run.jobs, tx.clone(), 4, &run.uploads).await;
+                if result.is_ok()
+                    && let Some(manifest) = &run.uploads.manifest
+                {
+                    manifest.remove();
+                }
+            }
+            if result.is_ok() {
+                for (op, keys) in batches.iter().filter(|(_, keys)| !keys.is_empty()) {
+                    if let Err(e) = hooks.run(false, *op, &run.remote, &run.bucket, keys).await {
+                        let _ = hook_tx.send(e.to_string()).await;
+                    }
+                }
+            }
+            let _ = tx
+                .send(DownloadMsg {
+                    bytes_downloaded: 0,
+                    total_bytes: 0,
+                    files_done: 0,
+                    files_total: 0,
+                    complete: true,
+                    error: result.err().map(|e| e.to_string()),
+                })
+                .await;
+        });
+        self.download_task = Some(task);
+    }
+}
@@ -0,0 +1,196 @@
+use std::future::Future;
+
+use futures_util::FutureExt;
+use tokio::task::JoinHandle;
+
+use super::App;
+use super::confirm::Confirm;
+
+/// What a background job is doing; used to cancel a whole family at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskKind {
+    /// Streaming every key of the open bucket for search.
+    Index,
+    /// A download, ranged download or sync.
+    Transfer,
+    /// Fetching a preview, or waiting on the viewer it opened.
+    Preview,
+    /// Listing for a dry-run plan.
+    Plan,
+    /// Copy or move between remotes.
+    Copy,
+    /// Deleting everything under a prefix.
+    Delete,
+    /// Writing a bucket inventory report.
+    Inventory,
+    /// Presigning marked objects.
+    Links,
+    /// Presigning slides ahead of the cursor.
+    Slideshow,
+    /// A configured shell action.
+    Action,
+    /// A plugin hook or command.
+    Plugin,
+    /// Connecting to a remote and listing its buckets ahead of use.
+    Connect,
+    /// Listing the directory the cursor rests on, ahead of entering it.
+    Prefetch,
+    /// Fetching new temporary credentials before the current ones expire.
+    Credentials,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskId(u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskStatus {
+    Running,
+    Finished,
+}
+
+pub struct Task {
+    pub id: TaskId,
+    pub kind: TaskKind,
+    pub label: String,
+    handle: JoinHandle<()>,
+}
+
+impl Task {
+    pub fn status(&self) -> TaskStatus {
+        if self.handle.is_finished() {
+            TaskStatus::Finished
+        } else {
+            TaskStatus::Running
+        }
+    }
+}
+
+/// Owns every job the app spawns, so they can be found, cancelled and shut down
+/// together. Results still come back over each feature's own channel.
+#[derive(Default)]
+pub struct TaskManager {
+    next_id: u64,
+    tasks: Vec<Task>,
+    /// Label of a job that panicked, until the event loop takes it and exits.
+    panicked: Option<String>,
+}
+
+impl TaskManager {
+    pub fn spawn<F>(&mut self, kind: TaskKind, label: impl Into<String>, future: F) -> TaskId
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.next_id += 1;
+        let id = TaskId(self.next_id);
+        self.tasks.push(Task {
+            id,
+            kind,
+            label: label.into(),
+            handle: tokio::spawn(future),
+        });
+        id
+    }
+
+    /// Abort one job. Unknown or already reaped ids are ignored.
+    pub fn cancel(&mut self, id: TaskId) {
+        self.tasks.retain(|t| {
+            if t.id == id {
+                t.handle.abort();
+            }
+            t.id != id
+        });
+    }
+
+    /// Abort every job of `kind`.
+    pub fn cancel_kind(&mut self, kind: TaskKind) {
+        self.tasks.retain(|t| {
+            if t.kind == kind {
+                t.handle.abort();
+            }
+            t.kind != kind
+        });
+    }
+
+    /// Forget jobs that have finished, noting one that ended in a panic.
+    pub fn reap(&mut self) {
+        let panicked = &mut self.panicked;
+        self.tasks.retain_mut(|t| {
+            if !t.handle.is_finished() {
+                return true;
+            }
+            if let Some(Err(e)) = (&mut t.handle).now_or_never()
+                && e.is_panic()
+            {
+                panicked.get_or_insert_with(|| t.label.clone());
+            }
+            false
+        });
+    }
+
+    /// The label of a job that panicked since the last call.
+    pub fn take_panicked(&mut self) -> Option<String> {
+        self.panicked.take()
+    }
+
+    /// Abort everything still running, e.g. on quit, and wait until it has stopped.
+    pub async fn shutdown(&mut self) {
+        for task in &self.tasks {
+            task.handle.abort();
+        }
+        for task in self.tasks.drain(..) {
+            // Cancelled, or it ended on its own meanwhile; either way it's stopped
+            let _ = task.handle.await;
+        }
+    }
+
+    /// Jobs still running, oldest first.
+    pub fn running(&self) -> impl Iterator<Item = &Task> {
+        self.tasks.iter().filter(|t| t.status() == TaskStatus::Running)
+    }
+}
+
+impl App {
+    /// Whether a download, sync, copy or prefix delete is still running.
+    pub fn transfer_running(&self) -> bool {
+        self.download_rx.is_some() || self.copy_rx.is_some() || self.delete_rx.is_some()
+    }
+
+    /// Quit, asking first if that would abort a transfer.
+    pub fn request_quit(&mut self) {
+        if self.transfer_running() && self.should_confirm(Confirm::Quit, "") {
+            self.confirm_quit = true;
+        } else {
+            self.should_quit = true;
+        }
+    }
+
+    /// Abort every background job and delete the files of downloads they left unfinished.
+    pub async fn shutdown_tasks(&mut self) {
+        // Jobs only stop at their next await; cleaning up earlier would race their writes
+        self.tasks.shutdown().await;
+        for client in self.clients.values() {
+            client.remove_partial_files();
+        }
+    }
+
+    /// Apply whatever background jobs have sent since the last call. Call every tick.
+    pub fn drain_tasks(&mut self) {
+        self.drain_index();
+        self.drain_download();
+        self.drain_preview();
+        self.drain_slideshow();
+        self.drain_plan();
+        self.drain_copy();
+        self.drain_delete();
+        self.drain_inventory();
+        self.drain_links();
+        self.drain_plugins();
+        self.drain_action();
+        self.drain_hooks();
+        self.drain_prewarm();
+        self.drain_prefetch();
+        self.prefetch_hovered();
+        self.watch_credentials();
+        self.tasks.reap();
+    }
+}
@@ -0,0 +1,408 @@
+//! The app driven through `dispatch`, the way the event loop drives it, against a
+//! local-directory remote in a temp dir. Popups get their keys as [`PopupAction`]s.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use tempfile::TempDir;
+
+use crate::credentials::{AliasConfig, McConfig, RemoteSettings};
+use crate::object_store::CopyOptions;
+use crate::settings::Settings;
+
+use super::action::Action;
+use super::help::HelpMode;
+use super::history::{CopySpec, DownloadSpec, TransferSpec};
+use super::tasks::TaskKind;
+use super::mode::{
+    CopyPickerAction, DeleteConfirmAction, HelpAction, Mode, PickerAction, PlanAction, PopupAction,
+    PromptAction, ResumeAction, SearchAction,
+};
+use super::{App, Entry, Location, Pane};
+
+/// A remote named `local` holding bucket `bkt`:
+///
+/// ```text
+/// a.txt  b.txt  docs/guide.md  docs/readme.md  photos/x.jpg  photos/y.jpg
+/// ```
+fn fixture() -> (App, TempDir) {
+    fixture_with(RemoteSettings::default())
+}
+
+/// The fixture with `remote` as the alias's options in settings.json.
+fn fixture_with(remote: RemoteSettings) -> (App, TempDir) {
+    let root = tempfile::tempdir().unwrap();
+    for (path, body) in [
+        ("a.txt", "alpha"),
+        ("b.txt", "bravo"),
+        ("docs/guide.md", "# guide"),
+        ("docs/readme.md", "# readme"),
+        ("photos/x.jpg", "x"),
+        ("photos/y.jpg", "y"),
+    ] {
+        let file = root.path().join("bkt").join(path);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, body).unwrap();
+    }
+    // Nothing read from or written to the user's data dir
+    let remote = RemoteSettings {
+        backend: Some("local".to_string()),
+        ..remote
+    };
+    let settings = Settings {
+        persist_history: false,
+        remotes: HashMap::from([("local".to_string(), remote)]),
+        ..Settings::default()
+    };
+    let alias = AliasConfig {
+        url: root.path().display().to_string(),
+        ..AliasConfig::default()
+    };
+    let config = McConfig {
+        version: "10".to_string(),
+        aliases: HashMap::from([("local".to_string(), alias)]),
+    };
+    (App::new(config, settings), root)
+}
+
+/// Apply what background jobs send until none is left running.
+async fn settle(app: &mut App) {
+    for _ in 0..500 {
+        app.drain_tasks();
+        if app.tasks.running().next().is_none() {
+            app.drain_tasks();
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    panic!("background jobs still running");
+}
+
+/// Open bucket `bkt` of the `local` remote.
+async fn open_bucket(app: &mut App) {
+    app.dispatch(Action::Select).await;
+    assert!(matches!(&app.location, Location::BucketList { remote } if remote == "local"));
+    app.dispatch(Action::Select).await;
+    settle(app).await;
+}
+
+fn names(app: &App) -> Vec<&str> {
+    app.entries
+        .iter()
+        .map(|e| match e {
+            Entry::Object(obj) => obj.display_name.as_str(),
+            Entry::Bucket(b) => b.name.as_str(),
+            Entry::Group(g) => g.prefix.as_str(),
+        })
+        .collect()
+}
+
+fn selected_name(app: &App) -> Option<&str> {
+    let i = app.browser_state.selected()?;
+    names(app).get(i).copied()
+}
+
+/// Move the cursor onto `name` with the same actions `j` / `gg` send.
+async fn cursor_to(app: &mut App, name: &str) {
+    app.dispatch(Action::JumpTop).await;
+    for _ in 0..app.entries.len() {
+        if selected_name(app) == Some(name) {
+            return;
+        }
+        app.dispatch(Action::MoveDown).await;
+    }
+    panic!("{} not listed in {:?}", name, names(app));
+}
+
+/// Type `text` into whichever prompt `wrap` addresses.
+async fn type_into<T>(app: &mut App, wrap: fn(T) -> PopupAction, input: fn(char) -> T, text: &str) {
+    for c in text.chars() {
+        app.dispatch(Action::Popup(wrap(input(c)))).await;
+    }
+}
+
+fn prefix(app: &App) -> &str {
+    match &app.location {
+        Location::ObjectList { prefix, .. } => prefix,
+        other => panic!("not in a bucket: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn navigates_into_prefixes_and_back() {
+    let (mut app, _root) = fixture();
+    open_bucket(&mut app).await;
+    assert_eq!(app.pane, Pane::Browser);
+    assert_eq!(prefix(&app), "");
+    assert_eq!(names(&app), ["docs", "photos", "a.txt", "b.txt"]);
+
+    cursor_to(&mut app, "photos").await;
+    app.dispatch(Action::Select).await;
+    assert_eq!(prefix(&app), "photos/");
+    assert_eq!(names(&app), ["x.jpg", "y.jpg"]);
+
+    // Back up a level to a fresh listing with the cursor at the top
+    app.dispatch(Action::Back).await;
+    assert_eq!(prefix(&app), "");
+    assert_eq!(selected_name(&app), Some("docs"));
+
+    app.dispatch(Action::JumpBottom).await;
+    assert_eq!(selected_name(&app), Some("b.txt"));
+
+    app.dispatch(Action::GoBucketList).await;
+    assert!(matches!(&app.location, Location::BucketList { .. }));
+    assert_eq!(names(&app), ["bkt"]);
+}
+
+#[tokio::test]
+async fn search_finds_nested_objects_and_reveals_them() {
+    let (mut app, _root) = fixture();
+    open_bucket(&mut app).await;
+
+    app.dispatch(Action::Search).await;
+    settle(&mut app).await;
+    assert_eq!(app.mode(), Mode::Search);
+    type_into(&mut app, PopupAction::Search, SearchAction::Input, "readme").await;
+    let hits: Vec<&str> = app
+        .entries
+        .iter()
+        .filter_map(|e| match e {
+            Entry::Object(obj) => Some(obj.key.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(hits, ["docs/readme.md"]);
+
+    // Picking a hit leaves search in the hit's prefix, on the hit
+    app.browser_state.select(Some(0));
+    app.dispatch(Action::Popup(PopupAction::Search(SearchAction::Select))).await;
+    assert_eq!(app.mode(), Mode::Normal);
+    assert_eq!(prefix(&app), "docs/");
+    assert_eq!(selected_name(&app), Some("readme.md"));
+}
+
+#[tokio::test]
+async fn marks_follow_the_cursor_and_clear_on_dismiss() {
+    let (mut app, _root) = fixture();
+    open_bucket(&mut app).await;
+
+    cursor_to(&mut app, "a.txt").await;
+    app.dispatch(Action::ToggleMark).await;
+    // Marking moves on to the next entry, so marking again takes that one too
+    assert_eq!(selected_name(&app), Some("b.txt"));
+    app.dispatch(Action::ToggleMark).await;
+    assert!(app.is_marked("a.txt") && app.is_marked("b.txt"));
+    assert_eq!(app.marked.len(), 2);
+
+    cursor_to(&mut app, "a.txt").await;
+    app.dispatch(Action::ToggleMark).await;
+    assert!(!app.is_marked("a.txt"));
+
+    app.dispatch(Action::Dismiss).await;
+    assert!(app.marked.is_empty());
+}
+
+#[tokio::test]
+async fn deletes_an_object_once_confirmed() {
+    let (mut app, root) = fixture();
+    open_bucket(&mut app).await;
+
+    cursor_to(&mut app, "b.txt").await;
+    app.dispatch(Action::Delete).await;
+    assert_eq!(app.mode(), Mode::ConfirmDelete);
+    assert_eq!(app.confirm_delete.as_ref().unwrap().key, "b.txt");
+    assert!(root.path().join("bkt/b.txt").exists());
+
+    // The popup starts on "no"; switch to "yes" first
+    app.dispatch(Action::Popup(PopupAction::ConfirmDelete(DeleteConfirmAction::Toggle))).await;
+    app.dispatch(Action::Popup(PopupAction::ConfirmDelete(DeleteConfirmAction::Submit))).await;
+    assert_eq!(app.mode(), Mode::Normal);
+    assert!(!root.path().join("bkt/b.txt").exists());
+    assert_eq!(names(&app), ["docs", "photos", "a.txt"]);
+}
+
+#[tokio::test]
+async fn deletes_a_prefix_after_its_plan() {
+    let (mut app, root) = fixture();
+    open_bucket(&mut app).await;
+
+    cursor_to(&mut app, "photos").await;
+    app.dispatch(Action::Delete).await;
+    settle(&mut app).await;
+    assert_eq!(app.mode(), Mode::Plan);
+    assert_eq!(app.plan.as_ref().unwrap().items.len(), 2);
+
+    app.dispatch(Action::Popup(PopupAction::Plan(PlanAction::Confirm))).await;
+    settle(&mut app).await;
+    assert!(app.delete_progress.is_none());
+    assert!(!has_files(&root.path().join("bkt/photos")));
+    assert_eq!(names(&app), ["docs", "a.txt", "b.txt"]);
+}
+
+#[tokio::test]
+async fn declining_a_delete_keeps_the_object() {
+    let (mut app, root) = fixture();
+    open_bucket(&mut app).await;
+
+    cursor_to(&mut app, "a.txt").await;
+    app.dispatch(Action::Delete).await;
+    app.dispatch(Action::Popup(PopupAction::ConfirmDelete(DeleteConfirmAction::Submit))).await;
+    assert_eq!(app.mode(), Mode::Normal);
+    assert!(root.path().join("bkt/a.txt").exists());
+    assert_eq!(names(&app), ["docs", "photos", "a.txt", "b.txt"]);
+}
+
+#[tokio::test]
+async fn moves_on_a_protected_remote_once_its_name_is_typed() {
+    let (mut app, root) = fixture_with(RemoteSettings {
+        protection: Some("confirm".to_string()),
+        ..RemoteSettings::default()
+    });
+    open_bucket(&mut app).await;
+
+    cursor_to(&mut app, "a.txt").await;
+    app.dispatch(Action::Move).await;
+    let picker = app.copy_picker.as_mut().expect("a move picks its destination first");
+    let docs = picker.rows.iter().position(|r| r.name == "docs").unwrap();
+    picker.state.select(Some(docs));
+    app.dispatch(Action::Popup(PopupAction::CopyPicker(CopyPickerAction::Enter))).await;
+    app.dispatch(Action::Popup(PopupAction::CopyPicker(CopyPickerAction::Confirm))).await;
+    assert_eq!(app.mode(), Mode::ConfirmProtected);
+
+    // Nothing runs until the remote's name matches
+    type_into(&mut app, PopupAction::ConfirmProtected, PromptAction::Input, "loc").await;
+    app.dispatch(Action::Popup(PopupAction::ConfirmProtected(PromptAction::Submit))).await;
+    assert_eq!(app.mode(), Mode::ConfirmProtected);
+    assert!(root.path().join("bkt/a.txt").exists());
+
+    type_into(&mut app, PopupAction::ConfirmProtected, PromptAction::Input, "al").await;
+    app.dispatch(Action::Popup(PopupAction::ConfirmProtected(PromptAction::Submit))).await;
+    settle(&mut app).await;
+    assert_eq!(app.mode(), Mode::Normal);
+    assert!(!root.path().join("bkt/a.txt").exists());
+    assert!(root.path().join("bkt/docs/a.txt").exists());
+}
+
+#[tokio::test]
+async fn resumes_unfinished_copies_and_downloads_in_turn() {
+    let (mut app, root) = fixture();
+    let dest = root.path().join("saved-b.txt");
+    app.unfinished = vec![
+        TransferSpec::Copy(CopySpec {
+            name: "a.txt".to_string(),
+            moving: false,
+            remote: "local".to_string(),
+            bucket: "bkt".to_string(),
+            key: "a.txt".to_string(),
+            is_dir: false,
+            size: 5,
+            dst_remote: "local".to_string(),
+            dst_bucket: "bkt".to_string(),
+            dst_key: "docs/a.txt".to_string(),
+            options: CopyOptions::default(),
+        }),
+        TransferSpec::Download(DownloadSpec {
+            name: "saved-b.txt".to_string(),
+            remote: "local".to_string(),
+            bucket: "bkt".to_string(),
+            key: "b.txt".to_string(),
+            is_dir: false,
+            dest: dest.clone(),
+            range: None,
+        }),
+    ];
+    app.resume_prompt = true;
+
+    app.dispatch(Action::Popup(PopupAction::Resume(ResumeAction::Resume))).await;
+    // The download waits for the copy; the event loop starts it once that is done
+    for _ in 0..2 {
+        settle(&mut app).await;
+        app.start_next_queued().await;
+    }
+    settle(&mut app).await;
+    assert!(root.path().join("bkt/docs/a.txt").exists());
+    assert_eq!(std::fs::read_to_string(&dest).unwrap(), "bravo");
+    assert!(app.unfinished.is_empty());
+}
+
+#[tokio::test]
+async fn a_panicking_job_is_reported_to_the_event_loop_once() {
+    let (mut app, _root) = fixture();
+    app.tasks.spawn(TaskKind::Plugin, "boom", async { panic!("boom") });
+    settle(&mut app).await;
+    assert_eq!(app.tasks.take_panicked().as_deref(), Some("boom"));
+    assert_eq!(app.tasks.take_panicked(), None);
+}
+
+#[tokio::test]
+async fn jump_list_reopens_a_visited_prefix() {
+    let (mut app, _root) = fixture();
+    open_bucket(&mut app).await;
+    cursor_to(&mut app, "photos").await;
+    app.dispatch(Action::Select).await;
+    app.dispatch(Action::GoBucketRoot).await;
+    assert_eq!(prefix(&app), "");
+
+    app.dispatch(Action::Jumps).await;
+    assert_eq!(app.mode(), Mode::Jumps);
+    type_into(&mut app, PopupAction::Jumps, PickerAction::Input, "pht").await;
+    assert_eq!(app.jump_items().len(), 1);
+    app.dispatch(Action::Popup(PopupAction::Jumps(PickerAction::Submit))).await;
+    assert_eq!(app.mode(), Mode::Normal);
+    assert_eq!(prefix(&app), "photos/");
+}
+
+#[tokio::test]
+async fn bucket_prompt_opens_the_typed_bucket() {
+    let (mut app, _root) = fixture();
+    app.dispatch(Action::Select).await;
+    app.dispatch(Action::BucketInput).await;
+    assert_eq!(app.mode(), Mode::BucketInput);
+    type_into(&mut app, PopupAction::BucketInput, PromptAction::Input, "bkx").await;
+    app.dispatch(Action::Popup(PopupAction::BucketInput(PromptAction::Backspace))).await;
+    type_into(&mut app, PopupAction::BucketInput, PromptAction::Input, "t").await;
+    app.dispatch(Action::Popup(PopupAction::BucketInput(PromptAction::Submit))).await;
+    assert_eq!(app.mode(), Mode::Normal);
+    assert!(matches!(&app.location, Location::ObjectList { bucket, .. } if bucket == "bkt"));
+}
+
+#[tokio::test]
+async fn sequences_wait_for_their_second_key() {
+    let (mut app, _root) = fixture();
+    open_bucket(&mut app).await;
+    app.dispatch(Action::JumpBottom).await;
+
+    app.dispatch(Action::Pending(Some('g'))).await;
+    assert_eq!(app.mode(), Mode::Sequence('g'));
+    // A key that finishes no sequence just drops it
+    app.dispatch(Action::Pending(None)).await;
+    assert_eq!(app.mode(), Mode::Normal);
+
+    app.dispatch(Action::Pending(Some('g'))).await;
+    app.dispatch(Action::JumpTop).await;
+    assert_eq!(app.mode(), Mode::Normal);
+    assert_eq!(selected_name(&app), Some("docs"));
+}
+
+#[tokio::test]
+async fn help_covers_the_mode_it_was_opened_from() {
+    let (mut app, _root) = fixture();
+    open_bucket(&mut app).await;
+    app.dispatch(Action::Search).await;
+    assert!(app.mode().takes_text());
+
+    app.dispatch(Action::Help).await;
+    assert_eq!(app.mode(), Mode::Help);
+    assert_eq!(app.help.as_ref().unwrap().mode, HelpMode::Search);
+    app.dispatch(Action::Popup(PopupAction::Help(HelpAction::CycleMode(true)))).await;
+    assert_eq!(app.help.as_ref().unwrap().mode, HelpMode::Context);
+
+    // Closing hands the keys back to the search underneath
+    app.dispatch(Action::Popup(PopupAction::Help(HelpAction::Close))).await;
+    assert_eq!(app.mode(), Mode::Search);
+}
+
+fn has_files(dir: &Path) -> bool {
+    std::fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_some())
+}
@@ -0,0 +1,74 @@
+use std::collections::VecDeque;
+
+/// Seconds of throughput kept for the progress sparkline.
+pub const WINDOW_SECS: usize = 60;
+
+/// Seconds the current speed is measured over: long enough to smooth out
+/// bursty progress messages, short enough to show a stall within moments.
+const SPEED_WINDOW_SECS: f64 = 4.0;
+
+/// Bytes a transfer moved in each second, for the last `WINDOW_SECS` of it, and
+/// its speed over the last few seconds.
+#[derive(Default)]
+pub struct Throughput {
+    seconds: VecDeque<u64>,
+    /// `(elapsed, total bytes)` samples covering the speed window, plus the one
+    /// just before it as the starting point.
+    recent: VecDeque<(f64, u64)>,
+    /// Second of the transfer the back of `seconds` is filling.
+    current: u64,
+    last_bytes: u64,
+}
+
+impl Throughput {
+    /// Account for the transfer having moved `bytes` in total after `elapsed_secs`.
+    /// Called every tick, so seconds without progress show up as zeros.
+    pub fn record(&mut self, elapsed_secs: f64, bytes: u64) {
+        let second = elapsed_secs as u64;
+        if self.seconds.is_empty() {
+            self.seconds.push_back(0);
+            self.current = second;
+        }
+        while self.current < second {
+            self.seconds.push_back(0);
+            self.current += 1;
+        }
+        // The window holds finished seconds plus the one being filled
+        while self.seconds.len() > WINDOW_SECS + 1 {
+            self.seconds.pop_front();
+        }
+        if let Some(back) = self.seconds.back_mut() {
+            *back += bytes.saturating_sub(self.last_bytes);
+        }
+        self.last_bytes = self.last_bytes.max(bytes);
+
+        self.recent.push_back((elapsed_secs, self.last_bytes));
+        while self.recent.get(1).is_some_and(|&(t, _)| t <= elapsed_secs - SPEED_WINDOW_SECS) {
+            self.recent.pop_front();
+        }
+    }
+
+    /// Bytes per second over the last `SPEED_WINDOW_SECS`; `None` until there is
+    /// half a second of samples to measure over.
+    pub fn speed(&self) -> Option<f64> {
+        let (&(t0, b0), &(t1, b1)) = (self.recent.front()?, self.recent.back()?);
+        (t1 - t0 >= 0.5).then(|| (b1 - b0) as f64 / (t1 - t0))
+    }
+
+    /// Bytes per finished second, oldest first; the second still filling is left
+    /// out so the graph doesn't always end in a dip.
+    pub fn samples(&self) -> impl Iterator<Item = u64> + '_ {
+        self.seconds.iter().take(self.seconds.len().saturating_sub(1)).copied()
+    }
+}
+
+/// The last `width` samples as a row of block characters scaled to their peak.
+pub fn sparkline(samples: impl Iterator<Item = u64>, width: usize) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let samples: Vec<u64> = samples.collect();
+    let shown = &samples[samples.len().saturating_sub(width)..];
+    let peak = shown.iter().copied().max().unwrap_or(0).max(1);
+    let mut line: String = " ".repeat(width - shown.len());
+    line.extend(shown.iter().map(|&s| BARS[((s * 7).div_ceil(peak)) as usize]));
+    line
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+use crate::object_store::ObjectEntry;
+
+use super::{parent_prefix, App, Entry, Location, Pane};
+
+/// Tree rendering of the listing (`Shift+T`): directories expand in place, their
+/// children listed the first time they're opened and kept in `entries` under them.
+#[derive(Default)]
+pub struct TreeView {
+    /// Listings of the expanded prefixes, arranged, kept while the tree stays at this
+    /// prefix so collapsing and expanding again needs no request.
+    children: HashMap<String, Vec<ObjectEntry>>,
+    /// Prefixes shown expanded; a subset of `children`.
+    expanded: Vec<String>,
+}
+
+impl TreeView {
+    pub fn is_expanded(&self, key: &str) -> bool {
+        self.expanded.iter().any(|k| k == key)
+    }
+
+    /// Forget everything listed; the tree starts over collapsed.
+    pub(crate) fn reset(&mut self) {
+        self.children.clear();
+        self.expanded.clear();
+    }
+
+    /// Drop objects that were deleted from the cached listings.
+    pub(crate) fn forget(&mut self, gone: impl Fn(&str) -> bool) {
+        for children in self.children.values_mut() {
+            children.retain(|o| !gone(&o.key));
+        }
+        self.children.retain(|prefix, _| !gone(prefix));
+        self.expanded.retain(|prefix| !gone(prefix));
+    }
+}
+
+impl App {
+    /// Switch between the flat listing and the tree. Leaving the tree keeps the
+    /// cursor on the top-level entry the selection was under.
+    pub fn toggle_tree(&mut self) {
+        if self.search_active {
+            return;
+        }
+        if self.tree.take().is_none() {
+            self.tree = Some(TreeView::default());
+            self.notify("Tree view: l expands, h collapses");
+            return;
+        }
+        let Location::ObjectList { prefix, .. } = &self.location else {
+            return;
+        };
+        let selected = self.selected_key();
+        let top = |key: &str| parent_prefix(key) == *prefix;
+        let keep = selected.map(|key| {
+            let mut key = key;
+            while !top(&key) && key.len() > prefix.len() {
+                key = parent_prefix(&key);
+            }
+            key
+        });
+        self.entries.retain(|e| top(e.key()));
+        self.select_key(keep.as_deref());
+    }
+
+    /// How deep `key` sits below the current prefix, when the tree is shown (and
+    /// not covered by search results).
+    pub fn tree_depth(&self, key: &str) -> Option<usize> {
+        if self.tree.is_none() || self.search_active {
+            return None;
+        }
+        let Location::ObjectList { prefix, .. } = &self.location else {
+            return None;
+        };
+        let rest = key.strip_prefix(prefix.as_str()).unwrap_or(key);
+        Some(rest.trim_end_matches('/').matches('/').count())
+    }
+
+    /// `l` on a directory in the tree: expand it in place, listing it the first
+    /// time, or collapse it again. Returns false when the selection isn't a directory.
+    pub async fn tree_toggle(&mut self) -> bool {
+        let Some(tree) = self.tree.as_ref().filter(|_| self.pane == Pane::Browser && !self.search_active) else {
+            return false;
+        };
+        let Some(idx) = self.browser_state.selected() else {
+            return false;
+        };
+        let Some(Entry::Object(dir)) = self.entries.get(idx).filter(|e| matches!(e, Entry::Object(o) if o.is_dir))
+        else {
+            return false;
+        };
+        let key = dir.key.clone();
+        if tree.is_expanded(&key) {
+            self.tree_collapse(&key);
+            return true;
+        }
+
+        if !tree.children.contains_key(&key) {
+            let Location::ObjectList { remote, bucket, .. } = &self.location else {
+                return true;
+            };
+            let Some(client) = self.clients.get(remote).cloned() else {
+                return true;
+            };
+            match client.list_objects(bucket, &key).await {
+                Ok(objects) => {
+                    let objects = self.arrange_listing(objects);
+                    if let Some(tree) = &mut self.tree {
+                        tree.children.insert(key.clone(), objects);
+                    }
+                }
+                Err(e) => {
+                    self.notify_error(format!("Failed to list objects: {}", e));
+                    return true;
+                }
+            }
+        }
+        let Some(tree) = &mut self.tree else {
+            return true;
+        };
+        tree.expanded.push(key.clone());
+        let mut rows = Vec::new();
+        push_children(tree, &key, &mut rows);
+        self.entries.splice(idx + 1..idx + 1, rows);
+        true
+    }
+
+    /// `h` in the tree: collapse the selected directory, or the one the selection
+    /// is in. Returns false at the top level, where `h` goes up a prefix as usual.
+    pub fn tree_back(&mut self) -> bool {
+        let Some(tree) = self.tree.as_ref().filter(|_| self.pane == Pane::Browser && !self.search_active) else {
+            return false;
+        };
+        let Some(key) = self.selected_key() else {
+            return false;
+        };
+        if tree.is_expanded(&key) {
+            self.tree_collapse(&key);
+            return true;
+        }
+        if self.tree_depth(&key).unwrap_or(0) == 0 {
+            return false;
+        }
+        let parent = parent_prefix(&key);
+        self.tree_collapse(&parent);
+        self.select_key(Some(&parent));
+        true
+    }
+
+    /// Hide everything under `prefix`, and close the directories inside it too.
+    fn tree_collapse(&mut self, prefix: &str) {
+        let selected = self.selected_key();
+        if let Some(tree) = &mut self.tree {
+            tree.expanded.retain(|k| !k.starts_with(prefix));
+        }
+        self.entries.retain(|e| e.key() == prefix || !e.key().starts_with(prefix));
+        match selected {
+            Some(key) if key.starts_with(prefix) => self.select_key(Some(prefix)),
+            other => self.select_key(other.as_deref()),
+        }
+    }
+
+    /// Put the expanded directories' children back under them after the top level
+    /// was listed or sorted again.
+    pub(crate) fn tree_rows(&self, top: Vec<ObjectEntry>) -> Vec<Entry> {
+        let mut rows = Vec::new();
+        for obj in top {
+            let key = obj.key.clone();
+            rows.push(Entry::Object(obj));
+            if let Some(tree) = &self.tree
+                && tree.is_expanded(&key)
+            {
+                push_children(tree, &key, &mut rows);
+            }
+        }
+        rows
+    }
+
+    /// Sort the cached listings with the current preferences, as the top level is.
+    pub(crate) fn resort_tree(&mut self) {
+        let Some(mut tree) = self.tree.take() else {
+            return;
+        };
+        for children in tree.children.values_mut() {
+            *children = self.arrange_listing(std::mem::take(children));
+        }
+        self.tree = Some(tree);
+    }
+
+    /// List the expanded directories again, after a change may have added to them.
+    pub(crate) async fn relist_tree(&mut self) {
+        let Some(mut tree) = self.tree.take() else {
+            return;
+        };
+        let Location::ObjectList { remote, bucket, .. } = &self.location else {
+            self.tree = Some(tree);
+            return;
+        };
+        tree.children.retain(|k, _| tree.expanded.contains(k));
+        if let Some(client) = self.clients.get(remote).cloned() {
+            for prefix in tree.expanded.clone() {
+                match client.list_objects(bucket, &prefix).await {
+                    Ok(objects) => {
+                        tree.children.insert(prefix, self.arrange_listing(objects));
+                    }
+                    Err(_) => {
+                        tree.children.remove(&prefix);
+                        tree.expanded.retain(|k| !k.starts_with(&prefix));
+                    }
+                }
+            }
+        }
+        self.tree = Some(tree);
+    }
+
+    pub(crate) fn selected_key(&self) -> Option<String> {
+        self.browser_state
+            .selected()
+            .and_then(|i| self.entries.get(i))
+            .map(|e| e.key().to_string())
+    }
+
+    fn select_key(&mut self, key: Option<&str>) {
+        if let Some(pos) = key.and_then(|key| self.entries.iter().position(|e| e.key() == key)) {
+            self.browser_state.select(Some(pos));
+        }
+        self.fix_selection();
+    }
+}
+
+/// `prefix`'s children in order, each followed by its own when expanded.
+fn push_children(tree: &TreeView, prefix: &str, rows: &mut Vec<Entry>) {
+    let Some(children) = tree.children.get(prefix) else {
+        return;
+    };
+    for child in children {
+        rows.push(Entry::Object(child.clone()));
+        if child.is_dir && tree.is_expanded(&child.key) {
+            push_children(tree, &child.key, rows);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use anyhow::Result;
+use aws_sdk_s3::types::ChecksumAlgorithm;
+use base64::Engine;
+use sha2::Digest;
+use tokio::io::AsyncReadExt;
+
+/// Checksum sent with uploads (`x-amz-checksum-*`) for the endpoint to verify,
+/// chosen per remote with `checksum` in the mc config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checksum {
+    Sha256,
+    Crc32,
+}
+
+impl Checksum {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "sha256" | "sha-256" => Ok(Checksum::Sha256),
+            "crc32" => Ok(Checksum::Crc32),
+            other => anyhow::bail!("Unknown checksum '{}' (expected \"sha256\" or \"crc32\")", other),
+        }
+    }
+
+    pub fn algorithm(self) -> ChecksumAlgorithm {
+        match self {
+            Checksum::Sha256 => ChecksumAlgorithm::Sha256,
+            Checksum::Crc32 => ChecksumAlgorithm::Crc32,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Checksum::Sha256 => "SHA-256",
+            Checksum::Crc32 => "CRC32",
+        }
+    }
+
+    /// Base64 checksum of `data`, as the header carries it.
+    pub fn of(self, data: &[u8]) -> String {
+        let mut hasher = Hasher::new(self);
+        hasher.update(data);
+        hasher.finish()
+    }
+
+    /// Base64 checksum of a local file, read in chunks.
+    pub async fn of_file(self, path: &Path) -> Result<String> {
+        let mut file = tokio::fs::File::open(path).await?;
+        let mut hasher = Hasher::new(self);
+        let mut buf = vec![0u8; 256 * 1024];
+        loop {
+            let n = file.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        Ok(hasher.finish())
+    }
+}
+
+enum Hasher {
+    Sha256(sha2::Sha256),
+    Crc32(crc32fast::Hasher),
+}
+
+impl Hasher {
+    fn new(checksum: Checksum) -> Self {
+        match checksum {
+            Checksum::Sha256 => Hasher::Sha256(sha2::Sha256::new()),
+            Checksum::Crc32 => Hasher::Crc32(crc32fast::Hasher::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(h) => h.update(data),
+            Hasher::Crc32(h) => h.update(data),
+        }
+    }
+
+    fn finish(self) -> String {
+        let engine = base64::engine::general_purpose::STANDARD;
+        match self {
+            Hasher::Sha256(h) => engine.encode(h.finalize()),
+            Hasher::Crc32(h) => engine.encode(h.finalize().to_be_bytes()),
+        }
+    }
+}
+
+/// Check the checksum the endpoint stored against the one sent.
+pub fn verify(checksum: Checksum, key: &str, sent: &str, stored: Option<&str>) -> Result<()> {
+    match stored {
+        Some(stored) if stored == sent => Ok(()),
+        Some(stored) => anyhow::bail!(
+            "{} mismatch for {}: sent {}, endpoint stored {}",
+            checksum.label(),
+            key,
+            sent,
+            stored
+        ),
+        None => anyhow::bail!(
+            "Endpoint returned no {} for {}; it may not support upload checksums",
+            checksum.label(),
+            key
+        ),
+    }
+}
@@ -1,28 +1,236 @@
-use serde::Deserialize;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct McConfig {
     #[allow(dead_code)]
     pub version: String,
     pub aliases: HashMap<String, AliasConfig>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct AliasConfig {
     pub url: String,
-    #[serde(rename = "accessKey")]
+    #[serde(rename = "accessKey", default)]
     pub access_key: String,
-    #[serde(rename = "secretKey")]
+    #[serde(rename = "secretKey", default)]
     pub secret_key: String,
+    /// STS session token of temporary credentials, as `mc alias set` stores it.
+    #[serde(rename = "sessionToken", default, skip_serializing_if = "Option::is_none")]
+    pub session_token: Option<String>,
+    /// When the session token expires, RFC 3339 (s3-like-yazi extension).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<String>,
     #[allow(dead_code)]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub api: Option<String>,
-    #[allow(dead_code)]
+    /// Addressing style: "on" (path), "off" (virtual-host) or "auto".
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub path: Option<String>,
+
+    // The rest are s3-like-yazi's own, set per alias in settings.json (see
+    // `RemoteSettings`). Configs written before that may still carry them, so
+    // they are read here but never written back.
+    /// Command printing temporary credentials as JSON, like the AWS CLI's
+    /// `credential_process`; run again shortly before they expire. Takes the
+    /// place of the keys above.
+    #[serde(default, skip_serializing)]
+    pub credential_process: Option<String>,
+    /// Environment tag: "prod", "staging" or "dev".
+    #[serde(default, skip_serializing)]
+    pub tag: Option<String>,
+    /// Guard for destructive ops: "none", "confirm" or "readonly".
+    #[serde(default, skip_serializing)]
+    pub protection: Option<String>,
+    /// Known bucket names, shown when the key may not call ListBuckets.
+    #[serde(default, skip_serializing)]
+    pub buckets: Vec<String>,
+    /// Provider name ("aws", "minio", "b2", "r2", "wasabi", "ceph", "gcs") selecting
+    /// compatibility quirks; guessed from the URL when absent.
+    #[serde(default, skip_serializing)]
+    pub provider: Option<String>,
+    /// Send unsigned requests, for public buckets. Also implied when both keys are empty.
+    #[serde(default, skip_serializing)]
+    pub anonymous: bool,
+    /// Signing region override (e.g. "us-gov-west-1"); disables per-bucket region lookup.
+    #[serde(default, skip_serializing)]
+    pub region: Option<String>,
+    /// Storage backend: "s3" (default) or "local", which serves the directory in
+    /// `url` with each sub-directory as a bucket.
+    #[serde(default, skip_serializing)]
+    pub backend: Option<String>,
+    /// Checksum sent with uploads for the endpoint to verify: "sha256" or "crc32".
+    #[serde(default, skip_serializing)]
+    pub checksum: Option<String>,
+    /// Further named key pairs for the same endpoint, e.g. a read-only and an
+    /// admin key, switched between at runtime.
+    #[serde(default, skip_serializing)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// s3-like-yazi's options for one alias, from the `remotes` block of settings.json
+/// keyed by alias name. They are kept out of the mc config because `mc alias set`
+/// rewrites it and drops keys it doesn't know. Each one set here replaces the
+/// alias's own.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RemoteSettings {
+    pub tag: Option<String>,
+    pub protection: Option<String>,
+    pub buckets: Option<Vec<String>>,
+    pub provider: Option<String>,
+    pub anonymous: Option<bool>,
+    pub region: Option<String>,
+    pub backend: Option<String>,
+    pub checksum: Option<String>,
+    pub profiles: Option<BTreeMap<String, Profile>>,
+    pub credential_process: Option<String>,
+}
+
+/// A key pair an alias can use instead of its own `accessKey` / `secretKey`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    #[serde(rename = "accessKey", default)]
+    pub access_key: String,
+    #[serde(rename = "secretKey", default)]
+    pub secret_key: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RemoteTag {
+    Prod,
+    Staging,
+    Dev,
+}
+
+impl RemoteTag {
+    pub fn label(self) -> &'static str {
+        match self {
+            RemoteTag::Prod => "prod",
+            RemoteTag::Staging => "staging",
+            RemoteTag::Dev => "dev",
+        }
+    }
+}
+
+/// How destructive operations are guarded on a remote.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Protection {
+    None,
+    /// Require typing the alias name before a destructive op runs.
+    Confirm,
+    /// Refuse destructive ops entirely.
+    ReadOnly,
+}
+
+impl AliasConfig {
+    /// Take every option `remote` sets.
+    fn apply(&mut self, remote: &RemoteSettings) {
+        let RemoteSettings {
+            tag,
+            protection,
+            buckets,
+            provider,
+            anonymous,
+            region,
+            backend,
+            checksum,
+            profiles,
+            credential_process,
+        } = remote.clone();
+        let set = |field: &mut Option<String>, value: Option<String>| {
+            if value.is_some() {
+                *field = value;
+            }
+        };
+        set(&mut self.tag, tag);
+        set(&mut self.protection, protection);
+        set(&mut self.provider, provider);
+        set(&mut self.region, region);
+        set(&mut self.backend, backend);
+        set(&mut self.checksum, checksum);
+        set(&mut self.credential_process, credential_process);
+        if let Some(buckets) = buckets {
+            self.buckets = buckets;
+        }
+        if let Some(anonymous) = anonymous {
+            self.anonymous = anonymous;
+        }
+        if let Some(profiles) = profiles {
+            self.profiles = profiles;
+        }
+    }
+
+    pub fn is_anonymous(&self) -> bool {
+        self.anonymous
+            || (self.access_key.is_empty() && self.secret_key.is_empty() && self.credential_process.is_none())
+    }
+
+    pub fn tag(&self) -> Option<RemoteTag> {
+        match self.tag.as_deref()?.to_lowercase().as_str() {
+            "prod" | "production" => Some(RemoteTag::Prod),
+            "staging" | "stage" => Some(RemoteTag::Staging),
+            "dev" | "development" => Some(RemoteTag::Dev),
+            _ => None,
+        }
+    }
+
+    /// This alias signing with the keys of profile `name`, if it has one by that name.
+    pub fn with_profile(&self, name: &str) -> Option<AliasConfig> {
+        let profile = self.profiles.get(name)?;
+        Some(AliasConfig {
+            access_key: profile.access_key.clone(),
+            secret_key: profile.secret_key.clone(),
+            session_token: None,
+            expiration: None,
+            credential_process: None,
+            ..self.clone()
+        })
+    }
+
+    /// Explicit `protection` wins; otherwise prod-tagged remotes require confirmation.
+    pub fn protection(&self) -> Protection {
+        match self.protection.as_deref().map(|p| p.to_lowercase()).as_deref() {
+            Some("readonly") | Some("read-only") => Protection::ReadOnly,
+            Some("confirm") => Protection::Confirm,
+            Some("none") => Protection::None,
+            _ if self.tag() == Some(RemoteTag::Prod) => Protection::Confirm,
+            _ => Protection::None,
+        }
+    }
 }
 
 impl McConfig {
+    /// A config with no aliases, for running on command-line remotes alone.
+    pub fn empty() -> Self {
+        Self {
+            version: "10".to_string(),
+            aliases: HashMap::new(),
+        }
+    }
+
+    /// Alias for a local directory served as a remote (`--local DIR`).
+    pub fn local_alias(dir: &str) -> AliasConfig {
+        AliasConfig {
+            url: dir.to_string(),
+            backend: Some("local".to_string()),
+            ..AliasConfig::default()
+        }
+    }
+
+    /// Apply the settings' per-alias options to the aliases they name.
+    pub fn apply_remote_settings(&mut self, remotes: &HashMap<String, RemoteSettings>) {
+        for (alias, config) in &mut self.aliases {
+            if let Some(remote) = remotes.get(alias) {
+                config.apply(remote);
+            }
+        }
+    }
+
     pub fn load() -> anyhow::Result<Self> {
         let path = Self::config_path()?;
         let content = std::fs::read_to_string(&path)
@@ -32,22 +240,81 @@ impl McConfig {
         Ok(config)
     }
 
-    fn config_path() -> anyhow::Result<PathBuf> {
+    /// Load the config, or `None` when no config file exists yet (first run).
+    pub fn load_if_exists() -> anyhow::Result<Option<Self>> {
+        if Self::find_config_path()?.is_none() {
+            return Ok(None);
+        }
+        Self::load().map(Some)
+    }
+
+    /// Write the config in mc's format, creating the parent directory if needed.
+    /// It holds secret keys, so both are private to the user, and it is written to
+    /// a temp file and renamed into place so a failed write never truncates it.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            let mut dir = std::fs::DirBuilder::new();
+            dir.recursive(true);
+            #[cfg(unix)]
+            dir.mode(0o700);
+            dir.create(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        let tmp = path.with_extension("json.tmp");
+        let write = || -> std::io::Result<()> {
+            // A leftover temp file would keep its old mode
+            let _ = std::fs::remove_file(&tmp);
+            let mut options = std::fs::OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            options.mode(0o600);
+            let mut file = options.open(&tmp)?;
+            file.write_all(content.as_bytes())?;
+            file.sync_all()?;
+            std::fs::rename(&tmp, path)
+        };
+        write().map_err(|e| {
+            let _ = std::fs::remove_file(&tmp);
+            anyhow::anyhow!("Failed to write {}: {}", path.display(), e)
+        })
+    }
+
+    /// Standard mc config location (`~/.mc/config.json`), used when creating a new config.
+    pub fn default_config_path() -> anyhow::Result<PathBuf> {
+        let home =
+            dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot determine home directory"))?;
+        Ok(home.join(".mc").join("config.json"))
+    }
+
+    fn find_config_path() -> anyhow::Result<Option<PathBuf>> {
         let home =
             dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot determine home directory"))?;
 
         // Try ~/.mc/config.json first (standard mc location)
         let mc_path = home.join(".mc").join("config.json");
         if mc_path.exists() {
-            return Ok(mc_path);
+            return Ok(Some(mc_path));
         }
 
         // Try ~/.mcli/config.json (alternative location)
         let mcli_path = home.join(".mcli").join("config.json");
         if mcli_path.exists() {
-            return Ok(mcli_path);
+            return Ok(Some(mcli_path));
         }
 
+        Ok(None)
+    }
+
+    fn config_path() -> anyhow::Result<PathBuf> {
+        if let Some(path) = Self::find_config_path()? {
+            return Ok(path);
+        }
+
+        let home =
+            dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot determine home directory"))?;
+        let mc_path = home.join(".mc").join("config.json");
+        let mcli_path = home.join(".mcli").join("config.json");
+
         anyhow::bail!(
             "MinIO client config not found.\n\
              Searched:\n  {}\n  {}\n\
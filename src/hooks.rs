@@ -0,0 +1,103 @@
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
+
+/// How long a hook may run before it is killed.
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Shell commands run around operations, from the `hooks` block of settings.json.
+/// A failing `pre_*` hook cancels the operation; a failing `post_*` hook is only reported.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HooksConfig {
+    pub pre_delete: Option<String>,
+    pub post_delete: Option<String>,
+    pub pre_upload: Option<String>,
+    pub post_upload: Option<String>,
+    pub pre_download: Option<String>,
+    pub post_download: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Delete,
+    Upload,
+    Download,
+}
+
+impl Op {
+    fn name(self) -> &'static str {
+        match self {
+            Op::Delete => "delete",
+            Op::Upload => "upload",
+            Op::Download => "download",
+        }
+    }
+}
+
+impl HooksConfig {
+    fn command(&self, pre: bool, op: Op) -> Option<&str> {
+        match (pre, op) {
+            (true, Op::Delete) => self.pre_delete.as_deref(),
+            (false, Op::Delete) => self.post_delete.as_deref(),
+            (true, Op::Upload) => self.pre_upload.as_deref(),
+            (false, Op::Upload) => self.post_upload.as_deref(),
+            (true, Op::Download) => self.pre_download.as_deref(),
+            (false, Op::Download) => self.post_download.as_deref(),
+        }
+    }
+
+    pub fn has(&self, pre: bool, op: Op) -> bool {
+        self.command(pre, op).is_some()
+    }
+
+    /// Run the `pre_*` or `post_*` hook for `op`, if configured, with the affected
+    /// keys on stdin (one per line) and the context in `S3Y_*` variables.
+    pub async fn run(
+        &self,
+        pre: bool,
+        op: Op,
+        remote: &str,
+        bucket: &str,
+        keys: &[String],
+    ) -> anyhow::Result<()> {
+        let Some(cmd) = self.command(pre, op) else {
+            return Ok(());
+        };
+        let stage = if pre { "pre" } else { "post" };
+        let name = format!("{}_{}", stage, op.name());
+        let mut child = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(cmd)
+            .env("S3Y_HOOK", &name)
+            .env("S3Y_REMOTE", remote)
+            .env("S3Y_BUCKET", bucket)
+            .env("S3Y_COUNT", keys.len().to_string())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("{} hook: {}", name, e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            let mut input = keys.join("\n");
+            input.push('\n');
+            // Written on the side so a hook that never reads its stdin runs into
+            // the timeout instead of blocking here; stdin closes when the task ends
+            tokio::spawn(async move {
+                // A hook that ignores stdin may exit before reading it
+                let _ = stdin.write_all(input.as_bytes()).await;
+            });
+        }
+        let output = tokio::time::timeout(TIMEOUT, child.wait_with_output())
+            .await
+            .map_err(|_| anyhow::anyhow!("{} hook timed out after {}s", name, TIMEOUT.as_secs()))??;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let reason = stderr.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("");
+            anyhow::bail!("{} hook exited with {} {}", name, output.status, reason.trim());
+        }
+        Ok(())
+    }
+}
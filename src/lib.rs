@@ -0,0 +1,20 @@
+//! s3-like-yazi as a library: the app, its storage backends and the UI, used by
+//! the binary and by the integration tests under `tests/`.
+
+pub mod app;
+mod checksum;
+pub mod clipboard;
+pub mod credentials;
+mod hooks;
+mod local_store;
+mod plugins;
+mod provider;
+pub mod object_store;
+mod s3_client;
+mod s3_error;
+pub mod settings;
+mod shortener;
+mod storage;
+mod temp_credentials;
+pub mod ui;
+mod upload_manifest;
@@ -0,0 +1,245 @@
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::object_store::{
+    Body, BucketInfo, ObjectEntry, ObjectMetadata, ObjectStore, PartFiles, Timestamp,
+};
+
+/// A local directory served as a remote: each sub-directory is a bucket and
+/// paths below it are keys. Handy for trying things out without an endpoint.
+pub struct LocalStore {
+    root: PathBuf,
+    partials: PartFiles,
+}
+
+impl LocalStore {
+    /// `url` is the directory, optionally written as `file:///path`.
+    pub fn new(url: &str) -> Result<Self> {
+        let root = PathBuf::from(url.strip_prefix("file://").unwrap_or(url));
+        if !root.is_dir() {
+            anyhow::bail!("{} is not a directory", root.display());
+        }
+        Ok(Self {
+            root,
+            partials: PartFiles::default(),
+        })
+    }
+
+    /// Filesystem path of `key` in `bucket`, refusing anything that would escape the root.
+    fn path(&self, bucket: &str, key: &str) -> Result<PathBuf> {
+        if bucket.is_empty() || bucket.contains('/') || bucket == "." || bucket == ".." {
+            anyhow::bail!("Invalid bucket name '{}'", bucket);
+        }
+        let mut path = self.root.join(bucket);
+        for part in key.split('/').filter(|p| !p.is_empty()) {
+            if part == "." || part == ".." {
+                anyhow::bail!("Invalid key '{}'", key);
+            }
+            path.push(part);
+        }
+        Ok(path)
+    }
+}
+
+/// Unix seconds and nanos of a file's modification time.
+fn modified(meta: &Metadata) -> Option<(i64, u32)> {
+    let since = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((since.as_secs() as i64, since.subsec_nanos()))
+}
+
+fn timestamp((secs, nanos): (i64, u32)) -> Option<Timestamp> {
+    Timestamp::from_timestamp(secs, nanos)
+}
+
+fn file_entry(key: String, meta: &Metadata) -> ObjectEntry {
+    let mtime = modified(meta);
+    ObjectEntry {
+        display_name: key.clone(),
+        key,
+        size: meta.len() as i64,
+        last_modified: mtime.and_then(timestamp),
+        // Stands in for an ETag: changes whenever the file is rewritten
+        etag: mtime.map(|(s, n)| format!("\"{:x}.{:x}-{:x}\"", s, n, meta.len())),
+        is_dir: false,
+        storage_class: None,
+    }
+}
+
+/// Split a listing prefix into the directory it names and the start of the entry names.
+fn split_prefix(prefix: &str) -> (&str, &str) {
+    match prefix.rfind('/') {
+        Some(i) => prefix.split_at(i + 1),
+        None => ("", prefix),
+    }
+}
+
+/// Files below `dir` (whose keys start with `dir_key`) at any depth.
+fn walk(dir: &Path, dir_key: &str, out: &mut Vec<ObjectEntry>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let meta = entry.metadata()?;
+        if meta.is_dir() {
+            walk(&entry.path(), &format!("{}{}/", dir_key, name), out)?;
+        } else {
+            out.push(file_entry(format!("{}{}", dir_key, name), &meta));
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl ObjectStore for LocalStore {
+    async fn list_buckets(&self) -> Result<Vec<BucketInfo>> {
+        let mut buckets = Vec::new();
+        let mut dir = tokio::fs::read_dir(&self.root).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            let meta = entry.metadata().await?;
+            if meta.is_dir() {
+                buckets.push(BucketInfo {
+                    name: entry.file_name().to_string_lossy().into_owned(),
+                    creation_date: modified(&meta).and_then(timestamp),
+                });
+            }
+        }
+        buckets.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(buckets)
+    }
+
+    async fn list_objects(&self, bucket: &str, prefix: &str) -> Result<Vec<ObjectEntry>> {
+        let (dir_key, start) = split_prefix(prefix);
+        let dir = self.path(bucket, dir_key)?;
+        let mut dirs = Vec::new();
+        let mut files = Vec::new();
+        let mut read = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = read.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !name.starts_with(start) {
+                continue;
+            }
+            let meta = entry.metadata().await?;
+            let key = format!("{}{}", dir_key, name);
+            let display_name = key.strip_prefix(prefix).unwrap_or(&key).to_string();
+            if meta.is_dir() {
+                dirs.push(ObjectEntry {
+                    key: format!("{}/", key),
+                    display_name,
+                    size: 0,
+                    last_modified: None,
+                    etag: None,
+                    is_dir: true,
+                    storage_class: None,
+                });
+            } else {
+                let mut obj = file_entry(key, &meta);
+                obj.display_name = display_name;
+                files.push(obj);
+            }
+        }
+        dirs.sort_by(|a, b| a.key.cmp(&b.key));
+        files.sort_by(|a, b| a.key.cmp(&b.key));
+        dirs.extend(files);
+        Ok(dirs)
+    }
+
+    async fn list_all_keys(&self, bucket: &str, prefix: &str) -> Result<Vec<ObjectEntry>> {
+        let (dir_key, _) = split_prefix(prefix);
+        let dir = self.path(bucket, dir_key)?;
+        let dir_key = dir_key.to_string();
+        let prefix = prefix.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut all = Vec::new();
+            if dir.is_dir() {
+                walk(&dir, &dir_key, &mut all)?;
+            }
+            all.retain(|o| o.key.starts_with(&prefix));
+            all.sort_by(|a, b| a.key.cmp(&b.key));
+            Ok(all)
+        })
+        .await?
+    }
+
+    async fn head_object(&self, bucket: &str, key: &str) -> Result<ObjectMetadata> {
+        let meta = tokio::fs::metadata(self.path(bucket, key)?).await?;
+        let entry = file_entry(key.to_string(), &meta);
+        Ok(ObjectMetadata {
+            key: entry.key,
+            size: entry.size,
+            content_type: None,
+            last_modified: entry.last_modified,
+            etag: entry.etag,
+            version_id: None,
+            storage_class: None,
+            user_metadata: Default::default(),
+            content_encoding: None,
+            cache_control: None,
+            image_info: Vec::new(),
+        })
+    }
+
+    async fn open_object(&self, bucket: &str, key: &str, range: Option<(u64, u64)>) -> Result<Body> {
+        let mut file = tokio::fs::File::open(self.path(bucket, key)?).await?;
+        match range {
+            Some((start, end)) => {
+                file.seek(std::io::SeekFrom::Start(start)).await?;
+                Ok(Box::new(file.take(end.saturating_sub(start))))
+            }
+            None => Ok(Box::new(file)),
+        }
+    }
+
+    /// Files have no ACLs; `acl` is ignored.
+    async fn upload_object(&self, bucket: &str, key: &str, src: &Path, _acl: Option<&str>) -> Result<()> {
+        let dest = self.path(bucket, key)?;
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::copy(src, dest).await?;
+        Ok(())
+    }
+
+    /// Remove the file, then any directories it leaves empty, the way a prefix
+    /// disappears once its last object is gone.
+    async fn delete_object(&self, bucket: &str, key: &str) -> Result<()> {
+        let path = self.path(bucket, key)?;
+        // Like S3, deleting a key that's already gone succeeds
+        match tokio::fs::remove_file(&path).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            result => result?,
+        }
+        let bucket_dir = self.path(bucket, "")?;
+        let mut dir = path.parent();
+        while let Some(d) = dir
+            && d != bucket_dir
+            && tokio::fs::remove_dir(d).await.is_ok()
+        {
+            dir = d.parent();
+        }
+        Ok(())
+    }
+
+    async fn copy_object(&self, src_bucket: &str, src_key: &str, dst_bucket: &str, dst_key: &str)
+        -> Result<()> {
+        let dest = self.path(dst_bucket, dst_key)?;
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::copy(self.path(src_bucket, src_key)?, dest).await?;
+        Ok(())
+    }
+
+    /// A `file://` URL; fine for ffplay and anything else on this machine.
+    async fn presign_get_object(&self, bucket: &str, key: &str) -> Result<String> {
+        let path = std::path::absolute(self.path(bucket, key)?)?;
+        Ok(format!("file://{}", path.display()))
+    }
+
+    fn partials(&self) -> &PartFiles {
+        &self.partials
+    }
+}
@@ -1,11 +1,90 @@
-mod app;
-mod credentials;
-mod s3_client;
-mod ui;
+use s3_like_yazi::{app, clipboard, credentials, object_store, settings, ui};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let config = credentials::McConfig::load()?;
-    let mut app = app::App::new(config);
+    ui::install_panic_hook();
+    let Args { local_dir, put } = parse_args()?;
+    if let Some((alias, bucket, key)) = put {
+        return run_put(&alias, &bucket, &key).await;
+    }
+    let config = match credentials::McConfig::load_if_exists()? {
+        Some(config) => config,
+        // Browsing a local directory needs no endpoint, so no wizard either
+        None if local_dir.is_some() => credentials::McConfig::empty(),
+        None => match ui::setup::run_wizard().await? {
+            Some(config) => config,
+            None => return Ok(()),
+        },
+    };
+    let settings = settings::Settings::load()?;
+    let mut app = app::App::new(config, settings);
+    if let Some(dir) = local_dir {
+        app.add_cli_remote("local", credentials::McConfig::local_alias(&dir));
+    }
+    app.prewarm_remotes();
     ui::run(&mut app).await
 }
+
+struct Args {
+    /// `--local DIR` adds the directory as a remote named "local", for demos and
+    /// trying the UI without an S3 endpoint.
+    local_dir: Option<String>,
+    /// `--put ALIAS BUCKET KEY` uploads stdin (or the clipboard) as an object.
+    put: Option<(String, String, String)>,
+}
+
+fn parse_args() -> anyhow::Result<Args> {
+    const USAGE: &str = "Usage: s3-like-yazi [--local DIR] [--put ALIAS BUCKET KEY]";
+    let mut args = std::env::args().skip(1);
+    let mut parsed = Args {
+        local_dir: None,
+        put: None,
+    };
+    let value = |args: &mut std::iter::Skip<std::env::Args>, what: &str| {
+        args.next()
+            .ok_or_else(|| anyhow::anyhow!("Missing {}\n{}", what, USAGE))
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--local" => parsed.local_dir = Some(value(&mut args, "directory for --local")?),
+            "--put" => {
+                let alias = value(&mut args, "alias for --put")?;
+                let bucket = value(&mut args, "bucket for --put")?;
+                let key = value(&mut args, "key for --put")?;
+                parsed.put = Some((alias, bucket, key));
+            }
+            _ => anyhow::bail!("Unknown argument '{}'\n{}", arg, USAGE),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Upload text piped to stdin as `key`, or the clipboard's when nothing is piped.
+async fn run_put(alias: &str, bucket: &str, key: &str) -> anyhow::Result<()> {
+    use std::io::{IsTerminal, Read};
+
+    let mut config = credentials::McConfig::load()?;
+    config.apply_remote_settings(&settings::Settings::load()?.remotes);
+    let alias_config = config
+        .aliases
+        .get(alias)
+        .ok_or_else(|| anyhow::anyhow!("Unknown alias: {}", alias))?;
+    if alias_config.protection() == credentials::Protection::ReadOnly {
+        anyhow::bail!("Remote '{}' is read-only", alias);
+    }
+    let data = if std::io::stdin().is_terminal() {
+        clipboard::paste()?.into_bytes()
+    } else {
+        let mut data = Vec::new();
+        std::io::stdin().read_to_end(&mut data)?;
+        data
+    };
+    if data.is_empty() {
+        anyhow::bail!("Nothing to upload");
+    }
+    let store = object_store::connect(alias, alias_config)?;
+    let tmp = std::env::temp_dir().join(format!("s3-like-yazi-put-{}", std::process::id()));
+    object_store::put_bytes(&*store, bucket, key, &data, &tmp).await?;
+    println!("Uploaded {} bytes to {}/{}/{}", data.len(), alias, bucket, key);
+    Ok(())
+}
@@ -0,0 +1,744 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::{mpsc, Semaphore};
+
+use crate::credentials::AliasConfig;
+use crate::local_store::LocalStore;
+use crate::s3_client::S3Client;
+use crate::upload_manifest::{FileStamp, PendingUpload, UploadManifest};
+
+/// A connected remote, shared between the UI and background tasks.
+pub type Store = Arc<dyn ObjectStore>;
+
+/// Object contents being read, whole or a byte range.
+pub type Body = Box<dyn AsyncRead + Send + Unpin>;
+
+#[derive(Debug, Clone)]
+pub struct BucketInfo {
+    pub name: String,
+    pub creation_date: Option<Timestamp>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectEntry {
+    pub key: String,
+    pub display_name: String,
+    pub size: i64,
+    pub last_modified: Option<Timestamp>,
+    /// ETag from the listing; changes whenever the content does.
+    pub etag: Option<String>,
+    pub is_dir: bool,
+    /// STANDARD, GLACIER, ... when the listing says; `None` for prefixes and local files.
+    pub storage_class: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub struct ObjectMetadata {
+    pub key: String,
+    pub size: i64,
+    pub content_type: Option<String>,
+    pub last_modified: Option<Timestamp>,
+    pub etag: Option<String>,
+    pub version_id: Option<String>,
+    pub storage_class: Option<String>,
+    pub user_metadata: HashMap<String, String>,
+    pub content_encoding: Option<String>,
+    pub cache_control: Option<String>,
+    /// Dimensions, format and EXIF fields, filled in for image objects.
+    pub image_info: Vec<(String, String)>,
+}
+
+/// What a server-side copy changes on the way, all in its one CopyObject request.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CopyOptions {
+    /// STANDARD_IA, GLACIER, ...; `None` leaves it to the bucket default.
+    pub storage_class: Option<String>,
+    /// `AES256` or `aws:kms`; `None` leaves it to the bucket default.
+    pub sse: Option<String>,
+    /// User metadata replacing the source's; `None` copies it unchanged.
+    pub metadata: Option<Vec<(String, String)>>,
+}
+
+impl CopyOptions {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// How `transfer_files` uploads.
+#[derive(Clone, Default)]
+pub struct UploadOptions {
+    /// Records progress, so running the same upload again resumes it.
+    pub manifest: Option<Arc<UploadManifest>>,
+    /// Canned ACL the uploaded objects get.
+    pub acl: Option<String>,
+}
+
+/// A part of a multipart upload that is on the server.
+#[derive(Debug, Clone)]
+pub struct UploadedPart {
+    /// From 1.
+    pub number: i32,
+    pub etag: String,
+    pub size: u64,
+    /// Base64 checksum of the part, when uploads carry one.
+    pub checksum: Option<String>,
+}
+
+/// One file to move between a bucket and the local filesystem.
+pub struct TransferJob {
+    pub key: String,
+    pub local: std::path::PathBuf,
+    pub size: u64,
+    /// Local -> bucket instead of bucket -> local.
+    pub upload: bool,
+}
+
+/// Progress updates sent from download tasks to the UI.
+#[derive(Clone)]
+pub struct DownloadMsg {
+    pub bytes_downloaded: u64,
+    pub total_bytes: u64,
+    pub files_done: usize,
+    pub files_total: usize,
+    pub complete: bool,
+    pub error: Option<String>,
+}
+
+/// Messages sent from the background indexing task to the UI.
+pub enum IndexMsg {
+    Batch(Vec<ObjectEntry>),
+    Done,
+    Error(String),
+}
+
+/// Progress of a recursive delete, sent from the background task to the UI.
+pub enum DeleteMsg {
+    /// Another batch of this many objects is gone.
+    Deleted(usize),
+    /// Keys of the last batch the endpoint refused to delete.
+    Failed(Vec<DeleteFailure>),
+    Done,
+    Error(String),
+}
+
+/// A key a delete left in place (e.g. under retention), and why.
+pub struct DeleteFailure {
+    pub key: String,
+    pub reason: String,
+}
+
+/// Report one deleted batch of `batch` keys, `failed` of which are still there.
+/// Returns how many were deleted.
+pub async fn report_batch(progress: &mpsc::Sender<DeleteMsg>, batch: usize, failed: Vec<DeleteFailure>) -> usize {
+    let deleted = batch - failed.len();
+    let _ = progress.send(DeleteMsg::Deleted(deleted)).await;
+    if !failed.is_empty() {
+        let _ = progress.send(DeleteMsg::Failed(failed)).await;
+    }
+    deleted
+}
+
+/// A point in time as the stores report it; formatted only for display.
+/// Keys handed to other programs, such as a subshell's AWS CLI.
+#[derive(Debug, Clone)]
+pub struct SigningKeys {
+    pub access_key: String,
+    pub secret_key: String,
+    pub session_token: Option<String>,
+}
+
+pub type Timestamp = chrono::DateTime<chrono::Utc>;
+
+/// Open the backend an alias is configured for (`backend`, S3 when absent).
+pub fn connect(alias: &str, alias_config: &AliasConfig) -> Result<Store> {
+    match alias_config.backend.as_deref() {
+        None | Some("s3") => Ok(Arc::new(S3Client::new(alias, alias_config)?)),
+        Some("local") => Ok(Arc::new(LocalStore::new(&alias_config.url)?)),
+        Some(other) => anyhow::bail!(
+            "Unknown backend '{}' for '{}' (expected \"s3\" or \"local\")",
+            other,
+            alias
+        ),
+    }
+}
+
+/// Buckets, keys and objects as the browser sees them. A backend implements the
+/// primitive operations; downloads, copies between remotes and `.part` file
+/// handling are built on top of them.
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    async fn list_buckets(&self) -> Result<Vec<BucketInfo>>;
+
+    /// One level under `prefix`: sub-prefixes as directories first, then objects,
+    /// with `display_name` relative to the prefix.
+    async fn list_objects(&self, bucket: &str, prefix: &str) -> Result<Vec<ObjectEntry>>;
+
+    /// `list_objects`, and whether it is only the first page of a level too big
+    /// to list in one request.
+    async fn list_objects_page(&self, bucket: &str, prefix: &str) -> Result<(Vec<ObjectEntry>, bool)> {
+        Ok((self.list_objects(bucket, prefix).await?, false))
+    }
+
+    /// Every object key (and size) under `prefix`, at any depth.
+    async fn list_all_keys(&self, bucket: &str, prefix: &str) -> Result<Vec<ObjectEntry>>;
+
+    async fn head_object(&self, bucket: &str, key: &str) -> Result<ObjectMetadata>;
+
+    /// Read an object, or bytes `start..end` of it (end exclusive).
+    async fn open_object(&self, bucket: &str, key: &str, range: Option<(u64, u64)>) -> Result<Body>;
+
+    /// Upload a local file as `key`, with a canned ACL where the backend has them.
+    async fn upload_object(&self, bucket: &str, key: &str, src: &Path, acl: Option<&str>) -> Result<()>;
+
+    /// Replace an object's ACL with a canned one (`private`, `public-read`, ...).
+    async fn put_object_acl(&self, _bucket: &str, _key: &str, _acl: &str) -> Result<()> {
+        anyhow::bail!("This remote has no ACLs")
+    }
+
+    /// Whether this backend uploads large files in parts, with the multipart calls below.
+    fn multipart(&self) -> bool {
+        false
+    }
+
+    /// Start a multipart upload of `key`, returning its upload id.
+    async fn create_multipart(&self, _bucket: &str, _key: &str, _acl: Option<&str>) -> Result<String> {
+        anyhow::bail!("Multipart uploads are not supported by this remote")
+    }
+
+    /// Upload part `number` (from 1).
+    async fn upload_part(&self, _bucket: &str, _key: &str, _upload_id: &str, _number: i32, _data: Vec<u8>)
+        -> Result<UploadedPart> {
+        anyhow::bail!("Multipart uploads are not supported by this remote")
+    }
+
+    /// Parts of a multipart upload already uploaded.
+    async fn list_parts(&self, _bucket: &str, _key: &str, _upload_id: &str) -> Result<Vec<UploadedPart>> {
+        anyhow::bail!("Multipart uploads are not supported by this remote")
+    }
+
+    /// Assemble the uploaded parts, in order, into the object.
+    async fn complete_multipart(&self, _bucket: &str, _key: &str, _upload_id: &str, _parts: Vec<UploadedPart>)
+        -> Result<()> {
+        anyhow::bail!("Multipart uploads are not supported by this remote")
+    }
+
+    async fn abort_multipart(&self, _bucket: &str, _key: &str, _upload_id: &str) -> Result<()> {
+        anyhow::bail!("Multipart uploads are not supported by this remote")
+    }
+
+    async fn delete_object(&self, bucket: &str, key: &str) -> Result<()>;
+
+    /// Copy one object within this remote.
+    async fn copy_object(&self, src_bucket: &str, src_key: &str, dst_bucket: &str, dst_key: &str)
+        -> Result<()>;
+
+    /// `copy_object`, changing the storage class, encryption or metadata of the copy.
+    async fn copy_object_with(
+        &self,
+        src_bucket: &str,
+        src_key: &str,
+        dst_bucket: &str,
+        dst_key: &str,
+        options: &CopyOptions,
+    ) -> Result<()> {
+        if !options.is_empty() {
+            anyhow::bail!("This remote can't change storage class, encryption or metadata on copy");
+        }
+        self.copy_object(src_bucket, src_key, dst_bucket, dst_key).await
+    }
+
+    /// A URL other programs (ffplay, a browser) can fetch the object from.
+    async fn presign_get_object(&self, bucket: &str, key: &str) -> Result<String>;
+
+    /// `.part` files this remote's downloads are writing.
+    fn partials(&self) -> &PartFiles;
+
+    /// Unsigned web URL of an object, for stores reachable over HTTP.
+    fn object_url(&self, _bucket: &str, _key: &str) -> Option<String> {
+        None
+    }
+
+    /// When the credentials in use expire, for temporary ones.
+    fn credentials_expiry(&self) -> Option<Timestamp> {
+        None
+    }
+
+    /// Whether new credentials can be fetched before these expire; otherwise
+    /// they have to be replaced in the config.
+    fn renews_credentials(&self) -> bool {
+        false
+    }
+
+    /// Fetch new credentials if the current ones are about to expire.
+    async fn renew_credentials(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// The keys requests are signed with now, for stores that fetch them at run
+    /// time rather than reading them from the config.
+    async fn current_keys(&self) -> Result<Option<SigningKeys>> {
+        Ok(None)
+    }
+
+    /// Stream all objects in a bucket to a channel in batches, so the UI stays
+    /// responsive while a large bucket is indexed.
+    async fn stream_all_objects(&self, bucket: &str, tx: mpsc::Sender<IndexMsg>) {
+        match self.list_all_keys(bucket, "").await {
+            Ok(objects) => {
+                let files: Vec<ObjectEntry> =
+                    objects.into_iter().filter(|o| !o.key.ends_with('/')).collect();
+                for batch in files.chunks(1000) {
+                    if tx.send(IndexMsg::Batch(batch.to_vec())).await.is_err() {
+                        return;
+                    }
+                }
+                let _ = tx.send(IndexMsg::Done).await;
+            }
+            Err(e) => {
+                let _ = tx.send(IndexMsg::Error(e.to_string())).await;
+            }
+        }
+    }
+
+    /// Recursively delete all objects under `prefix`, reporting each batch as it
+    /// goes. Returns the count deleted.
+    async fn delete_prefix(&self, bucket: &str, prefix: &str, progress: &mpsc::Sender<DeleteMsg>) -> Result<usize> {
+        let keys: Vec<String> = self
+            .list_all_keys(bucket, prefix)
+            .await?
+            .into_iter()
+            .map(|o| o.key)
+            .collect();
+        let mut deleted = 0;
+        for chunk in keys.chunks(1000) {
+            let failed = self.delete_keys(bucket, chunk).await?;
+            deleted += report_batch(progress, chunk.len(), failed).await;
+        }
+        Ok(deleted)
+    }
+
+    /// Delete the given keys; ones already gone count as deleted. Returns the keys
+    /// that were refused, while `Err` means the request as a whole failed.
+    async fn delete_keys(&self, bucket: &str, keys: &[String]) -> Result<Vec<DeleteFailure>> {
+        let mut failed = Vec::new();
+        for key in keys {
+            if let Err(e) = self.delete_object(bucket, key).await {
+                failed.push(DeleteFailure {
+                    key: key.clone(),
+                    reason: format!("{:#}", e),
+                });
+            }
+        }
+        Ok(failed)
+    }
+
+    /// Download bytes of an object into memory.
+    async fn get_object_range(&self, bucket: &str, key: &str, start: u64, end: u64) -> Result<Vec<u8>> {
+        let mut body = self.open_object(bucket, key, Some((start, end))).await?;
+        let mut bytes = Vec::new();
+        body.read_to_end(&mut bytes).await?;
+        Ok(bytes)
+    }
+
+    /// Stream bytes `start..end` of an object into a local file, reporting progress.
+    async fn download_range(
+        &self,
+        bucket: &str,
+        key: &str,
+        start: u64,
+        end: u64,
+        dest: &Path,
+        tx: &mpsc::Sender<DownloadMsg>,
+    ) -> Result<()> {
+        let body = self.open_object(bucket, key, Some((start, end))).await?;
+        write_part(self.partials(), body, dest, end.saturating_sub(start), tx).await
+    }
+
+    /// Download a single object to a local file, reporting progress.
+    async fn download_object(
+        &self,
+        bucket: &str,
+        key: &str,
+        dest: &Path,
+        tx: &mpsc::Sender<DownloadMsg>,
+    ) -> Result<()> {
+        let total_bytes = self.head_object(bucket, key).await?.size as u64;
+        let body = self.open_object(bucket, key, None).await?;
+        write_part(self.partials(), body, dest, total_bytes, tx).await
+    }
+
+    /// Copy one object to another remote by way of a local temp file.
+    async fn copy_object_to(
+        &self,
+        src_bucket: &str,
+        src_key: &str,
+        dst: &dyn ObjectStore,
+        dst_bucket: &str,
+        dst_key: &str,
+        tmp: &Path,
+    ) -> Result<()> {
+        let mut body = self.open_object(src_bucket, src_key, None).await?;
+        if let Some(parent) = tmp.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::File::create(tmp).await?;
+        tokio::io::copy(&mut body, &mut file).await?;
+        drop(file);
+
+        let result = dst.upload_object(dst_bucket, dst_key, tmp, None).await;
+        let _ = tokio::fs::remove_file(tmp).await;
+        result
+    }
+
+    /// Delete the `.part` files of downloads that never finished, e.g. after their
+    /// tasks were aborted on quit. Returns how many were removed.
+    fn remove_partial_files(&self) -> usize {
+        self.partials().remove_all()
+    }
+}
+
+/// Downloads in progress, written to `<dest>.part` and renamed once complete.
+/// Shared between clones so they can be removed when the app quits mid-transfer.
+#[derive(Clone, Default)]
+pub struct PartFiles(Arc<Mutex<HashSet<PathBuf>>>);
+
+impl PartFiles {
+    /// Create the `.part` file a download of `dest` is written to, with its parent
+    /// directories, and remember it until [`Self::finish`].
+    pub async fn create(&self, dest: &Path) -> Result<(PathBuf, tokio::fs::File)> {
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut part = dest.as_os_str().to_owned();
+        part.push(".part");
+        let part = PathBuf::from(part);
+        let file = tokio::fs::File::create(&part).await?;
+        self.0.lock().unwrap().insert(part.clone());
+        Ok((part, file))
+    }
+
+    /// Move a finished `.part` file into place, or remove it if the download failed.
+    pub async fn finish(&self, part: &Path, dest: &Path, result: Result<()>) -> Result<()> {
+        self.0.lock().unwrap().remove(part);
+        match result {
+            Ok(()) => Ok(tokio::fs::rename(part, dest).await?),
+            Err(e) => {
+                let _ = tokio::fs::remove_file(part).await;
+                Err(e)
+            }
+        }
+    }
+
+    fn remove_all(&self) -> usize {
+        let parts: Vec<PathBuf> = self.0.lock().unwrap().drain().collect();
+        parts.iter().filter(|p| std::fs::remove_file(p).is_ok()).count()
+    }
+}
+
+/// Write `body` to `dest` through a `.part` file, reporting progress every 100ms.
+async fn write_part(
+    partials: &PartFiles,
+    mut body: Body,
+    dest: &Path,
+    total_bytes: u64,
+    tx: &mpsc::Sender<DownloadMsg>,
+) -> Result<()> {
+    let (part, mut file) = partials.create(dest).await?;
+    let mut downloaded: u64 = 0;
+    let mut last_report = Instant::now();
+    let mut buf = vec![0u8; 8192];
+
+    let result = async {
+        loop {
+            let n = body.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            file.write_all(&buf[..n]).await?;
+            downloaded += n as u64;
+
+            // Report progress every 100ms or at completion
+            if last_report.elapsed().as_millis() >= 100 || downloaded == total_bytes {
+                let _ = tx
+                    .send(DownloadMsg {
+                        bytes_downloaded: downloaded,
+                        total_bytes,
+                        files_done: 0,
+                        files_total: 1,
+                        complete: false,
+                        error: None,
+                    })
+                    .await;
+                last_report = Instant::now();
+            }
+        }
+        file.flush().await?;
+        Ok(())
+    }
+    .await;
+    drop(file);
+    partials.finish(&part, dest, result).await
+}
+
+/// Upload `data` as `key` by way of the local file `tmp`, so it goes through the
+/// same upload (checksums included) as a file would.
+pub async fn put_bytes(store: &dyn ObjectStore, bucket: &str, key: &str, data: &[u8], tmp: &Path) -> Result<()> {
+    if let Some(parent) = tmp.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(tmp, data).await?;
+    let result = store.upload_object(bucket, key, tmp, None).await;
+    let _ = tokio::fs::remove_file(tmp).await;
+    result
+}
+
+/// Whether a key's path below its prefix stays inside the directory it is joined
+/// to: keys are arbitrary strings, and `../`, a leading `/` or a drive letter would
+/// write elsewhere.
+pub fn stays_inside(rel: &str) -> bool {
+    Path::new(rel)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Download all objects under `prefix` to a local directory with concurrency.
+/// Reports aggregate progress through the channel, and returns the keys skipped
+/// because they would land outside `dest_dir`.
+pub async fn download_prefix(
+    store: &Store,
+    bucket: &str,
+    prefix: &str,
+    dest_dir: &Path,
+    tx: mpsc::Sender<DownloadMsg>,
+    concurrency: usize,
+) -> Result<Vec<String>> {
+    let mut skipped = Vec::new();
+    let mut jobs = Vec::new();
+    for o in store.list_all_keys(bucket, prefix).await? {
+        if o.key.ends_with('/') {
+            continue;
+        }
+        let rel = o.key.strip_prefix(prefix).unwrap_or(&o.key);
+        if !stays_inside(rel) {
+            skipped.push(o.key);
+            continue;
+        }
+        jobs.push(TransferJob {
+            local: dest_dir.join(rel),
+            size: o.size as u64,
+            key: o.key,
+            upload: false,
+        });
+    }
+    transfer_files(store, bucket, jobs, tx, concurrency, &UploadOptions::default()).await?;
+    Ok(skipped)
+}
+
+/// Multipart uploads use parts this big, or bigger for files that would need more
+/// than `MAX_PARTS`; files no bigger than one part go up in a single request.
+const PART_SIZE: u64 = 16 * 1024 * 1024;
+const MAX_PARTS: u64 = 10_000;
+
+/// Upload one file, in parts when it is large and the remote can, recording it in
+/// `manifest` so an interrupted upload picks up from the parts already there.
+/// `on_bytes` is told about each part as it goes up.
+async fn upload_resumable(
+    store: &dyn ObjectStore,
+    bucket: &str,
+    job: &TransferJob,
+    manifest: &UploadManifest,
+    acl: Option<&str>,
+    on_bytes: &(dyn Fn(u64) + Sync),
+) -> Result<()> {
+    let stamp = FileStamp::of(&job.local)?;
+    if !store.multipart() || stamp.size <= PART_SIZE {
+        store.upload_object(bucket, &job.key, &job.local, acl).await?;
+        manifest.finish(&job.key, stamp);
+        return Ok(());
+    }
+
+    let mut uploaded = Vec::new();
+    let resumed = match manifest.pending(&job.key) {
+        Some(pending) if pending.stamp == stamp => match store.list_parts(bucket, &job.key, &pending.upload_id).await {
+            Ok(parts) => {
+                uploaded = parts;
+                Some(pending)
+            }
+            // Aborted or expired on the server since
+            Err(_) => {
+                manifest.drop_pending(&job.key);
+                None
+            }
+        },
+        // The file changed, so its uploaded parts are no use
+        Some(pending) => {
+            let _ = store.abort_multipart(bucket, &job.key, &pending.upload_id).await;
+            manifest.drop_pending(&job.key);
+            None
+        }
+        None => None,
+    };
+    let pending = match resumed {
+        Some(pending) => pending,
+        None => {
+            let pending = PendingUpload {
+                upload_id: store.create_multipart(bucket, &job.key, acl).await?,
+                stamp,
+                part_size: PART_SIZE.max(stamp.size.div_ceil(MAX_PARTS).next_multiple_of(1024 * 1024)),
+            };
+            manifest.start(&job.key, pending.clone());
+            pending
+        }
+    };
+
+    let mut file = tokio::fs::File::open(&job.local).await?;
+    let mut parts = Vec::new();
+    for number in 1..=stamp.size.div_ceil(pending.part_size) as i32 {
+        let offset = (number as u64 - 1) * pending.part_size;
+        let len = pending.part_size.min(stamp.size - offset);
+        if let Some(part) = uploaded.iter().find(|p| p.number == number && p.size == len) {
+            parts.push(part.clone());
+            on_bytes(len);
+            continue;
+        }
+        file.seek(std::io::SeekFrom::Start(offset)).await?;
+        let mut data = vec![0u8; len as usize];
+        file.read_exact(&mut data).await?;
+        parts.push(store.upload_part(bucket, &job.key, &pending.upload_id, number, data).await?);
+        on_bytes(len);
+    }
+    store.complete_multipart(bucket, &job.key, &pending.upload_id, parts).await?;
+    manifest.finish(&job.key, stamp);
+    Ok(())
+}
+
+/// Run downloads and uploads with bounded concurrency, reporting aggregate
+/// progress through the channel.
+pub async fn transfer_files(
+    store: &Store,
+    bucket: &str,
+    jobs: Vec<TransferJob>,
+    tx: mpsc::Sender<DownloadMsg>,
+    concurrency: usize,
+    uploads: &UploadOptions,
+) -> Result<()> {
+    let files_total = jobs.len();
+    let total_bytes: u64 = jobs.iter().map(|j| j.size).sum();
+    let bytes_downloaded = Arc::new(AtomicU64::new(0));
+    let files_done = Arc::new(AtomicUsize::new(0));
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+
+    // Totals up front, so the gauge and ETA are right before any file has started
+    let _ = tx
+        .send(DownloadMsg {
+            bytes_downloaded: 0,
+            total_bytes,
+            files_done: 0,
+            files_total,
+            complete: false,
+            error: None,
+        })
+        .await;
+
+    // Dropping the set (when this task is aborted) aborts the workers with it
+    let mut workers = tokio::task::JoinSet::new();
+
+    for job in jobs {
+        let permit = semaphore.clone().acquire_owned().await?;
+        let store = store.clone();
+        let bucket = bucket.to_string();
+        let bytes_downloaded = bytes_downloaded.clone();
+        let files_done = files_done.clone();
+        let tx = tx.clone();
+        let uploads = uploads.clone();
+
+        workers.spawn(async move {
+            let report = |bytes: u64| DownloadMsg {
+                bytes_downloaded: bytes,
+                total_bytes,
+                files_done: files_done.load(Ordering::Relaxed),
+                files_total,
+                complete: false,
+                error: None,
+            };
+
+            let result: Result<()> = async {
+                if job.upload {
+                    let sent = AtomicU64::new(0);
+                    let on_bytes = |n: u64| {
+                        sent.fetch_add(n, Ordering::Relaxed);
+                        let prev = bytes_downloaded.fetch_add(n, Ordering::Relaxed);
+                        let _ = tx.try_send(report(prev + n));
+                    };
+                    let acl = uploads.acl.as_deref();
+                    match &uploads.manifest {
+                        Some(manifest) => upload_resumable(&*store, &bucket, &job, manifest, acl, &on_bytes).await?,
+                        None => store.upload_object(&bucket, &job.key, &job.local, acl).await?,
+                    }
+                    let rest = job.size.saturating_sub(sent.load(Ordering::Relaxed));
+                    let prev = bytes_downloaded.fetch_add(rest, Ordering::Relaxed);
+                    files_done.fetch_add(1, Ordering::Relaxed);
+                    let _ = tx.send(report(prev + rest)).await;
+                    return Ok(());
+                }
+
+                let mut body = store.open_object(&bucket, &job.key, None).await?;
+                let (part, mut file) = store.partials().create(&job.local).await?;
+                let mut buf = vec![0u8; 8192];
+                let mut last_report = Instant::now();
+
+                let written = async {
+                    loop {
+                        let n = body.read(&mut buf).await?;
+                        if n == 0 {
+                            break;
+                        }
+                        file.write_all(&buf[..n]).await?;
+                        let prev = bytes_downloaded.fetch_add(n as u64, Ordering::Relaxed);
+
+                        if last_report.elapsed().as_millis() >= 200 {
+                            let _ = tx.send(report(prev + n as u64)).await;
+                            last_report = Instant::now();
+                        }
+                    }
+                    file.flush().await?;
+                    Ok(())
+                }
+                .await;
+                drop(file);
+                store.partials().finish(&part, &job.local, written).await?;
+                files_done.fetch_add(1, Ordering::Relaxed);
+                // Small files finish between reports; count them as they do
+                let _ = tx.try_send(report(bytes_downloaded.load(Ordering::Relaxed)));
+                Ok(())
+            }
+            .await;
+
+            drop(permit);
+            result
+        });
+    }
+
+    // Wait for all transfers
+    let mut errors = Vec::new();
+    while let Some(joined) = workers.join_next().await {
+        if let Ok(Err(e)) = joined {
+            errors.push(e.to_string());
+        }
+    }
+
+    if !errors.is_empty() {
+        anyhow::bail!("{} files failed: {}", errors.len(), errors[0]);
+    }
+
+    Ok(())
+}
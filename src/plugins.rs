@@ -0,0 +1,131 @@
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+
+/// How long a plugin may run before it is killed.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Output lines of one run turned into notifications; the rest is dropped.
+const MAX_MESSAGES: usize = 3;
+
+/// Points in the app a plugin can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Hook {
+    /// Enter on an object.
+    #[serde(rename = "on_select")]
+    Select,
+    /// A preview was requested.
+    #[serde(rename = "on_preview")]
+    Preview,
+    /// A download finished successfully.
+    #[serde(rename = "on_download_complete")]
+    DownloadComplete,
+}
+
+impl Hook {
+    /// Name passed to the plugin as its first argument.
+    pub fn name(self) -> &'static str {
+        match self {
+            Hook::Select => "on_select",
+            Hook::Preview => "on_preview",
+            Hook::DownloadComplete => "on_download_complete",
+        }
+    }
+}
+
+/// A command a plugin adds to the command palette (`:`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginCommand {
+    /// Passed to the plugin as `command <name>`.
+    pub name: String,
+    /// Shown in the palette; defaults to the name.
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+/// An external executable from the `plugins` list of settings.json.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginConfig {
+    pub name: String,
+    /// Program to run; hooks call it as `<cmd> <hook>`, commands as `<cmd> command <name>`.
+    pub cmd: String,
+    #[serde(default)]
+    pub hooks: Vec<Hook>,
+    #[serde(default)]
+    pub commands: Vec<PluginCommand>,
+}
+
+/// What the plugin is told about, written to its stdin as one JSON object.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PluginEvent {
+    pub remote: String,
+    pub bucket: String,
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+    /// Where a download was saved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+    Info,
+    Warn,
+    Error,
+}
+
+/// One line of plugin output: `{"level": "warn", "message": "..."}` or plain text (info).
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginMessage {
+    #[serde(default = "default_level")]
+    pub level: Level,
+    pub message: String,
+}
+
+fn default_level() -> Level {
+    Level::Info
+}
+
+/// Run `plugin` with `args`, feeding it `event`, and collect what it printed.
+pub async fn run(
+    plugin: &PluginConfig,
+    args: &[&str],
+    event: &PluginEvent,
+) -> anyhow::Result<Vec<PluginMessage>> {
+    let mut child = tokio::process::Command::new(&plugin.cmd)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("{}: {}", plugin.cmd, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A plugin that ignores stdin may exit before reading it
+        let _ = stdin.write_all(&serde_json::to_vec(event)?).await;
+    }
+    let output = tokio::time::timeout(TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| anyhow::anyhow!("timed out after {}s", TIMEOUT.as_secs()))??;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("");
+        anyhow::bail!("exited with {} {}", output.status, reason.trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .take(MAX_MESSAGES)
+        .map(|line| {
+            serde_json::from_str(line).unwrap_or_else(|_| PluginMessage {
+                level: Level::Info,
+                message: line.trim().to_string(),
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,127 @@
+/// S3-compatible providers with known deviations from AWS behavior.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Provider {
+    Aws,
+    Minio,
+    Backblaze,
+    Cloudflare,
+    Wasabi,
+    Ceph,
+    Gcs,
+    Generic,
+}
+
+/// Behavior switches derived from the provider.
+#[derive(Debug, Clone, Copy)]
+pub struct Quirks {
+    /// Use ListObjects (v1, marker-based) instead of ListObjectsV2.
+    pub list_v1: bool,
+    /// Provider supports multi-object DeleteObjects; otherwise delete one by one.
+    pub batch_delete: bool,
+    /// Upper bound for presigned URL lifetimes.
+    pub max_presign_secs: u64,
+    /// Default addressing style when the alias `path` is "auto".
+    pub path_style: bool,
+    /// Signing region used unless the alias overrides it.
+    pub default_region: &'static str,
+    /// Buckets may live in different regions; resolve each with GetBucketLocation.
+    pub bucket_regions: bool,
+}
+
+impl Provider {
+    /// Parse the alias `provider` field; unknown names fall back to guessing from the URL.
+    pub fn resolve(name: Option<&str>, url: &str) -> Self {
+        name.and_then(Self::named).unwrap_or_else(|| Self::guess(url))
+    }
+
+    /// The provider an alias `provider` field names, if it is a known one.
+    pub fn named(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "aws" | "s3" => Some(Provider::Aws),
+            "minio" => Some(Provider::Minio),
+            "b2" | "backblaze" => Some(Provider::Backblaze),
+            "r2" | "cloudflare" => Some(Provider::Cloudflare),
+            "wasabi" => Some(Provider::Wasabi),
+            "ceph" | "rgw" => Some(Provider::Ceph),
+            "gcs" | "google" => Some(Provider::Gcs),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Provider::Aws => "AWS",
+            Provider::Minio => "MinIO",
+            Provider::Backblaze => "Backblaze B2",
+            Provider::Cloudflare => "Cloudflare R2",
+            Provider::Wasabi => "Wasabi",
+            Provider::Ceph => "Ceph",
+            Provider::Gcs => "Google Cloud Storage",
+            Provider::Generic => "S3-compatible",
+        }
+    }
+
+    fn guess(url: &str) -> Self {
+        let url = url.to_lowercase();
+        if url.contains("amazonaws.com") {
+            Provider::Aws
+        } else if url.contains("backblazeb2.com") {
+            Provider::Backblaze
+        } else if url.contains("r2.cloudflarestorage.com") {
+            Provider::Cloudflare
+        } else if url.contains("wasabisys.com") {
+            Provider::Wasabi
+        } else if url.contains("storage.googleapis.com") {
+            Provider::Gcs
+        } else {
+            Provider::Generic
+        }
+    }
+
+    pub fn quirks(self) -> Quirks {
+        let base = Quirks {
+            list_v1: false,
+            batch_delete: true,
+            max_presign_secs: 7 * 24 * 3600,
+            path_style: true,
+            default_region: "us-east-1",
+            bucket_regions: false,
+        };
+        match self {
+            Provider::Aws => Quirks {
+                path_style: false,
+                bucket_regions: true,
+                ..base
+            },
+            Provider::Cloudflare => Quirks {
+                default_region: "auto",
+                ..base
+            },
+            Provider::Ceph => Quirks {
+                list_v1: true,
+                ..base
+            },
+            Provider::Gcs => Quirks {
+                batch_delete: false,
+                path_style: false,
+                ..base
+            },
+            Provider::Backblaze | Provider::Wasabi | Provider::Minio | Provider::Generic => base,
+        }
+    }
+}
+
+/// Region embedded in an AWS endpoint host, e.g. `s3.us-gov-west-1.amazonaws.com`
+/// or `s3-eu-west-1.amazonaws.com`.
+pub fn region_from_url(url: &str) -> Option<String> {
+    let host = url.split("://").last()?.split(['/', ':']).next()?;
+    let rest = host.strip_suffix(".amazonaws.com")?;
+    rest.split('.')
+        .map(|label| label.strip_prefix("s3-").unwrap_or(label))
+        .find(|label| {
+            label.contains('-')
+                && label.ends_with(|c: char| c.is_ascii_digit())
+                && *label != "external-1"
+        })
+        .map(str::to_string)
+}
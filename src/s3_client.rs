@@ -1,200 +1,476 @@
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 use anyhow::Result;
-use aws_sdk_s3::config::{BehaviorVersion, Credentials, Region};
-use aws_sdk_s3::types::{Delete, ObjectIdentifier};
+use async_trait::async_trait;
+use aws_sdk_s3::config::{BehaviorVersion, Credentials, IdentityCache, Region};
+use aws_sdk_s3::error::{ProvideErrorMetadata, SdkError};
+use aws_sdk_s3::types::{
+    CompletedMultipartUpload, CompletedPart, Delete, MetadataDirective, ObjectCannedAcl, ObjectIdentifier,
+    ServerSideEncryption, StorageClass,
+};
 use aws_sdk_s3::Client;
-use tokio::sync::{mpsc, Semaphore};
+use tokio::sync::mpsc;
+
+use crate::checksum::{self, Checksum};
+use crate::credentials::AliasConfig;
+use crate::object_store::{
+    self, Body, BucketInfo, CopyOptions, DeleteFailure, DeleteMsg, IndexMsg, ObjectEntry, ObjectMetadata,
+    ObjectStore, PartFiles, SigningKeys, Timestamp, UploadedPart,
+};
+use crate::provider::{self, Provider, Quirks};
+use crate::s3_error;
+use crate::temp_credentials::{self, CredentialProcess};
 
 #[derive(Clone)]
 pub struct S3Client {
     client: Client,
     #[allow(dead_code)]
     pub alias: String,
+    pub endpoint: String,
+    quirks: Quirks,
+    /// Use ListObjects v1: set by the provider profile or after v2 failed once.
+    /// Shared between clones so detection happens once per client.
+    list_v1: Arc<AtomicBool>,
+    /// Unsigned requests (public buckets); presigning is replaced by plain URLs.
+    anonymous: bool,
+    path_style: bool,
+    /// Look up each bucket's region (AWS without an explicit `region`).
+    resolve_regions: bool,
+    /// Per-bucket clients signing for the bucket's region, shared between clones.
+    bucket_clients: Arc<Mutex<HashMap<String, Client>>>,
+    /// `.part` files downloads are writing.
+    partials: PartFiles,
+    /// Checksum uploads carry and have verified.
+    checksum: Option<Checksum>,
+    /// Source of renewable temporary credentials, when the alias has a `credential_process`.
+    credential_process: Option<CredentialProcess>,
+    /// Expiry of the alias's fixed session credentials.
+    session_expiry: Option<Timestamp>,
 }
 
-#[derive(Debug, Clone)]
-pub struct BucketInfo {
-    pub name: String,
-    pub creation_date: Option<String>,
+/// One page of a listing, independent of the ListObjects API version used.
+struct ListPage {
+    /// Objects with `display_name` set to the full key.
+    objects: Vec<ObjectEntry>,
+    /// Common prefixes (only when listed with a delimiter).
+    prefixes: Vec<String>,
+    /// Where the next page starts, or `None` on the last page.
+    next: Option<PageCursor>,
 }
 
-#[derive(Debug, Clone)]
-pub struct ObjectEntry {
-    pub key: String,
-    pub display_name: String,
-    pub size: i64,
-    pub last_modified: Option<String>,
-    pub is_dir: bool,
+/// Pagination position. Carries both the v2 continuation token and the last
+/// key seen, so a listing can switch to v1 markers mid-way.
+struct PageCursor {
+    token: Option<String>,
+    marker: String,
 }
 
-#[derive(Debug, Clone)]
-#[allow(dead_code)]
-pub struct ObjectMetadata {
-    pub key: String,
-    pub size: i64,
-    pub content_type: Option<String>,
-    pub last_modified: Option<String>,
-    pub etag: Option<String>,
-    pub version_id: Option<String>,
-    pub storage_class: Option<String>,
-    pub user_metadata: HashMap<String, String>,
-    pub content_encoding: Option<String>,
-    pub cache_control: Option<String>,
+impl ListPage {
+    fn last_key(&self) -> String {
+        let last_object = self.objects.last().map(|o| o.key.clone());
+        last_object
+            .into_iter()
+            .chain(self.prefixes.last().cloned())
+            .max()
+            .unwrap_or_default()
+    }
 }
 
-/// Progress updates sent from download tasks to the UI.
-#[derive(Clone)]
-pub struct DownloadMsg {
-    pub bytes_downloaded: u64,
-    pub total_bytes: u64,
-    pub files_done: usize,
-    pub files_total: usize,
-    pub complete: bool,
-    pub error: Option<String>,
+fn timestamp(dt: &aws_sdk_s3::primitives::DateTime) -> Timestamp {
+    Timestamp::from_timestamp(dt.secs(), dt.subsec_nanos()).unwrap_or_default()
 }
 
-/// Messages sent from the background indexing task to the UI.
-pub enum IndexMsg {
-    Batch(Vec<ObjectEntry>),
-    Done,
-    Error(String),
+/// Percent-encode an object key for use in a URL path, keeping `/` separators.
+pub(crate) fn encode_key(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    for b in key.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
 }
 
-fn format_aws_datetime(dt: &aws_sdk_s3::primitives::DateTime) -> String {
-    chrono::DateTime::from_timestamp(dt.secs(), dt.subsec_nanos())
-        .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
-        .unwrap_or_default()
+fn listed_entry(key: &str, o: &aws_sdk_s3::types::Object) -> ObjectEntry {
+    ObjectEntry {
+        key: key.to_string(),
+        display_name: key.to_string(),
+        size: o.size().unwrap_or(0),
+        last_modified: o.last_modified().map(timestamp),
+        etag: o.e_tag().map(str::to_string),
+        is_dir: false,
+        storage_class: o.storage_class().map(|c| c.as_str().to_string()),
+    }
 }
 
 impl S3Client {
-    pub fn new(alias: &str, url: &str, access_key: &str, secret_key: &str) -> Result<Self> {
-        let credentials =
-            Credentials::new(access_key, secret_key, None, None, "yazi-like-s3");
-
-        let config = aws_sdk_s3::Config::builder()
+    pub fn new(alias: &str, alias_config: &AliasConfig) -> Result<Self> {
+        let url = &alias_config.url;
+        let provider = Provider::resolve(alias_config.provider.as_deref(), url);
+        let quirks = provider.quirks();
+
+        // mc's `path` setting: "on" / "off" force the style, "auto" follows the provider
+        let path_style = match alias_config.path.as_deref() {
+            Some("on") => true,
+            Some("off") => false,
+            _ => quirks.path_style,
+        };
+
+        // Explicit region wins; AWS endpoints may carry one in the host name
+        let region = alias_config
+            .region
+            .clone()
+            .or_else(|| provider::region_from_url(url))
+            .unwrap_or_else(|| quirks.default_region.to_string());
+        let resolve_regions = quirks.bucket_regions && alias_config.region.is_none();
+        let checksum = alias_config.checksum.as_deref().map(Checksum::parse).transpose()?;
+
+        let mut builder = aws_sdk_s3::Config::builder()
             .behavior_version(BehaviorVersion::latest())
             .endpoint_url(url)
-            .region(Region::new("us-east-1"))
-            .credentials_provider(credentials)
-            .force_path_style(true)
-            .build();
+            .region(Region::new(region))
+            .force_path_style(path_style);
+
+        let anonymous = alias_config.is_anonymous();
+        let credential_process = alias_config.credential_process.as_deref().map(CredentialProcess::new);
+        let session_expiry = alias_config
+            .expiration
+            .as_deref()
+            .map(temp_credentials::parse_expiration)
+            .transpose()?;
+        if anonymous {
+            // No credentials provider: SigV4 is skipped and requests go out unsigned
+            builder = builder.allow_no_auth();
+        } else if let Some(process) = &credential_process {
+            // The process keeps its credentials and renews them itself, ahead of
+            // the SDK's own cache which would only do so at the last moment
+            builder = builder
+                .credentials_provider(process.clone())
+                .identity_cache(IdentityCache::no_cache());
+        } else {
+            builder = builder.credentials_provider(Credentials::new(
+                &alias_config.access_key,
+                &alias_config.secret_key,
+                alias_config.session_token.clone(),
+                session_expiry.map(std::time::SystemTime::from),
+                "yazi-like-s3",
+            ));
+        }
+        let config = builder.build();
 
         let client = Client::from_conf(config);
 
         Ok(Self {
             client,
             alias: alias.to_string(),
+            endpoint: url.to_string(),
+            quirks,
+            list_v1: Arc::new(AtomicBool::new(quirks.list_v1)),
+            anonymous,
+            path_style,
+            resolve_regions,
+            bucket_clients: Arc::new(Mutex::new(HashMap::new())),
+            partials: PartFiles::default(),
+            checksum,
+            credential_process,
+            session_expiry,
         })
     }
 
-    pub async fn list_buckets(&self) -> Result<Vec<BucketInfo>> {
-        let output = self.client.list_buckets().send().await?;
+    /// Client signing for the bucket's own region. Resolved once per bucket with
+    /// GetBucketLocation and cached. When the lookup is refused, the region comes
+    /// from the error's `x-amz-bucket-region` header if it has one, otherwise the
+    /// default client is cached so every request doesn't pay for another lookup.
+    async fn bucket_client(&self, bucket: &str) -> Client {
+        if !self.resolve_regions {
+            return self.client.clone();
+        }
+        if let Some(client) = self.bucket_clients.lock().unwrap().get(bucket) {
+            return client.clone();
+        }
+
+        let region = match self.client.get_bucket_location().bucket(bucket).send().await {
+            Ok(output) => match output.location_constraint().map(|c| c.as_str()) {
+                None | Some("") => "us-east-1".to_string(),
+                Some("EU") => "eu-west-1".to_string(),
+                Some(r) => r.to_string(),
+            },
+            Err(err) => match err
+                .raw_response()
+                .and_then(|r| r.headers().get("x-amz-bucket-region"))
+            {
+                Some(r) => r.to_string(),
+                None => {
+                    let client = self.client.clone();
+                    self.bucket_clients
+                        .lock()
+                        .unwrap()
+                        .insert(bucket.to_string(), client.clone());
+                    return client;
+                }
+            },
+        };
+
+        let default_region = self.client.config().region().map(|r| r.as_ref());
+        let client = if default_region != Some(region.as_str()) {
+            let config = self.client.config().to_builder().region(Region::new(region)).build();
+            Client::from_conf(config)
+        } else {
+            self.client.clone()
+        };
+        self.bucket_clients
+            .lock()
+            .unwrap()
+            .insert(bucket.to_string(), client.clone());
+        client
+    }
+
+    /// Map an SDK error to an actionable, classified error for this endpoint.
+    fn err<E>(&self, err: SdkError<E>) -> anyhow::Error
+    where
+        E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+    {
+        s3_error::classify(err, &self.endpoint)
+    }
+
+    /// Fetch one listing page. Uses ListObjectsV2 unless the provider needs v1
+    /// or the endpoint was detected not to support v2, then sticks to v1.
+    async fn list_page(
+        &self,
+        bucket: &str,
+        prefix: &str,
+        delimited: bool,
+        cursor: Option<&PageCursor>,
+    ) -> Result<ListPage> {
+        if !self.list_v1.load(Ordering::Relaxed) {
+            let token = cursor.and_then(|c| c.token.as_deref());
+            if let Some(page) = self.list_page_v2(bucket, prefix, delimited, token).await? {
+                return Ok(page);
+            }
+            self.list_v1.store(true, Ordering::Relaxed);
+        }
+        let marker = cursor.map(|c| c.marker.as_str());
+        self.list_page_v1(bucket, prefix, delimited, marker).await
+    }
+
+    /// ListObjectsV2 page, or `None` if the endpoint doesn't handle v2 properly.
+    async fn list_page_v2(
+        &self,
+        bucket: &str,
+        prefix: &str,
+        delimited: bool,
+        token: Option<&str>,
+    ) -> Result<Option<ListPage>> {
+        let mut builder = self.bucket_client(bucket).await.list_objects_v2().bucket(bucket);
+        if !prefix.is_empty() {
+            builder = builder.prefix(prefix);
+        }
+        if delimited {
+            builder = builder.delimiter("/");
+        }
+        if let Some(token) = token {
+            builder = builder.continuation_token(token);
+        }
+        let output = match builder.send().await {
+            Ok(output) => output,
+            Err(e) => {
+                let err = self.err(e);
+                if s3_error::is_unsupported_v2(&err) {
+                    return Ok(None);
+                }
+                return Err(err);
+            }
+        };
+
+        // Legacy servers ignore list-type=2 and answer in v1 form: truncated, no token
+        let truncated = output.is_truncated().unwrap_or(false);
+        if truncated && output.next_continuation_token().is_none() {
+            return Ok(None);
+        }
+
+        let mut page = ListPage {
+            objects: output
+                .contents()
+                .iter()
+                .filter_map(|o| o.key().map(|k| listed_entry(k, o)))
+                .collect(),
+            prefixes: output
+                .common_prefixes()
+                .iter()
+                .filter_map(|cp| cp.prefix().map(|p| p.to_string()))
+                .collect(),
+            next: None,
+        };
+        if let Some(token) = output.next_continuation_token() {
+            page.next = Some(PageCursor {
+                token: Some(token.to_string()),
+                marker: page.last_key(),
+            });
+        }
+        Ok(Some(page))
+    }
+
+    /// ListObjects (v1) page using markers.
+    async fn list_page_v1(
+        &self,
+        bucket: &str,
+        prefix: &str,
+        delimited: bool,
+        marker: Option<&str>,
+    ) -> Result<ListPage> {
+        let mut builder = self.bucket_client(bucket).await.list_objects().bucket(bucket);
+        if !prefix.is_empty() {
+            builder = builder.prefix(prefix);
+        }
+        if delimited {
+            builder = builder.delimiter("/");
+        }
+        if let Some(marker) = marker {
+            builder = builder.marker(marker);
+        }
+        let output = builder.send().await.map_err(|e| self.err(e))?;
+
+        let mut page = ListPage {
+            objects: output
+                .contents()
+                .iter()
+                .filter_map(|o| o.key().map(|k| listed_entry(k, o)))
+                .collect(),
+            prefixes: output
+                .common_prefixes()
+                .iter()
+                .filter_map(|cp| cp.prefix().map(|p| p.to_string()))
+                .collect(),
+            next: None,
+        };
+
+        // v1 only returns NextMarker with a delimiter; otherwise continue after the last key
+        if output.is_truncated().unwrap_or(false) {
+            let marker = output
+                .next_marker()
+                .map(|m| m.to_string())
+                .unwrap_or_else(|| page.last_key());
+            page.next = Some(PageCursor {
+                token: None,
+                marker,
+            });
+        }
+        Ok(page)
+    }
+
+    /// Plain (unsigned) object URL, for anonymous remotes and copied web links.
+    fn public_url(&self, bucket: &str, key: &str) -> String {
+        let endpoint = self.endpoint.trim_end_matches('/');
+        let key = encode_key(key);
+        if self.path_style {
+            return format!("{}/{}/{}", endpoint, bucket, key);
+        }
+        match endpoint.split_once("://") {
+            Some((scheme, host)) => format!("{}://{}.{}/{}", scheme, bucket, host, key),
+            None => format!("{}.{}/{}", bucket, endpoint, key),
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for S3Client {
+    async fn list_buckets(&self) -> Result<Vec<BucketInfo>> {
+        let output = self.client.list_buckets().send().await.map_err(|e| self.err(e))?;
         let buckets = output
             .buckets()
             .iter()
             .filter_map(|b| {
                 b.name().map(|name| BucketInfo {
                     name: name.to_string(),
-                    creation_date: b.creation_date().map(format_aws_datetime),
+                    creation_date: b.creation_date().map(timestamp),
                 })
             })
             .collect();
         Ok(buckets)
     }
 
-    pub async fn list_objects(&self, bucket: &str, prefix: &str) -> Result<Vec<ObjectEntry>> {
-        let mut builder = self.client.list_objects_v2().bucket(bucket).delimiter("/");
-
-        if !prefix.is_empty() {
-            builder = builder.prefix(prefix);
-        }
+    async fn list_objects(&self, bucket: &str, prefix: &str) -> Result<Vec<ObjectEntry>> {
+        Ok(self.list_objects_page(bucket, prefix).await?.0)
+    }
 
-        let output = builder.send().await?;
+    async fn list_objects_page(&self, bucket: &str, prefix: &str) -> Result<(Vec<ObjectEntry>, bool)> {
+        let page = self.list_page(bucket, prefix, true, None).await?;
+        let truncated = page.next.is_some();
         let mut entries = Vec::new();
 
         // Directories (common prefixes) first
-        for cp in output.common_prefixes() {
-            if let Some(p) = cp.prefix() {
-                let display = p.strip_prefix(prefix).unwrap_or(p);
-                let display = display.trim_end_matches('/');
-                if !display.is_empty() {
-                    entries.push(ObjectEntry {
-                        key: p.to_string(),
-                        display_name: display.to_string(),
-                        size: 0,
-                        last_modified: None,
-                        is_dir: true,
-                    });
-                }
+        for p in &page.prefixes {
+            let display = p.strip_prefix(prefix).unwrap_or(p);
+            let display = display.trim_end_matches('/');
+            if !display.is_empty() {
+                entries.push(ObjectEntry {
+                    key: p.to_string(),
+                    display_name: display.to_string(),
+                    size: 0,
+                    last_modified: None,
+                    etag: None,
+                    is_dir: true,
+                    storage_class: None,
+                });
             }
         }
 
         // Files
-        for obj in output.contents() {
-            if let Some(key) = obj.key() {
-                // Skip the prefix itself if returned as an object
-                if key == prefix {
-                    continue;
-                }
-                let display = key.strip_prefix(prefix).unwrap_or(key);
-                entries.push(ObjectEntry {
-                    key: key.to_string(),
-                    display_name: display.to_string(),
-                    size: obj.size().unwrap_or(0),
-                    last_modified: obj.last_modified().map(format_aws_datetime),
-                    is_dir: false,
-                });
+        for mut obj in page.objects {
+            // Skip the prefix itself if returned as an object
+            if obj.key == prefix {
+                continue;
             }
+            obj.display_name = obj.key.strip_prefix(prefix).unwrap_or(&obj.key).to_string();
+            entries.push(obj);
         }
 
-        Ok(entries)
+        Ok((entries, truncated))
+    }
+
+    /// List every object key (and size) under `prefix`, following pagination.
+    async fn list_all_keys(&self, bucket: &str, prefix: &str) -> Result<Vec<ObjectEntry>> {
+        let mut all = Vec::new();
+        let mut cursor: Option<PageCursor> = None;
+        loop {
+            let page = self.list_page(bucket, prefix, false, cursor.as_ref()).await?;
+            all.extend(page.objects);
+            match page.next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        Ok(all)
     }
 
     /// Stream ALL objects in a bucket to a channel, page by page.
     /// Runs as a background task — sends batches so the UI stays responsive.
-    pub async fn stream_all_objects(
+    async fn stream_all_objects(
         &self,
         bucket: &str,
-        tx: tokio::sync::mpsc::Sender<IndexMsg>,
+        tx: mpsc::Sender<IndexMsg>,
     ) {
-        let mut continuation_token: Option<String> = None;
+        let mut cursor: Option<PageCursor> = None;
 
         loop {
-            let mut builder = self.client.list_objects_v2().bucket(bucket);
-
-            if let Some(token) = &continuation_token {
-                builder = builder.continuation_token(token);
-            }
-
-            match builder.send().await {
-                Ok(output) => {
-                    let mut batch = Vec::new();
-                    for obj in output.contents() {
-                        if let Some(key) = obj.key() {
-                            if key.ends_with('/') {
-                                continue;
-                            }
-                            batch.push(ObjectEntry {
-                                key: key.to_string(),
-                                display_name: key.to_string(),
-                                size: obj.size().unwrap_or(0),
-                                last_modified: obj.last_modified().map(format_aws_datetime),
-                                is_dir: false,
-                            });
-                        }
+            match self.list_page(bucket, "", false, cursor.as_ref()).await {
+                Ok(page) => {
+                    let batch: Vec<ObjectEntry> = page
+                        .objects
+                        .into_iter()
+                        .filter(|o| !o.key.ends_with('/'))
+                        .collect();
+                    if !batch.is_empty() && tx.send(IndexMsg::Batch(batch)).await.is_err() {
+                        return; // receiver dropped, stop
                     }
-                    if !batch.is_empty() {
-                        if tx.send(IndexMsg::Batch(batch)).await.is_err() {
-                            return; // receiver dropped, stop
-                        }
-                    }
-                    match output.next_continuation_token() {
-                        Some(token) => continuation_token = Some(token.to_string()),
+                    match page.next {
+                        Some(next) => cursor = Some(next),
                         None => break,
                     }
                 }
@@ -207,70 +483,22 @@ impl S3Client {
         let _ = tx.send(IndexMsg::Done).await;
     }
 
-    pub async fn delete_object(&self, bucket: &str, key: &str) -> Result<()> {
-        self.client
-            .delete_object()
+    async fn head_object(&self, bucket: &str, key: &str) -> Result<ObjectMetadata> {
+        let output = self
+            .bucket_client(bucket)
+            .await
+            .head_object()
             .bucket(bucket)
             .key(key)
             .send()
-            .await?;
-        Ok(())
-    }
-
-    /// Recursively delete all objects under `prefix`. Returns the count deleted.
-    pub async fn delete_prefix(&self, bucket: &str, prefix: &str) -> Result<usize> {
-        let mut deleted = 0usize;
-        let mut continuation_token: Option<String> = None;
-
-        loop {
-            let mut builder = self.client.list_objects_v2().bucket(bucket).prefix(prefix);
-            if let Some(token) = &continuation_token {
-                builder = builder.continuation_token(token);
-            }
-
-            let output = builder.send().await?;
-            let keys: Vec<String> = output
-                .contents()
-                .iter()
-                .filter_map(|obj| obj.key().map(|k| k.to_string()))
-                .collect();
-
-            // Delete in batches of 1000 (S3 limit)
-            for chunk in keys.chunks(1000) {
-                let objects: Vec<ObjectIdentifier> = chunk
-                    .iter()
-                    .map(|k| ObjectIdentifier::builder().key(k).build().unwrap())
-                    .collect();
-                let delete = Delete::builder()
-                    .set_objects(Some(objects))
-                    .quiet(true)
-                    .build()?;
-                self.client
-                    .delete_objects()
-                    .bucket(bucket)
-                    .delete(delete)
-                    .send()
-                    .await?;
-                deleted += chunk.len();
-            }
-
-            match output.next_continuation_token() {
-                Some(token) => continuation_token = Some(token.to_string()),
-                None => break,
-            }
-        }
-
-        Ok(deleted)
-    }
-
-    pub async fn head_object(&self, bucket: &str, key: &str) -> Result<ObjectMetadata> {
-        let output = self.client.head_object().bucket(bucket).key(key).send().await?;
+            .await
+            .map_err(|e| self.err(e))?;
 
         Ok(ObjectMetadata {
             key: key.to_string(),
             size: output.content_length().unwrap_or(0),
             content_type: output.content_type().map(|s| s.to_string()),
-            last_modified: output.last_modified().map(format_aws_datetime),
+            last_modified: output.last_modified().map(timestamp),
             etag: output.e_tag().map(|s| s.to_string()),
             version_id: output.version_id().map(|s| s.to_string()),
             storage_class: output.storage_class().map(|s| s.as_str().to_string()),
@@ -280,226 +508,382 @@ impl S3Client {
                 .unwrap_or_default(),
             content_encoding: output.content_encoding().map(|s| s.to_string()),
             cache_control: output.cache_control().map(|s| s.to_string()),
+            image_info: Vec::new(),
         })
     }
 
-    /// Download a byte range of an object into memory.
-    /// Uses the HTTP Range header to avoid downloading the entire file.
-    pub async fn get_object_range(
-        &self,
-        bucket: &str,
-        key: &str,
-        start: u64,
-        end: u64,
-    ) -> Result<Vec<u8>> {
-        let range = format!("bytes={}-{}", start, end.saturating_sub(1));
-        let output = self
-            .client
-            .get_object()
+    /// GET the object, with a Range header when only part of it is wanted.
+    async fn open_object(&self, bucket: &str, key: &str, range: Option<(u64, u64)>) -> Result<Body> {
+        let mut builder = self.bucket_client(bucket).await.get_object().bucket(bucket).key(key);
+        if let Some((start, end)) = range {
+            builder = builder.range(format!("bytes={}-{}", start, end.saturating_sub(1)));
+        }
+        let output = builder.send().await.map_err(|e| self.err(e))?;
+        Ok(Box::new(output.body.into_async_read()))
+    }
+
+    /// Upload a local file as `key`, with its checksum when the remote asks for one.
+    async fn upload_object(&self, bucket: &str, key: &str, src: &Path, acl: Option<&str>) -> Result<()> {
+        let sent = match self.checksum {
+            Some(checksum) => Some((checksum, checksum.of_file(src).await?)),
+            None => None,
+        };
+        let body = aws_sdk_s3::primitives::ByteStream::from_path(src).await?;
+        let mut request = self
+            .bucket_client(bucket)
+            .await
+            .put_object()
             .bucket(bucket)
             .key(key)
-            .range(range)
-            .send()
-            .await?;
-        let bytes = output.body.collect().await?.into_bytes().to_vec();
-        Ok(bytes)
+            .body(body)
+            .set_acl(acl.map(ObjectCannedAcl::from));
+        request = match &sent {
+            Some((Checksum::Sha256, sum)) => request.checksum_sha256(sum),
+            Some((Checksum::Crc32, sum)) => request.checksum_crc32(sum),
+            None => request,
+        };
+        let output = request.send().await.map_err(|e| self.err(e))?;
+        if let Some((checksum, sum)) = &sent {
+            let stored = match checksum {
+                Checksum::Sha256 => output.checksum_sha256(),
+                Checksum::Crc32 => output.checksum_crc32(),
+            };
+            checksum::verify(*checksum, key, sum, stored)?;
+        }
+        Ok(())
     }
 
-    /// Generate a presigned GET URL for an object (for ffmpeg streaming).
-    /// The URL is valid for 1 hour and allows ffmpeg to seek within the file.
-    pub async fn presign_get_object(
-        &self,
-        bucket: &str,
-        key: &str,
-    ) -> Result<String> {
-        use aws_sdk_s3::presigning::PresigningConfig;
-        use std::time::Duration;
+    async fn put_object_acl(&self, bucket: &str, key: &str, acl: &str) -> Result<()> {
+        self.bucket_client(bucket)
+            .await
+            .put_object_acl()
+            .bucket(bucket)
+            .key(key)
+            .acl(ObjectCannedAcl::from(acl))
+            .send()
+            .await
+            .map_err(|e| self.err(e))?;
+        Ok(())
+    }
 
-        let presigning_config = PresigningConfig::builder()
-            .expires_in(Duration::from_secs(3600))
-            .build()?;
+    fn multipart(&self) -> bool {
+        true
+    }
 
-        let presigned = self
-            .client
-            .get_object()
+    async fn create_multipart(&self, bucket: &str, key: &str, acl: Option<&str>) -> Result<String> {
+        let output = self
+            .bucket_client(bucket)
+            .await
+            .create_multipart_upload()
             .bucket(bucket)
             .key(key)
-            .presigned(presigning_config)
-            .await?;
+            .set_checksum_algorithm(self.checksum.map(Checksum::algorithm))
+            .set_acl(acl.map(ObjectCannedAcl::from))
+            .send()
+            .await
+            .map_err(|e| self.err(e))?;
+        output
+            .upload_id()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("No upload id for {}", key))
+    }
 
-        Ok(presigned.uri().to_string())
+    async fn upload_part(&self, bucket: &str, key: &str, upload_id: &str, number: i32, data: Vec<u8>)
+        -> Result<UploadedPart> {
+        let size = data.len() as u64;
+        let sent = self.checksum.map(|checksum| (checksum, checksum.of(&data)));
+        let mut request = self
+            .bucket_client(bucket)
+            .await
+            .upload_part()
+            .bucket(bucket)
+            .key(key)
+            .upload_id(upload_id)
+            .part_number(number)
+            .body(data.into());
+        request = match &sent {
+            Some((Checksum::Sha256, sum)) => request.checksum_sha256(sum),
+            Some((Checksum::Crc32, sum)) => request.checksum_crc32(sum),
+            None => request,
+        };
+        let output = request.send().await.map_err(|e| self.err(e))?;
+        if let Some((checksum, sum)) = &sent {
+            let stored = match checksum {
+                Checksum::Sha256 => output.checksum_sha256(),
+                Checksum::Crc32 => output.checksum_crc32(),
+            };
+            checksum::verify(*checksum, &format!("{} part {}", key, number), sum, stored)?;
+        }
+        Ok(UploadedPart {
+            number,
+            etag: output.e_tag().unwrap_or_default().to_string(),
+            size,
+            checksum: sent.map(|(_, sum)| sum),
+        })
     }
 
-    /// Download a single object to a local file, reporting progress.
-    pub async fn download_object(
-        &self,
-        bucket: &str,
-        key: &str,
-        dest: &Path,
-        tx: &mpsc::Sender<DownloadMsg>,
-    ) -> Result<()> {
-        // Get object size first via head
-        let head = self.client.head_object().bucket(bucket).key(key).send().await?;
-        let total_bytes = head.content_length().unwrap_or(0) as u64;
+    async fn list_parts(&self, bucket: &str, key: &str, upload_id: &str) -> Result<Vec<UploadedPart>> {
+        let client = self.bucket_client(bucket).await;
+        let mut parts = Vec::new();
+        let mut marker: Option<String> = None;
+        loop {
+            let output = client
+                .list_parts()
+                .bucket(bucket)
+                .key(key)
+                .upload_id(upload_id)
+                .set_part_number_marker(marker.take())
+                .send()
+                .await
+                .map_err(|e| self.err(e))?;
+            parts.extend(output.parts().iter().filter_map(|p| {
+                Some(UploadedPart {
+                    number: p.part_number()?,
+                    etag: p.e_tag()?.to_string(),
+                    size: p.size()?.max(0) as u64,
+                    checksum: p.checksum_sha256().or(p.checksum_crc32()).map(str::to_string),
+                })
+            }));
+            match output.next_part_number_marker() {
+                Some(next) if output.is_truncated() == Some(true) => marker = Some(next.to_string()),
+                _ => break,
+            }
+        }
+        Ok(parts)
+    }
 
-        // Start download
-        let output = self.client.get_object().bucket(bucket).key(key).send().await?;
-        let mut body = output.body.into_async_read();
+    async fn complete_multipart(&self, bucket: &str, key: &str, upload_id: &str, parts: Vec<UploadedPart>)
+        -> Result<()> {
+        let parts = parts
+            .into_iter()
+            .map(|part| {
+                let builder = CompletedPart::builder().part_number(part.number).e_tag(part.etag);
+                match self.checksum {
+                    Some(Checksum::Sha256) => builder.set_checksum_sha256(part.checksum),
+                    Some(Checksum::Crc32) => builder.set_checksum_crc32(part.checksum),
+                    None => builder,
+                }
+                .build()
+            })
+            .collect();
+        self.bucket_client(bucket)
+            .await
+            .complete_multipart_upload()
+            .bucket(bucket)
+            .key(key)
+            .upload_id(upload_id)
+            .multipart_upload(CompletedMultipartUpload::builder().set_parts(Some(parts)).build())
+            .send()
+            .await
+            .map_err(|e| self.err(e))?;
+        Ok(())
+    }
 
-        // Ensure parent directory exists
-        if let Some(parent) = dest.parent() {
-            tokio::fs::create_dir_all(parent).await?;
-        }
+    async fn abort_multipart(&self, bucket: &str, key: &str, upload_id: &str) -> Result<()> {
+        self.bucket_client(bucket)
+            .await
+            .abort_multipart_upload()
+            .bucket(bucket)
+            .key(key)
+            .upload_id(upload_id)
+            .send()
+            .await
+            .map_err(|e| self.err(e))?;
+        Ok(())
+    }
 
-        let mut file = tokio::fs::File::create(dest).await?;
-        let mut downloaded: u64 = 0;
-        let mut last_report = Instant::now();
-        let mut buf = vec![0u8; 8192];
+    async fn delete_object(&self, bucket: &str, key: &str) -> Result<()> {
+        self.bucket_client(bucket)
+            .await
+            .delete_object()
+            .bucket(bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| self.err(e))?;
+        Ok(())
+    }
 
-        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// Recursively delete all objects under `prefix`, one listing page at a time.
+    async fn delete_prefix(&self, bucket: &str, prefix: &str, progress: &mpsc::Sender<DeleteMsg>) -> Result<usize> {
+        let mut deleted = 0usize;
+        let mut cursor: Option<PageCursor> = None;
 
         loop {
-            let n = body.read(&mut buf).await?;
-            if n == 0 {
-                break;
+            let page = self.list_page(bucket, prefix, false, cursor.as_ref()).await?;
+            let keys: Vec<String> = page.objects.into_iter().map(|o| o.key).collect();
+            let failed = self.delete_keys(bucket, &keys).await?;
+            deleted += object_store::report_batch(progress, keys.len(), failed).await;
+
+            // v1 markers point at the last listed key; deleted keys don't shift pagination
+            match page.next {
+                Some(next) => cursor = Some(next),
+                None => break,
             }
-            file.write_all(&buf[..n]).await?;
-            downloaded += n as u64;
-
-            // Report progress every 100ms or at completion
-            if last_report.elapsed().as_millis() >= 100 || downloaded == total_bytes {
-                let _ = tx
-                    .send(DownloadMsg {
-                        bytes_downloaded: downloaded,
-                        total_bytes,
-                        files_done: 0,
-                        files_total: 1,
-                        complete: false,
-                        error: None,
-                    })
-                    .await;
-                last_report = Instant::now();
+        }
+
+        Ok(deleted)
+    }
+
+    async fn delete_keys(&self, bucket: &str, keys: &[String]) -> Result<Vec<DeleteFailure>> {
+        let mut failed = Vec::new();
+        if !self.quirks.batch_delete {
+            for key in keys {
+                if let Err(e) = self.delete_object(bucket, key).await {
+                    failed.push(DeleteFailure {
+                        key: key.clone(),
+                        reason: format!("{:#}", e),
+                    });
+                }
             }
+            return Ok(failed);
         }
+        // Delete in batches of 1000 (S3 limit); missing keys aren't errors
+        for chunk in keys.chunks(1000) {
+            let objects: Vec<ObjectIdentifier> = chunk
+                .iter()
+                .map(|k| ObjectIdentifier::builder().key(k).build().unwrap())
+                .collect();
+            let delete = Delete::builder()
+                .set_objects(Some(objects))
+                .quiet(true)
+                .build()?;
+            let output = self
+                .bucket_client(bucket)
+                .await
+                .delete_objects()
+                .bucket(bucket)
+                .delete(delete)
+                .send()
+                .await
+                .map_err(|e| self.err(e))?;
+            // Quiet mode still lists the keys that weren't deleted (retention, legal hold, ...)
+            failed.extend(output.errors().iter().map(|e| DeleteFailure {
+                key: e.key().unwrap_or_default().to_string(),
+                reason: match (e.code(), e.message()) {
+                    (Some(code), Some(message)) => format!("{}: {}", code, message),
+                    (code, message) => code.or(message).unwrap_or("unknown error").to_string(),
+                },
+            }));
+        }
+        Ok(failed)
+    }
+
+    async fn copy_object(
+        &self,
+        src_bucket: &str,
+        src_key: &str,
+        dst_bucket: &str,
+        dst_key: &str,
+    ) -> Result<()> {
+        self.copy_object_with(src_bucket, src_key, dst_bucket, dst_key, &CopyOptions::default())
+            .await
+    }
 
-        file.flush().await?;
+    /// Server-side copy of one object within this endpoint.
+    async fn copy_object_with(
+        &self,
+        src_bucket: &str,
+        src_key: &str,
+        dst_bucket: &str,
+        dst_key: &str,
+        options: &CopyOptions,
+    ) -> Result<()> {
+        // CopySource is "bucket/key" with the key URL-encoded, slashes kept
+        let source = format!("{}/{}", src_bucket, encode_key(src_key));
+        let mut request = self
+            .bucket_client(dst_bucket)
+            .await
+            .copy_object()
+            .copy_source(source)
+            .bucket(dst_bucket)
+            .key(dst_key);
+        if let Some(class) = &options.storage_class {
+            request = request.storage_class(StorageClass::from(class.as_str()));
+        }
+        if let Some(sse) = &options.sse {
+            request = request.server_side_encryption(ServerSideEncryption::from(sse.as_str()));
+        }
+        if let Some(metadata) = &options.metadata {
+            // REPLACE drops the content headers along with the metadata, so carry those over
+            let head = self.head_object(src_bucket, src_key).await?;
+            request = request
+                .metadata_directive(MetadataDirective::Replace)
+                .set_metadata(Some(metadata.iter().cloned().collect()))
+                .set_content_type(head.content_type)
+                .set_content_encoding(head.content_encoding)
+                .set_cache_control(head.cache_control);
+        }
+        request.send().await.map_err(|e| self.err(e))?;
         Ok(())
     }
 
-    /// Download all objects under `prefix` to a local directory with concurrency.
-    /// Reports aggregate progress through the channel.
-    pub async fn download_prefix(
+    /// Generate a presigned GET URL for an object (for ffmpeg streaming).
+    /// The URL is valid for 1 hour and allows ffmpeg to seek within the file.
+    async fn presign_get_object(
         &self,
         bucket: &str,
-        prefix: &str,
-        dest_dir: &Path,
-        tx: mpsc::Sender<DownloadMsg>,
-        concurrency: usize,
-    ) -> Result<()> {
-        // First, list all objects under the prefix
-        let mut all_keys: Vec<(String, u64)> = Vec::new();
-        let mut continuation_token: Option<String> = None;
+        key: &str,
+    ) -> Result<String> {
+        use aws_sdk_s3::presigning::PresigningConfig;
+        use std::time::Duration;
 
-        loop {
-            let mut builder = self.client.list_objects_v2().bucket(bucket).prefix(prefix);
-            if let Some(token) = &continuation_token {
-                builder = builder.continuation_token(token);
-            }
-            let output = builder.send().await?;
-            for obj in output.contents() {
-                if let Some(key) = obj.key() {
-                    if key.ends_with('/') {
-                        continue;
-                    }
-                    all_keys.push((key.to_string(), obj.size().unwrap_or(0) as u64));
-                }
-            }
-            match output.next_continuation_token() {
-                Some(token) => continuation_token = Some(token.to_string()),
-                None => break,
-            }
+        if self.anonymous {
+            return Ok(self.public_url(bucket, key));
         }
 
-        let files_total = all_keys.len();
-        let total_bytes: u64 = all_keys.iter().map(|(_, s)| s).sum();
-        let bytes_downloaded = Arc::new(AtomicU64::new(0));
-        let files_done = Arc::new(AtomicUsize::new(0));
-        let semaphore = Arc::new(Semaphore::new(concurrency));
-
-        let mut handles = Vec::new();
-
-        for (key, _size) in &all_keys {
-            let permit = semaphore.clone().acquire_owned().await?;
-            let client = self.client.clone();
-            let bucket = bucket.to_string();
-            let key = key.clone();
-            let rel_path = key.strip_prefix(prefix).unwrap_or(&key).to_string();
-            let dest = dest_dir.join(&rel_path);
-            let bytes_downloaded = bytes_downloaded.clone();
-            let files_done = files_done.clone();
-            let tx = tx.clone();
-
-            let handle = tokio::spawn(async move {
-                let result: Result<()> = async {
-                    let output = client.get_object().bucket(&bucket).key(&key).send().await?;
-                    let mut body = output.body.into_async_read();
-
-                    if let Some(parent) = dest.parent() {
-                        tokio::fs::create_dir_all(parent).await?;
-                    }
+        let presigning_config = PresigningConfig::builder()
+            .expires_in(Duration::from_secs(3600.min(self.quirks.max_presign_secs)))
+            .build()?;
 
-                    let mut file = tokio::fs::File::create(&dest).await?;
-                    let mut buf = vec![0u8; 8192];
-                    let mut last_report = Instant::now();
-
-                    use tokio::io::{AsyncReadExt, AsyncWriteExt};
-
-                    loop {
-                        let n = body.read(&mut buf).await?;
-                        if n == 0 {
-                            break;
-                        }
-                        file.write_all(&buf[..n]).await?;
-                        let prev = bytes_downloaded.fetch_add(n as u64, Ordering::Relaxed);
-
-                        if last_report.elapsed().as_millis() >= 200 {
-                            let _ = tx
-                                .send(DownloadMsg {
-                                    bytes_downloaded: prev + n as u64,
-                                    total_bytes,
-                                    files_done: files_done.load(Ordering::Relaxed),
-                                    files_total,
-                                    complete: false,
-                                    error: None,
-                                })
-                                .await;
-                            last_report = Instant::now();
-                        }
-                    }
-                    file.flush().await?;
-                    files_done.fetch_add(1, Ordering::Relaxed);
-                    Ok(())
-                }
-                .await;
+        let presigned = self
+            .bucket_client(bucket)
+            .await
+            .get_object()
+            .bucket(bucket)
+            .key(key)
+            .presigned(presigning_config)
+            .await
+            .map_err(|e| self.err(e))?;
 
-                drop(permit);
-                result
-            });
-            handles.push(handle);
-        }
+        Ok(presigned.uri().to_string())
+    }
 
-        // Wait for all downloads
-        let mut errors = Vec::new();
-        for handle in handles {
-            if let Ok(Err(e)) = handle.await {
-                errors.push(e.to_string());
-            }
-        }
+    fn partials(&self) -> &PartFiles {
+        &self.partials
+    }
 
-        if !errors.is_empty() {
-            anyhow::bail!("{} files failed: {}", errors.len(), errors[0]);
+    fn object_url(&self, bucket: &str, key: &str) -> Option<String> {
+        Some(self.public_url(bucket, key))
+    }
+
+    fn credentials_expiry(&self) -> Option<Timestamp> {
+        match &self.credential_process {
+            Some(process) => process.expiry(),
+            None => self.session_expiry,
         }
+    }
+
+    fn renews_credentials(&self) -> bool {
+        self.credential_process.is_some()
+    }
 
+    async fn renew_credentials(&self) -> Result<()> {
+        if let Some(process) = &self.credential_process {
+            process.credentials().await?;
+        }
         Ok(())
     }
+
+    async fn current_keys(&self) -> Result<Option<SigningKeys>> {
+        let Some(process) = &self.credential_process else {
+            return Ok(None);
+        };
+        let credentials = process.credentials().await?;
+        Ok(Some(SigningKeys {
+            access_key: credentials.access_key_id().to_string(),
+            secret_key: credentials.secret_access_key().to_string(),
+            session_token: credentials.session_token().map(str::to_string),
+        }))
+    }
 }
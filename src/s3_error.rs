@@ -0,0 +1,138 @@
+use std::fmt;
+
+use aws_sdk_s3::error::{DisplayErrorContext, ProvideErrorMetadata, SdkError};
+
+/// Broad categories of S3 failures, used to pick an actionable message.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum S3ErrorKind {
+    InvalidAccessKey,
+    ExpiredToken,
+    SignatureMismatch,
+    AccessDenied,
+    NoSuchBucket,
+    NotFound,
+    Dns,
+    Tls,
+    Timeout,
+    Connection,
+    Other,
+}
+
+/// An SDK error classified into something the user can act on.
+#[derive(Debug)]
+pub struct S3Error {
+    pub kind: S3ErrorKind,
+    pub endpoint: String,
+    /// Service error code (e.g. "AccessDenied"), if the service sent one.
+    pub code: Option<String>,
+    /// HTTP status of the error response, if one was received.
+    pub status: Option<u16>,
+    /// Service error code and message, or the raw error chain.
+    pub detail: String,
+}
+
+impl fmt::Display for S3Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ep = &self.endpoint;
+        match self.kind {
+            S3ErrorKind::InvalidAccessKey => write!(f, "Access key rejected by endpoint {}", ep),
+            S3ErrorKind::ExpiredToken => {
+                write!(f, "Session credentials for {} have expired - renew them and press Shift+R", ep)
+            }
+            S3ErrorKind::SignatureMismatch => {
+                write!(f, "Secret key rejected by endpoint {} (signature mismatch)", ep)
+            }
+            S3ErrorKind::AccessDenied => {
+                write!(f, "Access denied by {} - check the key's policy", ep)
+            }
+            S3ErrorKind::NoSuchBucket => write!(f, "Bucket not found on {}", ep),
+            S3ErrorKind::NotFound => write!(f, "Object not found on {}", ep),
+            S3ErrorKind::Dns => write!(f, "Cannot resolve host of {} - check the URL", ep),
+            S3ErrorKind::Tls => {
+                write!(f, "TLS handshake with {} failed - check http/https and certificates", ep)
+            }
+            S3ErrorKind::Timeout => write!(f, "Timed out talking to {}", ep),
+            S3ErrorKind::Connection => {
+                write!(f, "Cannot connect to {} - is the server running?", ep)
+            }
+            S3ErrorKind::Other => write!(f, "{}", self.detail),
+        }
+    }
+}
+
+impl std::error::Error for S3Error {}
+
+/// Classify an SDK error (service code, HTTP status, or transport failure).
+pub fn classify<E>(err: SdkError<E>, endpoint: &str) -> anyhow::Error
+where
+    E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+{
+    let chain = DisplayErrorContext(&err).to_string();
+    let code = err.code().map(|c| c.to_string());
+    let status = err.raw_response().map(|r| r.status().as_u16());
+
+    let kind = match (code.as_deref(), status) {
+        (Some("InvalidAccessKeyId"), _) => S3ErrorKind::InvalidAccessKey,
+        (Some("ExpiredToken" | "TokenRefreshRequired"), _) => S3ErrorKind::ExpiredToken,
+        (Some("SignatureDoesNotMatch"), _) => S3ErrorKind::SignatureMismatch,
+        (Some("AccessDenied"), _) | (_, Some(403)) => S3ErrorKind::AccessDenied,
+        (Some("NoSuchBucket"), _) => S3ErrorKind::NoSuchBucket,
+        (Some("NoSuchKey"), _) | (_, Some(404)) => S3ErrorKind::NotFound,
+        _ => classify_transport(&err, &chain),
+    };
+
+    let detail = match &code {
+        Some(code) => format!("{}: {}", code, err.message().unwrap_or_default()),
+        None => chain,
+    };
+
+    S3Error {
+        kind,
+        endpoint: endpoint.to_string(),
+        code,
+        status,
+        detail,
+    }
+    .into()
+}
+
+fn classify_transport<E>(err: &SdkError<E>, chain: &str) -> S3ErrorKind {
+    let lower = chain.to_lowercase();
+    match err {
+        SdkError::TimeoutError(_) => S3ErrorKind::Timeout,
+        SdkError::DispatchFailure(failure) => {
+            if lower.contains("dns error") || lower.contains("failed to lookup address") {
+                S3ErrorKind::Dns
+            } else if lower.contains("certificate")
+                || lower.contains("tls")
+                || lower.contains("ssl")
+            {
+                S3ErrorKind::Tls
+            } else if failure.is_timeout() {
+                S3ErrorKind::Timeout
+            } else if failure.is_io() {
+                S3ErrorKind::Connection
+            } else {
+                S3ErrorKind::Other
+            }
+        }
+        _ => S3ErrorKind::Other,
+    }
+}
+
+/// The classified kind of an error, if it came from [`classify`].
+pub fn kind_of(err: &anyhow::Error) -> Option<S3ErrorKind> {
+    err.downcast_ref::<S3Error>().map(|e| e.kind)
+}
+
+/// Whether a ListObjectsV2 failure means the endpoint doesn't implement v2
+/// (or rejects its continuation tokens), so a v1 retry makes sense.
+pub fn is_unsupported_v2(err: &anyhow::Error) -> bool {
+    let Some(e) = err.downcast_ref::<S3Error>() else {
+        return false;
+    };
+    matches!(
+        e.code.as_deref(),
+        Some("NotImplemented" | "InvalidArgument" | "InvalidRequest" | "InvalidToken")
+    ) || matches!(e.status, Some(400 | 501))
+}
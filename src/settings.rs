@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::credentials::RemoteSettings;
+use crate::hooks::HooksConfig;
+use crate::plugins::PluginConfig;
+use crate::shortener::ShortenerConfig;
+
+/// Application settings from `<config dir>/s3-like-yazi/settings.json`.
+/// Every field is optional; a missing file means all defaults.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Keep the transfer history and recent search queries across sessions.
+    pub persist_history: bool,
+    /// Text preview chunk size in KB; `L` loads another chunk of this size.
+    pub preview_max_kb: u64,
+    /// Shorten shared presigned links through this endpoint.
+    pub shortener: Option<ShortenerConfig>,
+    /// Status bar segments, left to right. Whatever doesn't fit is cut off on the right.
+    pub status_bar: Vec<StatusSegment>,
+    /// External programs run on hooks and from the command palette.
+    pub plugins: Vec<PluginConfig>,
+    /// Shell commands bound to keys.
+    pub actions: Vec<ActionConfig>,
+    /// Shell commands run before and after deletes, uploads and downloads.
+    pub hooks: HooksConfig,
+    /// Connect every remote and list its buckets in the background at startup.
+    pub prewarm_remotes: bool,
+    /// List a directory in the background once the cursor rests on it.
+    pub prefetch_dirs: bool,
+    /// Index a bucket for search as soon as it's opened; otherwise the first search does.
+    pub auto_index: bool,
+    /// Pause indexing and ask before going past this many objects; 0 never asks.
+    pub index_confirm_above: usize,
+    /// File format of bucket inventory reports (`Shift+W`).
+    pub inventory_format: InventoryFormat,
+    /// Rows in the largest and recently-modified quick views (`Shift+V`).
+    pub quick_view_limit: usize,
+    /// Browser table columns after the mark/icon column, left to right.
+    pub columns: Vec<ColumnSpec>,
+    /// File-type icons from a Nerd Font; off shows letters for buckets and directories.
+    pub nerd_icons: bool,
+    /// Which operations ask before they run.
+    pub confirm: ConfirmConfig,
+    /// Tag, protection and the other s3-like-yazi options of each alias, by name.
+    pub remotes: HashMap<String, RemoteSettings>,
+}
+
+/// When to ask before an operation, from the `confirm` block of settings.json.
+/// Deletes on protected remotes ask for the remote name whatever this says.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ConfirmConfig {
+    /// Ask before deleting a single object...
+    pub delete_file: bool,
+    /// ...unless it is smaller than this many bytes.
+    pub delete_file_below: u64,
+    /// Review the plan before deleting a prefix.
+    pub delete_prefix: bool,
+    /// Review the plan before downloading a prefix.
+    pub download: bool,
+    /// Ask before quitting while a transfer runs.
+    pub quit: bool,
+}
+
+impl Default for ConfirmConfig {
+    fn default() -> Self {
+        Self {
+            delete_file: true,
+            delete_file_below: 0,
+            delete_prefix: true,
+            download: true,
+            quit: true,
+        }
+    }
+}
+
+/// A shell command bound to a key, from the `actions` list of settings.json.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ActionConfig {
+    /// Normal-mode key; takes precedence over the built-in binding of the same key.
+    pub key: char,
+    /// Run through `sh -c` after substituting `{remote}`, `{bucket}`, `{key}`,
+    /// `{name}`, `{prefix}`, `{uri}` and `{local}` (shell-quoted).
+    pub cmd: String,
+    /// Title of the output popup and palette entry; defaults to the command.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl ActionConfig {
+    pub fn title(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.cmd)
+    }
+}
+
+/// A piece of the status bar that can be turned on and reordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusSegment {
+    /// Key hints for the current mode.
+    Hints,
+    /// Object count and size of the current listing.
+    Totals,
+    /// Bucket index progress.
+    Index,
+    /// Compact transfer speed; when shown, it replaces the full progress line.
+    Speed,
+    /// Remote alias and bucket.
+    Remote,
+    /// Local time.
+    Clock,
+    /// Number of running background jobs and the oldest one's name.
+    Tasks,
+    /// Time until the current remote's temporary credentials expire.
+    Credentials,
+}
+
+/// A column of the browser table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    Name,
+    /// Object size; "dir" and "bucket" for the others.
+    Size,
+    Modified,
+    Etag,
+    StorageClass,
+}
+
+impl Column {
+    /// Width when the config gives none; `None` for the name, which fills what's left.
+    pub fn default_width(self) -> Option<u16> {
+        match self {
+            Column::Name => None,
+            Column::Size => Some(10),
+            Column::Modified => Some(16),
+            Column::Etag => Some(32),
+            Column::StorageClass => Some(12),
+        }
+    }
+}
+
+/// A column and its width: `"size"` or `{"column": "etag", "width": 12}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(from = "ColumnEntry")]
+pub struct ColumnSpec {
+    pub column: Column,
+    pub width: Option<u16>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ColumnEntry {
+    Plain(Column),
+    Sized {
+        column: Column,
+        #[serde(default)]
+        width: Option<u16>,
+    },
+}
+
+impl From<ColumnEntry> for ColumnSpec {
+    fn from(entry: ColumnEntry) -> Self {
+        match entry {
+            ColumnEntry::Plain(column) => ColumnSpec { column, width: None },
+            ColumnEntry::Sized { column, width } => ColumnSpec { column, width },
+        }
+    }
+}
+
+/// File format of a bucket inventory report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InventoryFormat {
+    Csv,
+    Parquet,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            persist_history: true,
+            preview_max_kb: 512,
+            shortener: None,
+            status_bar: vec![StatusSegment::Credentials, StatusSegment::Hints, StatusSegment::Totals],
+            plugins: Vec::new(),
+            actions: Vec::new(),
+            hooks: HooksConfig::default(),
+            prewarm_remotes: true,
+            prefetch_dirs: true,
+            auto_index: true,
+            index_confirm_above: 100_000,
+            inventory_format: InventoryFormat::Csv,
+            quick_view_limit: 100,
+            columns: [Column::Name, Column::Size, Column::Modified]
+                .into_iter()
+                .map(|column| ColumnSpec { column, width: None })
+                .collect(),
+            nerd_icons: false,
+            confirm: ConfirmConfig::default(),
+            remotes: HashMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> anyhow::Result<Self> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    fn path() -> Option<PathBuf> {
+        Some(crate::storage::config_dir()?.join("settings.json"))
+    }
+}
@@ -0,0 +1,82 @@
+use std::io::Write;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::storage;
+
+const LOG_FILE: &str = "shortlinks.tsv";
+
+/// Self-hosted URL shortener, from the `shortener` block of settings.json.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShortenerConfig {
+    /// Endpoint that accepts `POST {"url": "<long url>"}`.
+    pub url: String,
+    /// Sent as `Authorization: Bearer <token>` when set.
+    #[serde(default)]
+    pub token: Option<String>,
+    /// JSON field (dotted for nested objects) holding the short link. Without it,
+    /// common field names are tried and a plain-text body is used as is.
+    #[serde(default)]
+    pub field: Option<String>,
+}
+
+/// Common response fields of self-hosted shorteners (Shlink, Kutt, YOURLS, ...).
+const KNOWN_FIELDS: &[&str] = &["shortUrl", "short_url", "shorturl", "link", "short"];
+
+/// Shorten `long_url` and log the mapping locally.
+pub async fn shorten(config: &ShortenerConfig, long_url: &str) -> anyhow::Result<String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()?;
+    let mut request = client
+        .post(&config.url)
+        .json(&serde_json::json!({ "url": long_url }));
+    if let Some(token) = &config.token {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await?.error_for_status()?;
+    let body = response.text().await?;
+
+    let short = short_link(config, &body)
+        .ok_or_else(|| anyhow::anyhow!("No short link in shortener response"))?;
+    log_mapping(&short, long_url);
+    Ok(short)
+}
+
+/// Pick the short link out of a response body.
+fn short_link(config: &ShortenerConfig, body: &str) -> Option<String> {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(body) else {
+        let text = body.trim();
+        return text.starts_with("http").then(|| text.to_string());
+    };
+    let lookup = |path: &str| {
+        path.split('.')
+            .try_fold(&json, |value, part| value.get(part))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    match &config.field {
+        Some(field) => lookup(field),
+        None => KNOWN_FIELDS.iter().find_map(|f| lookup(f)),
+    }
+}
+
+/// Append `time short long` to the mapping log in the data dir; best effort.
+fn log_mapping(short: &str, long_url: &str) {
+    let Some(dir) = storage::data_dir() else {
+        return;
+    };
+    let _ = std::fs::create_dir_all(&dir);
+    let Ok(mut file) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(LOG_FILE))
+    else {
+        return;
+    };
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let _ = writeln!(file, "{}\t{}\t{}", secs, short, long_url);
+}
@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Directory for state persisted between sessions (`<data dir>/s3-like-yazi`).
+pub fn data_dir() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("s3-like-yazi"))
+}
+
+/// Directory of the hand-edited settings (`<config dir>/s3-like-yazi`).
+pub fn config_dir() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("s3-like-yazi"))
+}
+
+/// Load a JSON state file, falling back to the default when missing or unreadable.
+pub fn load_json<T: DeserializeOwned + Default>(name: &str) -> T {
+    data_dir()
+        .map(|dir| dir.join(name))
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Save a JSON state file, creating the data directory if needed.
+pub fn save_json<T: Serialize>(name: &str, value: &T) -> anyhow::Result<()> {
+    let dir = data_dir().ok_or_else(|| anyhow::anyhow!("Cannot determine data directory"))?;
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(name);
+    let content = serde_json::to_string_pretty(value)?;
+    std::fs::write(&path, content)
+        .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))?;
+    Ok(())
+}
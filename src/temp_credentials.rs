@@ -0,0 +1,118 @@
+//! Temporary credentials: keys printed by an alias's `credential_process`
+//! command, fetched again shortly before they expire.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::{Context, Result};
+use aws_credential_types::provider::{self, error::CredentialsError, future};
+use aws_sdk_s3::config::Credentials;
+use serde::Deserialize;
+
+use crate::object_store::Timestamp;
+
+/// Credentials this close to expiring are renewed before the next request.
+pub const RENEW_BEFORE: Duration = Duration::from_secs(5 * 60);
+
+/// The command isn't run again sooner than this, e.g. when it keeps printing
+/// cached credentials that are already due for renewal.
+const RERUN_AFTER: Duration = Duration::from_secs(30);
+
+/// What a `credential_process` prints, in the AWS CLI's format.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ProcessOutput {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+    expiration: Option<String>,
+}
+
+/// Credentials from a shell command, kept until they are about to expire.
+/// Clones share them.
+#[derive(Debug, Clone)]
+pub struct CredentialProcess {
+    command: String,
+    /// The credentials fetched last, and when.
+    current: Arc<Mutex<Option<(Credentials, Instant)>>>,
+    /// One run at a time; requests arriving meanwhile wait for its result.
+    running: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl CredentialProcess {
+    pub fn new(command: &str) -> Self {
+        Self {
+            command: command.to_string(),
+            current: Arc::default(),
+            running: Arc::default(),
+        }
+    }
+
+    /// When the credentials fetched last expire.
+    pub fn expiry(&self) -> Option<Timestamp> {
+        let current = self.current.lock().unwrap();
+        current.as_ref()?.0.expiry().map(Timestamp::from)
+    }
+
+    /// The current credentials, running the command first when there are none
+    /// yet or they expire within `RENEW_BEFORE`.
+    pub async fn credentials(&self) -> Result<Credentials> {
+        let _running = self.running.lock().await;
+        if let Some((current, fetched_at)) = self.current.lock().unwrap().clone() {
+            let now = SystemTime::now();
+            let fresh = current.expiry().is_none_or(|at| at > now + RENEW_BEFORE);
+            let just_run = fetched_at.elapsed() < RERUN_AFTER && current.expiry().is_none_or(|at| at > now);
+            if fresh || just_run {
+                return Ok(current);
+            }
+        }
+        let fetched = self.run().await?;
+        *self.current.lock().unwrap() = Some((fetched.clone(), Instant::now()));
+        Ok(fetched)
+    }
+
+    async fn run(&self) -> Result<Credentials> {
+        let output = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .output()
+            .await
+            .with_context(|| format!("Failed to run credential_process `{}`", self.command))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "credential_process `{}` failed ({}): {}",
+                self.command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let printed: ProcessOutput = serde_json::from_slice(&output.stdout)
+            .with_context(|| format!("credential_process `{}` printed no credentials JSON", self.command))?;
+        let expiry = printed.expiration.as_deref().map(parse_expiration).transpose()?;
+        Ok(Credentials::new(
+            printed.access_key_id,
+            printed.secret_access_key,
+            printed.session_token,
+            expiry.map(SystemTime::from),
+            "credential_process",
+        ))
+    }
+}
+
+impl provider::ProvideCredentials for CredentialProcess {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(async move {
+            self.credentials().await.map_err(|e| CredentialsError::provider_error(e.to_string()))
+        })
+    }
+}
+
+/// An RFC 3339 expiry time, as STS and the alias `expiration` give it.
+pub fn parse_expiration(s: &str) -> Result<Timestamp> {
+    chrono::DateTime::parse_from_rfc3339(s)
+        .map(|t| t.to_utc())
+        .with_context(|| format!("Invalid expiration time '{}'", s))
+}
@@ -0,0 +1,43 @@
+use std::io;
+
+use crossterm::cursor::Show;
+use crossterm::execute;
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+
+/// Raw mode and the alternate screen for as long as it lives; dropping it (also on an
+/// early return or unwind) puts the terminal back.
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    pub fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        // Constructed before the screen switch so a failure there still undoes raw mode
+        let guard = TerminalGuard;
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+/// Leave raw mode and the alternate screen and show the cursor. Safe to call twice.
+pub fn restore() {
+    let _ = terminal::disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, Show);
+}
+
+/// Restore the terminal before the panic is printed, so the message lands on the normal
+/// screen instead of vanishing with the alternate one, then hand over to the previous
+/// hook. A panic on the main task unwinds out of `run`; one in a background job is
+/// picked up by the event loop, which returns an error so cleanup still runs.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore();
+        previous(info);
+    }));
+}
@@ -0,0 +1,88 @@
+use ratatui::style::Color;
+
+/// Kind of file, from its extension, which picks its icon and color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Image,
+    Video,
+    Audio,
+    Archive,
+    Document,
+    Data,
+    Code,
+    Other,
+}
+
+fn category(name: &str) -> Category {
+    let Some((_, ext)) = name.rsplit_once('.') else {
+        return Category::Other;
+    };
+    match ext.to_lowercase().as_str() {
+        "jpg" | "jpeg" | "png" | "gif" | "webp" | "bmp" | "ico" | "tiff" | "tif" | "svg" | "heic" | "avif" | "raw" => {
+            Category::Image
+        }
+        "mp4" | "mkv" | "avi" | "mov" | "webm" | "flv" | "wmv" | "m4v" | "3gp" => Category::Video,
+        "mp3" | "wav" | "flac" | "ogg" | "oga" | "opus" | "m4a" | "aac" | "wma" | "aif" | "aiff" => Category::Audio,
+        "zip" | "gz" | "tgz" | "bz2" | "xz" | "zst" | "7z" | "rar" | "tar" | "lz4" | "br" => Category::Archive,
+        "pdf" | "doc" | "docx" | "odt" | "rtf" | "txt" | "md" | "markdown" | "xls" | "xlsx" | "ppt" | "pptx" | "epub" => {
+            Category::Document
+        }
+        "json" | "jsonl" | "ndjson" | "csv" | "tsv" | "parquet" | "avro" | "orc" | "yaml" | "yml" | "toml" | "xml"
+        | "sqlite" | "db" | "log" => Category::Data,
+        "sh" | "bash" | "zsh" | "fish" | "py" | "rs" | "go" | "js" | "ts" | "jsx" | "tsx" | "html" | "htm" | "css" | "scss"
+        | "sql" | "rb" | "lua" | "c" | "cpp" | "h" | "hpp" | "java" | "kt" | "swift" | "php" | "tf" | "hcl" => {
+            Category::Code
+        }
+        _ => Category::Other,
+    }
+}
+
+/// Icon column glyph of a bucket.
+pub fn bucket(nerd: bool) -> &'static str {
+    if nerd { "\u{f1c0}" } else { "B" } // nf-fa-database
+}
+
+/// Padlock shown on buckets locked against writes.
+pub fn lock(nerd: bool) -> &'static str {
+    if nerd { "\u{f023}" } else { "\u{1f512}" } // nf-fa-lock, 🔒
+}
+
+/// Icon column glyph of a directory, open when expanded in the tree.
+pub fn dir(nerd: bool, open: bool) -> &'static str {
+    match (nerd, open) {
+        (true, true) => "\u{f07c}", // nf-fa-folder_open
+        (true, false) => "\u{f07b}", // nf-fa-folder
+        (false, _) => "D",
+    }
+}
+
+/// Icon column glyph of a file and the color of its icon and name, by extension.
+/// Without Nerd Fonts the glyph is blank and only the color tells the kind apart.
+pub fn file(name: &str, nerd: bool) -> (&'static str, Color) {
+    let category = category(name);
+    let color = match category {
+        Category::Image => Color::Yellow,
+        Category::Video => Color::LightMagenta,
+        Category::Audio => Color::Cyan,
+        Category::Archive => Color::Red,
+        Category::Document => Color::LightBlue,
+        Category::Data => Color::LightGreen,
+        Category::Code => Color::Green,
+        Category::Other => Color::White,
+    };
+    if !nerd {
+        return (" ", color);
+    }
+    let glyph = match category {
+        Category::Image => "\u{f1c5}",    // nf-fa-file_image_o
+        Category::Video => "\u{f1c8}",    // nf-fa-file_video_o
+        Category::Audio => "\u{f1c7}",    // nf-fa-file_audio_o
+        Category::Archive => "\u{f1c6}",  // nf-fa-file_archive_o
+        Category::Document if name.to_lowercase().ends_with(".pdf") => "\u{f1c1}", // nf-fa-file_pdf_o
+        Category::Document => "\u{f15c}", // nf-fa-file_text
+        Category::Data => "\u{f0ce}",     // nf-fa-table
+        Category::Code => "\u{f1c9}",     // nf-fa-file_code_o
+        Category::Other => "\u{f15b}",    // nf-fa-file
+    };
+    (glyph, color)
+}
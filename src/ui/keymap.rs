@@ -0,0 +1,226 @@
+use crate::app::help::HelpMode;
+
+/// One row of the help popup. The tables below are the only source of the help
+/// text; when a key changes in the event loop, change it here too.
+pub struct Binding {
+    pub keys: &'static str,
+    pub desc: &'static str,
+}
+
+const fn b(keys: &'static str, desc: &'static str) -> Binding {
+    Binding { keys, desc }
+}
+
+const NORMAL: &[Binding] = &[
+    b("j / Down", "Move cursor down"),
+    b("k / Up", "Move cursor up"),
+    b("l / Enter", "Open / select item"),
+    b("a", "Actions menu for the selected entry"),
+    b("h / Bksp", "Go back / parent dir"),
+    b("Tab", "Switch pane"),
+    b("z", "Zoom focused pane / preview"),
+    b("m", "Show / hide metadata panel"),
+    b("gg / ge", "First / last entry"),
+    b("gr / gb", "Bucket root / bucket list"),
+    b("/ or Ctrl+P", "Search all objects"),
+    b("x / Shift+X", "Pause or resume / stop bucket indexing"),
+    b("n / N", "Next / prev match of the last search here"),
+    b("r", "Refresh current view"),
+    b("Shift+R", "Reload mc config (aliases)"),
+    b("b", "Open a bucket by name"),
+    b("Shift+C", "Download (copy) to local"),
+    b("d / Cmd+Bksp", "Delete file or directory"),
+    b("Shift+D", "Delete redundant directory markers here"),
+    b("p", "Preview file"),
+    b("i", "Peek: type, encoding, first line (1 KB read)"),
+    b("o / Shift+O", "Sort by name/size/date / reverse"),
+    b("Ctrl+N", "Toggle natural sort"),
+    b("Ctrl+G", "Toggle directories first"),
+    b("\\", "Filter listing by name (remembered)"),
+    b("Shift+H", "Transfer history"),
+    b(".", "Repeat last transfer"),
+    b("f", "Pin object or directory to favorites"),
+    b("Shift+F", "Favorites panel"),
+    b("Shift+S", "Image slideshow"),
+    b("yy", "Copy to any remote"),
+    b("yk / yu", "Copy key / s3:// URI to clipboard"),
+    b("ys / yw", "Copy key quoted for a shell / percent-encoded URL"),
+    b("yc / ya", "Copy mc / aws CLI commands for marked objects"),
+    b("Shift+M", "Move to any remote"),
+    b(":", "Plugin command palette"),
+    b("!", "Shell here (S3Y_* and AWS_* env set)"),
+    b("Space", "Mark / unmark object"),
+    b("Shift+E / Shift+I", "Export marked keys / import an exported list"),
+    b("Shift+W", "Write an inventory report of the bucket"),
+    b("Shift+V", "Quick views: largest / recently modified objects"),
+    b("Shift+T", "Tree view: l / h expand and collapse in place"),
+    b("Shift+L", "Lock this bucket against writes for the session"),
+    b("w", "Who am I: endpoint, access key and provider of the remote"),
+    b("Shift+K", "Switch the remote to its next credentials profile"),
+    b("Shift+J", "Jump list of prefixes visited this session"),
+    b("Shift+A", "Set the canned ACL of the selected object"),
+    b("Shift+U", "New object from $EDITOR or the clipboard"),
+    b("Shift+Y", "Duplicate the selected object next to it"),
+    b("Shift+P", "Presigned links for marked objects"),
+    b("Esc", "Dismiss notifications / metadata, clear marks"),
+    b("? / F1", "This help"),
+    b("q", "Quit (asks first while a transfer runs)"),
+];
+
+const DOWNLOAD: &[Binding] = &[
+    b("j / k", "Move in the focused pane"),
+    b("l / Enter", "Open local directory"),
+    b("h / Bksp", "Parent directory"),
+    b("Tab", "Switch pane"),
+    b("c", "Confirm download here"),
+    b("n", "Rename file before saving"),
+    b("/", "Filter the local listing by name"),
+    b("s", "Sync directory (Tab direction, p policy, a ACL, Enter plan)"),
+    b("r", "Save a byte range (first 100MB, last 1G, 0-4096)"),
+    b("z", "Zoom focused pane"),
+    b("Esc", "Cancel download"),
+];
+
+const SEARCH: &[Binding] = &[
+    b("Type", "Filter by name"),
+    b("Up / Down", "Navigate results; recent queries when empty"),
+    b("Enter", "Jump to file; expand or collapse a prefix group"),
+    b("Tab", "Expand or collapse the prefix group at the cursor"),
+    b("Ctrl+G", "Group results by prefix / flat list"),
+    b("Ctrl+O", "Peek at result's folder, keep results"),
+    b("F1", "This help"),
+    b("Esc", "Cancel search"),
+];
+
+const CONTEXT: &[Binding] = &[
+    b("j / k", "Move"),
+    b("l / Enter", "Open directory"),
+    b("h / Bksp", "Parent directory"),
+    b("Esc / q", "Back to the search results"),
+];
+
+const QUICK_VIEW: &[Binding] = &[
+    b("j / k", "Move"),
+    b("1-3 / Enter", "Show a view from the menu"),
+    b("l / Enter", "Open the object's folder"),
+    b("r", "Refresh from the index"),
+    b("h / Esc", "Back to the menu"),
+    b("q / Shift+V", "Close"),
+];
+
+const ENTRY_MENU: &[Binding] = &[
+    b("j / k", "Move"),
+    b("l / Enter", "Do it"),
+    b("Esc / q / a", "Close"),
+];
+
+const PREVIEW: &[Binding] = &[
+    b("j / k", "Scroll by line"),
+    b("Ctrl+D / Ctrl+U", "Scroll by page"),
+    b("h / l", "Scroll horizontally"),
+    b("0", "Back to column 1"),
+    b("g / G", "Top / bottom"),
+    b("w", "Toggle line wrapping"),
+    b("y", "Copy preview text"),
+    b("Shift+L", "Load the next chunk"),
+    b("z", "Zoom preview"),
+    b("q / Esc", "Close preview"),
+];
+
+const MEDIA: &[Binding] = &[
+    b("p / Enter", "Play"),
+    b("s", "Stop playback"),
+    b("z", "Zoom preview"),
+    b("q / Esc", "Close preview"),
+];
+
+const SLIDESHOW: &[Binding] = &[
+    b("n / l / Space", "Next image"),
+    b("N / h", "Previous image"),
+    b("Esc / q / S", "Stop"),
+];
+
+const FAVORITES: &[Binding] = &[
+    b("j / k", "Move"),
+    b("l / Enter", "Jump to favorite"),
+    b("p", "Jump and preview"),
+    b("c", "Download"),
+    b("s", "Share a presigned link"),
+    b("a", "List a pinned directory under remotes"),
+    b("d", "Remove favorite"),
+    b("Esc / q / F", "Close"),
+];
+
+const HISTORY: &[Binding] = &[
+    b("j / k", "Move"),
+    b("Enter", "Re-run transfer"),
+    b("Esc / q / H", "Close"),
+];
+
+const PLAN: &[Binding] = &[
+    b("j / k", "Scroll the plan"),
+    b("Enter", "Run it"),
+    b("e", "Export the plan as TSV"),
+    b("Esc / q", "Cancel"),
+];
+
+const COPY_PICKER: &[Binding] = &[
+    b("j / k", "Move"),
+    b("l / Enter", "Open remote, bucket or prefix"),
+    b("h / Bksp", "Go up"),
+    b("o", "Options: storage class (s), encryption (e), metadata (m)"),
+    b("c", "Copy / move into the current prefix"),
+    b("Esc / q", "Cancel"),
+];
+
+/// Second keys of the `g` (go) and `y` (yank) sequences, shown while one is pending.
+const GO: &[Binding] = &[
+    b("g", "First entry"),
+    b("e", "Last entry"),
+    b("r", "Bucket root"),
+    b("b", "Bucket list"),
+];
+
+const YANK: &[Binding] = &[
+    b("y", "Copy / move picker: copy to any remote"),
+    b("k", "Object key to clipboard"),
+    b("s", "Key quoted for a shell to clipboard"),
+    b("u", "s3:// URI to clipboard"),
+    b("w", "URL with the key percent-encoded to clipboard"),
+    b("c", "mc cp commands to clipboard"),
+    b("a", "aws s3 cp commands to clipboard"),
+];
+
+pub fn completions(prefix: char) -> &'static [Binding] {
+    match prefix {
+        'g' => GO,
+        'y' => YANK,
+        _ => &[],
+    }
+}
+
+const ACTION_OUTPUT: &[Binding] = &[
+    b("j / k", "Scroll"),
+    b("PgDn / PgUp", "Scroll by page"),
+    b("y", "Copy the output"),
+    b("Esc / q", "Close"),
+];
+
+pub fn bindings(mode: HelpMode) -> &'static [Binding] {
+    match mode {
+        HelpMode::Normal => NORMAL,
+        HelpMode::Download => DOWNLOAD,
+        HelpMode::Search => SEARCH,
+        HelpMode::Context => CONTEXT,
+        HelpMode::QuickView => QUICK_VIEW,
+        HelpMode::EntryMenu => ENTRY_MENU,
+        HelpMode::Preview => PREVIEW,
+        HelpMode::Media => MEDIA,
+        HelpMode::Slideshow => SLIDESHOW,
+        HelpMode::Favorites => FAVORITES,
+        HelpMode::History => HISTORY,
+        HelpMode::Plan => PLAN,
+        HelpMode::CopyPicker => COPY_PICKER,
+        HelpMode::ActionOutput => ACTION_OUTPUT,
+    }
+}
@@ -0,0 +1,386 @@
+//! Which action a key press stands for in the mode the app is in. Nothing here
+//! changes state; the event loop hands the action to `App::dispatch`.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::app::action::Action;
+use crate::app::cli::CliTool;
+use crate::app::links::{KeyForm, LinkTarget};
+use crate::app::mode::{
+    Answer, ConflictAction, ContextAction, CopyOptionsAction, CopyPickerAction,
+    DeleteConfirmAction, DownloadAction, FavoritesAction, HelpAction, ListAction, MediaAction,
+    Mode, OutputAction, PickerAction, PlanAction, PopupAction, PromptAction, QuickViewAction,
+    ResumeAction, SearchAction, SlideshowAction, SyncSetupAction, TextPreviewAction,
+    LinkBundleAction,
+};
+use crate::app::quick_views::QuickKind;
+use crate::app::App;
+
+/// The action a key stands for right now, if any.
+pub(super) fn key_action(app: &App, key: KeyEvent) -> Option<Action> {
+    let mode = app.mode();
+    if mode != Mode::Help
+        && (key.code == KeyCode::F(1) || key.code == KeyCode::Char('?') && !mode.takes_text())
+    {
+        return Some(Action::Help);
+    }
+    match mode {
+        Mode::Sequence(prefix) => {
+            // Anything but a known second key cancels the sequence
+            Some(sequence_action(prefix, key.code).unwrap_or(Action::Pending(None)))
+        }
+        Mode::Normal => {
+            if let KeyCode::Char(c) = key.code
+                && !key.modifiers.contains(KeyModifiers::CONTROL)
+                && let Some(index) = app.action_for_key(c)
+            {
+                // User-defined actions override the built-in key
+                return Some(Action::Custom(index));
+            }
+            normal_action(key)
+        }
+        mode => popup_action(mode, key).map(Action::Popup),
+    }
+}
+
+/// The browser action a key stands for in normal mode.
+fn normal_action(key: KeyEvent) -> Option<Action> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let action = match key.code {
+        KeyCode::Char(c @ ('g' | 'y')) if !ctrl => Action::Pending(Some(c)),
+        KeyCode::Char('!') => Action::Shell,
+        KeyCode::Char('q') => Action::Quit,
+        KeyCode::Char('p') if ctrl => Action::Search,
+        KeyCode::Char('C') => Action::DownloadMode,
+        KeyCode::Char('/') => Action::Search,
+        KeyCode::Up | KeyCode::Char('k') => Action::MoveUp,
+        KeyCode::Down | KeyCode::Char('j') => Action::MoveDown,
+        KeyCode::Enter | KeyCode::Char('l') => Action::Select,
+        KeyCode::Backspace if key.modifiers.contains(KeyModifiers::SUPER) => Action::Delete,
+        KeyCode::Char('d') => Action::Delete,
+        KeyCode::Char('D') => Action::CleanMarkers,
+        KeyCode::Backspace | KeyCode::Char('h') => Action::Back,
+        KeyCode::Char('r') => Action::Refresh,
+        KeyCode::Char('R') => Action::ReloadConfig,
+        KeyCode::Char('b') => Action::BucketInput,
+        KeyCode::Tab => Action::SwitchPane,
+        KeyCode::Char('p') => Action::Preview,
+        KeyCode::Char('i') => Action::Peek,
+        KeyCode::Char('n') if ctrl => Action::ToggleNaturalSort,
+        KeyCode::Char('g') if ctrl => Action::ToggleDirsFirst,
+        KeyCode::Char('o') => Action::CycleSort,
+        KeyCode::Char('O') => Action::ReverseSort,
+        KeyCode::Char('\\') => Action::Filter,
+        KeyCode::Char('n') => Action::SearchNext,
+        KeyCode::Char('N') => Action::SearchPrev,
+        KeyCode::Char('z') => Action::ToggleZoom,
+        KeyCode::Char('H') => Action::ToggleHistory,
+        KeyCode::Char('.') => Action::RepeatTransfer,
+        KeyCode::Char('f') => Action::ToggleFavorite,
+        KeyCode::Char('F') => Action::ToggleFavorites,
+        KeyCode::Char('S') => Action::Slideshow,
+        KeyCode::Char(':') => Action::Palette,
+        KeyCode::Char(' ') => Action::ToggleMark,
+        KeyCode::Char('E') => Action::ExportMarks,
+        KeyCode::Char('I') => Action::ImportMarks,
+        KeyCode::Char('W') => Action::Inventory,
+        KeyCode::Char('V') => Action::QuickViews,
+        KeyCode::Char('T') => Action::ToggleTree,
+        KeyCode::Char('L') => Action::ToggleBucketLock,
+        KeyCode::Char('w') => Action::Identity,
+        KeyCode::Char('K') => Action::CycleProfile,
+        KeyCode::Char('J') => Action::Jumps,
+        KeyCode::Char('A') => Action::SetAcl,
+        KeyCode::Char('U') => Action::NewObject,
+        KeyCode::Char('Y') => Action::Duplicate,
+        KeyCode::Char('a') => Action::EntryMenu,
+        KeyCode::Char('P') => Action::LinkBundle,
+        KeyCode::Char('M') => Action::Move,
+        KeyCode::Char('m') => Action::ToggleMetadata,
+        KeyCode::Char('x') => Action::ToggleIndexPause,
+        KeyCode::Char('X') => Action::StopIndexing,
+        KeyCode::Esc => Action::Dismiss,
+        _ => return None,
+    };
+    Some(action)
+}
+
+/// The action for the second key of a `g`/`y` sequence.
+fn sequence_action(prefix: char, code: KeyCode) -> Option<Action> {
+    let action = match (prefix, code) {
+        ('g', KeyCode::Char('g')) => Action::JumpTop,
+        ('g', KeyCode::Char('e')) => Action::JumpBottom,
+        ('g', KeyCode::Char('r')) => Action::GoBucketRoot,
+        ('g', KeyCode::Char('b')) => Action::GoBucketList,
+        ('y', KeyCode::Char('y')) => Action::Copy,
+        ('y', KeyCode::Char('k')) => Action::YankKey(KeyForm::Key),
+        ('y', KeyCode::Char('s')) => Action::YankKey(KeyForm::Shell),
+        ('y', KeyCode::Char('u')) => Action::YankKey(KeyForm::Uri),
+        ('y', KeyCode::Char('w')) => Action::YankKey(KeyForm::Url),
+        ('y', KeyCode::Char('c')) => Action::YankCommands(CliTool::Mc),
+        ('y', KeyCode::Char('a')) => Action::YankCommands(CliTool::Aws),
+        _ => return None,
+    };
+    Some(action)
+}
+
+/// Keys of a one-line text prompt.
+fn prompt(code: KeyCode) -> Option<PromptAction> {
+    let action = match code {
+        KeyCode::Esc => PromptAction::Cancel,
+        KeyCode::Enter => PromptAction::Submit,
+        KeyCode::Backspace => PromptAction::Backspace,
+        KeyCode::Char(c) => PromptAction::Input(c),
+        _ => return None,
+    };
+    Some(action)
+}
+
+/// Keys of a list narrowed by typing.
+fn picker(code: KeyCode) -> Option<PickerAction> {
+    let action = match code {
+        KeyCode::Esc => PickerAction::Cancel,
+        KeyCode::Enter => PickerAction::Submit,
+        KeyCode::Up => PickerAction::Move(false),
+        KeyCode::Down => PickerAction::Move(true),
+        KeyCode::Backspace => PickerAction::Backspace,
+        KeyCode::Char(c) => PickerAction::Input(c),
+        _ => return None,
+    };
+    Some(action)
+}
+
+/// The action a key stands for in a popup or sub-mode.
+fn popup_action(mode: Mode, key: KeyEvent) -> Option<PopupAction> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let code = key.code;
+    let action = match mode {
+        Mode::Help => PopupAction::Help(match code {
+            KeyCode::Esc | KeyCode::F(1) => HelpAction::Close,
+            KeyCode::Tab => HelpAction::CycleMode(true),
+            KeyCode::BackTab => HelpAction::CycleMode(false),
+            KeyCode::Down => HelpAction::Scroll(true, 1),
+            KeyCode::Up => HelpAction::Scroll(false, 1),
+            KeyCode::PageDown => HelpAction::Scroll(true, 10),
+            KeyCode::PageUp => HelpAction::Scroll(false, 10),
+            KeyCode::Backspace => HelpAction::Backspace,
+            KeyCode::Char(c) => HelpAction::Input(c),
+            _ => return None,
+        }),
+        Mode::ActionOutput => PopupAction::ActionOutput(match code {
+            KeyCode::Char('j') | KeyCode::Down => OutputAction::Scroll(true, 1),
+            KeyCode::Char('k') | KeyCode::Up => OutputAction::Scroll(false, 1),
+            KeyCode::PageDown => OutputAction::Scroll(true, 20),
+            KeyCode::PageUp => OutputAction::Scroll(false, 20),
+            KeyCode::Char('y') => OutputAction::Copy,
+            KeyCode::Esc | KeyCode::Char('q') => OutputAction::Close,
+            _ => return None,
+        }),
+        Mode::Palette => PopupAction::Palette(picker(code)?),
+        Mode::Jumps => PopupAction::Jumps(picker(code)?),
+        Mode::ConfirmDelete => PopupAction::ConfirmDelete(match code {
+            KeyCode::Tab => DeleteConfirmAction::Toggle,
+            KeyCode::Enter => DeleteConfirmAction::Submit,
+            KeyCode::Esc => DeleteConfirmAction::Cancel,
+            KeyCode::Backspace => DeleteConfirmAction::Backspace,
+            KeyCode::Char(c) => DeleteConfirmAction::Input(c),
+            _ => return None,
+        }),
+        Mode::ConfirmProtected => PopupAction::ConfirmProtected(prompt(code)?),
+        Mode::Plan => PopupAction::Plan(match code {
+            KeyCode::Char('j') | KeyCode::Down => PlanAction::Move(true),
+            KeyCode::Char('k') | KeyCode::Up => PlanAction::Move(false),
+            KeyCode::Enter => PlanAction::Confirm,
+            KeyCode::Char('e') => PlanAction::Export,
+            KeyCode::Esc | KeyCode::Char('q') => PlanAction::Cancel,
+            _ => return None,
+        }),
+        Mode::LinkBundle => PopupAction::LinkBundle(match code {
+            KeyCode::Char('y') => LinkBundleAction::Generate(LinkTarget::Clipboard),
+            KeyCode::Char('w') => LinkBundleAction::Generate(LinkTarget::Csv),
+            KeyCode::Esc | KeyCode::Char('q') => LinkBundleAction::Cancel,
+            _ => return None,
+        }),
+        Mode::CopyMetadata => PopupAction::CopyMetadata(prompt(code)?),
+        Mode::CopyOptions => PopupAction::CopyOptions(match code {
+            KeyCode::Char('s') => CopyOptionsAction::CycleStorageClass,
+            KeyCode::Char('e') => CopyOptionsAction::CycleSse,
+            KeyCode::Char('m') => CopyOptionsAction::EditMetadata,
+            KeyCode::Enter | KeyCode::Esc | KeyCode::Char('o' | 'q') => CopyOptionsAction::Close,
+            _ => return None,
+        }),
+        Mode::CopyPicker => PopupAction::CopyPicker(match code {
+            KeyCode::Char('o') => CopyPickerAction::Options,
+            KeyCode::Char('j') | KeyCode::Down => CopyPickerAction::Move(true),
+            KeyCode::Char('k') | KeyCode::Up => CopyPickerAction::Move(false),
+            KeyCode::Enter | KeyCode::Char('l') => CopyPickerAction::Enter,
+            KeyCode::Backspace | KeyCode::Char('h') => CopyPickerAction::Back,
+            KeyCode::Char('c') => CopyPickerAction::Confirm,
+            KeyCode::Esc | KeyCode::Char('q') => CopyPickerAction::Cancel,
+            _ => return None,
+        }),
+        Mode::Filter => PopupAction::Filter(prompt(code)?),
+        Mode::MarksImport => PopupAction::MarksImport(prompt(code)?),
+        Mode::BucketInput => PopupAction::BucketInput(prompt(code)?),
+        Mode::Duplicate => PopupAction::Duplicate(prompt(code)?),
+        Mode::NewObject if ctrl && code == KeyCode::Char('v') => {
+            PopupAction::NewObject(PromptAction::Paste)
+        }
+        Mode::NewObject => PopupAction::NewObject(prompt(code)?),
+        Mode::Identity => match code {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q' | 'w') => PopupAction::CloseIdentity,
+            _ => return None,
+        },
+        Mode::Resume => PopupAction::Resume(match code {
+            KeyCode::Char('y') | KeyCode::Enter => ResumeAction::Resume,
+            KeyCode::Char('n') => ResumeAction::Discard,
+            KeyCode::Esc | KeyCode::Char('q') => ResumeAction::Later,
+            _ => return None,
+        }),
+        Mode::ConfirmQuit => PopupAction::ConfirmQuit(match code {
+            KeyCode::Char('y') | KeyCode::Enter => Answer::Yes,
+            KeyCode::Char('n' | 'q') | KeyCode::Esc => Answer::No,
+            _ => return None,
+        }),
+        Mode::ConfirmIndex => PopupAction::ConfirmIndex(match code {
+            KeyCode::Char('y') | KeyCode::Enter => Answer::Yes,
+            KeyCode::Char('n') | KeyCode::Esc => Answer::No,
+            _ => return None,
+        }),
+        Mode::DownloadConflict => PopupAction::DownloadConflict(match code {
+            KeyCode::Char('o') => ConflictAction::Overwrite,
+            KeyCode::Char('r') => ConflictAction::Rename,
+            KeyCode::Esc | KeyCode::Char('q') => ConflictAction::Cancel,
+            _ => return None,
+        }),
+        Mode::RangeInput => PopupAction::RangeInput(prompt(code)?),
+        Mode::Rename => PopupAction::Rename(prompt(code)?),
+        Mode::SyncSetup => PopupAction::SyncSetup(match code {
+            KeyCode::Tab | KeyCode::Char('d') => SyncSetupAction::ToggleDirection,
+            KeyCode::Char('p') => SyncSetupAction::CyclePolicy,
+            KeyCode::Char('a') => SyncSetupAction::CycleAcl,
+            KeyCode::Enter => SyncSetupAction::Plan,
+            KeyCode::Esc | KeyCode::Char('q') => SyncSetupAction::Cancel,
+            _ => return None,
+        }),
+        Mode::LocalSearch => PopupAction::LocalSearch(picker(code)?),
+        Mode::Download => PopupAction::Download(match code {
+            KeyCode::Esc => DownloadAction::Cancel,
+            KeyCode::Char('j') | KeyCode::Down => DownloadAction::Move(true),
+            KeyCode::Char('k') | KeyCode::Up => DownloadAction::Move(false),
+            KeyCode::Enter | KeyCode::Char('l') => DownloadAction::Enter,
+            KeyCode::Backspace | KeyCode::Char('h') => DownloadAction::Back,
+            KeyCode::Char('c') => DownloadAction::Download,
+            KeyCode::Char('n') => DownloadAction::Rename,
+            KeyCode::Char('/') => DownloadAction::Search,
+            KeyCode::Char('s') => DownloadAction::Sync,
+            KeyCode::Char('r') => DownloadAction::Range,
+            KeyCode::Tab => DownloadAction::SwitchPane,
+            KeyCode::Char('z') => DownloadAction::Zoom,
+            _ => return None,
+        }),
+        Mode::Slideshow => PopupAction::Slideshow(match code {
+            KeyCode::Char('n' | 'l' | 'j' | ' ') | KeyCode::Right | KeyCode::Down => {
+                SlideshowAction::Step(true)
+            }
+            KeyCode::Char('N' | 'h' | 'k') | KeyCode::Left | KeyCode::Up => {
+                SlideshowAction::Step(false)
+            }
+            KeyCode::Esc | KeyCode::Char('q' | 'S') => SlideshowAction::Stop,
+            _ => return None,
+        }),
+        Mode::Favorites => PopupAction::Favorites(match code {
+            KeyCode::Char('j') | KeyCode::Down => FavoritesAction::Move(true),
+            KeyCode::Char('k') | KeyCode::Up => FavoritesAction::Move(false),
+            KeyCode::Enter | KeyCode::Char('l') => FavoritesAction::Open,
+            KeyCode::Char('p') => FavoritesAction::Preview,
+            KeyCode::Char('c') => FavoritesAction::Download,
+            KeyCode::Char('s') => FavoritesAction::Share,
+            KeyCode::Char('a') => FavoritesAction::TogglePromoted,
+            KeyCode::Char('d') => FavoritesAction::Remove,
+            KeyCode::Esc | KeyCode::Char('q' | 'F') => FavoritesAction::Close,
+            _ => return None,
+        }),
+        Mode::History => PopupAction::History(match code {
+            KeyCode::Char('j') | KeyCode::Down => ListAction::Move(true),
+            KeyCode::Char('k') | KeyCode::Up => ListAction::Move(false),
+            KeyCode::Enter => ListAction::Submit,
+            KeyCode::Esc | KeyCode::Char('q' | 'H') => ListAction::Cancel,
+            _ => return None,
+        }),
+        Mode::AclPicker => PopupAction::AclPicker(match code {
+            KeyCode::Char('j') | KeyCode::Down => ListAction::Move(true),
+            KeyCode::Char('k') | KeyCode::Up => ListAction::Move(false),
+            KeyCode::Enter => ListAction::Submit,
+            KeyCode::Esc | KeyCode::Char('q') => ListAction::Cancel,
+            _ => return None,
+        }),
+        Mode::EntryMenu => PopupAction::EntryMenu(match code {
+            KeyCode::Char('j') | KeyCode::Down => ListAction::Move(true),
+            KeyCode::Char('k') | KeyCode::Up => ListAction::Move(false),
+            KeyCode::Char('l') | KeyCode::Enter => ListAction::Submit,
+            KeyCode::Esc | KeyCode::Char('q' | 'a') => ListAction::Cancel,
+            _ => return None,
+        }),
+        Mode::QuickView(kind) => PopupAction::QuickView(match (kind, code) {
+            (_, KeyCode::Char('j') | KeyCode::Down) => QuickViewAction::Move(true),
+            (_, KeyCode::Char('k') | KeyCode::Up) => QuickViewAction::Move(false),
+            (None, KeyCode::Char(c @ '1'..='3')) => {
+                QuickViewAction::Show(QuickKind::ALL[c as usize - '1' as usize])
+            }
+            (None, KeyCode::Char('l') | KeyCode::Enter) => QuickViewAction::MenuSelect,
+            (Some(_), KeyCode::Char('l') | KeyCode::Enter) => QuickViewAction::Reveal,
+            (Some(_), KeyCode::Char('h') | KeyCode::Backspace) => QuickViewAction::Back,
+            (Some(kind), KeyCode::Char('r')) => QuickViewAction::Show(kind),
+            (_, KeyCode::Char('q' | 'V')) => QuickViewAction::Close,
+            (_, KeyCode::Esc) => QuickViewAction::Back,
+            _ => return None,
+        }),
+        Mode::Context => PopupAction::Context(match code {
+            KeyCode::Char('j') | KeyCode::Down => ContextAction::Move(true),
+            KeyCode::Char('k') | KeyCode::Up => ContextAction::Move(false),
+            KeyCode::Char('l') | KeyCode::Enter => ContextAction::Enter,
+            KeyCode::Char('h') | KeyCode::Backspace => ContextAction::Back,
+            KeyCode::Esc | KeyCode::Char('q') => ContextAction::Close,
+            _ => return None,
+        }),
+        Mode::Search => PopupAction::Search(match code {
+            KeyCode::Char('o') if ctrl => SearchAction::OpenContext,
+            KeyCode::Char('g') if ctrl => SearchAction::ToggleGrouping,
+            KeyCode::Tab => SearchAction::ToggleGroup,
+            KeyCode::Esc => SearchAction::Cancel,
+            KeyCode::Enter => SearchAction::Select,
+            KeyCode::Up => SearchAction::Move(false),
+            KeyCode::Down => SearchAction::Move(true),
+            KeyCode::Backspace => SearchAction::Backspace,
+            KeyCode::Char(c) => SearchAction::Input(c),
+            _ => return None,
+        }),
+        Mode::Media => PopupAction::Media(match code {
+            KeyCode::Char('p') | KeyCode::Enter => MediaAction::Play,
+            KeyCode::Char('s') => MediaAction::Stop,
+            KeyCode::Char('z') => MediaAction::Zoom,
+            KeyCode::Char('q') | KeyCode::Esc => MediaAction::Close,
+            _ => return None,
+        }),
+        Mode::TextPreview => PopupAction::TextPreview(match code {
+            KeyCode::Char('j') | KeyCode::Down => TextPreviewAction::Scroll(true, 1),
+            KeyCode::Char('k') | KeyCode::Up => TextPreviewAction::Scroll(false, 1),
+            KeyCode::Char('d') if ctrl => TextPreviewAction::Scroll(true, 20),
+            KeyCode::Char('u') if ctrl => TextPreviewAction::Scroll(false, 20),
+            KeyCode::Char('L') => TextPreviewAction::LoadMore,
+            KeyCode::Char('h') | KeyCode::Left => TextPreviewAction::ScrollSideways(false),
+            KeyCode::Char('l') | KeyCode::Right => TextPreviewAction::ScrollSideways(true),
+            KeyCode::Char('0') => TextPreviewAction::ResetSideways,
+            KeyCode::Char('w') => TextPreviewAction::ToggleWrap,
+            KeyCode::Char('y') => TextPreviewAction::Copy,
+            KeyCode::Char('g') => TextPreviewAction::Top,
+            KeyCode::Char('G') => TextPreviewAction::Bottom,
+            KeyCode::Char('z') => TextPreviewAction::Zoom,
+            KeyCode::Char('q') | KeyCode::Esc => TextPreviewAction::Close,
+            _ => return None,
+        }),
+        Mode::Sequence(_) | Mode::Normal => return None,
+    };
+    Some(action)
+}
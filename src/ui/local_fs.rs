@@ -6,6 +6,8 @@ use ratatui::Frame;
 
 use crate::app::{App, Pane};
 
+use super::icons;
+
 pub fn render_local_fs(frame: &mut Frame, app: &mut App, area: Rect) {
     let border_style = if app.pane == Pane::LocalFs {
         Style::default().fg(Color::Cyan)
@@ -19,30 +21,51 @@ pub fn render_local_fs(frame: &mut Frame, app: &mut App, area: Rect) {
     ))];
 
     for entry in &app.local_entries {
+        let nerd = app.settings.nerd_icons;
         let (icon, color) = if entry.is_dir {
-            ("D ", Color::Blue)
+            (icons::dir(nerd, false), Color::Blue)
         } else {
-            ("  ", Color::White)
+            icons::file(&entry.name, nerd)
         };
         let display = if entry.is_dir {
-            format!("{}{}/", icon, entry.name)
+            format!("{} {}/", icon, entry.name)
         } else {
-            format!("{}{}", icon, entry.name)
+            format!("{} {}", icon, entry.name)
         };
         items.push(ListItem::new(Span::styled(display, Style::default().fg(color))));
     }
 
     let path_display = app.local_path_display();
-    let title = format!(" Save to: {} ", path_display);
+    let title = if app.local_search_active {
+        format!(
+            " Save to: {} [/{}_ {} matches] ",
+            path_display,
+            app.local_search_query,
+            app.local_entries.len()
+        )
+    } else {
+        format!(" Save to: {} ", path_display)
+    };
 
-    let bottom_hint = Line::from(vec![
-        Span::styled(" c", Style::default().fg(Color::Yellow)),
-        Span::raw(": save here "),
-        Span::styled("n", Style::default().fg(Color::Yellow)),
-        Span::raw(": rename "),
-        Span::styled("Esc", Style::default().fg(Color::Yellow)),
-        Span::raw(": cancel "),
-    ]);
+    let bottom_hint = if app.local_search_active {
+        Line::from(vec![
+            Span::styled(" Enter", Style::default().fg(Color::Yellow)),
+            Span::raw(": select "),
+            Span::styled("Esc", Style::default().fg(Color::Yellow)),
+            Span::raw(": clear filter "),
+        ])
+    } else {
+        Line::from(vec![
+            Span::styled(" c", Style::default().fg(Color::Yellow)),
+            Span::raw(": save here "),
+            Span::styled("n", Style::default().fg(Color::Yellow)),
+            Span::raw(": rename "),
+            Span::styled("/", Style::default().fg(Color::Yellow)),
+            Span::raw(": filter "),
+            Span::styled("Esc", Style::default().fg(Color::Yellow)),
+            Span::raw(": cancel "),
+        ])
+    };
 
     let list = List::new(items)
         .block(
@@ -83,6 +106,17 @@ pub fn render_download_target(frame: &mut Frame, app: &App, area: Rect) {
             Span::raw(app.rename_input.as_deref().unwrap_or("")),
             Span::styled("_", Style::default().fg(Color::DarkGray)),
         ])
+    } else if let Some(original) = &app.name_adjusted_from {
+        Line::from(vec![
+            Span::styled(" Save as: ", Style::default().fg(Color::Cyan)),
+            Span::styled(&target, Style::default().fg(Color::Green)),
+            Span::styled(
+                format!("  (adjusted, {} exists)", original),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled("  c", Style::default().fg(Color::Yellow)),
+            Span::raw(" save"),
+        ])
     } else if app.rename_input.is_some() {
         Line::from(vec![
             Span::styled(" Save as: ", Style::default().fg(Color::Cyan)),
@@ -109,7 +143,8 @@ pub fn render_download_progress(app: &App, area_width: u16) -> Option<Line<'stat
     };
 
     let pct = if progress.total_bytes > 0 {
-        (progress.bytes_downloaded as f64 / progress.total_bytes as f64 * 100.0) as u16
+        // Sizes can grow while a prefix downloads, so bytes may briefly pass the total
+        (progress.bytes_downloaded as f64 / progress.total_bytes as f64 * 100.0).min(100.0) as u16
     } else {
         0
     };
@@ -124,8 +159,9 @@ pub fn render_download_progress(app: &App, area_width: u16) -> Option<Line<'stat
         "\u{2591}".repeat(empty),    // ░
     );
 
-    // Speed
+    // Speed over the last few seconds, and over the whole transfer
     let speed = humansize::format_size(progress.speed_bps as u64, humansize::BINARY);
+    let avg_speed = humansize::format_size(progress.avg_speed_bps as u64, humansize::BINARY);
 
     // ETA
     let eta = if progress.speed_bps > 0.0 && progress.total_bytes > progress.bytes_downloaded {
@@ -140,6 +176,14 @@ pub fn render_download_progress(app: &App, area_width: u16) -> Option<Line<'stat
         "-".to_string()
     };
 
+    // Throughput over the last minute, where the line has room for it
+    let spark_width = (area_width as usize).saturating_sub(105).min(crate::app::throughput::WINDOW_SECS);
+    let spark = if spark_width >= 10 {
+        format!("  {}", crate::app::throughput::sparkline(progress.throughput.samples(), spark_width))
+    } else {
+        String::new()
+    };
+
     // File count for directory downloads
     let files_info = if progress.files_total > 1 {
         format!(" {}/{} files", progress.files_done, progress.files_total)
@@ -161,9 +205,10 @@ pub fn render_download_progress(app: &App, area_width: u16) -> Option<Line<'stat
             Style::default().fg(Color::White),
         ),
         Span::styled(
-            format!("  {}/s", speed),
+            format!("  {}/s (avg {}/s)", speed, avg_speed),
             Style::default().fg(Color::DarkGray),
         ),
+        Span::styled(spark, Style::default().fg(Color::Cyan)),
         Span::styled(
             format!("  ETA {}", eta),
             Style::default().fg(Color::DarkGray),
@@ -1,228 +1,183 @@
+mod guard;
+mod icons;
+mod keymap;
+mod keys;
 pub mod local_fs;
 mod popups;
 mod render;
+pub mod setup;
 mod status;
+mod text;
 
 use std::io;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::event::{Event, EventStream, KeyEventKind};
+use futures_util::StreamExt;
 use crossterm::execute;
-use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::terminal::{self, EnterAlternateScreen};
 
-use crate::app::{App, Pane};
+use crate::app::action::Action;
+use crate::app::mode::{PopupAction, PromptAction};
+use crate::app::App;
+
+pub use guard::install_panic_hook;
 
 pub async fn run(app: &mut App) -> anyhow::Result<()> {
-    terminal::enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    let guard = guard::TerminalGuard::enter()?;
 
-    let backend = ratatui::backend::CrosstermBackend::new(stdout);
+    let backend = ratatui::backend::CrosstermBackend::new(io::stdout());
     let mut terminal = ratatui::Terminal::new(backend)?;
 
     let result = event_loop(&mut terminal, app).await;
 
+    app.stop_slideshow();
+    app.preview.stop_playback();
     app.cleanup_preview();
+    app.shutdown_tasks().await;
 
-    terminal::disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
-
+    drop(guard);
     result
 }
 
+/// Leave the TUI for an interactive shell in the local directory, with the current
+/// location and the alias's credentials in its environment; come back when it exits.
+async fn suspend_to_shell(
+    terminal: &mut ratatui::Terminal<ratatui::backend::CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+) -> anyhow::Result<()> {
+    let shell = if cfg!(windows) {
+        std::env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".to_string())
+    } else {
+        std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string())
+    };
+
+    let env = app.shell_env().await;
+    guard::restore();
+    println!("s3-like-yazi: {} (exit to return)", app.location_display());
+
+    let status = tokio::process::Command::new(&shell)
+        .current_dir(&app.local_path)
+        .envs(env)
+        .status()
+        .await;
+
+    terminal::enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    terminal.clear()?;
+
+    if let Err(e) = status {
+        app.notify_error(format!("Failed to start {}: {}", shell, e));
+    }
+    Ok(())
+}
+
+/// Leave the TUI for `$VISUAL` / `$EDITOR` on a draft of the new object's text, and
+/// upload what was saved once it exits.
+async fn edit_new_object(
+    terminal: &mut ratatui::Terminal<ratatui::backend::CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+) -> anyhow::Result<()> {
+    let Some(draft) = app.new_object_draft() else {
+        app.notify_warn("Type a name first");
+        return Ok(());
+    };
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    if let Some(parent) = draft.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::remove_file(&draft);
+
+    guard::restore();
+    // The variable may carry arguments, e.g. "code --wait"
+    let mut words = editor.split_whitespace();
+    let status = tokio::process::Command::new(words.next().unwrap_or("vi"))
+        .args(words)
+        .arg(&draft)
+        .status()
+        .await;
+
+    terminal::enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    terminal.clear()?;
+
+    match status {
+        Ok(_) => app.finish_new_object_draft().await,
+        Err(e) => app.notify_error(format!("Failed to start {}: {}", editor, e)),
+    }
+    Ok(())
+}
+
+/// Shortest time between two redraws caused by background messages.
+const MIN_FRAME: Duration = Duration::from_millis(33);
+
 async fn event_loop(
     terminal: &mut ratatui::Terminal<ratatui::backend::CrosstermBackend<io::Stdout>>,
     app: &mut App,
 ) -> anyhow::Result<()> {
+    let mut events = EventStream::new();
     loop {
-        app.drain_index();
-        app.drain_download();
-        app.drain_preview();
+        app.drain_tasks();
+        // The panic hook has already put the terminal back; leave through the
+        // normal error path so the other jobs are shut down and cleaned up
+        if let Some(label) = app.tasks.take_panicked() {
+            anyhow::bail!("Background job \"{}\" panicked", label);
+        }
+        app.reload_if_stale().await;
+        app.start_next_queued().await;
+        app.toasts.prune();
 
         terminal.draw(|frame| render::render(frame, app))?;
+        let last_draw = Instant::now();
 
-        if event::poll(Duration::from_millis(50))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind != KeyEventKind::Press {
-                    continue;
+        // Sleep until a key, a resize, a background message or a timed change
+        let timeout = app.idle_timeout();
+        let event = tokio::select! {
+            event = events.next() => event.transpose()?,
+            _ = app.next_message() => {
+                // Transfers report many times a second; batch them into frames
+                tokio::time::sleep_until((last_draw + MIN_FRAME).into()).await;
+                None
+            }
+            _ = async {
+                match timeout {
+                    Some(t) => tokio::time::sleep(t).await,
+                    None => std::future::pending().await,
                 }
+            } => None,
+        };
+        if let Some(Event::Resize(..)) = event {
+            // Drop whatever the old size left on screen; the next draw lays out afresh
+            terminal.autoresize()?;
+            terminal.clear()?;
+            continue;
+        }
+        if let Some(Event::Key(key)) = event {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
 
-                if app.confirm_delete.is_some() {
-                    // ── Delete confirmation ──
-                    match key.code {
-                        KeyCode::Tab => app.toggle_delete_confirm(),
-                        KeyCode::Enter => {
-                            let yes = app
-                                .confirm_delete
-                                .as_ref()
-                                .map_or(false, |c| c.selected_yes);
-                            if yes {
-                                app.confirm_delete_yes().await;
-                            } else {
-                                app.confirm_delete = None;
-                            }
-                        }
-                        KeyCode::Esc => {
-                            app.confirm_delete = None;
-                        }
-                        _ => {}
-                    }
-                } else if app.rename_active {
-                    // ── Rename input mode (sub-mode of download) ──
-                    match key.code {
-                        KeyCode::Esc => app.cancel_rename(),
-                        KeyCode::Enter => app.finish_rename(),
-                        KeyCode::Backspace => app.rename_backspace(),
-                        KeyCode::Char(c) => app.rename_char(c),
-                        _ => {}
-                    }
-                } else if app.download_mode {
-                    // ── Download mode: local FS navigation ──
-                    match key.code {
-                        KeyCode::Esc => app.cancel_download_mode(),
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            if app.pane == Pane::LocalFs {
-                                if app.local_state.selected().is_none() {
-                                    // Already at top
-                                } else {
-                                    let i = app.local_state.selected().unwrap();
-                                    if i == 0 {
-                                        app.local_state.select(None);
-                                    } else {
-                                        app.local_move_up();
-                                    }
-                                }
-                            } else {
-                                app.move_up();
-                            }
-                        }
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            if app.pane == Pane::LocalFs {
-                                if app.local_state.selected().is_none() {
-                                    if !app.local_entries.is_empty() {
-                                        app.local_state.select(Some(0));
-                                    }
-                                } else {
-                                    app.local_move_down();
-                                }
-                            } else {
-                                app.move_down();
-                            }
-                        }
-                        KeyCode::Enter | KeyCode::Char('l') => {
-                            if app.pane == Pane::LocalFs {
-                                if app.local_state.selected().is_none() {
-                                    app.local_go_back();
-                                } else {
-                                    let idx = app.local_state.selected().unwrap();
-                                    if idx < app.local_entries.len()
-                                        && app.local_entries[idx].is_dir
-                                    {
-                                        app.local_enter();
-                                    }
-                                }
-                            } else {
-                                app.select().await;
-                            }
-                        }
-                        KeyCode::Backspace | KeyCode::Char('h') => {
-                            if app.pane == Pane::LocalFs {
-                                app.local_go_back();
-                            } else {
-                                app.go_back().await;
-                            }
-                        }
-                        KeyCode::Char('c') => {
-                            app.confirm_download().await;
-                        }
-                        KeyCode::Char('n') => {
-                            if app.pane == Pane::LocalFs {
-                                app.start_rename();
-                            }
-                        }
-                        KeyCode::Tab => app.switch_pane(),
-                        _ => {}
-                    }
-                } else if app.show_help {
-                    app.show_help = false;
-                } else if app.search_active {
-                    // ── Search mode ──
-                    match key.code {
-                        KeyCode::Esc => app.cancel_search(),
-                        KeyCode::Enter => app.select().await,
-                        KeyCode::Up => app.move_up(),
-                        KeyCode::Down => app.move_down(),
-                        KeyCode::Backspace => app.search_backspace(),
-                        KeyCode::Char(c) => app.search_input(c),
-                        _ => {}
-                    }
-                } else if app.preview.text_content.is_some() {
-                    // ── Text preview scroll mode ──
-                    match key.code {
-                        KeyCode::Char('j') | KeyCode::Down => app.preview.scroll_down(1),
-                        KeyCode::Char('k') | KeyCode::Up => app.preview.scroll_up(1),
-                        KeyCode::Char('d')
-                            if key.modifiers.contains(KeyModifiers::CONTROL) =>
-                        {
-                            app.preview.scroll_down(20);
-                        }
-                        KeyCode::Char('u')
-                            if key.modifiers.contains(KeyModifiers::CONTROL) =>
-                        {
-                            app.preview.scroll_up(20);
-                        }
-                        KeyCode::Char('g') => app.preview.scroll_offset = 0,
-                        KeyCode::Char('G') => {
-                            app.preview.scroll_offset = app.preview.line_count.saturating_sub(1);
-                        }
-                        KeyCode::Char('q') | KeyCode::Esc => {
-                            app.preview.clear();
-                            app.status_message = None;
-                        }
-                        _ => {}
-                    }
-                } else {
-                    // ── Normal mode ──
-                    match key.code {
-                        KeyCode::Char('q') => {
-                            app.should_quit = true;
-                        }
-                        KeyCode::Char('p')
-                            if key.modifiers.contains(KeyModifiers::CONTROL) =>
-                        {
-                            app.start_search();
-                        }
-                        KeyCode::Char('C') => app.start_download_mode(),
-                        KeyCode::Char('/') => app.start_search(),
-                        KeyCode::Up | KeyCode::Char('k') => app.move_up(),
-                        KeyCode::Down | KeyCode::Char('j') => app.move_down(),
-                        KeyCode::Enter | KeyCode::Char('l') => app.select().await,
-                        KeyCode::Backspace
-                            if key.modifiers.contains(KeyModifiers::SUPER) =>
-                        {
-                            app.request_delete();
-                        }
-                        KeyCode::Char('d') => app.request_delete(),
-                        KeyCode::Backspace | KeyCode::Char('h') => app.go_back().await,
-                        KeyCode::Char('r') => app.refresh().await,
-                        KeyCode::Tab => app.switch_pane(),
-                        KeyCode::Char('p') => app.request_preview(),
-                        KeyCode::Char('?') => app.show_help = true,
-                        KeyCode::Esc => {
-                            app.error = None;
-                            app.metadata = None;
-                            app.status_message = None;
-                            app.download_progress = None;
-                            app.preview.clear();
-                        }
-                        _ => {}
-                    }
+            let Some(action) = keys::key_action(app, key) else {
+                continue;
+            };
+            match action {
+                Action::Shell => {
+                    // The old stream's reader would compete with the shell for input
+                    events = EventStream::new();
+                    suspend_to_shell(terminal, app).await?;
                 }
-
-                if app.should_quit {
-                    break;
+                Action::Popup(PopupAction::NewObject(PromptAction::Submit)) => {
+                    // The old stream's reader would compete with the editor for input
+                    events = EventStream::new();
+                    edit_new_object(terminal, app).await?;
                 }
+                action => app.dispatch(action).await,
+            }
+
+            if app.should_quit {
+                break;
             }
         }
     }
@@ -1,10 +1,31 @@
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Clear, Paragraph};
+use ratatui::widgets::{Block, Clear, List, ListItem, Paragraph, Wrap};
 use ratatui::Frame;
 
+use crate::app::acl::CANNED_ACLS;
+use crate::app::help::HelpMode;
+use crate::app::notify::Severity;
+use crate::app::plan::PlanAction;
+use crate::app::quick_views::QuickKind;
 use crate::app::App;
 
+use super::keymap;
+use super::text::local_time;
+
+/// A `width` x `height` rectangle centered in `area`, shrunk to fit when the terminal
+/// is smaller than the popup.
+pub fn centered(area: ratatui::layout::Rect, width: u16, height: u16) -> ratatui::layout::Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    ratatui::layout::Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
 pub fn render_confirm_delete(frame: &mut Frame, app: &App) {
     let confirm = match &app.confirm_delete {
         Some(v) => v,
@@ -13,18 +34,23 @@ pub fn render_confirm_delete(frame: &mut Frame, app: &App) {
 
     let area = frame.area();
     let width = 54u16.min(area.width.saturating_sub(4));
-    let height = 8u16;
-    let x = (area.width.saturating_sub(width)) / 2;
-    let y = (area.height.saturating_sub(height)) / 2;
-    let popup = ratatui::layout::Rect::new(x, y, width, height);
+    let height = if confirm.typed.is_some() { 12u16 } else { 9u16 };
+    let popup = centered(area, width, height);
 
-    let label = if confirm.is_dir {
+    let label = if let Some(markers) = &confirm.markers {
+        format!("  Delete {} directory markers under \"{}\"?", markers.len(), confirm.display_name)
+    } else if confirm.is_dir {
         format!("  Delete directory \"{}\" recursively?", confirm.display_name)
     } else {
         format!("  Delete \"{}\"?", confirm.display_name)
     };
 
-    let (no_style, yes_style) = if confirm.selected_yes {
+    let (no_style, yes_style) = if confirm.selected_yes && !confirm.typed_ok() {
+        (
+            Style::default().fg(Color::DarkGray),
+            Style::default().fg(Color::DarkGray).bg(Color::White),
+        )
+    } else if confirm.selected_yes {
         (
             Style::default().fg(Color::DarkGray),
             Style::default()
@@ -42,13 +68,37 @@ pub fn render_confirm_delete(frame: &mut Frame, app: &App) {
         )
     };
 
-    let lines = vec![
+    let mut lines = vec![
         Line::from(""),
         Line::from(Span::styled(
             label,
             Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
         )),
+        Line::from(Span::styled(
+            match app.identity(&confirm.remote) {
+                Some(identity) => format!("  on {} @ {}", confirm.remote, identity.host()),
+                None => format!("  on {}", confirm.remote),
+            },
+            Style::default().fg(Color::DarkGray),
+        )),
         Line::from(""),
+    ];
+
+    if let Some(typed) = &confirm.typed {
+        let input_color = if confirm.typed_ok() { Color::Green } else { Color::White };
+        lines.push(Line::from(Span::styled(
+            format!("  Protected remote: type '{}' to confirm", confirm.remote),
+            Style::default().fg(Color::Yellow),
+        )));
+        lines.push(Line::from(vec![
+            Span::raw("  > "),
+            Span::styled(typed.clone(), Style::default().fg(input_color)),
+            Span::styled("_", Style::default().fg(Color::DarkGray)),
+        ]));
+        lines.push(Line::from(""));
+    }
+
+    lines.extend([
         Line::from(vec![
             Span::raw("          "),
             Span::styled(" No ", no_style),
@@ -60,7 +110,7 @@ pub fn render_confirm_delete(frame: &mut Frame, app: &App) {
             "  Tab switch  Enter confirm  Esc cancel",
             Style::default().fg(Color::DarkGray),
         )),
-    ];
+    ]);
 
     let block = Block::bordered()
         .title(" Confirm Delete ")
@@ -71,78 +121,1137 @@ pub fn render_confirm_delete(frame: &mut Frame, app: &App) {
     frame.render_widget(Paragraph::new(lines).block(block), popup);
 }
 
-pub fn render_help(frame: &mut Frame) {
+/// A copy, sync or ACL change on a protected remote, waiting for its name.
+pub fn render_confirm_protected(frame: &mut Frame, app: &App) {
+    let Some(confirm) = &app.protected_confirm else {
+        return;
+    };
     let area = frame.area();
+    let width = 60u16.min(area.width.saturating_sub(4));
+    let popup = centered(area, width, 10);
 
-    let width = 52u16.min(area.width.saturating_sub(4));
-    let height = 30u16.min(area.height.saturating_sub(2));
-    let x = (area.width.saturating_sub(width)) / 2;
-    let y = (area.height.saturating_sub(height)) / 2;
-    let popup = ratatui::layout::Rect::new(x, y, width, height);
-
-    let key = |k: &str| Span::styled(format!(" {:<14}", k), Style::default().fg(Color::Yellow));
-    let desc = |d: &str| Span::styled(d.to_string(), Style::default().fg(Color::White));
-
+    let input_color = if confirm.typed_ok() { Color::Green } else { Color::White };
     let lines = vec![
         Line::from(""),
         Line::from(Span::styled(
-            "  Navigation",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
+            format!("  {}", confirm.summary),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            match app.identity(&confirm.remote) {
+                Some(identity) => format!("  on {} @ {}", confirm.remote, identity.host()),
+                None => format!("  on {}", confirm.remote),
+            },
+            Style::default().fg(Color::DarkGray),
         )),
-        Line::from(vec![key("j / Down"), desc("Move cursor down")]),
-        Line::from(vec![key("k / Up"), desc("Move cursor up")]),
-        Line::from(vec![key("l / Enter"), desc("Open / select item")]),
-        Line::from(vec![key("h / Bksp"), desc("Go back / parent dir")]),
-        Line::from(vec![key("Tab"), desc("Switch pane")]),
         Line::from(""),
         Line::from(Span::styled(
-            "  Actions",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
+            format!("  Protected remote: type '{}' to confirm", confirm.remote),
+            Style::default().fg(Color::Yellow),
         )),
-        Line::from(vec![key("/ or Ctrl+P"), desc("Search all objects")]),
-        Line::from(vec![key("r"), desc("Refresh current view")]),
-        Line::from(vec![key("Shift+C"), desc("Download (copy) to local")]),
-        Line::from(vec![key("d / Cmd+Bksp"), desc("Delete file or directory")]),
-        Line::from(vec![key("p"), desc("Preview file (text/image/video)")]),
-        Line::from(vec![key("Esc"), desc("Dismiss error / metadata")]),
-        Line::from(vec![key("q"), desc("Quit")]),
+        Line::from(vec![
+            Span::raw("  > "),
+            Span::styled(confirm.typed.clone(), Style::default().fg(input_color)),
+            Span::styled("_", Style::default().fg(Color::DarkGray)),
+        ]),
         Line::from(""),
         Line::from(Span::styled(
-            "  Download Mode",
+            "  Enter confirm  Esc cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    let block = Block::bordered()
+        .title(" Protected Remote ")
+        .title_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Red));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+pub fn render_help(frame: &mut Frame, app: &App) {
+    let Some(help) = &app.help else {
+        return;
+    };
+    let area = frame.area();
+
+    let width = 60u16.min(area.width.saturating_sub(4));
+    let height = 40u16.min(area.height.saturating_sub(2));
+    let popup = centered(area, width, height);
+
+    let key = |k: &str| Span::styled(format!(" {:<16}", k), Style::default().fg(Color::Yellow));
+    let desc = |d: &str| Span::styled(d.to_string(), Style::default().fg(Color::White));
+    let heading = |t: &str| {
+        Line::from(Span::styled(
+            format!("  {}", t),
             Style::default()
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::BOLD),
+        ))
+    };
+
+    let mut lines = Vec::new();
+    if help.query.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  Type to search every mode",
+            Style::default().fg(Color::DarkGray),
+        )));
+        lines.push(Line::from(""));
+        lines.push(heading(help.mode.title()));
+        for binding in keymap::bindings(help.mode) {
+            lines.push(Line::from(vec![key(binding.keys), desc(binding.desc)]));
+        }
+    } else {
+        lines.push(Line::from(vec![
+            Span::styled(" /", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(help.query.as_str()),
+            Span::styled("_", Style::default().fg(Color::DarkGray)),
+        ]));
+        let query = help.query.to_lowercase();
+        for mode in HelpMode::ALL {
+            let matches: Vec<_> = keymap::bindings(mode)
+                .iter()
+                .filter(|b| {
+                    b.keys.to_lowercase().contains(&query) || b.desc.to_lowercase().contains(&query)
+                })
+                .collect();
+            if matches.is_empty() {
+                continue;
+            }
+            lines.push(Line::from(""));
+            lines.push(heading(mode.title()));
+            for binding in matches {
+                lines.push(Line::from(vec![key(binding.keys), desc(binding.desc)]));
+            }
+        }
+        if lines.len() == 1 {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "  No matching keys",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+    }
+
+    let visible = height.saturating_sub(2) as usize;
+    let scroll = help.scroll.min(lines.len().saturating_sub(visible));
+
+    let block = Block::bordered()
+        .title(" Keybindings ")
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .title_bottom(" Tab other modes  Up/Down scroll  Esc close ")
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines).block(block).scroll((scroll as u16, 0)),
+        popup,
+    );
+}
+
+fn format_duration(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m{}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h{}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
+pub fn render_history(frame: &mut Frame, app: &mut App) {
+    let area = frame.area();
+    let width = 100u16.min(area.width.saturating_sub(4));
+    let height = 24u16.min(area.height.saturating_sub(2));
+    let popup = centered(area, width, height);
+
+    let ok: Vec<_> = app.history.iter().filter(|r| r.error.is_none()).collect();
+    let total_bytes: u64 = ok.iter().map(|r| r.bytes).sum();
+    let total_secs: f64 = ok.iter().map(|r| r.duration_secs).sum();
+    let avg_speed = if total_secs > 0.0 {
+        total_bytes as f64 / total_secs
+    } else {
+        0.0
+    };
+    let stats = format!(
+        " {} transfers, {} failed, {} total, avg {}/s ",
+        app.history.len(),
+        app.history.len() - ok.len(),
+        humansize::format_size(total_bytes, humansize::BINARY),
+        humansize::format_size(avg_speed as u64, humansize::BINARY),
+    );
+
+    let items: Vec<ListItem> = app
+        .history
+        .iter()
+        .rev()
+        .map(|r| {
+            let (mark, color) = if r.error.is_some() {
+                ("\u{2717}", Color::Red) // ✗
+            } else {
+                ("\u{2713}", Color::Green) // ✓
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} ", mark), Style::default().fg(color)),
+                Span::styled(
+                    format!("{} ", r.finished().map(|t| local_time(&t)).unwrap_or_default()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(format!("{:<28} ", r.spec.name()), Style::default().fg(Color::White)),
+                Span::styled(
+                    format!(
+                        "{:>10} ",
+                        humansize::format_size(r.bytes, humansize::BINARY)
+                    ),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(
+                    format!(
+                        "{:>6} {:>10}/s",
+                        format_duration(r.duration_secs),
+                        humansize::format_size(r.avg_speed_bps() as u64, humansize::BINARY)
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+
+    let detail = match app.selected_history() {
+        Some(r) => {
+            let mut detail = format!(" {} ", r.spec.route());
+            if let Some(e) = &r.error {
+                detail.push_str(&format!("({}) ", e));
+            }
+            detail
+        }
+        None => " No transfers yet ".to_string(),
+    };
+    let hint = Line::from(" j/k move  Enter re-run  Esc close ")
+        .style(Style::default().fg(Color::DarkGray))
+        .right_aligned();
+
+    let block = Block::bordered()
+        .title(" Transfer History ")
+        .title(Line::from(stats).right_aligned())
+        .title_bottom(Line::from(detail).style(Style::default().fg(Color::Cyan)))
+        .title_bottom(hint)
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, &mut app.history_state);
+}
+
+pub fn render_favorites(frame: &mut Frame, app: &mut App) {
+    let area = frame.area();
+    let width = 80u16.min(area.width.saturating_sub(4));
+    let height = 20u16.min(area.height.saturating_sub(2));
+    let popup = centered(area, width, height);
+
+    let items: Vec<ListItem> = app
+        .visible_favorites()
+        .iter()
+        .map(|f| {
+            let name = if f.is_dir() { format!("{}/", f.name()) } else { f.name().to_string() };
+            let mut spans = vec![
+                Span::styled("\u{2605} ", Style::default().fg(Color::Yellow)), // ★
+                Span::styled(format!("{:<24} ", name), Style::default().fg(Color::White)),
+                Span::styled(
+                    format!("{}:{}/{}", f.remote, f.bucket, f.key),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ];
+            if f.promoted {
+                spans.push(Span::styled("  in remotes", Style::default().fg(Color::Cyan)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let hint = Line::from(" Enter go  p preview  c download here  s copy link  a list as remote  d unpin  Esc close ")
+        .style(Style::default().fg(Color::DarkGray));
+    let block = Block::bordered()
+        .title(" Favorites ")
+        .title_bottom(hint)
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, &mut app.favorites_state);
+}
+
+pub fn render_context_view(frame: &mut Frame, app: &mut App) {
+    let Some(view) = &mut app.context_view else {
+        return;
+    };
+    let area = frame.area();
+    let width = 90u16.min(area.width.saturating_sub(4));
+    let height = 24u16.min(area.height.saturating_sub(2));
+    let popup = centered(area, width, height);
+
+    let items: Vec<ListItem> = view
+        .entries
+        .iter()
+        .map(|obj| {
+            if obj.is_dir {
+                ListItem::new(Line::from(Span::styled(
+                    format!("  {}/", obj.display_name),
+                    Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD),
+                )))
+            } else {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{:>10}  ", humansize::format_size(obj.size as u64, humansize::BINARY)),
+                        Style::default().fg(Color::Green),
+                    ),
+                    Span::styled(obj.display_name.as_str(), Style::default().fg(Color::White)),
+                    Span::styled(
+                        format!("  {}", obj.last_modified.as_ref().map(local_time).unwrap_or_default()),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]))
+            }
+        })
+        .collect();
+
+    let hint = Line::from(" l open  h up  Esc back to results ")
+        .style(Style::default().fg(Color::DarkGray));
+    let block = Block::bordered()
+        .title(format!(" {} / {} / {} ", view.remote, view.bucket, view.prefix))
+        .title_bottom(hint)
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, &mut view.state);
+}
+
+pub fn render_entry_menu(frame: &mut Frame, app: &mut App) {
+    let Some(menu) = &mut app.entry_menu else {
+        return;
+    };
+    let area = frame.area();
+    let width = 40u16.min(area.width.saturating_sub(4));
+    let popup = centered(area, width, menu.items.len() as u16 + 2);
+    let label_width = width.saturating_sub(12) as usize;
+
+    let items: Vec<ListItem> = menu
+        .items
+        .iter()
+        .map(|item| {
+            ListItem::new(Line::from(vec![
+                Span::raw(format!(" {:<w$}", item.label, w = label_width)),
+                Span::styled(format!("{:>8}", item.keys), Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+    let block = Block::bordered()
+        .title(format!(" {} ", menu.title))
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Cyan));
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, &mut menu.state);
+}
+
+pub fn render_acl_picker(frame: &mut Frame, app: &mut App) {
+    let Some(picker) = &mut app.acl_picker else {
+        return;
+    };
+    let area = frame.area();
+    let width = 44u16.min(area.width.saturating_sub(4));
+    let popup = centered(area, width, CANNED_ACLS.len() as u16 + 2);
+
+    let items: Vec<ListItem> = CANNED_ACLS.iter().map(|acl| ListItem::new(format!(" {}", acl))).collect();
+    let block = Block::bordered()
+        .title(format!(" ACL of {} ", picker.name))
+        .title_bottom(Line::from(" Enter set  Esc cancel ").style(Style::default().fg(Color::DarkGray)))
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Cyan));
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, &mut picker.state);
+}
+
+pub fn render_quick_view(frame: &mut Frame, app: &mut App) {
+    let Some(view) = &mut app.quick_view else {
+        return;
+    };
+    let area = frame.area();
+    let Some(kind) = view.kind else {
+        let width = 44u16.min(area.width.saturating_sub(4));
+        let popup = centered(area, width, QuickKind::ALL.len() as u16 + 2);
+        let items: Vec<ListItem> = QuickKind::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, kind)| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!(" {} ", i + 1), Style::default().fg(Color::Yellow)),
+                    Span::raw(kind.label()),
+                ]))
+            })
+            .collect();
+        let block = Block::bordered()
+            .title(format!(" Quick views: {} ", view.bucket))
+            .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+            .border_style(Style::default().fg(Color::Cyan));
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_widget(Clear, popup);
+        frame.render_stateful_widget(list, popup, &mut view.menu);
+        return;
+    };
+
+    let width = 100u16.min(area.width.saturating_sub(4));
+    let height = 24u16.min(area.height.saturating_sub(2));
+    let popup = centered(area, width, height);
+
+    let items: Vec<ListItem> = view
+        .entries
+        .iter()
+        .map(|obj| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:>10}  ", humansize::format_size(obj.size as u64, humansize::BINARY)),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(
+                    format!("{}  ", obj.last_modified.as_ref().map(local_time).unwrap_or_default()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(obj.key.as_str(), Style::default().fg(Color::White)),
+            ]))
+        })
+        .collect();
+
+    // Say when the answer covers only part of the bucket
+    let scope = if view.complete {
+        String::new()
+    } else {
+        format!(" (first {} objects indexed, r to refresh)", view.indexed)
+    };
+    let hint = Line::from(" l open  r refresh  h menu  Esc back ")
+        .style(Style::default().fg(Color::DarkGray));
+    let block = Block::bordered()
+        .title(format!(" {}: {}{} ", kind.label(), view.bucket, scope))
+        .title_bottom(hint)
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Cyan));
+
+    if view.entries.is_empty() {
+        let text = if view.indexed == 0 {
+            "  Nothing indexed yet; press r once indexing has made progress"
+        } else {
+            "  No objects match"
+        };
+        frame.render_widget(Clear, popup);
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::styled(text, Style::default().fg(Color::DarkGray)))).block(block),
+            popup,
+        );
+        return;
+    }
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, &mut view.state);
+}
+
+pub fn render_download_conflict(frame: &mut Frame, app: &App) {
+    let Some(suggested) = &app.download_conflict else {
+        return;
+    };
+    let target = app.download_target_name().unwrap_or_default();
+    let area = frame.area();
+    let width = 60u16.min(area.width.saturating_sub(4));
+    let height = 8u16.min(area.height.saturating_sub(2));
+    let popup = centered(area, width, height);
+
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("  \"{}\" already exists here", target),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
         )),
-        Line::from(vec![key("c"), desc("Confirm download here")]),
-        Line::from(vec![key("n"), desc("Rename file before saving")]),
-        Line::from(vec![key("Esc"), desc("Cancel download")]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  r", Style::default().fg(Color::Yellow)),
+            Span::raw(format!(" save as \"{}\"", suggested)),
+        ]),
+        Line::from(vec![
+            Span::styled("  o", Style::default().fg(Color::Yellow)),
+            Span::raw(" overwrite"),
+        ]),
+    ];
+
+    let block = Block::bordered()
+        .title(" File Exists ")
+        .title_bottom(Line::from(" Esc back ").style(Style::default().fg(Color::DarkGray)))
+        .title_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Yellow));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+pub fn render_confirm_quit(frame: &mut Frame) {
+    let area = frame.area();
+    let width = 54u16.min(area.width.saturating_sub(4));
+    let height = 7u16.min(area.height.saturating_sub(2));
+    let popup = centered(area, width, height);
+
+    let lines = vec![
         Line::from(""),
         Line::from(Span::styled(
-            "  Search Mode",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
+            "  A transfer is in progress - quit anyway?",
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
         )),
-        Line::from(vec![key("Type"), desc("Filter by name")]),
-        Line::from(vec![key("Up / Down"), desc("Navigate results")]),
-        Line::from(vec![key("Enter"), desc("Jump to file")]),
-        Line::from(vec![key("Esc"), desc("Cancel search")]),
         Line::from(""),
         Line::from(Span::styled(
-            "  Press any key to close",
+            "  It is offered for resuming at the next launch.",
             Style::default().fg(Color::DarkGray),
         )),
     ];
 
     let block = Block::bordered()
-        .title(" Keybindings ")
+        .title(" Quit ")
+        .title_bottom(Line::from(" y quit  n/Esc stay ").style(Style::default().fg(Color::DarkGray)))
+        .title_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Yellow));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+pub fn render_resume_prompt(frame: &mut Frame, app: &App) {
+    const SHOWN: usize = 6;
+    let area = frame.area();
+    let count = app.unfinished.len();
+    let width = 64u16.min(area.width.saturating_sub(4));
+    let height = (count.min(SHOWN) as u16 + 5 + (count > SHOWN) as u16).min(area.height.saturating_sub(2));
+    let popup = centered(area, width, height);
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                "  {} unfinished transfer{} - resume?",
+                count,
+                if count == 1 { "" } else { "s" }
+            ),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    for spec in app.unfinished.iter().take(SHOWN) {
+        lines.push(Line::from(vec![
+            Span::raw(format!("  {}", spec.name())),
+            Span::styled(format!("  {}", spec.route()), Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+    if count > SHOWN {
+        lines.push(Line::from(Span::styled(
+            format!("  ...and {} more", count - SHOWN),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let block = Block::bordered()
+        .title(" Unfinished Transfers ")
+        .title_bottom(
+            Line::from(" y resume  n discard  Esc later ").style(Style::default().fg(Color::DarkGray)),
+        )
+        .title_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Yellow));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+pub fn render_identity(frame: &mut Frame, app: &App) {
+    let Some(identity) = &app.identity_popup else {
+        return;
+    };
+    let field = |name: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("  {:<12}", name), Style::default().fg(Color::DarkGray)),
+            Span::raw(value),
+        ])
+    };
+    let mut lines = vec![
+        Line::from(""),
+        field("Alias", identity.alias.clone()),
+        field("Endpoint", identity.endpoint.clone()),
+        field(
+            "Access key",
+            identity.access_key.clone().unwrap_or_else(|| "none".to_string()),
+        ),
+        field(
+            "Provider",
+            if identity.provider_guessed {
+                format!("{} (guessed from the URL)", identity.provider)
+            } else {
+                identity.provider.to_string()
+            },
+        ),
+    ];
+    if let Some(left) = app.credentials_left(&identity.alias) {
+        let mins = left.as_secs().div_ceil(60);
+        lines.push(field(
+            "Expires",
+            if mins == 0 { "expired".to_string() } else { format!("in {} min", mins) },
+        ));
+    }
+    if let Some(profile) = &identity.profile {
+        lines.push(field("Profile", profile.clone()));
+    }
+    if let Some(region) = &identity.region {
+        lines.push(field("Region", region.clone()));
+    }
+    if let Some(tag) = app.remote_tag(&identity.alias) {
+        lines.push(field("Tag", tag.label().to_string()));
+    }
+
+    let area = frame.area();
+    let width = 64u16.min(area.width.saturating_sub(4));
+    let height = (lines.len() as u16 + 3).min(area.height.saturating_sub(2));
+    let popup = centered(area, width, height);
+
+    let block = Block::bordered()
+        .title(" Who am I ")
+        .title_bottom(Line::from(" Esc close ").style(Style::default().fg(Color::DarkGray)))
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+pub fn render_confirm_index(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+    let width = 58u16.min(area.width.saturating_sub(4));
+    let height = 8u16.min(area.height.saturating_sub(2));
+    let popup = centered(area, width, height);
+    let bucket = app.index_key.as_ref().map(|(_, b)| b.as_str()).unwrap_or_default();
+
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("  {} has more than {} objects.", bucket, app.index_object_count()),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        )),
+        Line::from("  Keep indexing it for search?"),
+        Line::from(""),
+        Line::from(Span::styled(
+            "  Listing a large bucket costs requests and egress.",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    let block = Block::bordered()
+        .title(" Index bucket ")
+        .title_bottom(Line::from(" y keep indexing  n/Esc stop here ").style(Style::default().fg(Color::DarkGray)))
+        .title_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Yellow));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+pub fn render_link_bundle(frame: &mut Frame, app: &App) {
+    let Some(bundle) = &app.link_bundle else {
+        return;
+    };
+    let area = frame.area();
+    let width = 54u16.min(area.width.saturating_sub(4));
+    let height = 7u16.min(area.height.saturating_sub(2));
+    let popup = centered(area, width, height);
+
+    let count = bundle.objects.len();
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                "  Presigned links for {} {}",
+                count,
+                if count == 1 { "object" } else { "objects" }
+            ),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  y", Style::default().fg(Color::Yellow)),
+            Span::raw(" copy list to clipboard   "),
+            Span::styled("w", Style::default().fg(Color::Yellow)),
+            Span::raw(" write CSV here"),
+        ]),
+    ];
+
+    let block = Block::bordered()
+        .title(" Share Links ")
+        .title_bottom(Line::from(" Esc cancel ").style(Style::default().fg(Color::DarkGray)))
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+pub fn render_copy_picker(frame: &mut Frame, app: &mut App) {
+    let Some(picker) = &mut app.copy_picker else {
+        return;
+    };
+    let area = frame.area();
+    let width = 70u16.min(area.width.saturating_sub(4));
+    let height = 20u16.min(area.height.saturating_sub(2));
+    let popup = centered(area, width, height);
+
+    let items: Vec<ListItem> = picker
+        .rows
+        .iter()
+        .map(|row| {
+            if row.is_dir {
+                ListItem::new(Line::from(Span::styled(
+                    format!("  {}", row.name),
+                    Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD),
+                )))
+            } else {
+                ListItem::new(Line::from(Span::styled(
+                    format!("  {}", row.name),
+                    Style::default().fg(Color::DarkGray),
+                )))
+            }
+        })
+        .collect();
+
+    let verb = if picker.moving { "Move" } else { "Copy" };
+    let target = picker
+        .target_display()
+        .unwrap_or_else(|| "pick a remote and bucket".to_string());
+    let hint = Line::from(format!(
+        " Enter open  h back  o options  c {} here  Esc cancel ",
+        verb.to_lowercase()
+    ))
+    .style(Style::default().fg(Color::DarkGray));
+    let mut block = Block::bordered()
+        .title(format!(" {} {} -> {} ", verb, picker.source.name, target))
+        .title_bottom(hint)
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Cyan));
+    if !picker.options.is_empty() {
+        let options = &picker.options;
+        let set: Vec<&str> = [
+            options.storage_class.as_deref(),
+            options.sse.as_deref(),
+            options.metadata.as_ref().map(|_| "metadata"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        block = block.title(
+            Line::from(format!(" {} ", set.join(", ")))
+                .right_aligned()
+                .style(Style::default().fg(Color::Yellow)),
+        );
+    }
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, &mut picker.state);
+}
+
+pub fn render_copy_options(frame: &mut Frame, app: &App) {
+    let Some(picker) = &app.copy_picker else {
+        return;
+    };
+    let area = frame.area();
+    let width = 70u16.min(area.width.saturating_sub(4));
+    let height = 8u16.min(area.height.saturating_sub(2));
+    let popup = centered(area, width, height);
+
+    let label = Style::default().fg(Color::DarkGray);
+    let value = |v: Option<String>, unset: &str| match v {
+        Some(v) => Span::styled(v, Style::default().fg(Color::Yellow)),
+        None => Span::styled(unset.to_string(), Style::default().fg(Color::DarkGray)),
+    };
+    let options = &picker.options;
+    let metadata = match &picker.metadata_input {
+        Some(input) => Span::styled(format!("{}_", input), Style::default().fg(Color::White)),
+        None => value(
+            options
+                .metadata
+                .as_ref()
+                .map(|pairs| pairs.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join(", ")),
+            "copied from the source",
+        ),
+    };
+    let lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  Storage class  ", label),
+            value(options.storage_class.clone(), "bucket default"),
+        ]),
+        Line::from(vec![
+            Span::styled("  Encryption     ", label),
+            value(options.sse.clone(), "bucket default"),
+        ]),
+        Line::from(vec![Span::styled("  Metadata       ", label), metadata]),
+    ];
+
+    let hint = if picker.metadata_input.is_some() {
+        " key=value, ...  Enter set  Esc cancel "
+    } else {
+        " s storage class  e encryption  m metadata  Enter done "
+    };
+    let block = Block::bordered()
+        .title(" Copy options ")
+        .title_bottom(Line::from(hint).style(Style::default().fg(Color::DarkGray)))
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+pub fn render_sync_setup(frame: &mut Frame, app: &App) {
+    let Some(setup) = &app.sync_setup else {
+        return;
+    };
+    let area = frame.area();
+    let width = 70u16.min(area.width.saturating_sub(4));
+    let height = 10u16.min(area.height.saturating_sub(2));
+    let popup = centered(area, width, height);
+
+    let label = Style::default().fg(Color::DarkGray);
+    let value = Style::default().fg(Color::White).add_modifier(Modifier::BOLD);
+    let lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  Bucket     ", label),
+            Span::styled(format!("{}/{}", setup.bucket, setup.prefix), value),
+        ]),
+        Line::from(vec![
+            Span::styled("  Local      ", label),
+            Span::styled(setup.local.display().to_string(), value),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  Direction  ", label),
+            Span::styled(setup.direction.label(), Style::default().fg(Color::Yellow)),
+        ]),
+        Line::from(vec![
+            Span::styled("  Policy     ", label),
+            Span::styled(setup.policy.label(), Style::default().fg(Color::Yellow)),
+        ]),
+        Line::from(vec![
+            Span::styled("  ACL        ", label),
+            match &setup.acl {
+                Some(acl) => Span::styled(acl.clone(), Style::default().fg(Color::Yellow)),
+                None => Span::styled("bucket default (uploads only)", label),
+            },
+        ]),
+    ];
+
+    let hint = Line::from(" Tab direction  p policy  a ACL  Enter plan  Esc cancel ")
+        .style(Style::default().fg(Color::DarkGray));
+    let block = Block::bordered()
+        .title(" Sync ")
+        .title_bottom(hint)
         .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
         .border_style(Style::default().fg(Color::Cyan));
 
     frame.render_widget(Clear, popup);
     frame.render_widget(Paragraph::new(lines).block(block), popup);
 }
+
+pub fn render_plan(frame: &mut Frame, app: &mut App) {
+    let Some(plan) = &mut app.plan else {
+        return;
+    };
+    let area = frame.area();
+    let width = 100u16.min(area.width.saturating_sub(4));
+    let height = 24u16.min(area.height.saturating_sub(2));
+    let popup = centered(area, width, height);
+
+    let items: Vec<ListItem> = plan
+        .items
+        .iter()
+        .map(|item| {
+            let color = match item.action {
+                PlanAction::Download | PlanAction::Upload => Color::Green,
+                PlanAction::Overwrite => Color::Yellow,
+                PlanAction::Delete => Color::Red,
+                PlanAction::Skip => Color::DarkGray,
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{:<10}", item.action.label()), Style::default().fg(color)),
+                Span::styled(
+                    format!("{:>10}  ", humansize::format_size(item.size, humansize::BINARY)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(item.path.as_str(), Style::default().fg(Color::White)),
+            ]))
+        })
+        .collect();
+
+    let summary = Line::from(format!(" {} ", plan.summary())).style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    );
+    let hint = Line::from(" Enter confirm  e export  j/k scroll  Esc cancel ")
+        .style(Style::default().fg(Color::DarkGray));
+    let block = Block::bordered()
+        .title(format!(" Plan: {} ", plan.title))
+        .title(summary.right_aligned())
+        .title_bottom(hint)
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, &mut plan.state);
+}
+
+/// Stacked notifications in the bottom-right corner, newest at the bottom.
+pub fn render_toasts(frame: &mut Frame, app: &App) {
+    if app.toasts.is_empty() {
+        return;
+    }
+    let area = frame.area();
+    let width = 60u16.min(area.width.saturating_sub(2));
+    let inner = width.saturating_sub(4).max(1) as usize;
+    let x = area.width.saturating_sub(width + 1);
+    // Keep the status bar visible
+    let mut bottom = area.height.saturating_sub(1);
+
+    let toasts: Vec<_> = app.toasts.iter().collect();
+    for toast in toasts.into_iter().rev() {
+        let lines = toast.message.chars().count().div_ceil(inner).clamp(1, 3) as u16;
+        let height = lines + 2;
+        if bottom < height + 1 {
+            break;
+        }
+        bottom -= height;
+        let popup = ratatui::layout::Rect::new(x, bottom, width, height);
+        let (color, title) = match toast.severity {
+            Severity::Info => (Color::Green, " Info "),
+            Severity::Warn => (Color::Yellow, " Warning "),
+            Severity::Error => (Color::Red, " Error "),
+        };
+        let block = Block::bordered()
+            .title(title)
+            .border_style(Style::default().fg(color));
+        let text = Paragraph::new(toast.message.as_str())
+            .style(Style::default().fg(color))
+            .wrap(Wrap { trim: true })
+            .block(block);
+        frame.render_widget(Clear, popup);
+        frame.render_widget(text, popup);
+    }
+}
+
+/// Which-key panel listing the completions of a pending `g` / `y` sequence.
+pub fn render_pending_keys(frame: &mut Frame, app: &App) {
+    let Some(prefix) = app.pending_key else {
+        return;
+    };
+    let completions = keymap::completions(prefix);
+    let area = frame.area();
+    let width = 44u16.min(area.width.saturating_sub(2));
+    let height = (completions.len() as u16 + 2).min(area.height.saturating_sub(1));
+    let x = area.width.saturating_sub(width + 1);
+    // Sit just above the status bar
+    let y = area.height.saturating_sub(height + 1);
+    let popup = ratatui::layout::Rect::new(x, y, width, height);
+
+    let lines: Vec<Line> = completions
+        .iter()
+        .map(|b| {
+            Line::from(vec![
+                Span::styled(format!(" {}{}  ", prefix, b.keys), Style::default().fg(Color::Yellow)),
+                Span::raw(b.desc),
+            ])
+        })
+        .collect();
+    let block = Block::bordered()
+        .title(format!(" {}\u{2026} ", prefix)) // …
+        .title_bottom(" Esc cancel ")
+        .border_style(Style::default().fg(Color::Cyan));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+/// Plugin command palette: typed filter on top, matching commands below.
+pub fn render_palette(frame: &mut Frame, app: &mut App) {
+    let items = app.palette_items();
+    let Some(palette) = &mut app.palette else {
+        return;
+    };
+    let area = frame.area();
+    let width = 60u16.min(area.width.saturating_sub(4));
+    let height = (items.len() as u16 + 4).clamp(6, 20).min(area.height.saturating_sub(2));
+    let mut popup = centered(area, width, height);
+    // Sit high so the list grows downward as the query narrows it
+    popup.y = area.y + (area.height / 4).min(area.height - popup.height);
+
+    let block = Block::bordered()
+        .title(" Commands ")
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .title_bottom(" Enter run  Esc close ")
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(popup);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(block, popup);
+
+    let input = Line::from(vec![
+        Span::styled(" :", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Span::raw(palette.query.as_str()),
+        Span::styled("_", Style::default().fg(Color::DarkGray)),
+    ]);
+    frame.render_widget(
+        Paragraph::new(input),
+        ratatui::layout::Rect::new(inner.x, inner.y, inner.width, inner.height.min(1)),
+    );
+
+    let rows: Vec<ListItem> = items
+        .iter()
+        .map(|item| {
+            ListItem::new(Line::from(vec![
+                Span::raw(format!(" {}", item.title)),
+                Span::styled(format!("  {}", item.source), Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+    let list = List::new(rows).highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+    let list_area = ratatui::layout::Rect::new(
+        inner.x,
+        inner.y + inner.height.min(2),
+        inner.width,
+        inner.height.saturating_sub(2),
+    );
+    frame.render_stateful_widget(list, list_area, &mut palette.state);
+}
+
+/// Jump list: typed fuzzy filter on top, visited prefixes below, most recent first.
+pub fn render_jumps(frame: &mut Frame, app: &mut App) {
+    let labels: Vec<String> = app.jump_items().iter().map(|j| j.label()).collect();
+    let Some(picker) = &mut app.jump_picker else {
+        return;
+    };
+    let area = frame.area();
+    let width = 70u16.min(area.width.saturating_sub(4));
+    let height = (labels.len() as u16 + 4).clamp(6, 20).min(area.height.saturating_sub(2));
+    let mut popup = centered(area, width, height);
+    popup.y = area.y + (area.height / 4).min(area.height - popup.height);
+
+    let block = Block::bordered()
+        .title(" Jump list ")
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .title_bottom(" Enter open  Esc close ")
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(popup);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(block, popup);
+
+    let input = Line::from(vec![
+        Span::styled(" >", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Span::raw(picker.query.as_str()),
+        Span::styled("_", Style::default().fg(Color::DarkGray)),
+    ]);
+    frame.render_widget(
+        Paragraph::new(input),
+        ratatui::layout::Rect::new(inner.x, inner.y, inner.width, inner.height.min(1)),
+    );
+
+    let rows: Vec<ListItem> = labels
+        .into_iter()
+        .map(|label| ListItem::new(Line::from(format!(" {}", label))))
+        .collect();
+    let list = List::new(rows).highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+    let list_area = ratatui::layout::Rect::new(
+        inner.x,
+        inner.y + inner.height.min(2),
+        inner.width,
+        inner.height.saturating_sub(2),
+    );
+    frame.render_stateful_widget(list, list_area, &mut picker.state);
+}
+
+/// Scrollable output of a configured action.
+pub fn render_action_output(frame: &mut Frame, app: &App) {
+    let Some(output) = &app.action_output else {
+        return;
+    };
+    let area = frame.area();
+    let width = 100u16.min(area.width.saturating_sub(4));
+    let height = 30u16.min(area.height.saturating_sub(2));
+    let popup = centered(area, width, height);
+
+    let lines: Vec<Line> = output.lines.iter().map(|l| Line::raw(l.as_str())).collect();
+    let block = Block::bordered()
+        .title(format!(" {} ", output.title))
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .title_bottom(format!(
+            " {}/{}  j/k scroll  y copy  Esc close ",
+            (output.scroll + 1).min(output.lines.len()),
+            output.lines.len()
+        ))
+        .border_style(Style::default().fg(Color::Cyan));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((output.scroll as u16, 0)),
+        popup,
+    );
+}
@@ -1,23 +1,58 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
 use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{
-    Block, Cell, HighlightSpacing, List, ListItem, Paragraph, Row, Table, Wrap,
+    Block, Cell, HighlightSpacing, List, ListItem, Paragraph, Row, Table, TableState, Wrap,
 };
 use ratatui::Frame;
 
-use crate::app::{App, Entry, Pane};
+use crate::app::media::Thumbnail;
+use crate::app::{App, Entry, Location, Pane};
+use crate::credentials::RemoteTag;
+use crate::settings::{Column, ColumnSpec};
 
+use super::icons;
 use super::local_fs;
 use super::popups;
 use super::status;
+use super::text::{local_time, middle_ellipsis};
+
+/// Below this width the side-by-side panes give way to the focused one alone.
+const COMPACT_WIDTH: u16 = 80;
+
+/// Smallest terminal anything but a size notice is drawn in.
+const MIN_WIDTH: u16 = 20;
+const MIN_HEIGHT: u16 = 8;
 
 pub fn render(frame: &mut Frame, app: &mut App) {
+    let screen = frame.area();
+    if screen.width < MIN_WIDTH || screen.height < MIN_HEIGHT {
+        let notice = Paragraph::new(format!("Terminal too small ({}x{})", screen.width, screen.height))
+            .style(Style::default().fg(Color::Yellow))
+            .wrap(Wrap { trim: true });
+        frame.render_widget(notice, screen);
+        return;
+    }
+    let compact = screen.width < COMPACT_WIDTH;
+
     let has_text_preview = app.preview.text_content.is_some()
+        || app.preview.media.is_some()
         || app.preview.loading
         || app.preview.error.is_some();
 
-    let meta_height = if has_text_preview { Constraint::Percentage(40) } else { Constraint::Length(7) };
+    let meta_height = if has_text_preview {
+        Constraint::Percentage(40)
+    } else {
+        let target_line = u16::from(app.download_mode);
+        let panel = if app.show_metadata {
+            metadata_height(app, screen)
+        } else {
+            0
+        };
+        Constraint::Length(panel + target_line)
+    };
 
     let outer = Layout::default()
         .direction(Direction::Vertical)
@@ -27,15 +62,20 @@ pub fn render(frame: &mut Frame, app: &mut App) {
             meta_height,           // Metadata or text preview panel
             Constraint::Length(1), // Status / search bar
         ])
-        .split(frame.area());
+        .split(screen);
 
-    // Title bar
-    let title = Line::from(vec![
+    // Title bar (colored by the current remote's environment tag)
+    let current_tag = app.current_remote().and_then(|r| app.remote_tag(r));
+    let title_label = match current_tag {
+        Some(tag) => format!(" S3 Explorer [{}] ", tag.label()),
+        None => " S3 Explorer ".to_string(),
+    };
+    let mut title = Line::from(vec![
         Span::styled(
-            " S3 Explorer ",
+            title_label,
             Style::default()
                 .fg(Color::Black)
-                .bg(Color::Cyan)
+                .bg(current_tag.map_or(Color::Cyan, tag_color))
                 .add_modifier(Modifier::BOLD),
         ),
         Span::styled(
@@ -43,10 +83,44 @@ pub fn render(frame: &mut Frame, app: &mut App) {
             Style::default().fg(Color::DarkGray),
         ),
     ]);
+    // Where requests go, so look-alike aliases can't be confused
+    if let Some(identity) = app.current_remote().and_then(|r| app.identity(r)) {
+        title.push_span(Span::styled(
+            format!("@ {} ({}) ", identity.host(), identity.provider),
+            Style::default().fg(Color::Cyan),
+        ));
+        if let Some(profile) = &identity.profile {
+            title.push_span(Span::styled(format!("as {} ", profile), Style::default().fg(Color::Magenta)));
+        }
+    }
+    if app.zoomed {
+        title.push_span(Span::styled("[zoom] ", Style::default().fg(Color::Yellow)));
+    } else if compact {
+        title.push_span(Span::styled("[compact] ", Style::default().fg(Color::Yellow)));
+    }
     frame.render_widget(Paragraph::new(title), outer[0]);
 
     // Main content: remotes + browser (+ local FS on right when downloading)
-    if app.download_mode {
+    if app.zoomed {
+        render_zoomed(frame, app, outer[1].union(outer[2]), has_text_preview);
+    } else if compact {
+        // Narrow terminal: one pane at a time (Tab switches), panels below stay as they are
+        render_focused_pane(frame, app, outer[1]);
+        if app.download_mode {
+            let meta_layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)])
+                .split(outer[2]);
+            local_fs::render_download_target(frame, app, meta_layout[0]);
+            if app.show_metadata {
+                render_metadata(frame, app, meta_layout[1]);
+            }
+        } else if has_text_preview {
+            render_text_preview(frame, app, outer[2]);
+        } else if app.show_metadata {
+            render_metadata(frame, app, outer[2]);
+        }
+    } else if app.download_mode {
         let content = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
@@ -63,12 +137,14 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         // Show download target info in the metadata area
         let meta_layout = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(1), Constraint::Min(1)])
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
             .split(outer[2]);
 
         local_fs::render_download_target(frame, app, meta_layout[0]);
-        render_metadata(frame, app, meta_layout[1]);
-    } else if app.preview.text_content.is_some() || app.preview.loading || app.preview.error.is_some() {
+        if app.show_metadata {
+            render_metadata(frame, app, meta_layout[1]);
+        }
+    } else if has_text_preview {
         // Text preview active: split main area into browser (top) and preview (bottom)
         let content = Layout::default()
             .direction(Direction::Horizontal)
@@ -88,10 +164,24 @@ pub fn render(frame: &mut Frame, app: &mut App) {
 
         render_remotes(frame, app, content[0]);
         render_browser(frame, app, content[1]);
-        render_metadata(frame, app, outer[2]);
+        if app.show_metadata {
+            render_metadata(frame, app, outer[2]);
+        }
     }
 
-    if app.search_active {
+    if app.range_input.is_some() {
+        status::render_range_input(frame, app, outer[3]);
+    } else if app.filter_input.is_some() {
+        status::render_filter_input(frame, app, outer[3]);
+    } else if app.marks_input.is_some() {
+        status::render_marks_input(frame, app, outer[3]);
+    } else if app.bucket_input.is_some() {
+        status::render_bucket_input(frame, app, outer[3]);
+    } else if app.new_object.is_some() {
+        status::render_new_object_input(frame, app, outer[3]);
+    } else if app.duplicate.is_some() {
+        status::render_duplicate_input(frame, app, outer[3]);
+    } else if app.search_active {
         status::render_search_bar(frame, app, outer[3]);
     } else {
         status::render_status_bar(frame, app, outer[3]);
@@ -101,8 +191,133 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         popups::render_confirm_delete(frame, app);
     }
 
-    if app.show_help {
-        popups::render_help(frame);
+    if app.show_history {
+        popups::render_history(frame, app);
+    }
+
+    if app.context_view.is_some() {
+        popups::render_context_view(frame, app);
+    }
+
+    if app.quick_view.is_some() {
+        popups::render_quick_view(frame, app);
+    }
+
+    if app.entry_menu.is_some() {
+        popups::render_entry_menu(frame, app);
+    }
+
+    if app.acl_picker.is_some() {
+        popups::render_acl_picker(frame, app);
+    }
+
+    if app.download_conflict.is_some() {
+        popups::render_download_conflict(frame, app);
+    }
+
+    if app.confirm_quit {
+        popups::render_confirm_quit(frame);
+    }
+
+    if app.resume_prompt {
+        popups::render_resume_prompt(frame, app);
+    }
+
+    if app.identity_popup.is_some() {
+        popups::render_identity(frame, app);
+    }
+
+    if app.confirm_index {
+        popups::render_confirm_index(frame, app);
+    }
+
+    if app.link_bundle.is_some() {
+        popups::render_link_bundle(frame, app);
+    }
+
+    if app.copy_picker.is_some() {
+        popups::render_copy_picker(frame, app);
+    }
+
+    if app.copy_picker.as_ref().is_some_and(|p| p.options_open) {
+        popups::render_copy_options(frame, app);
+    }
+
+    if app.sync_setup.is_some() {
+        popups::render_sync_setup(frame, app);
+    }
+
+    if app.plan.is_some() {
+        popups::render_plan(frame, app);
+    }
+
+    if app.protected_confirm.is_some() {
+        popups::render_confirm_protected(frame, app);
+    }
+
+    if app.show_favorites {
+        popups::render_favorites(frame, app);
+    }
+
+    if app.help.is_some() {
+        popups::render_help(frame, app);
+    }
+
+    if app.action_output.is_some() {
+        popups::render_action_output(frame, app);
+    }
+
+    if app.palette.is_some() {
+        popups::render_palette(frame, app);
+    }
+
+    if app.jump_picker.is_some() {
+        popups::render_jumps(frame, app);
+    }
+
+    popups::render_pending_keys(frame, app);
+    popups::render_toasts(frame, app);
+}
+
+/// Render only the focused pane (or the open text preview) across the whole content area.
+fn render_zoomed(
+    frame: &mut Frame,
+    app: &mut App,
+    area: ratatui::layout::Rect,
+    has_text_preview: bool,
+) {
+    if has_text_preview && !app.download_mode {
+        render_text_preview(frame, app, area);
+        return;
+    }
+
+    match app.pane {
+        Pane::LocalFs => {
+            let layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(1), Constraint::Length(1)])
+                .split(area);
+            local_fs::render_local_fs(frame, app, layout[0]);
+            local_fs::render_download_target(frame, app, layout[1]);
+        }
+        _ => render_focused_pane(frame, app, area),
+    }
+}
+
+/// Render just the pane that has focus into `area`.
+fn render_focused_pane(frame: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
+    match app.pane {
+        Pane::Remotes => render_remotes(frame, app, area),
+        Pane::Browser => render_browser(frame, app, area),
+        Pane::LocalFs => local_fs::render_local_fs(frame, app, area),
+    }
+}
+
+fn tag_color(tag: RemoteTag) -> Color {
+    match tag {
+        RemoteTag::Prod => Color::Red,
+        RemoteTag::Staging => Color::Yellow,
+        RemoteTag::Dev => Color::Green,
     }
 }
 
@@ -116,7 +331,25 @@ fn render_remotes(frame: &mut Frame, app: &mut App, area: ratatui::layout::Rect)
     let items: Vec<ListItem> = app
         .remotes
         .iter()
-        .map(|name| ListItem::new(format!("  {}", name)))
+        .map(|name| {
+            let mut spans = vec![Span::raw(format!("  {}", name))];
+            if let Some(tag) = app.remote_tag(name) {
+                spans.push(Span::styled(
+                    format!(" {}", tag.label()),
+                    Style::default().fg(tag_color(tag)),
+                ));
+            }
+            if app.remote_is_anonymous(name) {
+                spans.push(Span::styled(" anon", Style::default().fg(Color::DarkGray)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .chain(app.promoted_prefixes().into_iter().map(|fav| {
+            ListItem::new(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(fav.alias_label(), Style::default().fg(Color::Cyan)),
+            ]))
+        }))
         .collect();
 
     let list = List::new(items)
@@ -135,6 +368,86 @@ fn render_remotes(frame: &mut Frame, app: &mut App, area: ratatui::layout::Rect)
     frame.render_stateful_widget(list, area, &mut app.remote_state);
 }
 
+/// Byte ranges of every case-insensitive, non-overlapping occurrence of `query` in `name`.
+fn match_ranges(name: &str, query: &str) -> Vec<(usize, usize)> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let chars: Vec<(usize, char)> = name.char_indices().collect();
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let mut lowered = chars[i..].iter().flat_map(|(_, c)| c.to_lowercase());
+        if query.iter().all(|q| lowered.next() == Some(*q)) {
+            // Find how many source chars the match consumed
+            let mut consumed = 0;
+            let mut len = 0;
+            while len < query.len() {
+                len += chars[i + consumed].1.to_lowercase().count();
+                consumed += 1;
+            }
+            let end = chars.get(i + consumed).map_or(name.len(), |(b, _)| *b);
+            ranges.push((chars[i].0, end));
+            i += consumed;
+        } else {
+            i += 1;
+        }
+    }
+    ranges
+}
+
+/// `name` with the parts matching the search query emphasized.
+fn highlight_matches(name: &str, query: &str, base: Style) -> Line<'static> {
+    let hit = base
+        .fg(Color::Black)
+        .bg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    for (start, end) in match_ranges(name, query) {
+        if start > pos {
+            spans.push(Span::styled(name[pos..start].to_string(), base));
+        }
+        spans.push(Span::styled(name[start..end].to_string(), hit));
+        pos = end;
+    }
+    if pos < name.len() {
+        spans.push(Span::styled(name[pos..].to_string(), base));
+    }
+    Line::from(spans)
+}
+
+/// Narrowest the name column gets before other columns are dropped to make room.
+const NAME_MIN_WIDTH: u16 = 20;
+
+/// The configured columns with their widths (0 for a name that fills the rest), the
+/// name always among them. Columns that don't fit `area_width` are dropped from the
+/// right, so narrow panes keep the name readable.
+fn fit_columns(specs: &[ColumnSpec], area_width: u16) -> Vec<(Column, u16)> {
+    let mut columns: Vec<(Column, u16)> = specs
+        .iter()
+        .map(|spec| (spec.column, spec.width.or(spec.column.default_width()).unwrap_or(0)))
+        .collect();
+    if !columns.iter().any(|(column, _)| *column == Column::Name) {
+        columns.insert(0, (Column::Name, 0));
+    }
+    // Borders, the highlight symbol and the icon column with its spacing
+    let available = area_width.saturating_sub(6);
+    let needed = |columns: &[(Column, u16)]| -> u16 {
+        columns
+            .iter()
+            .map(|(column, width)| if *column == Column::Name { (*width).max(NAME_MIN_WIDTH) } else { *width } + 1)
+            .sum()
+    };
+    while needed(&columns) > available
+        && let Some(pos) = columns.iter().rposition(|(column, _)| *column != Column::Name)
+    {
+        columns.remove(pos);
+    }
+    columns
+}
+
 fn render_browser(frame: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
     let border_style = if app.pane == Pane::Browser {
         Style::default().fg(Color::Cyan)
@@ -142,51 +455,70 @@ fn render_browser(frame: &mut Frame, app: &mut App, area: ratatui::layout::Rect)
         Style::default().fg(Color::DarkGray)
     };
 
-    let row_data: Vec<(String, String, String, String, Color, Color)> = app
-        .entries
-        .iter()
-        .map(|entry| match entry {
-            Entry::Bucket(b) => {
-                let date = b.creation_date.clone().unwrap_or_default();
-                ("B".into(), b.name.clone(), "bucket".into(), date, Color::Yellow, Color::White)
-            }
-            Entry::Object(obj) if obj.is_dir => (
-                "D".into(),
-                format!("{}/", obj.display_name),
-                "dir".into(),
-                String::new(),
-                Color::Blue,
-                Color::Blue,
-            ),
-            Entry::Object(obj) => {
-                let size = humansize::format_size(obj.size as u64, humansize::BINARY);
-                let date = obj.last_modified.clone().unwrap_or_default();
-                (" ".into(), obj.display_name.clone(), size, date, Color::Reset, Color::White)
-            }
-        })
-        .collect();
-
-    let visible_len = row_data.len();
-
-    let rows: Vec<Row> = row_data
-        .iter()
-        .map(|(icon, name, size, date, icon_color, name_color)| {
-            let size_color = if icon.trim().is_empty() { Color::Green } else { Color::DarkGray };
-            Row::new(vec![
-                Cell::from(icon.as_str()).style(Style::default().fg(*icon_color)),
-                Cell::from(name.as_str()).style(Style::default().fg(*name_color)),
-                Cell::from(format!("{:>10}", size)).style(Style::default().fg(size_color)),
-                Cell::from(format!("{:>16}", date)).style(Style::default().fg(Color::DarkGray)),
-            ])
-        })
-        .collect();
-
-    let widths = [
-        Constraint::Length(1),  // icon
-        Constraint::Min(20),    // name (fills remaining)
-        Constraint::Length(10), // size / type
-        Constraint::Length(16), // date
-    ];
+    let nerd = app.settings.nerd_icons;
+    let visible_len = app.entries.len();
+    let columns = fit_columns(&app.settings.columns, area.width);
+    let query = if app.search_active { app.search_query.clone() } else { String::new() };
+    // The name column's share of the pane, which long names are cut to
+    let name_width = match columns.iter().find(|(column, _)| *column == Column::Name) {
+        Some((_, width)) if *width > 0 => *width,
+        _ => {
+            let others: u16 = columns
+                .iter()
+                .filter(|(column, _)| *column != Column::Name)
+                .map(|(_, width)| width + 1)
+                .sum();
+            area.width.saturating_sub(6).saturating_sub(others)
+        }
+    } as usize;
+
+    // Only the rows in view are built. Scroll the way the table would, so the
+    // selection stays visible, then hand it the window alone.
+    let height = (area.height.saturating_sub(2) as usize).max(1);
+    let selected = app.browser_state.selected().filter(|_| visible_len > 0).map(|s| s.min(visible_len - 1));
+    let mut offset = app.browser_state.offset();
+    if let Some(s) = selected {
+        if s < offset {
+            offset = s;
+        } else if s >= offset + height {
+            offset = s + 1 - height;
+        }
+    }
+    offset = offset.min(visible_len.saturating_sub(height));
+    *app.browser_state.offset_mut() = offset;
+    let window = offset..(offset + height).min(visible_len);
+
+    // Rebuild the rows only when something they show has changed
+    let key = {
+        let mut hasher = DefaultHasher::new();
+        (window.clone(), &columns, name_width, &query, nerd).hash(&mut hasher);
+        for entry in &app.entries[window.clone()] {
+            row_key(app, entry, &mut hasher);
+        }
+        hasher.finish()
+    };
+    if app.browser_rows.as_ref().is_none_or(|(k, _)| *k != key) {
+        let rows = app.entries[window.clone()]
+            .iter()
+            .map(|entry| browser_row(app, entry, &columns, name_width, &query))
+            .collect::<Vec<_>>();
+        let mut widths = vec![Constraint::Length(1)]; // icon
+        widths.extend(columns.iter().map(|(column, width)| match column {
+            // The name fills whatever the other columns leave
+            Column::Name if *width == 0 => Constraint::Min(NAME_MIN_WIDTH),
+            _ => Constraint::Length(*width),
+        }));
+        let table = Table::new(rows, widths)
+            .column_spacing(1)
+            .row_highlight_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("> ")
+            .highlight_spacing(HighlightSpacing::Always);
+        app.browser_rows = Some((key, table));
+    }
 
     let title = if app.search_active {
         format!(
@@ -195,25 +527,185 @@ fn render_browser(frame: &mut Frame, app: &mut App, area: ratatui::layout::Rect)
             visible_len
         )
     } else {
-        format!(" {} ", app.location_display())
+        let mut title = format!(" {} ", app.location_display());
+        if matches!(app.location, Location::ObjectList { .. }) {
+            let prefs = app.listing_prefs();
+            title.push_str(&format!(
+                "[{} {}{}{}] ",
+                prefs.sort.label(),
+                if prefs.descending { "\u{2193}" } else { "\u{2191}" }, // ↓ ↑
+                if prefs.natural { " natural" } else { "" },
+                if prefs.dirs_first { "" } else { " mixed" }
+            ));
+            if let Some(filter) = &prefs.filter {
+                title.push_str(&format!("[filter: {}] ", filter));
+            }
+            if app.tree.is_some() {
+                title.push_str("[tree] ");
+            }
+        }
+        if !app.marked.is_empty() {
+            title.push_str(&format!("[{} marked] ", app.marked.len()));
+        }
+        title
     };
 
-    let table = Table::new(rows, widths)
-        .block(
-            Block::bordered()
-                .title(title)
-                .border_style(border_style),
-        )
-        .column_spacing(1)
-        .row_highlight_style(
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol("> ")
-        .highlight_spacing(HighlightSpacing::Always);
+    let mut block = Block::bordered().title(title).border_style(border_style);
+    if let Location::ObjectList { remote, bucket, .. } = &app.location
+        && app.is_bucket_locked(remote, bucket)
+    {
+        block = block.title(
+            Line::from(format!(" {} locked ", icons::lock(nerd)))
+                .style(Style::default().fg(Color::White).bg(Color::Red)),
+        );
+    }
+    // Say when what's shown may not be everything, or not current
+    if !app.search_active
+        && !matches!(app.location, Location::RemoteList)
+        && let Some(badge) = app.listing_freshness.badge()
+    {
+        block = block.title(
+            Line::from(format!(" {} ", badge)).style(Style::default().fg(Color::Black).bg(Color::Yellow)),
+        );
+    }
+    // Index progress of this bucket, while it is still listing
+    if let Location::ObjectList { remote, bucket, .. } = &app.location
+        && app.index_key.as_ref().is_some_and(|(r, b)| r == remote && b == bucket)
+        && let Some(label) = app.index_progress_label()
+    {
+        block = block.title(
+            Line::from(format!(" {} ", label))
+                .right_aligned()
+                .style(Style::default().fg(Color::DarkGray)),
+        );
+    }
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    if let Some((_, table)) = &app.browser_rows {
+        let mut state = TableState::default().with_selected(selected.map(|s| s - offset));
+        frame.render_stateful_widget(table, inner, &mut state);
+    }
+}
+
+/// Feed everything `browser_row` draws for `entry` into `hasher`.
+fn row_key(app: &App, entry: &Entry, hasher: &mut DefaultHasher) {
+    match entry {
+        Entry::Bucket(b) => {
+            let locked = match &app.location {
+                Location::BucketList { remote } => app.is_bucket_locked(remote, &b.name),
+                _ => false,
+            };
+            (&b.name, &b.creation_date, locked).hash(hasher);
+        }
+        Entry::Object(obj) => {
+            (&obj.key, &obj.display_name, obj.size, obj.is_dir).hash(hasher);
+            (&obj.last_modified, &obj.etag, &obj.storage_class).hash(hasher);
+            let open = obj.is_dir && app.tree.as_ref().is_some_and(|t| t.is_expanded(&obj.key));
+            (app.tree_depth(&obj.key), open).hash(hasher);
+            (app.is_marked(&obj.key), app.is_favorite(&obj.key)).hash(hasher);
+        }
+        Entry::Group(g) => (&g.prefix, g.hits, g.bytes, g.expanded).hash(hasher),
+    }
+}
+
+/// The browser table row of one entry.
+fn browser_row(app: &App, entry: &Entry, columns: &[(Column, u16)], name_width: usize, query: &str) -> Row<'static> {
+    let nerd = app.settings.nerd_icons;
+    let (icon, name, icon_color, name_color) = match entry {
+        Entry::Bucket(b) => {
+            let name = match &app.location {
+                Location::BucketList { remote } if app.is_bucket_locked(remote, &b.name) => {
+                    format!("{} {}", b.name, icons::lock(nerd))
+                }
+                _ => b.name.clone(),
+            };
+            (icons::bucket(nerd), name, Color::Yellow, Color::White)
+        }
+        Entry::Group(g) => {
+            // ▾ expanded, ▸ collapsed
+            let name = format!(
+                "{} {} ({} hit{})",
+                if g.expanded { '\u{25be}' } else { '\u{25b8}' },
+                if g.prefix.is_empty() { "/" } else { &g.prefix },
+                g.hits,
+                if g.hits == 1 { "" } else { "s" }
+            );
+            (icons::dir(nerd, g.expanded), name, Color::Cyan, Color::Cyan)
+        }
+        Entry::Object(obj) if obj.is_dir => {
+            let open = app.tree.as_ref().is_some_and(|t| t.is_expanded(&obj.key));
+            let name = match app.tree_depth(&obj.key) {
+                // ▾ expanded, ▸ collapsed
+                Some(depth) => {
+                    format!("{}{} {}/", "  ".repeat(depth), if open { '\u{25be}' } else { '\u{25b8}' }, obj.display_name)
+                }
+                None => format!("{}/", obj.display_name),
+            };
+            (icons::dir(nerd, open), name, Color::Blue, Color::Blue)
+        }
+        Entry::Object(obj) => {
+            let name = match app.tree_depth(&obj.key) {
+                Some(depth) => format!("{}  {}", "  ".repeat(depth), obj.display_name),
+                None => obj.display_name.clone(),
+            };
+            if app.is_marked(&obj.key) {
+                ("+", name, Color::Magenta, Color::Magenta)
+            } else if app.is_favorite(&obj.key) {
+                ("\u{2605}", name, Color::Yellow, Color::White) // ★
+            } else if obj.size == 0 {
+                // Empty files and placeholders, easy to mistake for data
+                (icons::file(&obj.display_name, nerd).0, name, Color::DarkGray, Color::DarkGray)
+            } else {
+                let (icon, color) = icons::file(&obj.display_name, nerd);
+                (icon, name, color, color)
+            }
+        }
+    };
 
-    frame.render_stateful_widget(table, area, &mut app.browser_state);
+    let name = middle_ellipsis(&name, name_width);
+    let mut cells = vec![Cell::from(icon).style(Style::default().fg(icon_color))];
+    for (column, width) in columns {
+        let cell = match column {
+            Column::Name => Cell::from(highlight_matches(&name, query, Style::default().fg(name_color))),
+            Column::Size => {
+                let (text, color) = match entry {
+                    Entry::Bucket(_) => ("bucket".to_string(), Color::DarkGray),
+                    Entry::Group(g) => (humansize::format_size(g.bytes, humansize::BINARY), Color::Cyan),
+                    Entry::Object(obj) if obj.is_dir => ("dir".to_string(), Color::DarkGray),
+                    Entry::Object(obj) if obj.size == 0 => ("empty".to_string(), Color::DarkGray),
+                    Entry::Object(obj) => (humansize::format_size(obj.size as u64, humansize::BINARY), Color::Green),
+                };
+                Cell::from(format!("{:>w$}", text, w = *width as usize)).style(Style::default().fg(color))
+            }
+            Column::Modified => {
+                let date = match entry {
+                    Entry::Bucket(b) => b.creation_date.as_ref(),
+                    Entry::Object(obj) if obj.is_dir => None,
+                    Entry::Object(obj) => obj.last_modified.as_ref(),
+                    Entry::Group(_) => None,
+                };
+                Cell::from(format!("{:>w$}", date.map(local_time).unwrap_or_default(), w = *width as usize))
+                    .style(Style::default().fg(Color::DarkGray))
+            }
+            Column::Etag => {
+                let etag = match entry {
+                    Entry::Object(obj) => obj.etag.as_deref().map(|e| e.trim_matches('"')),
+                    Entry::Bucket(_) | Entry::Group(_) => None,
+                };
+                Cell::from(etag.unwrap_or("").to_string()).style(Style::default().fg(Color::DarkGray))
+            }
+            Column::StorageClass => {
+                let class = match entry {
+                    Entry::Object(obj) => obj.storage_class.as_deref(),
+                    Entry::Bucket(_) | Entry::Group(_) => None,
+                };
+                Cell::from(class.unwrap_or("").to_string()).style(Style::default().fg(Color::DarkGray))
+            }
+        };
+        cells.push(cell);
+    }
+    Row::new(cells)
 }
 
 fn render_text_preview(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
@@ -224,7 +716,7 @@ fn render_text_preview(frame: &mut Frame, app: &App, area: ratatui::layout::Rect
         .and_then(|k| k.rsplit('/').next())
         .unwrap_or("Preview");
 
-    if app.preview.loading {
+    if app.preview.loading && app.preview.text_content.is_none() {
         let block = Block::bordered()
             .title(format!(" Preview: {} ", name))
             .border_style(Style::default().fg(Color::Cyan));
@@ -244,49 +736,163 @@ fn render_text_preview(frame: &mut Frame, app: &App, area: ratatui::layout::Rect
         )))
         .block(block);
         frame.render_widget(content, area);
+    } else if let Some(media) = &app.preview.media {
+        let hint = if app.preview.is_playing() {
+            " \u{266a} playing  s stop  z zoom  q close " // ♪
+        } else {
+            " p play  z zoom  q close "
+        };
+        let block = Block::bordered()
+            .title(format!(" Preview: {} ", name))
+            .title_bottom(Line::from(hint).style(Style::default().fg(Color::DarkGray)))
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Min(20)])
+            .split(inner);
+
+        match &media.thumbnail {
+            Some(thumb) => frame.render_widget(Paragraph::new(thumbnail_lines(thumb, cols[0])), cols[0]),
+            None => frame.render_widget(
+                Paragraph::new(Span::styled("  No thumbnail", Style::default().fg(Color::DarkGray))),
+                cols[0],
+            ),
+        }
+
+        let info: Vec<Line> = media
+            .info
+            .iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(format!(" {:<9}", label), Style::default().fg(Color::Cyan)),
+                    Span::raw(value.as_str()),
+                ])
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(info).wrap(Wrap { trim: false }), cols[1]);
     } else if let Some(text) = &app.preview.text_content {
         // Inner height = area minus 2 border lines
         let inner_height = area.height.saturating_sub(2) as usize;
         let total = app.preview.line_count;
         let offset = app.preview.scroll_offset;
 
+        let records = app
+            .preview
+            .records
+            .map(|n| format!(" {} records", n))
+            .unwrap_or_default();
+        let columns = match app.preview.hscroll {
+            0 => String::new(),
+            h => format!(" col {}+", h + 1),
+        };
         let title = format!(
-            " Preview: {} [{}-{}/{}] ",
+            " Preview: {} [{}-{}/{}]{}{} ",
             name,
             offset + 1,
             (offset + inner_height).min(total),
             total,
+            columns,
+            records,
         );
 
-        let block = Block::bordered()
+        let mut block = Block::bordered()
             .title(title)
-            .title_bottom(Line::from(" j/k scroll  Ctrl+d/u page  g/G top/bottom  q close ").style(Style::default().fg(Color::DarkGray)))
+            .title_bottom(Line::from(" j/k scroll  h/l pan  w wrap  y copy  g/G top/bottom  q close ").style(Style::default().fg(Color::DarkGray)))
             .border_style(Style::default().fg(Color::Cyan));
+        if app.preview.loading {
+            block = block.title_bottom(
+                Line::from(" loading more... ").style(Style::default().fg(Color::Yellow)).right_aligned(),
+            );
+        } else if app.preview.is_truncated() {
+            let shown = humansize::format_size(app.preview.raw_text.len() as u64, humansize::BINARY);
+            block = block.title_bottom(
+                Line::from(format!(" truncated at {} - press L to load more ", shown))
+                    .style(Style::default().fg(Color::Yellow))
+                    .right_aligned(),
+            );
+        }
 
+        let hscroll = app.preview.hscroll;
         let lines: Vec<Line> = text
             .lines()
             .enumerate()
             .skip(offset)
             .take(inner_height)
             .map(|(i, line)| {
+                let visible = match line.char_indices().nth(hscroll) {
+                    _ if hscroll == 0 => line,
+                    Some((idx, _)) => &line[idx..],
+                    None => "",
+                };
                 Line::from(vec![
                     Span::styled(
                         format!(" {:>4} ", i + 1),
                         Style::default().fg(Color::DarkGray),
                     ),
                     Span::styled("│ ", Style::default().fg(Color::DarkGray)),
-                    Span::raw(line),
+                    Span::raw(visible),
                 ])
             })
             .collect();
 
-        let content = Paragraph::new(lines).block(block);
+        let mut content = Paragraph::new(lines).block(block);
+        if app.preview.wrap {
+            content = content.wrap(Wrap { trim: false });
+        }
         frame.render_widget(content, area);
     }
 }
 
-fn render_metadata(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    let content = if let Some(meta) = &app.metadata {
+/// Draw a thumbnail with upper-half blocks (two pixels per cell), scaled to fit `area`.
+fn thumbnail_lines(thumb: &Thumbnail, area: ratatui::layout::Rect) -> Vec<Line<'static>> {
+    let avail_w = area.width.max(1) as f64;
+    let avail_h = (area.height.max(1) * 2) as f64;
+    let scale = (thumb.width as f64 / avail_w).max(thumb.height as f64 / avail_h);
+    let out_w = ((thumb.width as f64 / scale) as usize).max(1);
+    let out_h = ((thumb.height as f64 / scale) as usize).max(2);
+
+    let sample = |x: usize, y: usize| {
+        let sx = ((x as f64 * scale) as usize).min(thumb.width - 1);
+        let sy = ((y as f64 * scale) as usize).min(thumb.height - 1);
+        let (r, g, b) = thumb.pixel(sx, sy);
+        Color::Rgb(r, g, b)
+    };
+
+    (0..out_h / 2)
+        .map(|row| {
+            let spans: Vec<Span> = (0..out_w)
+                .map(|x| {
+                    Span::styled(
+                        "\u{2580}", // ▀
+                        Style::default().fg(sample(x, row * 2)).bg(sample(x, row * 2 + 1)),
+                    )
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Height of the metadata panel: grows with the number of (wrapped) lines,
+/// capped at half the screen so the browser always keeps most of the space.
+fn metadata_height(app: &App, screen: ratatui::layout::Rect) -> u16 {
+    let inner_width = screen.width.saturating_sub(2).max(1) as usize;
+    let rows: usize = metadata_lines(app, inner_width)
+        .iter()
+        .map(|line| line.width().max(1).div_ceil(inner_width))
+        .sum();
+    let max = (screen.height / 2).max(3);
+    (rows as u16 + 2).clamp(3, max)
+}
+
+/// Metadata panel lines for `width` columns. The key wraps so all of it shows;
+/// other values are cut in the middle to fit on their line.
+fn metadata_lines(app: &App, width: usize) -> Vec<Line<'_>> {
+    if let Some(meta) = &app.metadata {
+        let fit = |value: &str| middle_ellipsis(value, width.saturating_sub(16)).into_owned();
         let mut lines = vec![
             Line::from(vec![
                 Span::styled("  Key:          ", Style::default().fg(Color::Cyan)),
@@ -302,37 +908,47 @@ fn render_metadata(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
             ]),
             Line::from(vec![
                 Span::styled("  Content-Type: ", Style::default().fg(Color::Cyan)),
-                Span::raw(meta.content_type.as_deref().unwrap_or("unknown")),
+                Span::raw(fit(meta.content_type.as_deref().unwrap_or("unknown"))),
             ]),
             Line::from(vec![
                 Span::styled("  Modified:     ", Style::default().fg(Color::Cyan)),
-                Span::raw(meta.last_modified.as_deref().unwrap_or("-")),
+                Span::raw(meta.last_modified.as_ref().map_or("-".to_string(), |t| fit(&local_time(t)))),
             ]),
             Line::from(vec![
                 Span::styled("  ETag:         ", Style::default().fg(Color::Cyan)),
-                Span::raw(meta.etag.as_deref().unwrap_or("-")),
+                Span::raw(fit(meta.etag.as_deref().unwrap_or("-"))),
             ]),
         ];
 
-        if !meta.user_metadata.is_empty() {
-            let meta_str: Vec<String> = meta
-                .user_metadata
-                .iter()
-                .map(|(k, v)| format!("{}={}", k, v))
-                .collect();
+        for (label, value) in &meta.image_info {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:<14}", format!("{}:", label)), Style::default().fg(Color::Cyan)),
+                Span::raw(fit(value)),
+            ]));
+        }
+
+        // One user metadata entry per line, sorted for a stable layout
+        let mut user_meta: Vec<(&String, &String)> = meta.user_metadata.iter().collect();
+        user_meta.sort();
+        for (i, (k, v)) in user_meta.into_iter().enumerate() {
+            let label = if i == 0 { "  Metadata:     " } else { "                " };
             lines.push(Line::from(vec![
-                Span::styled("  Metadata:     ", Style::default().fg(Color::Cyan)),
-                Span::raw(meta_str.join(", ")),
+                Span::styled(label, Style::default().fg(Color::Cyan)),
+                Span::raw(fit(&format!("{}={}", k, v))),
             ]));
         }
 
         lines
     } else {
         vec![Line::from(Span::styled(
-            "  Press Enter on a file to view metadata",
+            "  Press Enter on a file to view metadata (m to hide)",
             Style::default().fg(Color::DarkGray),
         ))]
-    };
+    }
+}
+
+fn render_metadata(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let content = metadata_lines(app, area.width.saturating_sub(2) as usize);
 
     let block = Block::bordered()
         .title(" Metadata ")
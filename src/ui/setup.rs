@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::io;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, Paragraph};
+use ratatui::Frame;
+
+use crate::credentials::{AliasConfig, McConfig};
+use crate::object_store::ObjectStore;
+use crate::s3_client::S3Client;
+
+const FIELD_LABELS: [&str; 4] = ["Alias name", "Endpoint URL", "Access key", "Secret key"];
+const SECRET_FIELD: usize = 3;
+
+/// First-run wizard state: collects one alias and tests it before saving.
+struct SetupWizard {
+    fields: [String; 4],
+    focus: usize,
+    /// Last test result: (message, is_error).
+    status: Option<(String, bool)>,
+}
+
+impl SetupWizard {
+    fn new() -> Self {
+        Self {
+            fields: [
+                "local".to_string(),
+                "http://localhost:9000".to_string(),
+                String::new(),
+                String::new(),
+            ],
+            focus: 0,
+            status: None,
+        }
+    }
+
+    fn next_field(&mut self) {
+        self.focus = (self.focus + 1) % self.fields.len();
+    }
+
+    fn prev_field(&mut self) {
+        self.focus = (self.focus + self.fields.len() - 1) % self.fields.len();
+    }
+
+    fn alias_config(&self) -> AliasConfig {
+        AliasConfig {
+            url: self.fields[1].trim().to_string(),
+            access_key: self.fields[2].trim().to_string(),
+            secret_key: self.fields[3].trim().to_string(),
+            api: Some("S3v4".to_string()),
+            path: Some("auto".to_string()),
+            ..AliasConfig::default()
+        }
+    }
+
+    /// Validate the form, test the connection with `list_buckets` and build the config.
+    async fn test_connection(&mut self) -> Option<McConfig> {
+        let alias = self.fields[0].trim().to_string();
+        if let Some(i) = self.fields.iter().position(|f| f.trim().is_empty()) {
+            self.status = Some((format!("{} is required", FIELD_LABELS[i]), true));
+            self.focus = i;
+            return None;
+        }
+
+        let alias_config = self.alias_config();
+        let client = match S3Client::new(&alias, &alias_config) {
+            Ok(c) => c,
+            Err(e) => {
+                self.status = Some((format!("Invalid settings: {}", e), true));
+                return None;
+            }
+        };
+
+        match client.list_buckets().await {
+            Ok(buckets) => {
+                self.status = Some((format!("Connected: {} buckets", buckets.len()), false));
+                Some(McConfig {
+                    version: "10".to_string(),
+                    aliases: HashMap::from([(alias, alias_config)]),
+                })
+            }
+            Err(e) => {
+                self.status = Some((format!("Connection failed: {}", e), true));
+                None
+            }
+        }
+    }
+}
+
+/// Run the setup wizard in its own terminal session.
+/// Returns the saved config, or `None` if the user quit without saving.
+pub async fn run_wizard() -> anyhow::Result<Option<McConfig>> {
+    let _guard = super::guard::TerminalGuard::enter()?;
+
+    let backend = ratatui::backend::CrosstermBackend::new(io::stdout());
+    let mut terminal = ratatui::Terminal::new(backend)?;
+
+    wizard_loop(&mut terminal).await
+}
+
+async fn wizard_loop(
+    terminal: &mut ratatui::Terminal<ratatui::backend::CrosstermBackend<io::Stdout>>,
+) -> anyhow::Result<Option<McConfig>> {
+    let mut wizard = SetupWizard::new();
+
+    loop {
+        terminal.draw(|frame| render_wizard(frame, &wizard))?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Ok(None);
+            }
+            KeyCode::Tab | KeyCode::Down => wizard.next_field(),
+            KeyCode::BackTab | KeyCode::Up => wizard.prev_field(),
+            KeyCode::Enter if wizard.focus + 1 < wizard.fields.len() => wizard.next_field(),
+            KeyCode::Enter => {
+                wizard.status = Some(("Testing connection...".to_string(), false));
+                terminal.draw(|frame| render_wizard(frame, &wizard))?;
+
+                if let Some(config) = wizard.test_connection().await {
+                    let path = McConfig::default_config_path()?;
+                    match config.save(&path) {
+                        Ok(()) => return Ok(Some(config)),
+                        Err(e) => wizard.status = Some((e.to_string(), true)),
+                    }
+                }
+            }
+            KeyCode::Backspace => {
+                wizard.fields[wizard.focus].pop();
+            }
+            KeyCode::Char(c) => wizard.fields[wizard.focus].push(c),
+            _ => {}
+        }
+    }
+}
+
+fn render_wizard(frame: &mut Frame, wizard: &SetupWizard) {
+    let area = frame.area();
+    let width = 64u16.min(area.width.saturating_sub(4));
+    let height = 16u16.min(area.height);
+    let popup = super::popups::centered(area, width, height);
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "  No mc config found. Add your first S3 remote:",
+            Style::default().fg(Color::White),
+        )),
+        Line::from(""),
+    ];
+
+    for (i, (label, value)) in FIELD_LABELS.iter().zip(wizard.fields.iter()).enumerate() {
+        let focused = i == wizard.focus;
+        let shown = if i == SECRET_FIELD {
+            "*".repeat(value.chars().count())
+        } else {
+            value.clone()
+        };
+        let label_style = if focused {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Cyan)
+        };
+        let mut spans = vec![
+            Span::styled(format!("  {:<14}", format!("{}:", label)), label_style),
+            Span::raw(shown),
+        ];
+        if focused {
+            spans.push(Span::styled("_", Style::default().fg(Color::DarkGray)));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    lines.push(Line::from(""));
+    if let Some((msg, is_error)) = &wizard.status {
+        let color = if *is_error { Color::Red } else { Color::Green };
+        lines.push(Line::from(Span::styled(
+            format!("  {}", msg),
+            Style::default().fg(color),
+        )));
+    } else {
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  Tab next field  Enter test & save  Esc quit",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let path = McConfig::default_config_path()
+        .map(|p| p.display().to_string())
+        .unwrap_or_default();
+    let block = Block::bordered()
+        .title(" S3 Explorer Setup ")
+        .title_bottom(Line::from(format!(" Saves to {} ", path)).style(Style::default().fg(Color::DarkGray)))
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
@@ -1,18 +1,16 @@
-use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
 use ratatui::Frame;
 
-use crate::app::App;
+use crate::app::{App, Location};
+use crate::settings::StatusSegment;
 use super::local_fs;
 
 pub fn render_search_bar(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    let index_status = if app.index_complete {
-        format!("{} objects", app.index_object_count())
-    } else {
-        format!("indexing {}...", app.index_object_count())
-    };
+    let index_status = app
+        .index_progress_label()
+        .unwrap_or_else(|| format!("{} objects", app.index_object_count()));
 
     let line = Line::from(vec![
         Span::styled(
@@ -35,31 +33,314 @@ pub fn render_search_bar(frame: &mut Frame, app: &App, area: ratatui::layout::Re
     frame.render_widget(Paragraph::new(line), area);
 }
 
+pub fn render_bucket_input(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let line = Line::from(vec![
+        Span::styled(
+            " Bucket: ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(app.bucket_input.as_deref().unwrap_or("")),
+        Span::styled("_", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            "  Enter open  Esc cancel",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+pub fn render_new_object_input(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let Some(new) = &app.new_object else {
+        return;
+    };
+    let line = Line::from(vec![
+        Span::styled(
+            " New object: ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(&new.prefix, Style::default().fg(Color::DarkGray)),
+        Span::raw(&new.name),
+        Span::styled("_", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            "  Enter write in $EDITOR  Ctrl+V from clipboard  Esc cancel",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+pub fn render_duplicate_input(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let Some(duplicate) = &app.duplicate else {
+        return;
+    };
+    let line = Line::from(vec![
+        Span::styled(
+            " Duplicate as: ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(&duplicate.prefix, Style::default().fg(Color::DarkGray)),
+        Span::raw(&duplicate.name),
+        Span::styled("_", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            "  Enter copy  Esc cancel",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+pub fn render_filter_input(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let line = Line::from(vec![
+        Span::styled(
+            " Filter: ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(app.filter_input.as_deref().unwrap_or("")),
+        Span::styled("_", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            "  Enter apply (empty clears)  Esc cancel",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+pub fn render_marks_input(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let line = Line::from(vec![
+        Span::styled(
+            " Import marks: ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(app.marks_input.as_deref().unwrap_or("")),
+        Span::styled("_", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            "  Enter import  Esc cancel",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+pub fn render_range_input(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let line = Line::from(vec![
+        Span::styled(
+            " Range: ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(app.range_input.as_deref().unwrap_or("")),
+        Span::styled("_", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            "  first 100MB | last 10MB | 0-4096 | 1G-   Enter save  Esc cancel",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
+
 pub fn render_status_bar(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    if let Some(err) = &app.error {
-        let content = Line::from(Span::styled(
-            format!(" Error: {} (press Esc to dismiss)", err),
-            Style::default().fg(Color::Red),
-        ));
-        frame.render_widget(Paragraph::new(content), area);
+    let segments = &app.settings.status_bar;
+
+    // Show download progress if active, unless the compact speed segment stands in for it
+    if !segments.contains(&StatusSegment::Speed)
+        && let Some(progress_line) = local_fs::render_download_progress(app, area.width)
+    {
+        frame.render_widget(Paragraph::new(progress_line), area);
         return;
     }
 
-    // Show download progress if active
-    if let Some(progress_line) = local_fs::render_download_progress(app, area.width) {
+    if let Some(progress_line) = render_delete_progress(app, area.width) {
+        frame.render_widget(Paragraph::new(progress_line), area);
+        return;
+    }
+    if let Some(progress_line) = render_inventory_progress(app) {
         frame.render_widget(Paragraph::new(progress_line), area);
         return;
     }
 
-    let cols = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Min(1), Constraint::Length(
-            app.status_message.as_ref().map_or(0, |m| m.len() as u16 + 2),
-        )])
-        .split(area);
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut spans: Vec<Span> = Vec::new();
+    for segment in segments {
+        let part = match segment {
+            StatusSegment::Hints => hint_spans(app),
+            StatusSegment::Totals => match app.listing_totals() {
+                Some((objects, bytes, dirs)) => {
+                    let mut text = format!(
+                        "{} object{}, {}",
+                        objects,
+                        if objects == 1 { "" } else { "s" },
+                        humansize::format_size(bytes, humansize::BINARY)
+                    );
+                    if dirs > 0 {
+                        text.push_str(&format!(" ({} dir{})", dirs, if dirs == 1 { "" } else { "s" }));
+                    }
+                    vec![Span::styled(text, dim)]
+                }
+                None => continue,
+            },
+            StatusSegment::Index => {
+                if app.index_key.is_none() {
+                    continue;
+                }
+                let text = app
+                    .index_progress_label()
+                    .unwrap_or_else(|| format!("index {}", app.index_object_count()));
+                vec![Span::styled(text, dim)]
+            }
+            StatusSegment::Speed => match &app.download_progress {
+                Some(p) if !p.complete => {
+                    let pct = (p.bytes_downloaded * 100).checked_div(p.total_bytes).unwrap_or(0);
+                    vec![Span::styled(
+                        format!(
+                            "{}% {}/s",
+                            pct,
+                            humansize::format_size(p.speed_bps as u64, humansize::BINARY)
+                        ),
+                        Style::default().fg(Color::Cyan),
+                    )]
+                }
+                _ => continue,
+            },
+            StatusSegment::Remote => match &app.location {
+                Location::RemoteList => continue,
+                Location::BucketList { remote } => vec![Span::styled(remote.clone(), dim)],
+                Location::ObjectList { remote, bucket, .. } => {
+                    vec![Span::styled(format!("{}/{}", remote, bucket), dim)]
+                }
+            },
+            StatusSegment::Clock => vec![Span::styled(
+                chrono::Local::now().format("%H:%M").to_string(),
+                dim,
+            )],
+            StatusSegment::Tasks => {
+                let mut running = app.tasks.running();
+                let Some(oldest) = running.next() else {
+                    continue;
+                };
+                let more = running.count();
+                let text = if more == 0 {
+                    oldest.label.clone()
+                } else {
+                    format!("{} +{}", oldest.label, more)
+                };
+                vec![Span::styled(text, Style::default().fg(Color::Cyan))]
+            }
+            StatusSegment::Credentials => {
+                let Some(left) = app.current_remote().and_then(|r| app.credentials_left(r)) else {
+                    continue;
+                };
+                let (text, color) = if left.is_zero() {
+                    ("credentials expired".to_string(), Color::Red)
+                } else {
+                    // Counted down in whole minutes, which is what the loop wakes for
+                    let mins = left.as_secs().div_ceil(60);
+                    let color = if mins <= 10 { Color::Yellow } else { Color::DarkGray };
+                    if mins < 60 {
+                        (format!("credentials {}m", mins), color)
+                    } else {
+                        (format!("credentials {}h{:02}m", mins / 60, mins % 60), color)
+                    }
+                };
+                vec![Span::styled(text, Style::default().fg(color))]
+            }
+        };
+        if !spans.is_empty() {
+            spans.push(Span::styled("  \u{2502} ", dim)); // │
+        } else if *segment != StatusSegment::Hints {
+            spans.push(Span::raw(" "));
+        }
+        spans.extend(part);
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
 
-    let hints = if app.download_mode {
-        Line::from(vec![
+/// Objects deleted so far by a running prefix delete, with a bar and ETA when
+/// the total is known from the index or the plan.
+fn render_delete_progress(app: &App, area_width: u16) -> Option<Line<'static>> {
+    let progress = app.delete_progress.as_ref()?;
+    let mut spans = vec![Span::styled(
+        format!(" \u{2717} {}/ ", progress.name), // ✗
+        Style::default().fg(Color::Red),
+    )];
+    match progress.total {
+        Some(total) if total > 0 => {
+            // The sweep after an indexed delete can find a few more than estimated
+            let pct = (progress.deleted * 100 / total).min(100);
+            let bar_width = 16usize.min(area_width.saturating_sub(60) as usize);
+            let filled = bar_width * pct / 100;
+            spans.push(Span::styled(
+                format!("[{}{}]", "\u{2588}".repeat(filled), "\u{2591}".repeat(bar_width - filled)),
+                Style::default().fg(Color::Green),
+            ));
+            spans.push(Span::raw(format!(" {}%  {}/{} objects", pct, progress.deleted, total)));
+        }
+        _ => spans.push(Span::raw(format!("{} objects", progress.deleted))),
+    }
+    spans.push(Span::styled(
+        format!("  {:.0}/s", progress.rate()),
+        Style::default().fg(Color::DarkGray),
+    ));
+    if let Some(secs) = progress.eta_secs() {
+        let eta = if secs < 60 {
+            format!("{}s", secs)
+        } else {
+            format!("{}m{}s", secs / 60, secs % 60)
+        };
+        spans.push(Span::styled(format!("  ETA {}", eta), Style::default().fg(Color::DarkGray)));
+    }
+    Some(Line::from(spans))
+}
+
+/// Objects written so far by a running inventory report.
+fn render_inventory_progress(app: &App) -> Option<Line<'static>> {
+    let progress = app.inventory.as_ref()?;
+    let pct = progress
+        .total
+        .filter(|total| *total > 0)
+        .map(|total| format!(" ({}%)", (progress.rows * 100 / total).min(99)))
+        .unwrap_or_default();
+    Some(Line::from(vec![
+        Span::styled(
+            format!(" \u{2261} inventory {} ", progress.bucket), // ≡
+            Style::default().fg(Color::Cyan),
+        ),
+        Span::raw(format!("{} objects{}", progress.rows, pct)),
+        Span::styled(
+            format!("  {:.0}/s", progress.rate()),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]))
+}
+
+/// Key hints for the current mode.
+fn hint_spans(app: &App) -> Vec<Span<'static>> {
+    if let Some(show) = &app.slideshow {
+        vec![
+            Span::styled(
+                format!(" Slideshow {}/{} ", show.index + 1, show.keys.len()),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!("{}  ", show.current_key())),
+            Span::styled("n/l", Style::default().fg(Color::Yellow)),
+            Span::raw(" next  "),
+            Span::styled("N/h", Style::default().fg(Color::Yellow)),
+            Span::raw(" prev  "),
+            Span::styled("Esc", Style::default().fg(Color::Yellow)),
+            Span::raw(" stop"),
+        ]
+    } else if app.download_mode {
+        vec![
             Span::styled(" j/k", Style::default().fg(Color::Yellow)),
             Span::raw(" nav  "),
             Span::styled("Enter", Style::default().fg(Color::Yellow)),
@@ -70,13 +351,17 @@ pub fn render_status_bar(frame: &mut Frame, app: &App, area: ratatui::layout::Re
             Span::raw(" confirm  "),
             Span::styled("n", Style::default().fg(Color::Yellow)),
             Span::raw(" rename  "),
+            Span::styled("s", Style::default().fg(Color::Yellow)),
+            Span::raw(" sync  "),
+            Span::styled("r", Style::default().fg(Color::Yellow)),
+            Span::raw(" range  "),
             Span::styled("Tab", Style::default().fg(Color::Yellow)),
             Span::raw(" pane  "),
             Span::styled("Esc", Style::default().fg(Color::Yellow)),
             Span::raw(" cancel"),
-        ])
+        ]
     } else {
-        Line::from(vec![
+        vec![
             Span::styled(" q", Style::default().fg(Color::Yellow)),
             Span::raw(" quit  "),
             Span::styled("j/k", Style::default().fg(Color::Yellow)),
@@ -93,15 +378,6 @@ pub fn render_status_bar(frame: &mut Frame, app: &App, area: ratatui::layout::Re
             Span::raw(" search  "),
             Span::styled("?", Style::default().fg(Color::Yellow)),
             Span::raw(" help"),
-        ])
-    };
-    frame.render_widget(Paragraph::new(hints), cols[0]);
-
-    if let Some(msg) = &app.status_message {
-        let status = Line::from(Span::styled(
-            format!(" {} ", msg),
-            Style::default().fg(Color::Green),
-        ));
-        frame.render_widget(Paragraph::new(status), cols[1]);
+        ]
     }
 }
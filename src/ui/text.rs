@@ -0,0 +1,79 @@
+use std::borrow::Cow;
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::object_store::Timestamp;
+
+/// A timestamp in the local timezone, as the date column and panels show it.
+pub fn local_time(t: &Timestamp) -> String {
+    t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string()
+}
+
+/// `s` cut down to `max` terminal columns by replacing its middle with `…`, so
+/// both the start of a key and its extension stay visible. Wide (CJK, emoji)
+/// characters count as two columns and are never split.
+pub fn middle_ellipsis(s: &str, max: usize) -> Cow<'_, str> {
+    if s.width() <= max {
+        return Cow::Borrowed(s);
+    }
+    if max == 0 {
+        return Cow::Borrowed("");
+    }
+    let budget = max - 1;
+    let (head_max, tail_max) = (budget - budget / 2, budget / 2);
+
+    let mut head_width = 0;
+    let head_end = s
+        .char_indices()
+        .find(|&(_, c)| {
+            head_width += c.width().unwrap_or(0);
+            head_width > head_max
+        })
+        .map_or(s.len(), |(i, _)| i);
+
+    let mut tail_width = 0;
+    let tail_start = s
+        .char_indices()
+        .rev()
+        .find(|&(_, c)| {
+            tail_width += c.width().unwrap_or(0);
+            tail_width > tail_max
+        })
+        .map_or(0, |(i, c)| i + c.len_utf8());
+
+    Cow::Owned(format!("{}\u{2026}{}", &s[..head_end], &s[tail_start.max(head_end)..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiny_widths_leave_only_what_fits() {
+        assert_eq!(middle_ellipsis("report.csv", 0), "");
+        assert_eq!(middle_ellipsis("report.csv", 1), "\u{2026}");
+        assert_eq!(middle_ellipsis("report.csv", 2), "r\u{2026}");
+    }
+
+    #[test]
+    fn an_exact_fit_is_borrowed_unchanged() {
+        assert!(matches!(middle_ellipsis("report.csv", 10), Cow::Borrowed("report.csv")));
+        assert!(matches!(middle_ellipsis("", 0), Cow::Borrowed("")));
+    }
+
+    #[test]
+    fn keeps_both_ends_of_a_long_key() {
+        assert_eq!(middle_ellipsis("report-2024.csv", 9), "repo\u{2026}.csv");
+    }
+
+    #[test]
+    fn wide_characters_count_twice_and_are_never_split() {
+        assert_eq!(middle_ellipsis("日本語のファイル.txt", 9), "日本\u{2026}.txt");
+        // An odd budget leaves a column empty rather than half a character
+        assert_eq!(middle_ellipsis("日本語日本語", 6), "日\u{2026}語");
+        assert_eq!(middle_ellipsis("📷📷📷📷", 5), "📷\u{2026}📷");
+        for max in 0..12 {
+            assert!(middle_ellipsis("日本語のファイル📷.txt", max).width() <= max);
+        }
+    }
+}
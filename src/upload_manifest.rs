@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+/// Size and modification time of a local file, to tell whether it changed since
+/// it was (partly) uploaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStamp {
+    pub size: u64,
+    /// Unix seconds.
+    pub modified: Option<i64>,
+}
+
+impl FileStamp {
+    pub fn of(path: &Path) -> std::io::Result<Self> {
+        let meta = std::fs::metadata(path)?;
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64);
+        Ok(Self {
+            size: meta.len(),
+            modified,
+        })
+    }
+}
+
+/// A multipart upload that was started and never completed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingUpload {
+    pub upload_id: String,
+    pub stamp: FileStamp,
+    pub part_size: u64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Entries {
+    /// Keys uploaded, with the file they were uploaded from.
+    done: HashMap<String, FileStamp>,
+    pending: HashMap<String, PendingUpload>,
+    #[serde(skip)]
+    saved_at: Option<Instant>,
+}
+
+/// Finished files are saved at most this often; losing the last few to a crash
+/// only means uploading them again.
+const SAVE_EVERY: Duration = Duration::from_secs(1);
+
+/// What a directory upload got through, saved as it goes so running the same
+/// upload again after an interruption skips finished files and resumes multipart
+/// uploads from their last part. Removed once an upload finishes cleanly.
+pub struct UploadManifest {
+    path: Option<PathBuf>,
+    entries: Mutex<Entries>,
+}
+
+impl UploadManifest {
+    /// The manifest of uploading `local` to `remote/bucket/prefix`, empty if there is none.
+    pub fn open(remote: &str, bucket: &str, prefix: &str, local: &Path) -> Self {
+        let name = format!(
+            "uploads/{:016x}.json",
+            fnv1a(&format!("{}\0{}\0{}\0{}", remote, bucket, prefix, local.display()))
+        );
+        Self {
+            entries: Mutex::new(storage::load_json(&name)),
+            path: storage::data_dir().map(|dir| dir.join(name)),
+        }
+    }
+
+    /// Whether `key` was already uploaded from this version of the file.
+    pub fn is_done(&self, key: &str, stamp: FileStamp) -> bool {
+        self.entries.lock().unwrap().done.get(key) == Some(&stamp)
+    }
+
+    pub fn pending(&self, key: &str) -> Option<PendingUpload> {
+        self.entries.lock().unwrap().pending.get(key).cloned()
+    }
+
+    pub fn start(&self, key: &str, upload: PendingUpload) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.pending.insert(key.to_string(), upload);
+        self.save(&mut entries);
+    }
+
+    pub fn finish(&self, key: &str, stamp: FileStamp) {
+        let mut entries = self.entries.lock().unwrap();
+        let was_pending = entries.pending.remove(key).is_some();
+        entries.done.insert(key.to_string(), stamp);
+        if was_pending || entries.saved_at.is_none_or(|t| t.elapsed() >= SAVE_EVERY) {
+            self.save(&mut entries);
+        }
+    }
+
+    /// Forget a multipart upload that was aborted or can't be resumed.
+    pub fn drop_pending(&self, key: &str) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.pending.remove(key);
+        self.save(&mut entries);
+    }
+
+    /// The upload finished: nothing left to resume.
+    pub fn remove(&self) {
+        if let Some(path) = &self.path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    fn save(&self, entries: &mut Entries) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        entries.saved_at = Some(Instant::now());
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_string(entries) {
+            let _ = std::fs::write(path, content);
+        }
+    }
+}
+
+/// FNV-1a, for a file name that stays the same from one build to the next.
+fn fnv1a(s: &str) -> u64 {
+    s.bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, b| (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3))
+}
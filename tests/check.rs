@@ -0,0 +1,291 @@
+//! Round trip against a live endpoint (MinIO, localstack, a real provider)
+//! through the same client the app uses. Everything is written under a fresh
+//! prefix and deleted again. Needs `--features integration` and the remote in
+//! the mc config:
+//!
+//! ```bash
+//! S3Y_CHECK_ALIAS=myminio S3Y_CHECK_BUCKET=scratch-bucket cargo test --features integration --test check
+//! ```
+#![cfg(feature = "integration")]
+
+use std::path::Path;
+use std::time::Instant;
+
+use anyhow::{ensure, Context, Result};
+use tokio::sync::mpsc;
+
+use s3_like_yazi::credentials::McConfig;
+use s3_like_yazi::settings::Settings;
+use s3_like_yazi::object_store::{self, DeleteMsg, DownloadMsg, IndexMsg, Store, TransferJob, UploadOptions};
+
+/// More than one listing page and one DeleteObjects batch (1000 keys each).
+const OBJECT_COUNT: usize = 1005;
+
+fn env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("set {} to run the endpoint check", name))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn round_trip() -> Result<()> {
+    let (alias, bucket) = (env("S3Y_CHECK_ALIAS"), env("S3Y_CHECK_BUCKET"));
+    // Provider, region and checksum may come from the alias's options in settings.json
+    let mut config = McConfig::load()?;
+    config.apply_remote_settings(&Settings::load()?.remotes);
+    let alias_config = config
+        .aliases
+        .get(&alias)
+        .with_context(|| format!("No remote named '{}' in the mc config", alias))?;
+    let store = object_store::connect(&alias, alias_config)?;
+
+    let prefix = format!("s3y-check-{}/", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let scratch = std::env::temp_dir().join(prefix.trim_end_matches('/'));
+
+    let check = Check {
+        store,
+        bucket,
+        prefix,
+    };
+    let mut failed = Vec::new();
+    if step(&mut failed, "upload", check.upload(&scratch)).await {
+        step(&mut failed, "list one level", check.list_objects()).await;
+        step(&mut failed, "list all keys (pagination)", check.list_all_keys()).await;
+        step(&mut failed, "stream for the index", check.stream()).await;
+        step(&mut failed, "head", check.head()).await;
+        step(&mut failed, "ranged read", check.range()).await;
+        step(&mut failed, "download one object", check.download_object(&scratch)).await;
+        step(&mut failed, "download prefix", check.download_prefix(&scratch)).await;
+        step(&mut failed, "presigned URL", check.presign()).await;
+    }
+    // Always clean up, whatever got uploaded
+    step(&mut failed, "delete prefix (batching)", check.delete_prefix()).await;
+    let _ = tokio::fs::remove_dir_all(&scratch).await;
+
+    ensure!(failed.is_empty(), "failed steps:\n{}", failed.join("\n"));
+    Ok(())
+}
+
+/// Run one step, remembering how it failed so every step still runs.
+async fn step(failed: &mut Vec<String>, name: &str, fut: impl Future<Output = Result<()>>) -> bool {
+    let started = Instant::now();
+    let result = fut.await;
+    if let Err(e) = &result {
+        failed.push(format!("  {} ({} ms): {:#}", name, started.elapsed().as_millis(), e));
+    }
+    result.is_ok()
+}
+
+struct Check {
+    store: Store,
+    bucket: String,
+    prefix: String,
+}
+
+/// Contents of the i-th test object; sizes vary so ranges and totals mean something.
+fn content(i: usize) -> Vec<u8> {
+    format!("object {}\n", i).repeat(i % 7 + 1).into_bytes()
+}
+
+fn key(prefix: &str, i: usize) -> String {
+    // Every tenth object one level down, so delimited listings see a sub-prefix
+    if i.is_multiple_of(10) {
+        format!("{}sub/{:04}.txt", prefix, i)
+    } else {
+        format!("{}{:04}.txt", prefix, i)
+    }
+}
+
+/// Collect every progress message a transfer sent, checking them as they arrive.
+async fn progress(mut rx: mpsc::Receiver<DownloadMsg>) -> Result<Vec<DownloadMsg>> {
+    let mut msgs = Vec::new();
+    while let Some(msg) = rx.recv().await {
+        ensure!(
+            msg.bytes_downloaded <= msg.total_bytes,
+            "progress past the total: {} of {} bytes",
+            msg.bytes_downloaded,
+            msg.total_bytes
+        );
+        ensure!(
+            msg.files_done <= msg.files_total,
+            "{} of {} files done",
+            msg.files_done,
+            msg.files_total
+        );
+        // Completion is the app's to report, once the task returns
+        ensure!(!msg.complete && msg.error.is_none(), "transfer reported its own completion");
+        msgs.push(msg);
+    }
+    Ok(msgs)
+}
+
+impl Check {
+    async fn upload(&self, scratch: &Path) -> Result<()> {
+        let src = scratch.join("src");
+        tokio::fs::create_dir_all(&src).await?;
+        let mut jobs = Vec::new();
+        for i in 0..OBJECT_COUNT {
+            let local = src.join(format!("{:04}", i));
+            let data = content(i);
+            tokio::fs::write(&local, &data).await?;
+            jobs.push(TransferJob {
+                key: key(&self.prefix, i),
+                local,
+                size: data.len() as u64,
+                upload: true,
+            });
+        }
+        let total: u64 = jobs.iter().map(|j| j.size).sum();
+
+        let (tx, rx) = mpsc::channel(64);
+        let collect = tokio::spawn(progress(rx));
+        object_store::transfer_files(&self.store, &self.bucket, jobs, tx, 8, &UploadOptions::default()).await?;
+        let msgs = collect.await??;
+        // Uploads report the totals, then once per file
+        ensure!(msgs.len() == OBJECT_COUNT + 1, "{} progress messages for {} files", msgs.len(), OBJECT_COUNT);
+        ensure!(msgs[0].total_bytes == total, "first message totals {} of {} bytes", msgs[0].total_bytes, total);
+        let last = msgs.iter().map(|m| m.bytes_downloaded).max().unwrap_or(0);
+        ensure!(last == total, "progress ended at {} of {} bytes", last, total);
+        Ok(())
+    }
+
+    async fn list_objects(&self) -> Result<()> {
+        let entries = self.store.list_objects(&self.bucket, &self.prefix).await?;
+        let dirs: Vec<_> = entries.iter().filter(|e| e.is_dir).collect();
+        let files = entries.len() - dirs.len();
+        ensure!(
+            dirs.len() == 1 && dirs[0].display_name == "sub",
+            "expected one sub-prefix 'sub', got {:?}",
+            dirs.iter().map(|d| &d.display_name).collect::<Vec<_>>()
+        );
+        let expected = OBJECT_COUNT - OBJECT_COUNT.div_ceil(10);
+        ensure!(files == expected, "{} objects at the top level, expected {}", files, expected);
+        Ok(())
+    }
+
+    async fn list_all_keys(&self) -> Result<()> {
+        let keys = self.store.list_all_keys(&self.bucket, &self.prefix).await?;
+        ensure!(keys.len() == OBJECT_COUNT, "listed {} keys, expected {}", keys.len(), OBJECT_COUNT);
+        let mut unique: Vec<_> = keys.iter().map(|k| &k.key).collect();
+        unique.sort();
+        unique.dedup();
+        ensure!(unique.len() == OBJECT_COUNT, "{} duplicate keys across pages", OBJECT_COUNT - unique.len());
+        Ok(())
+    }
+
+    async fn stream(&self) -> Result<()> {
+        let (tx, mut rx) = mpsc::channel(16);
+        let store = self.store.clone();
+        let bucket = self.bucket.clone();
+        tokio::spawn(async move { store.stream_all_objects(&bucket, tx).await });
+        let mut seen = 0;
+        loop {
+            match rx.recv().await {
+                Some(IndexMsg::Batch(batch)) => {
+                    seen += batch.iter().filter(|o| o.key.starts_with(&self.prefix)).count();
+                }
+                Some(IndexMsg::Done) => break,
+                Some(IndexMsg::Error(e)) => anyhow::bail!(e),
+                None => anyhow::bail!("stream ended without Done"),
+            }
+        }
+        ensure!(seen == OBJECT_COUNT, "streamed {} of our keys, expected {}", seen, OBJECT_COUNT);
+        Ok(())
+    }
+
+    async fn head(&self) -> Result<()> {
+        let meta = self.store.head_object(&self.bucket, &key(&self.prefix, 3)).await?;
+        let expected = content(3).len() as i64;
+        ensure!(meta.size == expected, "size {}, expected {}", meta.size, expected);
+        Ok(())
+    }
+
+    async fn range(&self) -> Result<()> {
+        let data = content(6);
+        let bytes = self.store.get_object_range(&self.bucket, &key(&self.prefix, 6), 4, 20).await?;
+        ensure!(bytes == data[4..20], "ranged read returned the wrong bytes");
+        Ok(())
+    }
+
+    async fn download_object(&self, scratch: &Path) -> Result<()> {
+        let dest = scratch.join("one").join("0006.txt");
+        let (tx, rx) = mpsc::channel(64);
+        let collect = tokio::spawn(progress(rx));
+        self.store.download_object(&self.bucket, &key(&self.prefix, 6), &dest, &tx).await?;
+        drop(tx);
+        let msgs = collect.await??;
+
+        ensure!(tokio::fs::read(&dest).await? == content(6), "downloaded bytes differ");
+        let part = format!("{}.part", dest.display());
+        ensure!(!Path::new(&part).exists(), "the .part file was left behind");
+        let last = msgs.last().context("no progress messages")?;
+        ensure!(
+            last.bytes_downloaded == last.total_bytes,
+            "progress ended at {} of {} bytes",
+            last.bytes_downloaded,
+            last.total_bytes
+        );
+        ensure!(
+            msgs.windows(2).all(|w| w[0].bytes_downloaded <= w[1].bytes_downloaded),
+            "progress went backwards"
+        );
+        Ok(())
+    }
+
+    async fn download_prefix(&self, scratch: &Path) -> Result<()> {
+        let dest = scratch.join("all");
+        let (tx, rx) = mpsc::channel(64);
+        let collect = tokio::spawn(progress(rx));
+        object_store::download_prefix(&self.store, &self.bucket, &self.prefix, &dest, tx, 8).await?;
+        let msgs = collect.await??;
+
+        for i in [0, 1, 500, OBJECT_COUNT - 1] {
+            let rel = key(&self.prefix, i);
+            let local = dest.join(rel.strip_prefix(&self.prefix).unwrap_or(&rel));
+            ensure!(tokio::fs::read(&local).await? == content(i), "{} differs", local.display());
+        }
+        let first = msgs.first().context("no progress messages")?;
+        ensure!(first.files_total == OBJECT_COUNT, "files_total {}, expected {}", first.files_total, OBJECT_COUNT);
+        ensure!(
+            first.bytes_downloaded == 0 && first.total_bytes > 0,
+            "first message is {} of {} bytes, expected the totals before any progress",
+            first.bytes_downloaded,
+            first.total_bytes
+        );
+        Ok(())
+    }
+
+    async fn presign(&self) -> Result<()> {
+        let url = self.store.presign_get_object(&self.bucket, &key(&self.prefix, 5)).await?;
+        if let Some(path) = url.strip_prefix("file://") {
+            ensure!(tokio::fs::read(path).await? == content(5), "file URL serves the wrong bytes");
+            return Ok(());
+        }
+        let response = reqwest::get(&url).await?.error_for_status()?;
+        ensure!(response.bytes().await? == content(5), "presigned URL serves the wrong bytes");
+        Ok(())
+    }
+
+    async fn delete_prefix(&self) -> Result<()> {
+        let (tx, mut rx) = mpsc::channel(16);
+        let collect = tokio::spawn(async move {
+            let (mut reported, mut failed) = (0, Vec::new());
+            while let Some(msg) = rx.recv().await {
+                match msg {
+                    DeleteMsg::Deleted(count) => reported += count,
+                    DeleteMsg::Failed(keys) => failed.extend(keys),
+                    DeleteMsg::Done | DeleteMsg::Error(_) => {}
+                }
+            }
+            (reported, failed)
+        });
+        let deleted = self.store.delete_prefix(&self.bucket, &self.prefix, &tx).await?;
+        drop(tx);
+        let (reported, failed) = collect.await?;
+        if let Some(first) = failed.first() {
+            anyhow::bail!("{} keys refused, e.g. {}: {}", failed.len(), first.key, first.reason);
+        }
+        ensure!(reported == deleted, "progress reported {} of {} deleted objects", reported, deleted);
+        let left = self.store.list_all_keys(&self.bucket, &self.prefix).await?;
+        ensure!(left.is_empty(), "{} keys left after deleting {}", left.len(), deleted);
+        Ok(())
+    }
+}